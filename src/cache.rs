@@ -0,0 +1,275 @@
+//! @dose
+//! purpose: The incremental generation cache, stored at .ai/.luny-cache. It remembers each
+//!     source file's content hash together with what parsing it produced (ASTInfo, @dose
+//!     comments) and its resolved graph edges, so a plain generate only re-parses files whose
+//!     content changed.
+//!
+//! when-editing:
+//!     - !Anything that changes what a parser or resolver produces for the same source must
+//!       invalidate the cache; the luny version and config_hash are checked on load
+//!     - A new file the resolvers read belongs in RESOLVER_CONFIG_FILES
+//!     - Cache entries are keyed by ParsedFile::relative, the same key as the dependency graph
+//!
+//! invariants:
+//!     - A cache that is missing, unreadable or written by another version loads as empty
+//!     - Cached edges are only reused when every source file is unchanged (same set, same
+//!       hashes): an import can resolve through other files (a Rust mod tree, a Go package)
+//!     - config_hash covers luny.toml and every resolver config file in the walked tree
+//!     - content_hash is stable across builds and platforms (FNV-1a), unlike DefaultHasher
+//!
+//! do-not:
+//!     - Never treat the cache as a source of truth: a failed load or save only costs a re-parse
+//!
+//! gotchas:
+//!     - Editing any source file re-resolves every file's edges; only parses stay cached
+//!     - Configs outside the walk (a tsconfig extended from node_modules) are not hashed
+//!     - The file is JSON; it is rewritten whole after every full generate
+//!
+//! flows:
+//!     - Load: Cache::load(root, config_hash) -> entry(relative, hash) in ParsedFile::parse_cached
+//!     - Edges: reusable_edges(files) -> pipeline::resolve_edges
+//!     - Save: Cache::from_files(config_hash, files, edges).save(root)
+
+use crate::exclusion::{build_walker, ExclusionConfig};
+use crate::pipeline::{Edges, ParsedFile};
+use crate::types::{ASTInfo, ExtractedComments};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Cache file name, inside .ai/
+pub const CACHE_FILE: &str = ".luny-cache";
+
+/// Project files ImportResolver reads besides sources; tsconfig.*.json and jsconfig.*.json
+/// (extends targets) are matched by prefix
+const RESOLVER_CONFIG_FILES: &[&str] = &[
+    "tsconfig.json",
+    "jsconfig.json",
+    "package.json",
+    "Cargo.toml",
+    "go.mod",
+    "go.work",
+    "pyproject.toml",
+    "setup.cfg",
+];
+
+/// Parse results and resolved edges of every source file from the last full generate
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Cache {
+    /// luny version that wrote the cache
+    version: String,
+    /// config_hash when the cache was written
+    config: String,
+    /// Root-relative path -> cached results
+    files: BTreeMap<String, CacheEntry>,
+}
+
+/// What luny derived from one version of a source file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    /// content_hash of the source text
+    pub hash: String,
+    pub ast_info: ASTInfo,
+    pub comments: ExtractedComments,
+    /// Resolved import targets and (call target, method) pairs
+    pub edges: Edges,
+}
+
+impl Cache {
+    /// Load the cache under root, or an empty one if it is missing or was written by another
+    /// luny version or for another luny.toml
+    pub fn load(root: &Path, config_hash: &str) -> Self {
+        let Ok(content) = fs::read_to_string(cache_path(root)) else {
+            return Self::default();
+        };
+        match serde_json::from_str::<Self>(&content) {
+            Ok(cache)
+                if cache.version == env!("CARGO_PKG_VERSION") && cache.config == config_hash =>
+            {
+                cache
+            }
+            _ => Self::default(),
+        }
+    }
+
+    /// Build the cache for a completed run; edges are in the order of files
+    pub fn from_files(config_hash: &str, files: &[ParsedFile], edges: &[Edges]) -> Self {
+        let files = files
+            .iter()
+            .zip(edges)
+            .map(|(file, edges)| {
+                let entry = CacheEntry {
+                    hash: file.hash.clone(),
+                    ast_info: file.ast_info.clone(),
+                    comments: file.comments.clone(),
+                    edges: edges.clone(),
+                };
+                (file.relative.clone(), entry)
+            })
+            .collect();
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            config: config_hash.to_string(),
+            files,
+        }
+    }
+
+    /// Write the cache to .ai/.luny-cache under root
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let path = cache_path(root);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("Failed to create output directory")?;
+        }
+        let content = serde_json::to_string(self).context("Failed to serialize cache")?;
+        fs::write(&path, content).context("Failed to write cache")
+    }
+
+    /// The cached entry for a file, if its content hash is unchanged
+    pub fn entry(&self, relative: &str, hash: &str) -> Option<&CacheEntry> {
+        self.files.get(relative).filter(|entry| entry.hash == hash)
+    }
+
+    /// Whether cached edges still hold for these files: true when no file was added, removed
+    /// or changed, since one file's content can change what another's imports resolve to
+    pub fn reusable_edges(&self, files: &[ParsedFile]) -> bool {
+        !self.files.is_empty()
+            && files.len() == self.files.len()
+            && files
+                .iter()
+                .all(|f| self.entry(&f.relative, &f.hash).is_some())
+    }
+}
+
+/// Path of the cache file under root
+pub fn cache_path(root: &Path) -> PathBuf {
+    root.join(".ai").join(CACHE_FILE)
+}
+
+/// content_hash of root/luny.toml together with every resolver config file under root
+/// (paths and contents, in path order), skipping the default-excluded directories
+pub fn config_hash(root: &Path) -> String {
+    let mut configs: Vec<PathBuf> = build_walker(root, &ExclusionConfig::default())
+        .build()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .map(|entry| entry.into_path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(is_resolver_config)
+        })
+        .collect();
+    configs.sort();
+
+    let mut bytes = fs::read(root.join("luny.toml")).unwrap_or_default();
+    for path in configs {
+        let relative = path.strip_prefix(root).unwrap_or(&path);
+        bytes.push(0);
+        bytes.extend_from_slice(relative.to_string_lossy().as_bytes());
+        bytes.push(0);
+        bytes.extend(fs::read(&path).unwrap_or_default());
+    }
+    content_hash(&bytes)
+}
+
+/// True for a file name the import resolvers read (tsconfig.app.json included)
+pub(crate) fn is_resolver_config(name: &str) -> bool {
+    RESOLVER_CONFIG_FILES.contains(&name)
+        || ((name.starts_with("tsconfig.") || name.starts_with("jsconfig."))
+            && name.ends_with(".json"))
+}
+
+/// Stable 64-bit FNV-1a hash of some bytes, as 16 hex digits
+pub fn content_hash(bytes: &[u8]) -> String {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    let hash = bytes
+        .iter()
+        .fold(OFFSET, |hash, &b| (hash ^ u64::from(b)).wrapping_mul(PRIME));
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ParserFactory;
+    use crate::pipeline::parse_files;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_content_hash_is_stable() {
+        assert_eq!(content_hash(b""), "cbf29ce484222325");
+        assert_eq!(content_hash(b"a"), "af63dc4c8601ec8c");
+        assert_ne!(content_hash(b"ab"), content_hash(b"ba"));
+    }
+
+    #[test]
+    fn test_save_load_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(
+            root.join("a.ts"),
+            "import { b } from './b';\nexport const a = 1;\n",
+        )
+        .unwrap();
+        fs::write(root.join("b.ts"), "export const b = 2;\n").unwrap();
+        let paths = [root.join("a.ts"), root.join("b.ts")];
+        let files: Vec<ParsedFile> =
+            parse_files(&paths, root, &ParserFactory::new(), &Cache::default())
+                .into_iter()
+                .map(Result::unwrap)
+                .collect();
+        let edges = vec![(vec!["b.ts".to_string()], vec![]), (vec![], vec![])];
+
+        Cache::from_files("cfg", &files, &edges).save(root).unwrap();
+
+        let cache = Cache::load(root, "cfg");
+        let entry = cache.entry("a.ts", &files[0].hash).unwrap();
+        assert_eq!(entry.ast_info.exports[0].name, "a");
+        assert_eq!(entry.edges, edges[0]);
+        assert!(cache.entry("a.ts", "0000000000000000").is_none());
+        assert!(cache.reusable_edges(&files));
+        assert!(!cache.reusable_edges(&files[..1]));
+        // Changing any one file drops every cached edge, not just that file's
+        let mut changed = files.clone();
+        changed[1].hash = content_hash(b"export const b = 3;\n");
+        assert!(!cache.reusable_edges(&changed));
+
+        // Another luny.toml, or a corrupt file, loads as empty
+        assert!(Cache::load(root, "other")
+            .entry("a.ts", &files[0].hash)
+            .is_none());
+        fs::write(cache_path(root), "not json").unwrap();
+        assert!(Cache::load(root, "cfg").files.is_empty());
+    }
+
+    #[test]
+    fn test_config_hash_covers_resolver_configs() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("web")).unwrap();
+        fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        let initial = config_hash(root);
+
+        fs::write(root.join("luny.toml"), "clean = false\n").unwrap();
+        let with_luny_toml = config_hash(root);
+        assert_ne!(with_luny_toml, initial);
+
+        // Nested and extended resolver configs count; sources and excluded dirs do not
+        fs::write(root.join("web/tsconfig.json"), "{}").unwrap();
+        let with_tsconfig = config_hash(root);
+        assert_ne!(with_tsconfig, with_luny_toml);
+        fs::write(root.join("tsconfig.base.json"), "{}").unwrap();
+        assert_ne!(config_hash(root), with_tsconfig);
+
+        let hash = config_hash(root);
+        fs::write(root.join("web/main.ts"), "export const a = 1;\n").unwrap();
+        fs::write(root.join("node_modules/pkg/package.json"), "{}").unwrap();
+        assert_eq!(config_hash(root), hash);
+
+        fs::write(root.join("go.mod"), "module example.com/app\n").unwrap();
+        assert_ne!(config_hash(root), hash);
+    }
+}
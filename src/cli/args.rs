@@ -20,9 +20,16 @@
 //!     - The strip command accepts "-" as input to read from stdin
 //!     - Token thresholds have separate warn and error levels that can be customized
 //!     - The --root flag is global but optional; defaults to current directory in main.rs
+//!     - --jobs sizes a per-command rayon pool (CommonOptions::thread_pool), not the global one,
+//!       so library callers can run commands side by side with different limits
+//!     - --format must match between generate and validate: validate reads TOONs in the format
+//!       it is given, and strict TOONs do not parse as compact ones
 
 use crate::exclusion::ExclusionConfig;
+use crate::formatter::ToonFormat;
 use clap::{Args, Parser, Subcommand};
+use rayon::{ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -84,6 +91,14 @@ pub struct CommonOptions {
     /// Don't respect .gitignore files
     #[arg(long)]
     pub no_gitignore: bool,
+
+    /// Maximum number of files processed in parallel (defaults to the number of CPUs)
+    #[arg(short, long, value_name = "N")]
+    pub jobs: Option<NonZeroUsize>,
+
+    /// TOON flavour to write and read: luny's compact toon, or spec-conformant toon-strict
+    #[arg(long, value_enum, default_value_t = ToonFormat::Toon)]
+    pub format: ToonFormat,
 }
 
 impl Default for CommonOptions {
@@ -94,6 +109,8 @@ impl Default for CommonOptions {
             token_error: 1000,
             exclude: Vec::new(),
             no_gitignore: false,
+            jobs: None,
+            format: ToonFormat::Toon,
         }
     }
 }
@@ -108,6 +125,13 @@ impl CommonOptions {
             respect_gitignore: !self.no_gitignore,
         }
     }
+
+    /// Thread pool that parallel file work runs in, capped at --jobs
+    pub fn thread_pool(&self) -> Result<ThreadPool, ThreadPoolBuildError> {
+        ThreadPoolBuilder::new()
+            .num_threads(self.jobs.map_or(0, NonZeroUsize::get))
+            .build()
+    }
 }
 
 #[derive(Args, Default)]
@@ -120,7 +144,7 @@ pub struct GenerateArgs {
     #[arg(long)]
     pub dry_run: bool,
 
-    /// Force regeneration even if TOON file exists
+    /// Ignore the parse cache and rewrite every TOON, even unchanged ones
     #[arg(short, long)]
     pub force: bool,
 
@@ -146,6 +170,10 @@ pub struct ValidateArgs {
     #[arg(long)]
     pub strict: bool,
 
+    /// Regenerate each TOON in memory and report every field that differs from disk
+    #[arg(long)]
+    pub diff: bool,
+
     #[command(flatten)]
     pub common: CommonOptions,
 }
@@ -249,6 +277,23 @@ mod tests {
         };
         assert_eq!(args.common.token_warn, 300);
         assert_eq!(args.common.token_error, 600);
+
+        // Parallelism: --jobs, -j
+        assert_eq!(args.common.jobs, None);
+        let cli = Cli::try_parse_from(["luny", "generate", "-j", "4"]).unwrap();
+        let Commands::Generate(args) = cli.command else {
+            panic!("Expected Generate")
+        };
+        assert_eq!(args.common.jobs, NonZeroUsize::new(4));
+        assert_eq!(args.common.thread_pool().unwrap().current_num_threads(), 4);
+
+        // Output format: --format
+        assert_eq!(args.common.format, ToonFormat::Toon);
+        let cli = Cli::try_parse_from(["luny", "generate", "--format", "toon-strict"]).unwrap();
+        let Commands::Generate(args) = cli.command else {
+            panic!("Expected Generate")
+        };
+        assert_eq!(args.common.format, ToonFormat::ToonStrict);
     }
 
     /// Comprehensive test for validate command and all its options
@@ -262,6 +307,7 @@ mod tests {
         assert!(args.paths.is_empty());
         assert!(!args.fix);
         assert!(!args.strict);
+        assert!(!args.diff);
         assert_eq!(args.common.token_warn, 500);
         assert_eq!(args.common.token_error, 1000);
 
@@ -284,6 +330,12 @@ mod tests {
             panic!("Expected Validate")
         };
         assert!(args.strict);
+
+        let cli = Cli::try_parse_from(["luny", "validate", "--diff"]).unwrap();
+        let Commands::Validate(args) = cli.command else {
+            panic!("Expected Validate")
+        };
+        assert!(args.diff);
     }
 
     /// Comprehensive test for strip command and all its options
@@ -379,6 +431,8 @@ mod tests {
         assert!(Cli::try_parse_from(["luny"]).is_err()); // Missing command
         assert!(Cli::try_parse_from(["luny", "invalid"]).is_err()); // Invalid command
         assert!(Cli::try_parse_from(["luny", "generate", "--token-warn", "not_a_number"]).is_err());
+        assert!(Cli::try_parse_from(["luny", "generate", "--jobs", "0"]).is_err());
+        assert!(Cli::try_parse_from(["luny", "generate", "--format", "json"]).is_err());
     }
 
     /// Test help output
@@ -0,0 +1,431 @@
+//! @dose
+//! purpose: This module builds the directory-level DOSE files (.ai/<dir>/_dir.toon) that let
+//!     an AI tool orient itself in a package before opening individual .toon files. Each
+//!     summary aggregates the directory's files and an optional @dose block from Markdown.
+//!
+//! when-editing:
+//!     - !Only files directly in a directory are summarized; subdirectories get their own _dir.toon
+//!     - Markdown @dose blocks live in <!-- @dose ... --> comments so they stay hidden when rendered
+//!
+//! invariants:
+//!     - A directory gets a _dir.toon only if it holds at least one supported source file
+//!     - _dose.md wins over README.md; a README without a @dose block lends its first paragraph
+//!       as the purpose
+//!     - imports lists only project files outside the directory (relative or resolved aliases),
+//!       never packages
+//!
+//! do-not:
+//!     - Never treat Markdown files as sources: they get no per-file .toon
+//!     - Never record import targets outside the root (absolute paths would leak into .ai/)
+//!
+//! gotchas:
+//!     - Exports are qualified with their file name (provider.ts:AuthProvider)
+//!     - A _dose.md without a comment marker is read whole as a @dose block
+//!     - File purposes are cut to their first sentence to keep the summary one screen long
+//!     - Exports stop after the first 30 in path order; a final …(+N more) entry counts the rest
+//!
+//! flows:
+//!     - Summarize: Group ParsedFiles by parent directory, collect purpose, exports, imports
+//!     - Document: Read _dose.md or README.md and merge its block over the aggregated data
+//!     - Write: Skip a _dir.toon whose content is unchanged unless --force, as for file .toon files
+
+use crate::cli::GenerateArgs;
+use crate::dependency::{normalize_separators, resolve_import_path};
+use crate::parser::toon_comment;
+use crate::pipeline::ParsedFile;
+use crate::resolver::ImportResolver;
+use crate::types::{ExportInfo, FileSummary, ImportInfo, ToonCommentBlock, ToonData};
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File name of a directory summary inside .ai/<dir>/
+pub const DIR_TOON: &str = "_dir.toon";
+
+/// Exports listed per directory; the rest are counted in a final "…(+N more)" entry
+const MAX_DIR_EXPORTS: usize = 30;
+
+/// README names checked for a directory's documentation, in order
+const README_NAMES: &[&str] = &["README.md", "Readme.md", "readme.md"];
+
+/// Data aggregated from the source files directly in one directory
+#[derive(Debug, Default)]
+pub struct DirSummary {
+    /// Sum of the files' token counts
+    pub tokens: usize,
+    /// One entry per file, in path order
+    pub files: Vec<FileSummary>,
+    /// Exports of every file, qualified as file:name and capped at MAX_DIR_EXPORTS
+    pub exports: Vec<ExportInfo>,
+    /// Import target outside the directory -> items imported from it
+    pub imports: BTreeMap<String, Vec<String>>,
+}
+
+/// Group parsed source files by their directory (relative to root) and summarize each directory
+pub fn summarize_directories(
+    files: &[ParsedFile],
+    root: &Path,
+    resolver: &ImportResolver,
+) -> BTreeMap<PathBuf, DirSummary> {
+    let mut dirs: BTreeMap<PathBuf, DirSummary> = BTreeMap::new();
+
+    for parsed in files {
+        let path = &parsed.path;
+        let relative = Path::new(&parsed.relative);
+        let ast_info = &parsed.ast_info;
+
+        let dir = relative.parent().unwrap_or(Path::new("")).to_path_buf();
+        let file = relative
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let purpose = first_sentence(&parsed.purpose());
+
+        let summary = dirs.entry(dir.clone()).or_default();
+        summary.tokens += ast_info.tokens;
+        summary
+            .exports
+            .extend(ast_info.exports.iter().map(|e| ExportInfo {
+                name: format!("{}:{}", file, e.name),
+                kind: e.kind.clone(),
+            }));
+
+        for import in &ast_info.imports {
+            let targets = resolver.resolve_import_local(import, path, parsed.language);
+            let target = match targets.as_slice() {
+                [target] => target.clone(),
+                // A whole package (Go): the package directory is the dependency
+                [first, ..] => match Path::new(first).parent() {
+                    Some(package) => normalize_separators(&package.to_string_lossy()),
+                    None => continue,
+                },
+                [] if import.from.starts_with('.') => {
+                    normalize_separators(&resolve_import_path(&import.from, path, root))
+                }
+                [] => continue,
+            };
+            let target_dir = if targets.len() > 1 {
+                Some(Path::new(&target))
+            } else {
+                Path::new(&target).parent()
+            };
+            if Path::new(&target).is_absolute() || target_dir == Some(dir.as_path()) {
+                continue;
+            }
+            let items = summary.imports.entry(target).or_default();
+            for item in &import.items {
+                if !items.contains(item) {
+                    items.push(item.clone());
+                }
+            }
+        }
+
+        summary.files.push(FileSummary { file, purpose });
+    }
+
+    for summary in dirs.values_mut() {
+        if summary.exports.len() > MAX_DIR_EXPORTS {
+            let more = summary.exports.len() - MAX_DIR_EXPORTS;
+            summary.exports.truncate(MAX_DIR_EXPORTS);
+            summary.exports.push(ExportInfo {
+                name: "…".to_string(),
+                kind: format!("+{} more", more),
+            });
+        }
+    }
+
+    dirs
+}
+
+/// Build the _dir.toon data for a directory, merging in its Markdown @dose block
+pub fn dir_toon_data(dir: &Path, summary: DirSummary, root: &Path) -> ToonData {
+    let block = markdown_block(&root.join(dir));
+
+    let purpose = block
+        .as_ref()
+        .and_then(|b| b.purpose.clone())
+        .unwrap_or_else(|| match dir.file_name() {
+            Some(name) => format!("{} directory", name.to_string_lossy()),
+            None => "Project root directory".to_string(),
+        });
+
+    let mut toon_data = ToonData::new(purpose, summary.tokens, summary.exports);
+    toon_data.files = Some(summary.files);
+    if !summary.imports.is_empty() {
+        toon_data.imports = Some(
+            summary
+                .imports
+                .into_iter()
+                .map(|(from, items)| ImportInfo { from, items })
+                .collect(),
+        );
+    }
+
+    if let Some(ref block) = block {
+        toon_data.when_editing = block.when_editing.clone();
+        toon_data.do_not = block.do_not.clone();
+        toon_data.invariants = block.invariants.clone();
+        toon_data.error_handling = block.error_handling.clone();
+        toon_data.constraints = block.constraints.clone();
+        toon_data.gotchas = block.gotchas.clone();
+        toon_data.flows = block.flows.clone();
+        toon_data.testing = block.testing.clone();
+        toon_data.common_mistakes = block.common_mistakes.clone();
+        toon_data.change_impacts = block.change_impacts.clone();
+        toon_data.related = block.related.clone();
+    }
+
+    toon_data
+}
+
+/// Write .ai/<dir>/_dir.toon. Returns false when the existing summary is already up to date.
+pub fn write_dir_summary(
+    dir: &Path,
+    summary: DirSummary,
+    args: &GenerateArgs,
+    root: &Path,
+    verbose: bool,
+) -> Result<bool> {
+    let toon_path = root.join(".ai").join(dir).join(DIR_TOON);
+    let content = args
+        .common
+        .format
+        .format(&dir_toon_data(dir, summary, root));
+
+    if !args.force && fs::read_to_string(&toon_path).is_ok_and(|existing| existing == content) {
+        if verbose {
+            println!("Skipping {} (TOON unchanged)", toon_path.display());
+        }
+        return Ok(false);
+    }
+
+    if args.dry_run {
+        println!("Would write to: {}", toon_path.display());
+        if verbose {
+            println!("---\n{}\n---", content);
+        }
+    } else {
+        if let Some(parent) = toon_path.parent() {
+            fs::create_dir_all(parent).context("Failed to create output directory")?;
+        }
+
+        fs::write(&toon_path, &content).context("Failed to write TOON file")?;
+
+        if verbose {
+            println!("Generated: {}", toon_path.display());
+        }
+    }
+
+    Ok(true)
+}
+
+/// The @dose block documenting a directory, from _dose.md or else its README
+pub fn markdown_block(dir: &Path) -> Option<ToonCommentBlock> {
+    if let Ok(content) = fs::read_to_string(dir.join("_dose.md")) {
+        let block = dose_comment(&content).unwrap_or_else(|| {
+            let content = content.trim_start();
+            content.strip_prefix("@dose").unwrap_or(content).to_string()
+        });
+        return Some(toon_comment::parse_toon_block(&block));
+    }
+
+    let content = README_NAMES
+        .iter()
+        .find_map(|name| fs::read_to_string(dir.join(name)).ok())?;
+    match dose_comment(&content) {
+        Some(block) => Some(toon_comment::parse_toon_block(&block)),
+        None => first_paragraph(&content).map(|purpose| ToonCommentBlock {
+            purpose: Some(purpose),
+            ..Default::default()
+        }),
+    }
+}
+
+/// Content of the first `<!-- @dose ... -->` comment, lines trimmed
+fn dose_comment(markdown: &str) -> Option<String> {
+    let pattern = Regex::new(r"<!--\s*@dose([\s\S]*?)-->").unwrap();
+    let caps = pattern.captures(markdown)?;
+    Some(
+        caps[1]
+            .lines()
+            .map(|line| line.trim())
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
+/// The first prose paragraph, skipping headings, badges, HTML, code, and lists
+fn first_paragraph(markdown: &str) -> Option<String> {
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut in_comment = false;
+    let mut in_code = false;
+
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+        if in_comment {
+            in_comment = !trimmed.contains("-->");
+            continue;
+        }
+        if trimmed.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+        if trimmed.starts_with("<!--") {
+            in_comment = !trimmed.contains("-->");
+            continue;
+        }
+
+        let is_prose = !trimmed.is_empty()
+            && !trimmed.starts_with(['#', '<', '!', '|', '-', '*', '=', '>'])
+            && !trimmed.starts_with("[!");
+        if is_prose {
+            paragraph.push(trimmed);
+        } else if !paragraph.is_empty() {
+            break;
+        }
+    }
+
+    (!paragraph.is_empty()).then(|| paragraph.join(" "))
+}
+
+/// First sentence of a purpose, whitespace collapsed
+fn first_sentence(text: &str) -> String {
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match collapsed.find(". ") {
+        Some(end) => collapsed[..=end].to_string(),
+        None => collapsed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ParserFactory;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_summarize_directories() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("auth")).unwrap();
+        fs::create_dir_all(root.join("lib")).unwrap();
+        fs::write(
+            root.join("auth/provider.ts"),
+            "/** @dose\npurpose: Auth context. Handles refresh.\ngotchas: Tokens expire\n*/\nimport { get } from '../lib/http';\nimport { User } from './user';\nimport React from 'react';\nexport function AuthProvider() {}\n",
+        )
+        .unwrap();
+        fs::write(root.join("auth/user.ts"), "export interface User {}\n").unwrap();
+        fs::write(root.join("lib/http.ts"), "export function get() {}\n").unwrap();
+
+        let files: Vec<ParsedFile> = ["auth/provider.ts", "auth/user.ts", "lib/http.ts"]
+            .iter()
+            .map(|name| ParsedFile::parse(&root.join(name), root, &ParserFactory::new()).unwrap())
+            .collect();
+        let resolver = ImportResolver::new(root);
+        let dirs = summarize_directories(&files, root, &resolver);
+        assert_eq!(dirs.len(), 2);
+
+        let auth = &dirs[Path::new("auth")];
+        let files: Vec<_> = auth
+            .files
+            .iter()
+            .map(|f| (&f.file[..], &f.purpose[..]))
+            .collect();
+        assert_eq!(
+            files,
+            vec![("provider.ts", "Auth context."), ("user.ts", "user module")]
+        );
+        let exports: Vec<_> = auth.exports.iter().map(|e| &e.name[..]).collect();
+        assert_eq!(exports, vec!["provider.ts:AuthProvider", "user.ts:User"]);
+        let imports: Vec<_> = auth.imports.keys().map(|k| &k[..]).collect();
+        assert_eq!(imports, vec!["lib/http.ts"]);
+    }
+
+    #[test]
+    fn test_summarize_directories_caps_exports() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let source: String = (0..MAX_DIR_EXPORTS + 5)
+            .map(|i| format!("export function f{}() {{}}\n", i))
+            .collect();
+        fs::write(root.join("many.ts"), source).unwrap();
+
+        let files =
+            vec![ParsedFile::parse(&root.join("many.ts"), root, &ParserFactory::new()).unwrap()];
+        let dirs = summarize_directories(&files, root, &ImportResolver::new(root));
+        let exports = &dirs[Path::new("")].exports;
+        assert_eq!(exports.len(), MAX_DIR_EXPORTS + 1);
+        assert_eq!(exports[0].name, "many.ts:f0");
+        let last = exports.last().unwrap();
+        assert_eq!(format!("{}({})", last.name, last.kind), "…(+5 more)");
+    }
+
+    #[test]
+    fn test_markdown_block_from_readme() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("README.md"),
+            "# Auth\n\n<!-- @dose\npurpose: Session handling\ngotchas:\n    - Tokens expire in 15 minutes\n-->\n\nPublic prose.\n",
+        )
+        .unwrap();
+
+        let block = markdown_block(temp_dir.path()).unwrap();
+        assert_eq!(block.purpose.as_deref(), Some("Session handling"));
+        assert_eq!(
+            block.gotchas,
+            Some(vec!["Tokens expire in 15 minutes".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_markdown_block_readme_paragraph() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("README.md"),
+            "# Billing\n\n[![CI](badge.svg)](ci)\n<!-- internal\nnote -->\n\nInvoices and payment\nprovider adapters.\n\n## Usage\n",
+        )
+        .unwrap();
+
+        let block = markdown_block(temp_dir.path()).unwrap();
+        assert_eq!(
+            block.purpose.as_deref(),
+            Some("Invoices and payment provider adapters.")
+        );
+    }
+
+    #[test]
+    fn test_markdown_block_prefers_dose_md() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("README.md"), "Readme purpose.\n").unwrap();
+        fs::write(
+            temp_dir.path().join("_dose.md"),
+            "@dose\npurpose: Dose purpose\ninvariants: Never blocks\n",
+        )
+        .unwrap();
+
+        let block = markdown_block(temp_dir.path()).unwrap();
+        assert_eq!(block.purpose.as_deref(), Some("Dose purpose"));
+        assert_eq!(block.invariants, Some(vec!["Never blocks".to_string()]));
+
+        assert!(markdown_block(&temp_dir.path().join("missing")).is_none());
+    }
+
+    #[test]
+    fn test_dir_toon_data_default_purpose() {
+        let temp_dir = TempDir::new().unwrap();
+        let data = dir_toon_data(
+            Path::new("src/auth"),
+            DirSummary::default(),
+            temp_dir.path(),
+        );
+        assert_eq!(data.purpose, "auth directory");
+        let data = dir_toon_data(Path::new(""), DirSummary::default(), temp_dir.path());
+        assert_eq!(data.purpose, "Project root directory");
+    }
+}
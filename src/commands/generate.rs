@@ -4,7 +4,8 @@
 //!     generate comprehensive documentation.
 //!
 //! when-editing:
-//!     - !The two-pass algorithm is critical: first pass builds dependency graph, second generates TOON
+//!     - !The two-pass algorithm is critical: first pass parses every file once and builds the
+//!       dependency graph, second formats TOON from the same ParsedFiles (see pipeline)
 //!     - !TOON files are placed in .ai/ directory mirroring source structure
 //!     - File collection excludes node_modules, .git, target, and __pycache__ directories
 //!
@@ -14,60 +15,72 @@
 //!     - Token thresholds trigger warnings or errors during generation
 //!
 //! do-not:
-//!     - Never overwrite existing TOON files unless --force is specified
+//!     - Never rewrite a TOON whose rendered content is unchanged unless --force is specified
 //!     - Never process files in excluded directories
 //!
 //! gotchas:
-//!     - Import path resolution tries multiple variants (with/without extension, with ./ prefix)
+//!     - Graph lookups try multiple path variants (with/without extension, with ./ prefix)
 //!     - Relative imports are resolved relative to the importing file
-//!     - Package imports are stored as-is without resolution
+//!     - Non-relative imports go through ImportResolver (aliases, workspaces); unresolved
+//!       package imports are stored as-is
+//!     - One import can name several files (a Go package); each gets the imported-by edge
+//!     - related merges @dose entries with parser-paired files (C/C++ header -> implementation)
+//!     - Directory summaries are only written for directories that were walked, so generating a
+//!       single file never overwrites its directory's _dir.toon with a partial view
+//!     - Without --force, unchanged sources reuse their parse from .ai/.luny-cache (see cache);
+//!       only runs without PATH arguments rewrite the cache, and --force ignores it
+//!     - A PATH run still parses the whole project (cached where unchanged) so the graph is
+//!       complete, but writes TOONs only for the files under PATH
+//!     - --format toon-strict writes spec-conformant TOON (formatter::strict) instead of the
+//!       compact form; switching formats rewrites every TOON since the content changes
+//!     - Parsing and TOON writing run in parallel (rayon, capped by --jobs); worker threads never
+//!       print, so messages and the aggregated error list come out in sorted file order
 //!
 //! flows:
-//!     - Collect: Walk directory tree finding supported source files
-//!     - Build graph: Parse each file, extract imports and calls, build reverse dependency maps
-//!     - Generate: For each file, extract AST + comments, merge with graph data, format TOON
-
+//!     - Collect: Walk directory tree finding supported source files (the whole project too,
+//!       for PATH runs)
+//!     - Parse: ParsedFile::parse_cached once per file (AST + @dose comments, cached if unchanged)
+//!     - Build graph: pipeline::resolve_edges resolves imports and calls into the shared graph
+//!     - Generate: For each ParsedFile, merge with graph data, format TOON, write if changed
+//!     - Cache: Save parses and edges to .ai/.luny-cache for the next run
+//!     - Summarize: Write .ai/<dir>/_dir.toon for each walked directory (see dir_summary)
+
+use crate::cache::{config_hash, Cache};
 use crate::cli::GenerateArgs;
-use crate::config::{Config, ThresholdMatcher};
+use crate::commands::{summarize_directories, write_dir_summary};
+use crate::config::Config;
+use crate::dependency::DependencyGraph;
 use crate::exclusion::{build_exclude_globset, build_walker};
-use crate::formatter::format_toon;
 use crate::parser::ParserFactory;
-use crate::types::{CalledByInfo, ToonData};
+use crate::pipeline::{graph_from_edges, parse_files, resolve_edges, toon_path, ParsedFile};
+use crate::resolver::ImportResolver;
 use anyhow::{Context, Result};
-use std::collections::HashMap;
+use rayon::prelude::*;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-/// Dependency graph for reverse lookups
-struct DependencyGraph {
-    /// Map from import target (relative path) to list of files that import it
-    imported_by: HashMap<String, Vec<String>>,
-    /// Map from call target (relative path) to list of (file, function) that call it
-    called_by: HashMap<String, Vec<CalledByInfo>>,
+pub fn run_generate(args: &GenerateArgs, root: &Path, verbose: bool) -> Result<()> {
+    run_generate_with_graph(args, root, verbose).map(|_| ())
 }
 
-impl DependencyGraph {
-    fn new() -> Self {
-        Self {
-            imported_by: HashMap::new(),
-            called_by: HashMap::new(),
-        }
-    }
-
-    fn get_imported_by(&self, file_path: &str) -> Vec<String> {
-        self.imported_by.get(file_path).cloned().unwrap_or_default()
-    }
-
-    fn get_called_by(&self, file_path: &str) -> Vec<CalledByInfo> {
-        self.called_by.get(file_path).cloned().unwrap_or_default()
-    }
+/// Run generate and return the dependency graph it built, so watch can start from it
+/// without parsing the tree again
+pub fn run_generate_with_graph(
+    args: &GenerateArgs,
+    root: &Path,
+    verbose: bool,
+) -> Result<DependencyGraph> {
+    let pool = args
+        .common
+        .thread_pool()
+        .context("Failed to start worker threads")?;
+    pool.install(|| generate(args, root, verbose))
 }
 
-pub fn run_generate(args: &GenerateArgs, root: &Path, verbose: bool) -> Result<()> {
-    let factory = ParserFactory::new();
-
+fn generate(args: &GenerateArgs, root: &Path, verbose: bool) -> Result<DependencyGraph> {
     // Load configuration from luny.toml
     let config = Config::load(root);
+    let factory = ParserFactory::from_config(&config);
     let threshold_matcher = config.threshold_matcher();
 
     // Clean the .ai directory if requested (via CLI flag or config)
@@ -90,12 +103,56 @@ pub fn run_generate(args: &GenerateArgs, root: &Path, verbose: bool) -> Result<(
     // Collect all files to process
     let files = collect_files(args, root, &root_canon, &factory, &config);
 
+    // imported-by and called-by need every file, so a PATH run parses the rest of the project
+    // as well (from the cache when unchanged) and only writes TOONs for its own files
+    let project_files = if args.paths.is_empty() {
+        files.clone()
+    } else {
+        let project_args = GenerateArgs {
+            common: args.common.clone(),
+            ..Default::default()
+        };
+        let mut project_files = collect_files(&project_args, root, &root_canon, &factory, &config);
+        project_files.extend(files.iter().cloned());
+        project_files.sort();
+        project_files.dedup();
+        project_files
+    };
+    let requested = |path: &PathBuf| files.binary_search(path).is_ok();
+
     if verbose {
-        println!("Building dependency graph for {} files...", files.len());
+        println!(
+            "Building dependency graph for {} files...",
+            project_files.len()
+        );
     }
 
-    // First pass: build dependency graph
-    let dep_graph = build_dependency_graph(&files, root, &factory, verbose);
+    // Without --force, files whose content hash is unchanged reuse their cached parse
+    let config_hash = config_hash(root);
+    let cache = if args.force {
+        Cache::default()
+    } else {
+        Cache::load(root, &config_hash)
+    };
+
+    // First pass: parse every file once, then build the dependency graph from the results;
+    // files outside PATH that fail to parse are left out of the graph without an error
+    let mut errors = Vec::new();
+    let mut parsed = Vec::with_capacity(project_files.len());
+    for (path, result) in
+        project_files
+            .iter()
+            .zip(parse_files(&project_files, root, &factory, &cache))
+    {
+        match result {
+            Ok(file) => parsed.push(file),
+            Err(e) if requested(path) => errors.push(format!("{}: {:#}", path.display(), e)),
+            Err(_) => {}
+        }
+    }
+    let resolver = ImportResolver::from_config(root, &config);
+    let edges = resolve_edges(&parsed, &resolver, &cache);
+    let dep_graph = graph_from_edges(&parsed, &edges);
 
     if verbose {
         println!(
@@ -105,43 +162,90 @@ pub fn run_generate(args: &GenerateArgs, root: &Path, verbose: bool) -> Result<(
         );
     }
 
-    // Second pass: generate TOON files
+    // Second pass: generate TOON files in parallel, then report in file order
     let mut processed = 0;
     let mut skipped = 0;
-    let mut errors = 0;
-
-    for path in &files {
-        match process_file(
-            path,
-            &factory,
-            args,
-            root,
-            &dep_graph,
-            &threshold_matcher,
-            verbose,
-        ) {
+
+    let targets: Vec<&ParsedFile> = parsed.iter().filter(|f| requested(&f.path)).collect();
+    let outcomes: Vec<_> = targets
+        .par_iter()
+        .map(|file| write_toon(file, args, root, &dep_graph))
+        .collect();
+    for (file, outcome) in targets.into_iter().zip(outcomes) {
+        let toon_path = toon_path(root, &file.path);
+        match outcome {
+            Ok(WriteOutcome::Unchanged) => {
+                skipped += 1;
+                if verbose {
+                    println!("Skipping {} (TOON unchanged)", file.path.display());
+                }
+            }
+            Ok(outcome) => {
+                processed += 1;
+                // Check token limits using per-file thresholds
+                let thresholds = threshold_matcher.get_thresholds(Path::new(&file.relative));
+                if let Some(warning) = file.token_warning(thresholds) {
+                    eprintln!("{}", warning);
+                }
+                match outcome {
+                    WriteOutcome::DryRun(content) => {
+                        println!("Would write to: {}", toon_path.display());
+                        if verbose {
+                            println!("---\n{}\n---", content);
+                        }
+                    }
+                    _ if verbose => println!("Generated: {}", toon_path.display()),
+                    _ => {}
+                }
+            }
+            Err(e) => errors.push(format!("{}: {:#}", file.path.display(), e)),
+        }
+    }
+
+    // Third pass: directory summaries, for directories walked in full
+    let walked: Vec<PathBuf> = input_paths(args, root)
+        .into_iter()
+        .filter(|p| p.is_dir())
+        .collect();
+    for (dir, summary) in summarize_directories(&parsed, root, &resolver) {
+        let full_dir = root.join(&dir);
+        if !walked.iter().any(|w| full_dir.starts_with(w)) {
+            continue;
+        }
+        match write_dir_summary(&dir, summary, args, root, verbose) {
             Ok(true) => processed += 1,
             Ok(false) => skipped += 1,
-            Err(e) => {
-                eprintln!("Error processing {}: {}", path.display(), e);
-                errors += 1;
-            }
+            Err(e) => errors.push(format!("{}: {:#}", full_dir.display(), e)),
+        }
+    }
+
+    // Only a run over the whole project knows the full file set the cache describes
+    if args.paths.is_empty() && !args.dry_run {
+        if let Err(e) = Cache::from_files(&config_hash, &parsed, &edges).save(root) {
+            eprintln!("Warning: {:#}", e);
         }
     }
 
     println!(
         "Generated: {}, Skipped: {}, Errors: {}",
-        processed, skipped, errors
+        processed,
+        skipped,
+        errors.len()
     );
 
-    if errors > 0 {
-        anyhow::bail!("{} files failed to process", errors);
+    if !errors.is_empty() {
+        anyhow::bail!(
+            "{} files failed to process:\n  {}",
+            errors.len(),
+            errors.join("\n  ")
+        );
     }
 
-    Ok(())
+    Ok(dep_graph)
 }
 
-fn collect_files(
+/// Source files under the input paths, honoring exclusions and symlink safety, sorted
+pub(crate) fn collect_files(
     args: &GenerateArgs,
     root: &Path,
     root_canon: &Path,
@@ -150,25 +254,13 @@ fn collect_files(
 ) -> Vec<PathBuf> {
     let mut files = Vec::new();
 
-    let paths = if args.paths.is_empty() {
-        vec![root.to_path_buf()]
-    } else {
-        args.paths.clone()
-    };
-
     // Build exclusion configuration from args, merging with config patterns
     let exclusion_config = args.common.exclusion_config(&config.exclude);
 
     // Build glob set for additional pattern matching (for paths specified via CLI)
     let exclude_globset = build_exclude_globset(&args.common.exclude);
 
-    for path in paths {
-        let full_path = if path.is_absolute() {
-            path
-        } else {
-            root.join(&path)
-        };
-
+    for full_path in input_paths(args, root) {
         if full_path.is_file() {
             // Check if single file matches exclude patterns
             if let Some(ref globset) = exclude_globset {
@@ -205,6 +297,23 @@ fn collect_files(
     files
 }
 
+/// Paths given on the command line (the root when none), made absolute against root
+fn input_paths(args: &GenerateArgs, root: &Path) -> Vec<PathBuf> {
+    if args.paths.is_empty() {
+        return vec![root.to_path_buf()];
+    }
+    args.paths
+        .iter()
+        .map(|path| {
+            if path.is_absolute() {
+                path.clone()
+            } else {
+                root.join(path)
+            }
+        })
+        .collect()
+}
+
 fn is_allowed_symlink_target(path: &Path, root_canon: &Path, unsafe_follow: bool) -> bool {
     if unsafe_follow {
         return true;
@@ -217,292 +326,49 @@ fn is_allowed_symlink_target(path: &Path, root_canon: &Path, unsafe_follow: bool
     }
 }
 
-fn build_dependency_graph(
-    files: &[PathBuf],
-    root: &Path,
-    factory: &ParserFactory,
-    _verbose: bool,
-) -> DependencyGraph {
-    let mut graph = DependencyGraph::new();
-
-    for path in files {
-        let Some(parser) = factory.get_parser(path) else {
-            continue;
-        };
-
-        let Ok(source) = fs::read_to_string(path) else {
-            continue;
-        };
-
-        let Ok(ast_info) = parser.extract_ast_info(&source, path) else {
-            continue;
-        };
-
-        let Ok(file_relative) = path.strip_prefix(root) else {
-            // Should never happen if collection is rooted correctly.
-            // Avoid leaking absolute paths into the dependency graph.
-            continue;
-        };
-        let file_relative = normalize_separators(&file_relative.to_string_lossy());
-
-        // Process imports to build imported_by
-        for import in &ast_info.imports {
-            let target = normalize_separators(&resolve_import_path(&import.from, path, root));
-            graph
-                .imported_by
-                .entry(target)
-                .or_default()
-                .push(file_relative.clone());
-        }
-
-        // Process calls to build called_by
-        for call in &ast_info.calls {
-            let target = normalize_separators(&resolve_import_path(&call.target, path, root));
-            graph
-                .called_by
-                .entry(target)
-                .or_default()
-                .push(CalledByInfo {
-                    from: file_relative.clone(),
-                    function: call.method.clone(),
-                });
-        }
-    }
-
-    graph
-}
-
-/// Resolve an import path to a canonical relative path
-fn resolve_import_path(import_from: &str, from_file: &Path, root: &Path) -> String {
-    if import_from.starts_with('.') {
-        // Relative import - resolve relative to the importing file
-        let from_dir = from_file.parent().unwrap_or(from_file);
-        let resolved = from_dir.join(import_from);
-
-        // Normalize (purely lexical) and make relative to root.
-        // Avoid canonicalize(): it performs filesystem IO and follows symlinks, which is slow and
-        // can escape the intended root in surprising ways.
-        let normalized = normalize_path(&resolved);
-        if let Ok(rel) = normalized.strip_prefix(root) {
-            return rel.to_string_lossy().to_string();
-        }
-        normalized.to_string_lossy().to_string()
-    } else {
-        // Package import - return as-is
-        import_from.to_string()
-    }
-}
-
-/// Simple path normalization (resolve . and ..)
-fn normalize_path(path: &Path) -> PathBuf {
-    let mut result = PathBuf::new();
-    for component in path.components() {
-        match component {
-            std::path::Component::ParentDir => {
-                result.pop();
-            }
-            std::path::Component::CurDir => {}
-            _ => {
-                result.push(component);
-            }
-        }
-    }
-    result
+/// What write_toon did with one file; printed by the caller once the parallel pass is done
+enum WriteOutcome {
+    /// The TOON on disk already has this content and --force was not given
+    Unchanged,
+    /// --dry-run: the content that would have been written
+    DryRun(String),
+    Written,
 }
 
-fn process_file(
-    path: &Path,
-    factory: &ParserFactory,
+/// Write one file's TOON. Prints nothing: it runs on worker threads
+fn write_toon(
+    file: &ParsedFile,
     args: &GenerateArgs,
     root: &Path,
     dep_graph: &DependencyGraph,
-    threshold_matcher: &ThresholdMatcher,
-    verbose: bool,
-) -> Result<bool> {
-    let parser = factory
-        .get_parser(path)
-        .context("No parser available for file")?;
-
-    // Compute TOON output path (keep full filename, add .toon suffix)
-    let relative = path
-        .strip_prefix(root)
-        .with_context(|| format!("File {} is outside root {}", path.display(), root.display()))?;
-    let relative_str = normalize_separators(&relative.to_string_lossy());
-    let toon_filename = format!(
-        "{}.toon",
-        relative.file_name().unwrap_or_default().to_string_lossy()
-    );
-    let toon_relative = relative.with_file_name(toon_filename);
-    let toon_path = root.join(".ai").join(toon_relative);
-
-    // Check if TOON file exists and we're not forcing regeneration
-    if toon_path.exists() && !args.force {
-        if verbose {
-            println!("Skipping {} (TOON exists)", path.display());
-        }
-        return Ok(false);
-    }
-
-    // Read source file
-    let source = fs::read_to_string(path).context("Failed to read source file")?;
-
-    // Extract AST info
-    let ast_info = parser.extract_ast_info(&source, path)?;
-
-    // Check token limits using per-file thresholds
-    let thresholds = threshold_matcher.get_thresholds(relative);
-    if let Some(error_threshold) = thresholds.error {
-        if ast_info.tokens > error_threshold {
-            eprintln!(
-                "ERROR: {} has {} tokens (exceeds error threshold of {})",
-                path.display(),
-                ast_info.tokens,
-                error_threshold
-            );
-        }
-    }
-    if let Some(warn_threshold) = thresholds.warn {
-        if ast_info.tokens > warn_threshold && thresholds.error.is_none_or(|e| ast_info.tokens <= e)
-        {
-            eprintln!(
-                "WARNING: {} has {} tokens (exceeds warning threshold of {})",
-                path.display(),
-                ast_info.tokens,
-                warn_threshold
-            );
-        }
-    }
-
-    // Extract @dose comments
-    let comments = parser.extract_toon_comments(&source)?;
-
-    // Build purpose from comments or generate default
-    let purpose = comments
-        .file_block
-        .as_ref()
-        .and_then(|b| b.purpose.clone())
-        .unwrap_or_else(|| {
-            // Generate default purpose from filename
-            let filename = path.file_stem().unwrap_or_default().to_string_lossy();
-            format!("{} module", filename)
-        });
-
-    // Build ToonData
-    let mut toon_data = ToonData::new(purpose, ast_info.tokens, ast_info.exports);
-
-    // Add AST-extracted data
-    if !ast_info.imports.is_empty() {
-        toon_data.imports = Some(ast_info.imports);
-    }
-    if !ast_info.calls.is_empty() {
-        toon_data.calls = Some(ast_info.calls);
-    }
-    if !ast_info.signatures.is_empty() {
-        toon_data.signatures = Some(ast_info.signatures);
-    }
-
-    // Add reverse dependency data from graph
-    // Try multiple path variations to find matches
-    let path_variants = get_path_variants(&relative_str);
-
-    let mut imported_by: Vec<String> = Vec::new();
-    let mut called_by: Vec<CalledByInfo> = Vec::new();
+) -> Result<WriteOutcome> {
+    let toon_path = toon_path(root, &file.path);
+    let content = args.common.format.format(&file.toon_data(dep_graph));
 
-    for variant in &path_variants {
-        imported_by.extend(dep_graph.get_imported_by(variant));
-        called_by.extend(dep_graph.get_called_by(variant));
+    // Without --force, leave a TOON alone when its content would not change
+    if !args.force && fs::read_to_string(&toon_path).is_ok_and(|existing| existing == content) {
+        return Ok(WriteOutcome::Unchanged);
     }
 
-    // Deduplicate
-    imported_by.sort();
-    imported_by.dedup();
-
-    if !imported_by.is_empty() {
-        toon_data.imported_by = Some(imported_by);
-    }
-    if !called_by.is_empty() {
-        // Deterministic ordering and de-dup (variants can overlap).
-        called_by.sort_by(|a, b| {
-            (a.from.as_str(), a.function.as_str()).cmp(&(b.from.as_str(), b.function.as_str()))
-        });
-        called_by.dedup_by(|a, b| a.from == b.from && a.function == b.function);
-        toon_data.called_by = Some(called_by);
-    }
-
-    // Add comment-extracted data
-    if let Some(ref block) = comments.file_block {
-        toon_data.when_editing = block.when_editing.clone();
-        toon_data.do_not = block.do_not.clone();
-        toon_data.invariants = block.invariants.clone();
-        toon_data.error_handling = block.error_handling.clone();
-        toon_data.constraints = block.constraints.clone();
-        toon_data.gotchas = block.gotchas.clone();
-        toon_data.flows = block.flows.clone();
-        toon_data.testing = block.testing.clone();
-        toon_data.common_mistakes = block.common_mistakes.clone();
-        toon_data.change_impacts = block.change_impacts.clone();
-        toon_data.related = block.related.clone();
-    }
-
-    // Add function-level annotations (inline @dose comments)
-    if !comments.function_annotations.is_empty() {
-        toon_data.function_annotations =
-            Some(comments.function_annotations.values().cloned().collect());
-    }
-
-    // Format TOON content
-    let content = format_toon(&toon_data);
-
     if args.dry_run {
-        println!("Would write to: {}", toon_path.display());
-        if verbose {
-            println!("---\n{}\n---", content);
-        }
-    } else {
-        // Ensure parent directory exists
-        if let Some(parent) = toon_path.parent() {
-            fs::create_dir_all(parent).context("Failed to create output directory")?;
-        }
-
-        fs::write(&toon_path, &content).context("Failed to write TOON file")?;
-
-        if verbose {
-            println!("Generated: {}", toon_path.display());
-        }
+        return Ok(WriteOutcome::DryRun(content));
     }
 
-    Ok(true)
-}
-
-/// Normalize path separators to forward slashes for cross-platform consistency
-fn normalize_separators(path: &str) -> String {
-    path.replace('\\', "/")
-}
-
-/// Get various path forms to match against imports
-fn get_path_variants(path: &str) -> Vec<String> {
-    let normalized = normalize_separators(path);
-    let mut variants = vec![normalized.clone()];
-
-    // Without extension
-    if let Some(without_ext) = normalized
-        .strip_suffix(".ts")
-        .or_else(|| normalized.strip_suffix(".tsx"))
-        .or_else(|| normalized.strip_suffix(".js"))
-        .or_else(|| normalized.strip_suffix(".jsx"))
-    {
-        variants.push(without_ext.to_string());
+    // Ensure parent directory exists
+    if let Some(parent) = toon_path.parent() {
+        fs::create_dir_all(parent).context("Failed to create output directory")?;
     }
+    fs::write(&toon_path, &content).context("Failed to write TOON file")?;
 
-    // With ./ prefix
-    variants.push(format!("./{}", normalized));
-
-    variants
+    Ok(WriteOutcome::Written)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dependency::{get_path_variants, normalize_path, resolve_import_path};
+    use crate::pipeline::build_graph;
+    use crate::types::CalledByInfo;
     use std::fs;
     use tempfile::TempDir;
 
@@ -855,11 +721,11 @@ mod tests {
 
     #[test]
     fn test_build_dependency_graph_empty() {
-        let factory = ParserFactory::new();
-        let files: Vec<PathBuf> = vec![];
+        let files: Vec<ParsedFile> = vec![];
         let temp_dir = TempDir::new().unwrap();
 
-        let graph = build_dependency_graph(&files, temp_dir.path(), &factory, false);
+        let resolver = ImportResolver::new(temp_dir.path());
+        let graph = build_graph(&files, &resolver);
         assert!(graph.imported_by.is_empty());
         assert!(graph.called_by.is_empty());
     }
@@ -876,8 +742,12 @@ mod tests {
         fs::write(&main_path, "import { foo } from './utils';").unwrap();
         fs::write(&utils_path, "export function foo() {}").unwrap();
 
-        let files = vec![main_path, utils_path];
-        let graph = build_dependency_graph(&files, temp_dir.path(), &factory, false);
+        let files: Vec<ParsedFile> = [main_path, utils_path]
+            .iter()
+            .map(|path| ParsedFile::parse(path, temp_dir.path(), &factory).unwrap())
+            .collect();
+        let resolver = ImportResolver::new(temp_dir.path());
+        let graph = build_graph(&files, &resolver);
 
         // main.ts imports from ./utils, so utils should be in imported_by
         assert!(!graph.imported_by.is_empty());
@@ -885,6 +755,149 @@ mod tests {
 
     // ==================== Integration Tests ====================
 
+    #[test]
+    fn test_run_generate_resolves_tsconfig_aliases() {
+        let temp_dir = TempDir::new().unwrap();
+        let src = temp_dir.path().join("src");
+        fs::create_dir(&src).unwrap();
+        fs::write(
+            temp_dir.path().join("tsconfig.json"),
+            r#"{ "compilerOptions": { "baseUrl": ".", "paths": { "@app/*": ["src/*"] } } }"#,
+        )
+        .unwrap();
+        fs::write(src.join("auth.ts"), "export function login() {}").unwrap();
+        fs::write(src.join("main.ts"), "import { login } from '@app/auth';").unwrap();
+
+        run_generate(&GenerateArgs::default(), temp_dir.path(), false).unwrap();
+
+        let content = fs::read_to_string(temp_dir.path().join(".ai/src/auth.ts.toon")).unwrap();
+        assert!(content.contains("imported-by[1]: src/main.ts"));
+    }
+
+    #[test]
+    fn test_run_generate_resolves_rust_module_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let src = temp_dir.path().join("src");
+        fs::create_dir_all(src.join("types")).unwrap();
+        fs::write(
+            temp_dir.path().join("Cargo.toml"),
+            "[package]\nname = \"app\"\n",
+        )
+        .unwrap();
+        fs::write(src.join("lib.rs"), "pub mod types;\npub mod parser;\n").unwrap();
+        fs::write(src.join("types/mod.rs"), "pub struct Token;\n").unwrap();
+        fs::write(
+            src.join("parser.rs"),
+            "use crate::types::Token;\n\npub fn parse() -> Token { Token }\n",
+        )
+        .unwrap();
+
+        run_generate(&GenerateArgs::default(), temp_dir.path(), false).unwrap();
+
+        let content =
+            fs::read_to_string(temp_dir.path().join(".ai/src/types/mod.rs.toon")).unwrap();
+        assert!(content.contains("imported-by[1]: src/parser.rs"));
+    }
+
+    #[test]
+    fn test_run_generate_resolves_go_packages() {
+        let temp_dir = TempDir::new().unwrap();
+        let auth = temp_dir.path().join("internal/auth");
+        fs::create_dir_all(&auth).unwrap();
+        fs::write(
+            temp_dir.path().join("go.mod"),
+            "module github.com/ourorg/svc\n",
+        )
+        .unwrap();
+        fs::write(auth.join("token.go"), "package auth\n\nfunc Issue() {}\n").unwrap();
+        fs::write(auth.join("session.go"), "package auth\n\nfunc Open() {}\n").unwrap();
+        fs::write(
+            temp_dir.path().join("main.go"),
+            "package main\n\nimport \"github.com/ourorg/svc/internal/auth\"\n\nfunc main() { auth.Issue() }\n",
+        )
+        .unwrap();
+
+        run_generate(&GenerateArgs::default(), temp_dir.path(), false).unwrap();
+
+        for file in ["token.go", "session.go"] {
+            let toon = temp_dir
+                .path()
+                .join(".ai/internal/auth")
+                .join(format!("{}.toon", file));
+            let content = fs::read_to_string(toon).unwrap();
+            assert!(content.contains("imported-by[1]: main.go"), "{}", content);
+        }
+    }
+
+    #[test]
+    fn test_run_generate_resolves_python_modules() {
+        let temp_dir = TempDir::new().unwrap();
+        let app = temp_dir.path().join("src/app");
+        fs::create_dir_all(app.join("services")).unwrap();
+        fs::write(
+            temp_dir.path().join("pyproject.toml"),
+            "[project]\nname = \"app\"\n",
+        )
+        .unwrap();
+        fs::write(app.join("__init__.py"), "").unwrap();
+        fs::write(app.join("models.py"), "class User:\n    pass\n").unwrap();
+        fs::write(app.join("services/__init__.py"), "").unwrap();
+        fs::write(
+            app.join("services/billing.py"),
+            "from ..models import User\n\ndef charge():\n    return User()\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("main.py"),
+            "import app.services.billing\n\napp.services.billing.charge()\n",
+        )
+        .unwrap();
+
+        run_generate(&GenerateArgs::default(), temp_dir.path(), false).unwrap();
+
+        let models =
+            fs::read_to_string(temp_dir.path().join(".ai/src/app/models.py.toon")).unwrap();
+        assert!(
+            models.contains("imported-by[1]: src/app/services/billing.py"),
+            "{}",
+            models
+        );
+        let billing =
+            fs::read_to_string(temp_dir.path().join(".ai/src/app/services/billing.py.toon"))
+                .unwrap();
+        assert!(billing.contains("imported-by[1]: main.py"), "{}", billing);
+        assert!(
+            billing.contains("called-by[1]: main.py,charge"),
+            "{}",
+            billing
+        );
+    }
+
+    #[test]
+    fn test_run_generate_aggregates_errors_in_file_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        // Not UTF-8, so reading the source fails
+        fs::write(root.join("b.ts"), [0xff, 0xfe]).unwrap();
+        fs::write(root.join("a.ts"), [0xff, 0xfe]).unwrap();
+        fs::write(root.join("ok.ts"), "export const x = 1;").unwrap();
+
+        let args = GenerateArgs {
+            common: crate::cli::CommonOptions {
+                jobs: std::num::NonZeroUsize::new(2),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let message = run_generate(&args, root, false).unwrap_err().to_string();
+
+        assert!(message.starts_with("2 files failed to process"));
+        let a = message.find("a.ts").unwrap();
+        let b = message.find("b.ts").unwrap();
+        assert!(a < b);
+        assert!(root.join(".ai/ok.ts.toon").exists());
+    }
+
     #[test]
     fn test_run_generate_dry_run() {
         let temp_dir = TempDir::new().unwrap();
@@ -922,25 +935,120 @@ mod tests {
     }
 
     #[test]
-    fn test_run_generate_skips_existing_without_force() {
+    fn test_run_generate_dir_summaries() {
         let temp_dir = TempDir::new().unwrap();
+        let auth_dir = temp_dir.path().join("src/auth");
+        fs::create_dir_all(&auth_dir).unwrap();
+        fs::write(auth_dir.join("session.ts"), "export const ttl = 15;").unwrap();
+        fs::write(
+            auth_dir.join("README.md"),
+            "# Auth\n\n<!-- @dose\npurpose: Session handling for the web app\n-->\n",
+        )
+        .unwrap();
+
+        // A single file leaves its directory's summary alone
+        let args = GenerateArgs {
+            paths: vec![auth_dir.join("session.ts")],
+            ..Default::default()
+        };
+        run_generate(&args, temp_dir.path(), false).unwrap();
+        assert!(!temp_dir.path().join(".ai/src/auth/_dir.toon").exists());
+
+        run_generate(&GenerateArgs::default(), temp_dir.path(), false).unwrap();
+        let content = fs::read_to_string(temp_dir.path().join(".ai/src/auth/_dir.toon")).unwrap();
+        assert!(content.starts_with("purpose: Session handling for the web app\n"));
+        assert!(content.contains("exports[1]: session.ts:ttl(const)"));
+        assert!(content.contains("files[1]:\n  session.ts: session module"));
+        assert!(!temp_dir.path().join(".ai/src/_dir.toon").exists());
+        assert!(!temp_dir.path().join(".ai/src/auth/README.md.toon").exists());
+    }
 
-        // Create a TypeScript file
-        fs::write(temp_dir.path().join("main.ts"), "export const x = 1;").unwrap();
-
-        // Create existing .ai directory and toon file
-        let ai_dir = temp_dir.path().join(".ai");
-        fs::create_dir(&ai_dir).unwrap();
-        fs::write(ai_dir.join("main.ts.toon"), "existing content").unwrap();
+    #[test]
+    fn test_run_generate_rewrites_only_changed_toons() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("main.ts"), "import { x } from './utils';\n").unwrap();
+        fs::write(root.join("utils.ts"), "export const x = 1;").unwrap();
+
+        run_generate(&GenerateArgs::default(), root, false).unwrap();
+        assert!(root.join(".ai/.luny-cache").exists());
+        let main_toon = root.join(".ai/main.ts.toon");
+        let written = fs::metadata(&main_toon).unwrap().modified().unwrap();
+
+        // A new importer changes utils' imported-by but leaves main's TOON as it was
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        fs::write(root.join("app.ts"), "import { x } from './utils';\n").unwrap();
+        run_generate(&GenerateArgs::default(), root, false).unwrap();
+
+        let utils = fs::read_to_string(root.join(".ai/utils.ts.toon")).unwrap();
+        assert!(utils.contains("imported-by[2]: app.ts,main.ts"));
+        assert_eq!(
+            fs::metadata(&main_toon).unwrap().modified().unwrap(),
+            written
+        );
 
-        let args = GenerateArgs::default();
+        // An out-of-date TOON is rewritten without --force
+        fs::write(&main_toon, "existing content").unwrap();
+        run_generate(&GenerateArgs::default(), root, false).unwrap();
+        assert!(fs::read_to_string(&main_toon)
+            .unwrap()
+            .starts_with("purpose:"));
+    }
 
-        let result = run_generate(&args, temp_dir.path(), false);
-        assert!(result.is_ok());
+    #[test]
+    fn test_run_generate_path_keeps_project_graph() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(
+            root.join("main.ts"),
+            "import { foo } from './utils';\nexport function run() { foo(); }\n",
+        )
+        .unwrap();
+        fs::write(root.join("utils.ts"), "export function foo() {}\n").unwrap();
+        run_generate(&GenerateArgs::default(), root, false).unwrap();
+        let full = fs::read_to_string(root.join(".ai/utils.ts.toon")).unwrap();
+        assert!(full.contains("imported-by[1]: main.ts"));
+        assert!(full.contains("called-by"));
+
+        // Regenerating one file keeps the edges from files outside PATH
+        fs::write(root.join(".ai/utils.ts.toon"), "stale").unwrap();
+        let main_toon = root.join(".ai/main.ts.toon");
+        fs::write(&main_toon, "untouched").unwrap();
+        let args = GenerateArgs {
+            paths: vec![PathBuf::from("utils.ts")],
+            ..Default::default()
+        };
+        run_generate(&args, root, false).unwrap();
+        assert_eq!(
+            fs::read_to_string(root.join(".ai/utils.ts.toon")).unwrap(),
+            full
+        );
+        assert_eq!(fs::read_to_string(&main_toon).unwrap(), "untouched");
+    }
 
-        // Existing content should be preserved
-        let content = fs::read_to_string(ai_dir.join("main.ts.toon")).unwrap();
-        assert_eq!(content, "existing content");
+    #[test]
+    fn test_run_generate_reuses_cached_parse() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("main.ts"), "export const x = 1;").unwrap();
+        run_generate(&GenerateArgs::default(), root, false).unwrap();
+
+        // Unchanged source: the cached parse (here tampered with) is used instead of the file
+        let cache_path = root.join(".ai/.luny-cache");
+        let cache = fs::read_to_string(&cache_path).unwrap();
+        fs::write(&cache_path, cache.replace("\"x\"", "\"cached\"")).unwrap();
+        run_generate(&GenerateArgs::default(), root, false).unwrap();
+        let toon = fs::read_to_string(root.join(".ai/main.ts.toon")).unwrap();
+        assert!(toon.contains("cached"));
+
+        // --force ignores the cache
+        let args = GenerateArgs {
+            force: true,
+            ..Default::default()
+        };
+        run_generate(&args, root, false).unwrap();
+        let toon = fs::read_to_string(root.join(".ai/main.ts.toon")).unwrap();
+        assert!(!toon.contains("cached"));
     }
 
     #[test]
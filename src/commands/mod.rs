@@ -1,8 +1,10 @@
+mod dir_summary;
 mod generate;
 mod strip;
 mod validate;
 mod watch;
 
+pub use dir_summary::*;
 pub use generate::*;
 pub use strip::*;
 pub use validate::*;
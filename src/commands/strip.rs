@@ -27,6 +27,7 @@
 //!     - Write: Output to file or stdout
 
 use crate::cli::StripArgs;
+use crate::config::Config;
 use crate::parser::ParserFactory;
 use anyhow::{Context, Result};
 use std::fs;
@@ -34,7 +35,7 @@ use std::io::{self, Read, Write};
 use std::path::Path;
 
 pub fn run_strip(args: &StripArgs, root: &Path, _verbose: bool) -> Result<()> {
-    let factory = ParserFactory::new();
+    let factory = ParserFactory::from_config(&Config::load(root));
 
     // Read source from file or stdin
     let (source, ext) = if let Some(ref input) = args.input {
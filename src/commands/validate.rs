@@ -12,6 +12,11 @@
 //!     - A TOON file is valid only if its source file exists
 //!     - The purpose field is always required
 //!     - Export mismatches generate warnings, not errors
+//!     - A fingerprint that differs from the source's is an error (stale); TOONs without one
+//!       (written before fingerprints existed) are not checked for staleness
+//!     - --diff compares formatted text, so it names the fields that differ, not the values
+//!     - TOONs are read in the --format they were generated with; a strict TOON that does not
+//!       decode is an error
 //!
 //! do-not:
 //!     - Never modify TOON files during validation
@@ -20,27 +25,46 @@
 //! gotchas:
 //!     - TOON path to source path conversion strips .ai/ prefix and .toon suffix
 //!     - Only purpose is required; all other semantic fields are optional
+//!     - _dir.toon summaries are only checked for their directory and purpose; --fix cannot
+//!       regenerate them (run generate --force)
 //!
 //! flows:
 //!     - Walk: Find all .toon files in .ai/ directory
 //!     - Validate: For each TOON, parse it, find source, compare exports, check thresholds
+//!     - Validate: TOON files are checked in parallel (capped by --jobs), reported in sorted order
+//!     - Diff: With --diff, regenerate each TOON in memory and compare it field by field
+//!     - Fix: Build the project graph once (as generate does), regenerate via pipeline
 
-use crate::cli::ValidateArgs;
+use crate::cache::{config_hash, Cache};
+use crate::cli::{GenerateArgs, ValidateArgs};
+use crate::commands::{collect_files, DIR_TOON};
 use crate::config::{Config, ThresholdMatcher};
+use crate::dependency::DependencyGraph;
 use crate::exclusion::{build_exclude_globset, build_walker};
-use crate::formatter::{format_toon, parse_toon};
+use crate::formatter::{changed_fields, ToonFormat};
 use crate::parser::ParserFactory;
-use crate::types::{ToonData, ValidationResult};
+use crate::pipeline::{build_graph, parse_files, ParsedFile};
+use crate::resolver::ImportResolver;
+use crate::types::ValidationResult;
 use anyhow::{Context, Result};
+use rayon::prelude::*;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 pub fn run_validate(args: &ValidateArgs, root: &Path, verbose: bool) -> Result<()> {
-    let factory = ParserFactory::new();
+    let pool = args
+        .common
+        .thread_pool()
+        .context("Failed to start worker threads")?;
+    pool.install(|| validate(args, root, verbose))
+}
+
+fn validate(args: &ValidateArgs, root: &Path, verbose: bool) -> Result<()> {
     let root_canon = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
 
     // Load configuration from luny.toml
     let config = Config::load(root);
+    let factory = ParserFactory::from_config(&config);
     let threshold_matcher = config.threshold_matcher();
 
     // Determine paths to process
@@ -53,6 +77,8 @@ pub fn run_validate(args: &ValidateArgs, root: &Path, verbose: bool) -> Result<(
     let mut valid = 0;
     let mut invalid = 0;
     let mut warnings = 0;
+    // Built on the first --fix, then shared by every file fixed
+    let mut dep_graph: Option<DependencyGraph> = None;
 
     for path in paths {
         let full_path = if path.is_absolute() {
@@ -104,28 +130,66 @@ pub fn run_validate(args: &ValidateArgs, root: &Path, verbose: bool) -> Result<(
         }
         toon_files.sort();
 
-        for toon_path in toon_files {
-            match validate_toon_file(&toon_path, &factory, &threshold_matcher, root, verbose) {
-                Ok(mut result) => {
-                    // Optional fix-up pass: regenerate invalid TOON files and re-validate.
-                    if args.fix && !result.is_valid() {
-                        if verbose {
-                            println!("Fixing: {}", toon_path.display());
-                        }
-                        if let Err(e) = fix_toon_file(&toon_path, &factory, root) {
-                            eprintln!("Error fixing {}: {}", toon_path.display(), e);
-                        } else {
-                            // Re-validate after fix attempt (counts reflect final state).
-                            result = validate_toon_file(
-                                &toon_path,
-                                &factory,
-                                &threshold_matcher,
-                                root,
-                                verbose,
-                            )?;
-                        }
-                    }
+        // The project graph is built once: up front for --diff, else only if something needs fixing
+        if args.diff {
+            dep_graph
+                .get_or_insert_with(|| project_graph(args, root, &root_canon, &factory, &config));
+        }
+        let check = |toon_path: &Path, graph: Option<&DependencyGraph>| {
+            let format = args.common.format;
+            let mut result =
+                validate_toon_file(toon_path, &factory, &threshold_matcher, format, root)?;
+            if let Some(graph) = graph.filter(|_| args.diff) {
+                for field in diff_toon_file(toon_path, &factory, format, root, graph)? {
+                    result.add_error(format!("Field '{}' differs from the source", field));
+                }
+            }
+            Ok::<_, anyhow::Error>(result)
+        };
 
+        // Validate in parallel
+        let results: Vec<_> = toon_files
+            .par_iter()
+            .map(|toon_path| check(toon_path, dep_graph.as_ref()))
+            .collect();
+        let needs_fix = args.fix && results.iter().any(|r| matches!(r, Ok(r) if !r.is_valid()));
+        let graph =
+            if needs_fix {
+                Some(&*dep_graph.get_or_insert_with(|| {
+                    project_graph(args, root, &root_canon, &factory, &config)
+                }))
+            } else {
+                None
+            };
+
+        // Optional fix-up pass: regenerate invalid TOON files and re-validate.
+        let checked: Vec<_> = toon_files
+            .par_iter()
+            .zip(results)
+            .map(|(toon_path, result)| match (graph, result) {
+                (Some(graph), Ok(result)) if !result.is_valid() => {
+                    match fix_toon_file(toon_path, &factory, args.common.format, root, graph) {
+                        // Re-validate after fix attempt (counts reflect final state).
+                        Ok(()) => (Some(Ok(())), check(toon_path, Some(graph))),
+                        Err(e) => (Some(Err(e)), Ok(result)),
+                    }
+                }
+                (_, result) => (None, result),
+            })
+            .collect();
+
+        // Report in file order
+        for (toon_path, (fix, result)) in toon_files.iter().zip(checked) {
+            match fix {
+                Some(Ok(())) if verbose => println!("Fixed: {}", toon_path.display()),
+                Some(Err(e)) => eprintln!("Error fixing {}: {:#}", toon_path.display(), e),
+                _ => {}
+            }
+            match result {
+                Ok(result) => {
+                    if verbose {
+                        println!("Validated: {}", toon_path.display());
+                    }
                     if result.errors.is_empty() {
                         valid += 1;
                         if !result.warnings.is_empty() {
@@ -144,7 +208,7 @@ pub fn run_validate(args: &ValidateArgs, root: &Path, verbose: bool) -> Result<(
                     }
                 }
                 Err(e) => {
-                    eprintln!("Error validating {}: {}", toon_path.display(), e);
+                    eprintln!("Error validating {}: {:#}", toon_path.display(), e);
                     invalid += 1;
                 }
             }
@@ -177,8 +241,8 @@ fn validate_toon_file(
     toon_path: &Path,
     factory: &ParserFactory,
     threshold_matcher: &ThresholdMatcher,
+    format: ToonFormat,
     root: &Path,
-    verbose: bool,
 ) -> Result<ValidationResult> {
     // Compute source path from TOON path
     // .ai/path/to/file.ts.toon -> path/to/file.ts
@@ -198,7 +262,25 @@ fn validate_toon_file(
 
     // Read TOON file
     let toon_content = fs::read_to_string(toon_path).context("Failed to read TOON file")?;
-    let toon_data = parse_toon(&toon_content);
+    let toon_data = match format.parse(&toon_content) {
+        Ok(toon_data) => toon_data,
+        Err(e) => {
+            result.add_error(format!("Invalid TOON: {}", e));
+            return Ok(result);
+        }
+    };
+
+    // Directory summaries (.ai/src/_dir.toon) document src/ rather than a source file
+    if toon_path.file_name().is_some_and(|name| name == DIR_TOON) {
+        let dir = source_path.parent().unwrap_or(root);
+        result.source_path = dir.to_string_lossy().to_string();
+        if !dir.is_dir() {
+            result.add_error("Directory no longer exists");
+        } else if toon_data.purpose.is_empty() {
+            result.add_error("Missing required field: purpose");
+        }
+        return Ok(result);
+    }
 
     // Check if source file exists
     if !source_path.exists() {
@@ -211,11 +293,19 @@ fn validate_toon_file(
         result.add_error("Missing required field: purpose");
     }
 
-    // Get parser for source file
-    if let Some(parser) = factory.get_parser(&source_path) {
-        // Read and parse source
-        let source = fs::read_to_string(&source_path).context("Failed to read source file")?;
-        let ast_info = parser.extract_ast_info(&source, &source_path)?;
+    // Parse the source the same way generate does
+    if factory.is_supported(&source_path) {
+        let parsed = ParsedFile::parse(&source_path, root, factory)?;
+        let ast_info = &parsed.ast_info;
+
+        // The fingerprint changes with the source text and the luny version
+        if toon_data
+            .fingerprint
+            .as_ref()
+            .is_some_and(|fingerprint| *fingerprint != parsed.fingerprint())
+        {
+            result.add_error("Stale: source changed since the TOON was generated");
+        }
 
         // Check token count using per-file thresholds
         let relative_source = source_path.strip_prefix(root).unwrap_or(&source_path);
@@ -255,15 +345,6 @@ fn validate_toon_file(
                 extra
             ));
         }
-
-        if verbose {
-            println!(
-                "Validated: {} ({} tokens, {} exports)",
-                toon_path.display(),
-                ast_info.tokens,
-                ast_info.exports.len()
-            );
-        }
     } else {
         result.add_warning("Could not find parser for source file");
     }
@@ -276,60 +357,24 @@ fn try_toon_path_to_source_path(toon_path: &Path, root: &Path) -> Option<PathBuf
     Some(root.join(relative.to_string_lossy().trim_end_matches(".toon")))
 }
 
-fn fix_toon_file(toon_path: &Path, factory: &ParserFactory, root: &Path) -> Result<()> {
+fn fix_toon_file(
+    toon_path: &Path,
+    factory: &ParserFactory,
+    format: ToonFormat,
+    root: &Path,
+    dep_graph: &DependencyGraph,
+) -> Result<()> {
     let source_path = try_toon_path_to_source_path(toon_path, root)
         .context("TOON file is outside the .ai/ directory for this root")?;
     if !source_path.exists() {
         anyhow::bail!("Source file no longer exists");
     }
-
-    let parser = factory
-        .get_parser(&source_path)
-        .context("Could not find parser for source file")?;
-
-    let source = fs::read_to_string(&source_path).context("Failed to read source file")?;
-    let ast_info = parser.extract_ast_info(&source, &source_path)?;
-    let comments = parser.extract_toon_comments(&source)?;
-
-    let purpose = comments
-        .file_block
-        .as_ref()
-        .and_then(|b| b.purpose.clone())
-        .unwrap_or_else(|| {
-            let filename = source_path
-                .file_stem()
-                .unwrap_or_default()
-                .to_string_lossy();
-            format!("{} module", filename)
-        });
-
-    let mut toon_data = ToonData::new(purpose, ast_info.tokens, ast_info.exports);
-
-    if !ast_info.imports.is_empty() {
-        toon_data.imports = Some(ast_info.imports);
-    }
-    if !ast_info.calls.is_empty() {
-        toon_data.calls = Some(ast_info.calls);
-    }
-    if !ast_info.signatures.is_empty() {
-        toon_data.signatures = Some(ast_info.signatures);
-    }
-
-    if let Some(ref block) = comments.file_block {
-        toon_data.when_editing = block.when_editing.clone();
-        toon_data.do_not = block.do_not.clone();
-        toon_data.invariants = block.invariants.clone();
-        toon_data.error_handling = block.error_handling.clone();
-        toon_data.constraints = block.constraints.clone();
-        toon_data.gotchas = block.gotchas.clone();
-        toon_data.flows = block.flows.clone();
-        toon_data.testing = block.testing.clone();
-        toon_data.common_mistakes = block.common_mistakes.clone();
-        toon_data.change_impacts = block.change_impacts.clone();
-        toon_data.related = block.related.clone();
+    if !factory.is_supported(&source_path) {
+        anyhow::bail!("Could not find parser for source file");
     }
 
-    let content = format_toon(&toon_data);
+    let parsed = ParsedFile::parse(&source_path, root, factory)?;
+    let content = format.format(&parsed.toon_data(dep_graph));
 
     if let Some(parent) = toon_path.parent() {
         fs::create_dir_all(parent).context("Failed to create output directory")?;
@@ -338,9 +383,56 @@ fn fix_toon_file(toon_path: &Path, factory: &ParserFactory, root: &Path) -> Resu
     Ok(())
 }
 
+/// Names of the TOON fields that differ from regenerating the source in memory; empty for
+/// _dir.toon summaries and files validate_toon_file already rejects
+fn diff_toon_file(
+    toon_path: &Path,
+    factory: &ParserFactory,
+    format: ToonFormat,
+    root: &Path,
+    dep_graph: &DependencyGraph,
+) -> Result<Vec<String>> {
+    let Some(source_path) = try_toon_path_to_source_path(toon_path, root) else {
+        return Ok(Vec::new());
+    };
+    if toon_path.file_name().is_some_and(|name| name == DIR_TOON)
+        || !source_path.is_file()
+        || !factory.is_supported(&source_path)
+    {
+        return Ok(Vec::new());
+    }
+
+    let parsed = ParsedFile::parse(&source_path, root, factory)?;
+    let expected = format.format(&parsed.toon_data(dep_graph));
+    let actual = fs::read_to_string(toon_path).context("Failed to read TOON file")?;
+    Ok(changed_fields(&expected, &actual))
+}
+
+/// Dependency graph of the whole project, as generate builds it, for --fix and --diff
+fn project_graph(
+    args: &ValidateArgs,
+    root: &Path,
+    root_canon: &Path,
+    factory: &ParserFactory,
+    config: &Config,
+) -> DependencyGraph {
+    let generate_args = GenerateArgs {
+        common: args.common.clone(),
+        ..Default::default()
+    };
+    let paths = collect_files(&generate_args, root, root_canon, factory, config);
+    let cache = Cache::load(root, &config_hash(root));
+    let files: Vec<ParsedFile> = parse_files(&paths, root, factory, &cache)
+        .into_iter()
+        .filter_map(Result::ok)
+        .collect();
+    build_graph(&files, &ImportResolver::from_config(root, config))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cli::CommonOptions;
     use std::fs;
     use tempfile::TempDir;
 
@@ -400,8 +492,8 @@ mod tests {
             &temp_dir.path().join(".ai/missing.ts.toon"),
             &factory,
             &threshold_matcher,
+            ToonFormat::Toon,
             temp_dir.path(),
-            false,
         )
         .unwrap();
 
@@ -409,6 +501,32 @@ mod tests {
         assert!(result.errors[0].contains("no longer exists"));
     }
 
+    #[test]
+    fn test_validate_dir_summary() {
+        let (temp_dir, factory, threshold_matcher) = create_test_env();
+        fs::create_dir_all(temp_dir.path().join(".ai/src")).unwrap();
+        fs::create_dir_all(temp_dir.path().join(".ai/gone")).unwrap();
+        fs::create_dir(temp_dir.path().join("src")).unwrap();
+        let content = "purpose: src directory\ntokens: ~10\n";
+        fs::write(temp_dir.path().join(".ai/src/_dir.toon"), content).unwrap();
+        fs::write(temp_dir.path().join(".ai/gone/_dir.toon"), content).unwrap();
+
+        let validate = |path: &str| {
+            validate_toon_file(
+                &temp_dir.path().join(path),
+                &factory,
+                &threshold_matcher,
+                ToonFormat::Toon,
+                temp_dir.path(),
+            )
+            .unwrap()
+        };
+
+        assert!(validate(".ai/src/_dir.toon").is_valid());
+        let result = validate(".ai/gone/_dir.toon");
+        assert_eq!(result.errors, vec!["Directory no longer exists"]);
+    }
+
     #[test]
     fn test_validate_missing_purpose() {
         let (temp_dir, factory, threshold_matcher) = create_test_env();
@@ -424,8 +542,8 @@ mod tests {
             &temp_dir.path().join(".ai/test.ts.toon"),
             &factory,
             &threshold_matcher,
+            ToonFormat::Toon,
             temp_dir.path(),
-            false,
         )
         .unwrap();
 
@@ -458,8 +576,8 @@ mod tests {
             &temp_dir.path().join(".ai/test.ts.toon"),
             &factory,
             &threshold_matcher,
+            ToonFormat::Toon,
             temp_dir.path(),
-            false,
         )
         .unwrap();
 
@@ -492,8 +610,8 @@ mod tests {
             &temp_dir.path().join(".ai/test.ts.toon"),
             &factory,
             &threshold_matcher,
+            ToonFormat::Toon,
             temp_dir.path(),
-            false,
         )
         .unwrap();
 
@@ -518,8 +636,8 @@ mod tests {
             &temp_dir.path().join(".ai/test.ts.toon"),
             &factory,
             &threshold_matcher,
+            ToonFormat::Toon,
             temp_dir.path(),
-            false,
         )
         .unwrap();
 
@@ -547,8 +665,8 @@ mod tests {
             &temp_dir.path().join(".ai/test.ts.toon"),
             &factory,
             &threshold_matcher,
+            ToonFormat::Toon,
             temp_dir.path(),
-            false,
         )
         .unwrap();
 
@@ -575,8 +693,8 @@ mod tests {
             &temp_dir.path().join(".ai/test.ts.toon"),
             &factory,
             &threshold_matcher,
+            ToonFormat::Toon,
             temp_dir.path(),
-            false,
         )
         .unwrap();
 
@@ -606,8 +724,8 @@ gotchas: None
             &temp_dir.path().join(".ai/test.ts.toon"),
             &factory,
             &threshold_matcher,
+            ToonFormat::Toon,
             temp_dir.path(),
-            false,
         )
         .unwrap();
 
@@ -615,6 +733,33 @@ gotchas: None
         assert!(result.errors.is_empty());
     }
 
+    #[test]
+    fn test_validate_stale_fingerprint() {
+        let (temp_dir, factory, threshold_matcher) = create_test_env();
+        let root = temp_dir.path();
+        fs::write(root.join("test.ts"), "export const x = 1;").unwrap();
+        crate::commands::run_generate(&GenerateArgs::default(), root, false).unwrap();
+
+        let validate = || {
+            validate_toon_file(
+                &root.join(".ai/test.ts.toon"),
+                &factory,
+                &threshold_matcher,
+                ToonFormat::Toon,
+                root,
+            )
+            .unwrap()
+        };
+        assert!(validate().is_valid());
+
+        // Same exports, different source: only the fingerprint notices
+        fs::write(root.join("test.ts"), "export const x = 2;").unwrap();
+        assert_eq!(
+            validate().errors,
+            vec!["Stale: source changed since the TOON was generated"]
+        );
+    }
+
     // ==================== run_validate Tests ====================
 
     #[test]
@@ -677,6 +822,86 @@ invariants: Always export x
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_run_validate_diff_reports_changed_fields() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("main.ts"), "import { x } from './utils';\n").unwrap();
+        fs::write(root.join("utils.ts"), "export const x = 1;").unwrap();
+        crate::commands::run_generate(&GenerateArgs::default(), root, false).unwrap();
+
+        // A new importer leaves utils.ts untouched, so its TOON is only wrong in imported-by
+        fs::write(root.join("app.ts"), "import { x } from './utils';\n").unwrap();
+        assert!(run_validate(&ValidateArgs::default(), root, false).is_ok());
+
+        let factory = ParserFactory::new();
+        let root_canon = root.canonicalize().unwrap();
+        let args = ValidateArgs {
+            diff: true,
+            ..Default::default()
+        };
+        let graph = project_graph(&args, root, &root_canon, &factory, &Config::default());
+        let changed = diff_toon_file(
+            &root.join(".ai/utils.ts.toon"),
+            &factory,
+            ToonFormat::Toon,
+            root,
+            &graph,
+        );
+        assert_eq!(changed.unwrap(), vec!["imported-by"]);
+        assert!(run_validate(&args, root, false).is_err());
+
+        let args = ValidateArgs {
+            diff: true,
+            fix: true,
+            ..Default::default()
+        };
+        assert!(run_validate(&args, root, false).is_ok());
+        let utils = fs::read_to_string(root.join(".ai/utils.ts.toon")).unwrap();
+        assert!(utils.contains("imported-by[2]: app.ts,main.ts"));
+    }
+
+    #[test]
+    fn test_run_validate_strict_format() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("main.ts"), "import { x } from './utils';\n").unwrap();
+        fs::write(root.join("utils.ts"), "export const x = 1;").unwrap();
+        let common = CommonOptions {
+            format: ToonFormat::ToonStrict,
+            ..Default::default()
+        };
+        let generate_args = GenerateArgs {
+            common: common.clone(),
+            ..Default::default()
+        };
+        crate::commands::run_generate(&generate_args, root, false).unwrap();
+
+        let utils = fs::read_to_string(root.join(".ai/utils.ts.toon")).unwrap();
+        assert!(utils.contains("exports[1]{name,kind}:\n  x,const"));
+        assert!(utils.contains("imported_by[1]: main.ts"));
+
+        let args = ValidateArgs {
+            diff: true,
+            common,
+            ..Default::default()
+        };
+        assert!(run_validate(&args, root, false).is_ok());
+
+        // A strict TOON that does not decode is an error
+        let result = validate_toon_file(
+            &root.join(".ai/utils.ts.toon"),
+            &ParserFactory::new(),
+            &Config::default().threshold_matcher(),
+            ToonFormat::ToonStrict,
+            root,
+        )
+        .unwrap();
+        assert!(result.is_valid());
+        fs::write(root.join(".ai/utils.ts.toon"), "purpose: \"unterminated").unwrap();
+        assert!(run_validate(&args, root, false).is_err());
+    }
+
     #[test]
     fn test_run_validate_strict_mode_fails_on_warnings() {
         let temp_dir = TempDir::new().unwrap();
@@ -9,27 +9,31 @@
 //!
 //! invariants:
 //!     - Initial full generation must complete before watching starts
-//!     - Config file changes trigger full regeneration
+//!     - Config file changes (luny.toml or a resolver config such as tsconfig.json or go.mod)
+//!       trigger full regeneration and rebuild the parser factory and resolver, so later events
+//!       see new [[language]] tables and import aliases
+//!     - Regenerated TOONs use the same --format (and other common options) as the initial run
 //!     - Deleted source files result in deleted .toon files
 //!
 //! flows:
-//!     - Initial: Run full generate, build complete dependency graph
+//!     - Initial: Run full generate and keep the dependency graph it built
 //!     - Watch: Receive notify events, debounce, determine affected files
 //!     - Update: Regenerate only affected .toon files, update graph
 
-use crate::cli::{GenerateArgs, WatchArgs};
-use crate::commands::run_generate;
+use crate::cache::is_resolver_config;
+use crate::cli::{CommonOptions, GenerateArgs, WatchArgs};
+use crate::commands::run_generate_with_graph;
 use crate::config::Config;
-use crate::dependency::{
-    get_path_variants, normalize_separators, resolve_import_path, DependencyGraph,
-};
-use crate::exclusion::build_walker;
-use crate::formatter::format_toon;
+use crate::dependency::{normalize_separators, DependencyGraph};
+use crate::exclusion::is_default_excluded_dir;
+use crate::formatter::ToonFormat;
 use crate::parser::ParserFactory;
-use crate::types::{CalledByInfo, ToonData};
+use crate::pipeline::{toon_path, ParsedFile};
+use crate::resolver::ImportResolver;
 use anyhow::{Context, Result};
 use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
-use std::collections::{HashMap, HashSet};
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::mpsc;
@@ -44,8 +48,8 @@ enum ChangeKind {
 }
 
 pub fn run_watch(args: &WatchArgs, root: &Path, verbose: bool) -> Result<()> {
-    let factory = ParserFactory::new();
     let config = Config::load(root);
+    let mut factory = ParserFactory::from_config(&config);
 
     // Initial full generation
     println!("Running initial generation...");
@@ -56,11 +60,9 @@ pub fn run_watch(args: &WatchArgs, root: &Path, verbose: bool) -> Result<()> {
         common: args.common.clone(),
         ..Default::default()
     };
-    run_generate(&generate_args, root, verbose)?;
-
-    // Build initial dependency graph
-    println!("Building dependency graph...");
-    let mut dep_graph = build_full_dependency_graph(root, &args.paths, &factory, &config, verbose)?;
+    // The dependency graph comes from the same parse that generated the TOON files
+    let mut dep_graph = run_generate_with_graph(&generate_args, root, verbose)?;
+    let mut resolver = ImportResolver::from_config(root, &config);
 
     // Set up file watcher
     let (tx, rx) = mpsc::channel();
@@ -111,8 +113,9 @@ pub fn run_watch(args: &WatchArgs, root: &Path, verbose: bool) -> Result<()> {
                     process_pending_changes(
                         &mut pending,
                         &mut dep_graph,
-                        &factory,
-                        &config,
+                        &mut resolver,
+                        &mut factory,
+                        &args.common,
                         root,
                         verbose,
                     );
@@ -128,103 +131,6 @@ pub fn run_watch(args: &WatchArgs, root: &Path, verbose: bool) -> Result<()> {
     Ok(())
 }
 
-/// Build dependency graph for all source files
-fn build_full_dependency_graph(
-    root: &Path,
-    paths: &[PathBuf],
-    factory: &ParserFactory,
-    config: &Config,
-    _verbose: bool,
-) -> Result<DependencyGraph> {
-    let mut graph = DependencyGraph::new();
-    let files = collect_source_files(root, paths, factory, config);
-
-    for path in &files {
-        if let Ok(source) = fs::read_to_string(path) {
-            if let Some(parser) = factory.get_parser(path) {
-                if let Ok(ast_info) = parser.extract_ast_info(&source, path) {
-                    let rel_path = path
-                        .strip_prefix(root)
-                        .unwrap_or(path)
-                        .to_string_lossy()
-                        .to_string();
-                    let rel_path = normalize_separators(&rel_path);
-
-                    // Extract imports
-                    let imports: Vec<String> = ast_info
-                        .imports
-                        .iter()
-                        .map(|imp| {
-                            normalize_separators(&resolve_import_path(&imp.from, path, root))
-                        })
-                        .collect();
-
-                    // Extract calls
-                    let calls: Vec<(String, String)> = ast_info
-                        .calls
-                        .iter()
-                        .map(|call| {
-                            (
-                                normalize_separators(&resolve_import_path(
-                                    &call.target,
-                                    path,
-                                    root,
-                                )),
-                                call.method.clone(),
-                            )
-                        })
-                        .collect();
-
-                    graph.add_file(&rel_path, imports, calls);
-                }
-            }
-        }
-    }
-
-    Ok(graph)
-}
-
-/// Collect all source files to process
-fn collect_source_files(
-    root: &Path,
-    paths: &[PathBuf],
-    factory: &ParserFactory,
-    config: &Config,
-) -> Vec<PathBuf> {
-    let mut files = Vec::new();
-
-    let search_paths = if paths.is_empty() {
-        vec![root.to_path_buf()]
-    } else {
-        paths.iter().map(|p| root.join(p)).collect()
-    };
-
-    let exclusion_config = crate::exclusion::ExclusionConfig {
-        patterns: config.exclude.clone(),
-        respect_gitignore: true,
-    };
-
-    for search_path in search_paths {
-        if search_path.is_file() && factory.is_supported(&search_path) {
-            files.push(search_path);
-        } else if search_path.is_dir() {
-            let mut walker = build_walker(&search_path, &exclusion_config);
-            walker.follow_links(true);
-
-            for entry in walker.build().filter_map(|e| e.ok()) {
-                let entry_path = entry.path();
-                if entry_path.is_file() && factory.is_supported(entry_path) {
-                    files.push(entry_path.to_path_buf());
-                }
-            }
-        }
-    }
-
-    files.sort();
-    files.dedup();
-    files
-}
-
 /// Process a notify event and add to pending changes
 fn process_event(
     event: &Event,
@@ -240,18 +146,22 @@ fn process_event(
     };
 
     for path in &event.paths {
-        // Skip non-source files (but include luny.toml for config changes)
-        let is_config = path.file_name().map(|n| n == "luny.toml").unwrap_or(false);
-        if !is_config && !factory.is_supported(path) {
+        // Skip files in .ai, .git, target and other default-excluded directories before
+        // is_supported, which reads extensionless files for a #! line
+        let excluded = path
+            .strip_prefix(root)
+            .ok()
+            .and_then(Path::parent)
+            .is_some_and(|dir| {
+                dir.components()
+                    .any(|c| is_default_excluded_dir(&c.as_os_str().to_string_lossy()))
+            });
+        if excluded {
             continue;
         }
 
-        // Skip files in .ai directory
-        if path
-            .strip_prefix(root)
-            .map(|p| p.starts_with(".ai"))
-            .unwrap_or(false)
-        {
+        // Skip non-source files (but include config files, which trigger a full regeneration)
+        if !is_config_file(path) && !factory.is_supported(path) {
             continue;
         }
 
@@ -270,38 +180,47 @@ fn process_event(
     }
 }
 
+/// luny.toml or a file the import resolvers read (tsconfig.json, go.mod, ...)
+fn is_config_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name == "luny.toml" || is_resolver_config(name))
+}
+
 /// Process all pending file changes
 fn process_pending_changes(
     pending: &mut HashMap<PathBuf, ChangeKind>,
     dep_graph: &mut DependencyGraph,
-    factory: &ParserFactory,
-    config: &Config,
+    resolver: &mut ImportResolver,
+    factory: &mut ParserFactory,
+    common: &CommonOptions,
     root: &Path,
     verbose: bool,
 ) {
     // Check for config file change -> full regen
-    let config_path = root.join("luny.toml");
-    if pending.contains_key(&config_path) {
+    if pending.keys().any(|path| is_config_file(path)) {
         println!("Config changed, running full regeneration...");
         pending.clear();
         // Reload config and regenerate everything
         let new_config = Config::load(root);
-        if let Ok(new_graph) = build_full_dependency_graph(root, &[], factory, &new_config, verbose)
-        {
-            *dep_graph = new_graph;
-        }
+        *factory = ParserFactory::from_config(&new_config);
+        *resolver = ImportResolver::from_config(root, &new_config);
         let generate_args = GenerateArgs {
             force: true,
             clean: true,
+            common: common.clone(),
             ..Default::default()
         };
-        let _ = run_generate(&generate_args, root, verbose);
+        if let Ok(new_graph) = run_generate_with_graph(&generate_args, root, verbose) {
+            *dep_graph = new_graph;
+        }
         return;
     }
 
     // Calculate all affected files
     let mut to_regenerate: HashSet<PathBuf> = HashSet::new();
     let mut to_delete: HashSet<PathBuf> = HashSet::new();
+    let mut parsed: BTreeMap<PathBuf, ParsedFile> = BTreeMap::new();
 
     let changes: Vec<_> = pending.drain().collect();
     let timestamp = chrono_lite_timestamp();
@@ -320,7 +239,7 @@ fn process_pending_changes(
                 let affected = dep_graph.remove_file(&rel_path);
 
                 // Queue .toon file for deletion
-                let toon_path = get_toon_path(root, &path);
+                let toon_path = toon_path(root, &path);
                 to_delete.insert(toon_path);
 
                 // Queue affected files for regeneration
@@ -335,50 +254,22 @@ fn process_pending_changes(
             }
             ChangeKind::Create | ChangeKind::Modify => {
                 // Re-parse and update graph
-                if let Ok(source) = fs::read_to_string(&path) {
-                    if let Some(parser) = factory.get_parser(&path) {
-                        if let Ok(ast_info) = parser.extract_ast_info(&source, &path) {
-                            // Extract imports and calls
-                            let imports: Vec<String> = ast_info
-                                .imports
-                                .iter()
-                                .map(|imp| {
-                                    normalize_separators(&resolve_import_path(
-                                        &imp.from, &path, root,
-                                    ))
-                                })
-                                .collect();
-
-                            let calls: Vec<(String, String)> = ast_info
-                                .calls
-                                .iter()
-                                .map(|call| {
-                                    (
-                                        normalize_separators(&resolve_import_path(
-                                            &call.target,
-                                            &path,
-                                            root,
-                                        )),
-                                        call.method.clone(),
-                                    )
-                                })
-                                .collect();
-
-                            // Update graph and get affected files
-                            let affected = dep_graph.update_file(&rel_path, imports, calls);
-
-                            // Queue direct file
-                            to_regenerate.insert(path.clone());
-
-                            // Queue indirectly affected files
-                            for affected_path in affected.indirect {
-                                let source_path = root.join(&affected_path);
-                                if source_path.exists() {
-                                    to_regenerate.insert(source_path);
-                                }
-                            }
+                if let Ok(file) = ParsedFile::parse(&path, root, factory) {
+                    let (imports, calls) = file.edges(resolver);
+
+                    // Update graph and get affected files
+                    let affected = dep_graph.update_file(&rel_path, imports, calls);
+
+                    // Queue indirectly affected files
+                    for affected_path in affected.indirect {
+                        let source_path = root.join(&affected_path);
+                        if source_path.exists() {
+                            to_regenerate.insert(source_path);
                         }
                     }
+
+                    // Queue direct file, already parsed
+                    parsed.insert(path.clone(), file);
                 }
 
                 let action = if kind == ChangeKind::Create {
@@ -404,19 +295,33 @@ fn process_pending_changes(
 
     // Regenerate affected files
     let mut regenerated = 0;
-    let threshold_matcher = config.threshold_matcher();
+    // Read per batch so threshold edits in luny.toml apply without restarting
+    let threshold_matcher = Config::load(root).threshold_matcher();
+
+    // Changed files were parsed above; files affected through the graph are parsed here.
+    // Keyed by path so regeneration is reported in sorted order
+    for source_path in to_regenerate {
+        if let Entry::Vacant(entry) = parsed.entry(source_path) {
+            match ParsedFile::parse(entry.key(), root, factory) {
+                Ok(file) => {
+                    entry.insert(file);
+                }
+                Err(e) => eprintln!("Failed to regenerate {}: {}", entry.key().display(), e),
+            }
+        }
+    }
 
-    for source_path in &to_regenerate {
+    for file in parsed.values() {
         match regenerate_single_file(
-            source_path,
+            file,
             dep_graph,
-            factory,
             &threshold_matcher,
+            common.format,
             root,
             verbose,
         ) {
             Ok(_) => regenerated += 1,
-            Err(e) => eprintln!("Failed to regenerate {}: {}", source_path.display(), e),
+            Err(e) => eprintln!("Failed to regenerate {}: {}", file.path.display(), e),
         }
     }
 
@@ -430,140 +335,25 @@ fn process_pending_changes(
     }
 }
 
-/// Get the .toon file path for a source file
-fn get_toon_path(root: &Path, source_path: &Path) -> PathBuf {
-    let relative = source_path.strip_prefix(root).unwrap_or(source_path);
-    let toon_filename = format!(
-        "{}.toon",
-        relative.file_name().unwrap_or_default().to_string_lossy()
-    );
-    let toon_relative = relative.with_file_name(toon_filename);
-    root.join(".ai").join(toon_relative)
-}
-
 /// Regenerate a single .toon file
 fn regenerate_single_file(
-    path: &Path,
+    file: &ParsedFile,
     dep_graph: &DependencyGraph,
-    factory: &ParserFactory,
     threshold_matcher: &crate::config::ThresholdMatcher,
+    format: ToonFormat,
     root: &Path,
     verbose: bool,
 ) -> Result<()> {
-    let parser = factory
-        .get_parser(path)
-        .context("No parser available for file")?;
-
-    let source = fs::read_to_string(path).context("Failed to read source file")?;
-    let ast_info = parser.extract_ast_info(&source, path)?;
-
-    // Compute TOON output path
-    let relative = path
-        .strip_prefix(root)
-        .with_context(|| format!("File {} is outside root {}", path.display(), root.display()))?;
-    let relative_str = normalize_separators(&relative.to_string_lossy());
-
     // Check token limits
-    let thresholds = threshold_matcher.get_thresholds(relative);
-    if let Some(error_threshold) = thresholds.error {
-        if ast_info.tokens > error_threshold {
-            eprintln!(
-                "ERROR: {} has {} tokens (exceeds error threshold of {})",
-                path.display(),
-                ast_info.tokens,
-                error_threshold
-            );
-        }
-    }
-    if let Some(warn_threshold) = thresholds.warn {
-        if ast_info.tokens > warn_threshold && thresholds.error.is_none_or(|e| ast_info.tokens <= e)
-        {
-            eprintln!(
-                "WARNING: {} has {} tokens (exceeds warning threshold of {})",
-                path.display(),
-                ast_info.tokens,
-                warn_threshold
-            );
-        }
-    }
-
-    // Extract @dose comments
-    let comments = parser.extract_toon_comments(&source)?;
-
-    // Build purpose from comments or generate default
-    let purpose = comments
-        .file_block
-        .as_ref()
-        .and_then(|b| b.purpose.clone())
-        .unwrap_or_else(|| {
-            let filename = path.file_stem().unwrap_or_default().to_string_lossy();
-            format!("{} module", filename)
-        });
-
-    // Build ToonData
-    let mut toon_data = ToonData::new(purpose, ast_info.tokens, ast_info.exports);
-
-    // Add AST-extracted data
-    if !ast_info.imports.is_empty() {
-        toon_data.imports = Some(ast_info.imports);
-    }
-    if !ast_info.calls.is_empty() {
-        toon_data.calls = Some(ast_info.calls);
-    }
-    if !ast_info.signatures.is_empty() {
-        toon_data.signatures = Some(ast_info.signatures);
-    }
-
-    // Add reverse dependency data from graph
-    let path_variants = get_path_variants(&relative_str);
-
-    let mut imported_by: Vec<String> = Vec::new();
-    let mut called_by: Vec<CalledByInfo> = Vec::new();
-
-    for variant in &path_variants {
-        imported_by.extend(dep_graph.get_imported_by(variant));
-        called_by.extend(dep_graph.get_called_by(variant));
-    }
-
-    // Deduplicate
-    imported_by.sort();
-    imported_by.dedup();
-
-    if !imported_by.is_empty() {
-        toon_data.imported_by = Some(imported_by);
-    }
-    if !called_by.is_empty() {
-        called_by.sort_by(|a, b| {
-            (a.from.as_str(), a.function.as_str()).cmp(&(b.from.as_str(), b.function.as_str()))
-        });
-        called_by.dedup_by(|a, b| a.from == b.from && a.function == b.function);
-        toon_data.called_by = Some(called_by);
-    }
-
-    // Add comment-extracted data
-    if let Some(ref block) = comments.file_block {
-        toon_data.when_editing = block.when_editing.clone();
-        toon_data.do_not = block.do_not.clone();
-        toon_data.invariants = block.invariants.clone();
-        toon_data.error_handling = block.error_handling.clone();
-        toon_data.constraints = block.constraints.clone();
-        toon_data.gotchas = block.gotchas.clone();
-        toon_data.flows = block.flows.clone();
-        toon_data.testing = block.testing.clone();
-        toon_data.common_mistakes = block.common_mistakes.clone();
-        toon_data.change_impacts = block.change_impacts.clone();
-        toon_data.related = block.related.clone();
-    }
-
-    // Add function-level annotations
-    if !comments.function_annotations.is_empty() {
-        toon_data.function_annotations =
-            Some(comments.function_annotations.values().cloned().collect());
+    if let Some(warning) =
+        file.token_warning(threshold_matcher.get_thresholds(Path::new(&file.relative)))
+    {
+        eprintln!("{}", warning);
     }
 
     // Format and write TOON content
-    let content = format_toon(&toon_data);
-    let toon_path = get_toon_path(root, path);
+    let content = format.format(&file.toon_data(dep_graph));
+    let toon_path = toon_path(root, &file.path);
 
     if let Some(parent) = toon_path.parent() {
         fs::create_dir_all(parent).context("Failed to create output directory")?;
@@ -590,3 +380,89 @@ fn chrono_lite_timestamp() -> String {
     let secs = secs % 60;
     format!("{:02}:{:02}:{:02}", hours, mins, secs)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_config_change_rebuilds_factory() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("main.py"), "def main():\n    pass\n").unwrap();
+
+        let config = Config::load(root);
+        let mut factory = ParserFactory::from_config(&config);
+        let mut resolver = ImportResolver::from_config(root, &config);
+        let mut dep_graph = DependencyGraph::new();
+        assert!(!factory.is_supported(Path::new("BUILD.bzl")));
+
+        fs::write(
+            root.join("luny.toml"),
+            "[[language]]\ngrammar = \"python\"\nextensions = [\"bzl\"]\n",
+        )
+        .unwrap();
+        let mut pending = HashMap::from([(root.join("luny.toml"), ChangeKind::Modify)]);
+        process_pending_changes(
+            &mut pending,
+            &mut dep_graph,
+            &mut resolver,
+            &mut factory,
+            &CommonOptions::default(),
+            root,
+            false,
+        );
+
+        assert!(factory.is_supported(Path::new("BUILD.bzl")));
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn test_resolver_config_change_rebuilds_resolver() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src/lib")).unwrap();
+        fs::write(root.join("src/lib/util.ts"), "export function util() {}\n").unwrap();
+        fs::write(
+            root.join("src/app.ts"),
+            "import { util } from '@lib/util';\nutil();\n",
+        )
+        .unwrap();
+
+        let config = Config::load(root);
+        let mut factory = ParserFactory::from_config(&config);
+        let mut resolver = ImportResolver::from_config(root, &config);
+        let mut dep_graph = DependencyGraph::new();
+        // The resolver caches the (missing) tsconfig on first use
+        let app = ParsedFile::parse(&root.join("src/app.ts"), root, &factory).unwrap();
+        assert_eq!(app.edges(&resolver).0, vec!["@lib/util".to_string()]);
+
+        // A tsconfig.json created after startup adds the @lib alias
+        let tsconfig = root.join("tsconfig.json");
+        fs::write(
+            &tsconfig,
+            r#"{"compilerOptions": {"baseUrl": ".", "paths": {"@lib/*": ["src/lib/*"]}}}"#,
+        )
+        .unwrap();
+        let event = Event::new(EventKind::Create(notify::event::CreateKind::File))
+            .add_path(tsconfig.clone());
+        let mut pending = HashMap::new();
+        process_event(&event, &mut pending, root, &factory);
+        assert_eq!(pending.get(&tsconfig), Some(&ChangeKind::Create));
+
+        process_pending_changes(
+            &mut pending,
+            &mut dep_graph,
+            &mut resolver,
+            &mut factory,
+            &CommonOptions::default(),
+            root,
+            false,
+        );
+
+        assert!(pending.is_empty());
+        let (imports, _) = app.edges(&resolver);
+        assert_eq!(imports, vec!["src/lib/util.ts".to_string()]);
+    }
+}
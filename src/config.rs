@@ -1,6 +1,7 @@
 //! @dose
 //! purpose: Configuration file parsing for luny.toml. Handles exclusion patterns,
-//!     default token thresholds, and per-pattern threshold overrides.
+//!     default token thresholds, per-pattern threshold overrides, config-defined
+//!     language parsers, and Python import source roots.
 //!
 //! when-editing:
 //!     - !Config is loaded once at startup and passed through the call chain
@@ -14,6 +15,7 @@
 //! gotchas:
 //!     - Patterns are matched against paths relative to project root
 //!     - First matching override wins (order matters in TOML array)
+//!     - A [[language]] without queries maps its extensions onto a built-in parser
 
 use globset::{Glob, GlobSet, GlobSetBuilder};
 use serde::Deserialize;
@@ -32,6 +34,94 @@ pub struct Config {
 
     /// Token threshold configuration
     pub tokens: TokenConfig,
+
+    /// Config-defined parsers and extension mappings
+    #[serde(rename = "language")]
+    pub languages: Vec<LanguageConfig>,
+
+    /// Python import resolution settings
+    pub python: PythonConfig,
+}
+
+/// The [python] table
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct PythonConfig {
+    /// Directories (relative to root) searched for absolute imports before detected roots
+    pub source_roots: Vec<String>,
+}
+
+/// A [[language]] table: a tree-sitter query parser, or extra extensions for a built-in one
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct LanguageConfig {
+    /// Language name reported by the parser (defaults to the grammar name)
+    pub name: Option<String>,
+
+    /// Compiled-in tree-sitter grammar (e.g., "python", "javascript", "tsx")
+    pub grammar: String,
+
+    /// File extensions without the leading dot
+    pub extensions: Vec<String>,
+
+    /// Comment delimiters used for @dose blocks
+    pub comments: CommentSyntax,
+
+    /// Tree-sitter queries; when all are empty the built-in parser is reused
+    pub queries: QueryConfig,
+}
+
+/// Comment delimiters for a config-defined language.
+/// An omitted table means C-style comments; an omitted key in a given table means none.
+#[derive(Debug, Deserialize, Clone)]
+pub struct CommentSyntax {
+    /// Line comment prefix (e.g., "//", "#")
+    #[serde(default)]
+    pub line: Option<String>,
+
+    /// Block comment open and close (e.g., ["/*", "*/"])
+    #[serde(default)]
+    pub block: Option<(String, String)>,
+}
+
+impl Default for CommentSyntax {
+    fn default() -> Self {
+        Self {
+            line: Some("//".to_string()),
+            block: Some(("/*".to_string(), "*/".to_string())),
+        }
+    }
+}
+
+/// Tree-sitter query strings for a config-defined language
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct QueryConfig {
+    /// Captures @name; the capture on the declaration names the export kind
+    pub exports: Option<String>,
+
+    /// Captures @from and optionally @item
+    pub imports: Option<String>,
+
+    /// Captures @method and optionally @target
+    pub calls: Option<String>,
+
+    /// Captures @name and optionally @body; the declaration capture names the kind
+    pub signatures: Option<String>,
+
+    /// Every capture is a string literal kept verbatim by minification
+    pub strings: Option<String>,
+}
+
+impl QueryConfig {
+    /// True when no query is set
+    pub fn is_empty(&self) -> bool {
+        self.exports.is_none()
+            && self.imports.is_none()
+            && self.calls.is_none()
+            && self.signatures.is_none()
+            && self.strings.is_none()
+    }
 }
 
 /// Token threshold configuration
@@ -193,6 +283,7 @@ mod tests {
         assert_eq!(config.tokens.warn, 500);
         assert_eq!(config.tokens.error, 1000);
         assert!(config.tokens.overrides.is_empty());
+        assert!(config.languages.is_empty());
     }
 
     #[test]
@@ -251,6 +342,48 @@ error = false
         assert_eq!(config.tokens.overrides[1].error, None);
     }
 
+    #[test]
+    fn test_load_config_with_languages() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_content = r##"
+[[language]]
+grammar = "javascript"
+extensions = ["mjs", "cjs"]
+
+[[language]]
+name = "starlark"
+grammar = "python"
+extensions = ["bzl"]
+comments = { line = "#" }
+
+[language.queries]
+exports = "(function_definition name: (identifier) @name) @fn"
+"##;
+        fs::write(temp_dir.path().join("luny.toml"), config_content).unwrap();
+
+        let config = Config::load(temp_dir.path());
+        assert_eq!(config.languages.len(), 2);
+        assert_eq!(config.languages[0].extensions, vec!["mjs", "cjs"]);
+        assert!(config.languages[0].queries.is_empty());
+        assert_eq!(config.languages[1].name.as_deref(), Some("starlark"));
+        assert_eq!(config.languages[1].comments.line.as_deref(), Some("#"));
+        assert_eq!(config.languages[1].comments.block, None);
+        assert!(config.languages[1].queries.exports.is_some());
+    }
+
+    #[test]
+    fn test_load_config_with_python_source_roots() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("luny.toml"),
+            "[python]\nsource-roots = [\"lib\", \"services/api\"]\n",
+        )
+        .unwrap();
+
+        let config = Config::load(temp_dir.path());
+        assert_eq!(config.python.source_roots, vec!["lib", "services/api"]);
+    }
+
     #[test]
     fn test_threshold_matcher_defaults() {
         let config = TokenConfig::default();
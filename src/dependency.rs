@@ -1,9 +1,9 @@
 //! @dose
 //! purpose: Shared dependency graph module for tracking import/call relationships
-//!     between source files. Used by both generate and watch commands.
+//!     between source files. Used by generate, watch and validate through pipeline.
 //!
 //! when-editing:
-//!     - !Both generate.rs and watch.rs depend on this module
+//!     - !generate, watch and validate all depend on this module (via pipeline.rs)
 //!     - !Path normalization must use forward slashes for cross-platform consistency
 //!     - The graph maintains both forward and reverse lookups for efficient updates
 //!
@@ -12,7 +12,8 @@
 //!     - All paths are normalized with forward slashes
 //!
 //! do-not:
-//!     - Never use filesystem IO for path resolution (use lexical normalization only)
+//!     - Never use filesystem IO in resolve_import_path (lexical normalization only); probing
+//!       for real files belongs to resolver::ImportResolver, which falls back to it
 
 use crate::types::CalledByInfo;
 use std::collections::{HashMap, HashSet};
@@ -67,6 +68,26 @@ impl DependencyGraph {
         self.called_by.get(file_path).cloned().unwrap_or_default()
     }
 
+    /// Files that import the given file and files/functions that call it, matched under
+    /// every form an unresolved import might take (see get_path_variants), sorted and
+    /// de-duplicated
+    pub fn get_dependents(&self, file_path: &str) -> (Vec<String>, Vec<CalledByInfo>) {
+        let mut imported_by: Vec<String> = Vec::new();
+        let mut called_by: Vec<CalledByInfo> = Vec::new();
+        for variant in get_path_variants(file_path) {
+            imported_by.extend(self.get_imported_by(&variant));
+            called_by.extend(self.get_called_by(&variant));
+        }
+
+        imported_by.sort();
+        imported_by.dedup();
+        called_by.sort_by(|a, b| {
+            (a.from.as_str(), a.function.as_str()).cmp(&(b.from.as_str(), b.function.as_str()))
+        });
+        called_by.dedup_by(|a, b| a.from == b.from && a.function == b.function);
+        (imported_by, called_by)
+    }
+
     /// Add a file to the dependency graph with its import and call relationships.
     /// This populates both forward and reverse maps.
     pub fn add_file(
@@ -108,6 +129,20 @@ impl DependencyGraph {
     /// Remove a file from the dependency graph. Returns the set of affected files
     /// whose .toon files need regeneration (their imported_by/called_by changed).
     pub fn remove_file(&mut self, file_path: &str) -> AffectedFiles {
+        let affected = self.remove_edges(file_path);
+
+        // Remove this file from imported_by (for files that import it)
+        self.imported_by.remove(file_path);
+
+        // Remove this file from called_by (for files that call it)
+        self.called_by.remove(file_path);
+
+        affected
+    }
+
+    /// Remove the relationships a file declares (its imports and calls), keeping the
+    /// entries of files that import or call it
+    fn remove_edges(&mut self, file_path: &str) -> AffectedFiles {
         let mut affected = AffectedFiles::new();
 
         // Get files this file imported (they lose an entry in their imported_by)
@@ -130,12 +165,6 @@ impl DependencyGraph {
             }
         }
 
-        // Remove this file from imported_by (for files that import it)
-        self.imported_by.remove(file_path);
-
-        // Remove this file from called_by (for files that call it)
-        self.called_by.remove(file_path);
-
         affected
     }
 
@@ -147,8 +176,9 @@ impl DependencyGraph {
         file_imports: Vec<String>,
         file_calls: Vec<(String, String)>,
     ) -> AffectedFiles {
-        // Remove old relationships (this adds old import/call targets to indirect)
-        let mut affected = self.remove_file(file_path);
+        // Remove old relationships (this adds old import/call targets to indirect); who
+        // imports or calls this file is unchanged by its own edit
+        let mut affected = self.remove_edges(file_path);
 
         // Add new relationships
         self.add_file(file_path, file_imports.clone(), file_calls.clone());
@@ -332,6 +362,18 @@ mod tests {
         assert!(affected.indirect.contains("api.ts")); // now imported
     }
 
+    #[test]
+    fn test_update_file_keeps_importers() {
+        let mut graph = DependencyGraph::new();
+        graph.add_file("main.ts", vec!["utils.ts".to_string()], vec![]);
+        graph.add_file("utils.ts", vec!["api.ts".to_string()], vec![]);
+
+        graph.update_file("utils.ts", vec![], vec![]);
+
+        assert_eq!(graph.get_imported_by("utils.ts"), vec!["main.ts"]);
+        assert!(graph.get_imported_by("api.ts").is_empty());
+    }
+
     #[test]
     fn test_get_affected_files() {
         let mut graph = DependencyGraph::new();
@@ -345,6 +387,31 @@ mod tests {
         assert!(affected.indirect.contains("app.ts"));
     }
 
+    #[test]
+    fn test_get_dependents_merges_variants() {
+        let mut graph = DependencyGraph::new();
+        graph.add_file(
+            "main.ts",
+            vec!["src/utils".to_string()],
+            vec![("src/utils".to_string(), "slug".to_string())],
+        );
+        graph.add_file(
+            "app.ts",
+            vec!["src/utils.ts".to_string()],
+            vec![("src/utils.ts".to_string(), "slug".to_string())],
+        );
+        graph.add_file(
+            "main.ts",
+            vec![],
+            vec![("src/utils.ts".to_string(), "slug".to_string())],
+        );
+
+        let (imported_by, called_by) = graph.get_dependents("src/utils.ts");
+        assert_eq!(imported_by, vec!["app.ts", "main.ts"]);
+        let callers: Vec<_> = called_by.iter().map(|c| c.from.as_str()).collect();
+        assert_eq!(callers, vec!["app.ts", "main.ts"]);
+    }
+
     #[test]
     fn test_normalize_separators() {
         assert_eq!(
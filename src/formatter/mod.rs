@@ -1,5 +1,38 @@
 mod compress;
+mod strict;
 mod toon;
 
 pub use compress::*;
+pub use strict::*;
 pub use toon::*;
+
+use crate::types::ToonData;
+use clap::ValueEnum;
+
+/// How TOON files are written and read back, selected with --format
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ToonFormat {
+    /// luny's compact TOON: U-curve field order, compressed and truncated text
+    #[default]
+    Toon,
+    /// Spec-conformant TOON: tabular arrays, quoting and escaping, exact round trips
+    ToonStrict,
+}
+
+impl ToonFormat {
+    /// Render ToonData in this format
+    pub fn format(self, data: &ToonData) -> String {
+        match self {
+            Self::Toon => format_toon(data),
+            Self::ToonStrict => format_toon_strict(data),
+        }
+    }
+
+    /// Read TOON text written in this format; only strict parsing can fail
+    pub fn parse(self, content: &str) -> Result<ToonData, DecodeError> {
+        match self {
+            Self::Toon => Ok(parse_toon(content)),
+            Self::ToonStrict => parse_toon_strict(content),
+        }
+    }
+}
@@ -0,0 +1,693 @@
+//! @dose
+//! purpose: Spec-conformant Token-Oriented Object Notation encoder and decoder, used by
+//!     --format toon-strict. ToonData goes through serde_json::Value, so keys are the
+//!     ToonData field names and any TOON library can read the output without luny-specific
+//!     rules.
+//!
+//! when-editing:
+//!     - !encode and decode must stay inverse: decode(encode(v)) == v for every Value luny emits
+//!     - Quoting rules live in needs_quotes; the decoder relies on them to tell strings from
+//!       numbers, booleans, null and keys
+//!
+//! invariants:
+//!     - Indentation is two spaces per level; the delimiter is always a comma
+//!     - Arrays of objects with identical keys and only primitive values are tabular
+//!       (key[N]{a,b}: with one indented row each); other non-primitive arrays use - items
+//!     - Array lengths in headers are checked on decode
+//!     - Text is written as is: no compression or truncation, unlike format_toon
+//!
+//! do-not:
+//!     - Never emit an unquoted string that would read back as another type or split a row
+//!
+//! gotchas:
+//!     - Key order follows ToonData's field order (serde_json preserve_order), not format_toon's
+//!       U-curve
+//!     - Output has no trailing newline, as the spec requires
+//!
+//! flows:
+//!     - format_toon_strict: ToonData -> serde_json::Value -> encode
+//!     - parse_toon_strict: decode -> serde_json::Value -> ToonData
+
+use crate::types::ToonData;
+use serde_json::{Map, Number, Value};
+use thiserror::Error;
+
+const INDENT: &str = "  ";
+
+#[derive(Error, Debug)]
+pub enum DecodeError {
+    #[error("line {line}: {message}")]
+    Syntax { line: usize, message: String },
+    #[error("TOON does not describe a DOSE file: {0}")]
+    Shape(#[from] serde_json::Error),
+}
+
+/// Format ToonData as strict TOON
+pub fn format_toon_strict(data: &ToonData) -> String {
+    // ToonData has only string keys and finite numbers, so this cannot fail
+    let value = serde_json::to_value(data).unwrap_or_default();
+    encode(&value)
+}
+
+/// Parse strict TOON into ToonData
+pub fn parse_toon_strict(content: &str) -> Result<ToonData, DecodeError> {
+    Ok(serde_json::from_value(decode(content)?)?)
+}
+
+// ==================== Encoding ====================
+
+/// Encode a value as a TOON document. The root is expected to be an object.
+pub fn encode(value: &Value) -> String {
+    let mut lines = Vec::new();
+    match value {
+        Value::Object(map) => encode_fields(map, 0, &mut lines),
+        other => lines.push(encode_primitive(other)),
+    }
+    lines.join("\n")
+}
+
+fn encode_fields(map: &Map<String, Value>, depth: usize, lines: &mut Vec<String>) {
+    for (key, value) in map {
+        encode_field(&INDENT.repeat(depth), key, value, depth, lines);
+    }
+}
+
+/// Encode one key: value at depth; prefix is what goes before the key (indent, or "- ")
+fn encode_field(prefix: &str, key: &str, value: &Value, depth: usize, lines: &mut Vec<String>) {
+    let key = encode_key(key);
+    match value {
+        Value::Object(map) => {
+            lines.push(format!("{}{}:", prefix, key));
+            encode_fields(map, depth + 1, lines);
+        }
+        Value::Array(items) => encode_array(prefix, &key, items, depth, lines),
+        primitive => lines.push(format!(
+            "{}{}: {}",
+            prefix,
+            key,
+            encode_primitive(primitive)
+        )),
+    }
+}
+
+fn encode_array(prefix: &str, key: &str, items: &[Value], depth: usize, lines: &mut Vec<String>) {
+    let len = items.len();
+    if items.iter().all(is_primitive) {
+        let values: Vec<String> = items.iter().map(encode_primitive).collect();
+        let sep = if values.is_empty() { "" } else { " " };
+        lines.push(format!(
+            "{}{}[{}]:{}{}",
+            prefix,
+            key,
+            len,
+            sep,
+            values.join(",")
+        ));
+    } else if let Some(fields) = tabular_fields(items) {
+        let header: Vec<String> = fields.iter().map(|f| encode_key(f)).collect();
+        lines.push(format!(
+            "{}{}[{}]{{{}}}:",
+            prefix,
+            key,
+            len,
+            header.join(",")
+        ));
+        let indent = INDENT.repeat(depth + 1);
+        for item in items {
+            let row: Vec<String> = fields.iter().map(|f| encode_primitive(&item[f])).collect();
+            lines.push(format!("{}{}", indent, row.join(",")));
+        }
+    } else {
+        lines.push(format!("{}{}[{}]:", prefix, key, len));
+        for item in items {
+            encode_list_item(item, depth + 1, lines);
+        }
+    }
+}
+
+/// Encode one "- " item of an expanded list at depth
+fn encode_list_item(item: &Value, depth: usize, lines: &mut Vec<String>) {
+    let hyphen = format!("{}- ", INDENT.repeat(depth));
+    match item {
+        Value::Object(map) if !map.is_empty() => {
+            // First field on the hyphen line, the rest one level below the hyphen
+            let mut fields = map.iter();
+            if let Some((key, value)) = fields.next() {
+                encode_field(&hyphen, key, value, depth + 1, lines);
+            }
+            for (key, value) in fields {
+                encode_field(&INDENT.repeat(depth + 1), key, value, depth + 1, lines);
+            }
+        }
+        Value::Object(_) => lines.push(hyphen.trim_end().to_string()),
+        Value::Array(items) => encode_array(&hyphen, "", items, depth, lines),
+        primitive => lines.push(format!("{}{}", hyphen, encode_primitive(primitive))),
+    }
+}
+
+/// Field names of a uniform array of flat objects, if it can be written as a table
+fn tabular_fields(items: &[Value]) -> Option<Vec<String>> {
+    let first = items.first()?.as_object()?;
+    if first.is_empty() {
+        return None;
+    }
+    let fields: Vec<String> = first.keys().cloned().collect();
+    let uniform = items.iter().all(|item| {
+        item.as_object().is_some_and(|map| {
+            map.len() == fields.len() && fields.iter().all(|f| map.get(f).is_some_and(is_primitive))
+        })
+    });
+    uniform.then_some(fields)
+}
+
+fn is_primitive(value: &Value) -> bool {
+    !matches!(value, Value::Array(_) | Value::Object(_))
+}
+
+fn encode_primitive(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) if needs_quotes(s) => quote(s),
+        Value::String(s) => s.clone(),
+        // Callers only pass primitives
+        Value::Array(_) | Value::Object(_) => "null".to_string(),
+    }
+}
+
+fn encode_key(key: &str) -> String {
+    let mut chars = key.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+    if valid {
+        key.to_string()
+    } else {
+        quote(key)
+    }
+}
+
+/// Whether a string value must be quoted to read back as the same string
+fn needs_quotes(s: &str) -> bool {
+    s.is_empty()
+        || s.trim() != s
+        || matches!(s, "true" | "false" | "null")
+        || looks_numeric(s)
+        || s.starts_with('-')
+        || s.chars()
+            .any(|c| matches!(c, ':' | '"' | '\\' | '[' | ']' | '{' | '}' | ',') || c.is_control())
+}
+
+/// Numbers, including forms like 05 or 1e6 that a decoder could read as numbers
+fn looks_numeric(s: &str) -> bool {
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    digits.starts_with(|c: char| c.is_ascii_digit())
+        && digits
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-'))
+}
+
+fn quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+// ==================== Decoding ====================
+
+/// A non-blank line: 1-based number, depth and the text after the indentation
+struct Line<'a> {
+    number: usize,
+    depth: usize,
+    text: &'a str,
+}
+
+/// Decode a TOON document whose root is an object
+pub fn decode(content: &str) -> Result<Value, DecodeError> {
+    let mut lines = Vec::new();
+    for (index, raw) in content.lines().enumerate() {
+        if raw.trim().is_empty() {
+            continue;
+        }
+        let text = raw.trim_start_matches(' ');
+        let spaces = raw.len() - text.len();
+        if spaces % INDENT.len() != 0 || text.starts_with('\t') {
+            return Err(syntax(
+                index + 1,
+                "indentation must be a multiple of two spaces",
+            ));
+        }
+        lines.push(Line {
+            number: index + 1,
+            depth: spaces / INDENT.len(),
+            text: text.trim_end(),
+        });
+    }
+
+    let mut decoder = Decoder { lines, pos: 0 };
+    let root = decoder.object(0)?;
+    match decoder.lines.get(decoder.pos) {
+        Some(line) => Err(syntax(line.number, "unexpected indentation")),
+        None => Ok(Value::Object(root)),
+    }
+}
+
+fn syntax(line: usize, message: impl Into<String>) -> DecodeError {
+    DecodeError::Syntax {
+        line,
+        message: message.into(),
+    }
+}
+
+struct Decoder<'a> {
+    lines: Vec<Line<'a>>,
+    pos: usize,
+}
+
+impl Decoder<'_> {
+    fn peek_depth(&self) -> Option<usize> {
+        self.lines.get(self.pos).map(|line| line.depth)
+    }
+
+    /// Fields at exactly depth, until a shallower line
+    fn object(&mut self, depth: usize) -> Result<Map<String, Value>, DecodeError> {
+        let mut map = Map::new();
+        while self.peek_depth() == Some(depth) {
+            let line = &self.lines[self.pos];
+            let (number, text) = (line.number, line.text);
+            if text.starts_with("- ") || text == "-" {
+                return Err(syntax(number, "list item outside an array"));
+            }
+            self.pos += 1;
+            let (key, value) = self.field(text, number, depth)?;
+            map.insert(key, value);
+        }
+        Ok(map)
+    }
+
+    /// Parse "key: value", "key:", "key[N]: ..." or "key[N]{a,b}:" whose nested lines are at
+    /// depth + 1; the line itself has already been consumed
+    fn field(
+        &mut self,
+        text: &str,
+        number: usize,
+        depth: usize,
+    ) -> Result<(String, Value), DecodeError> {
+        let (key, rest) = parse_key(text).ok_or_else(|| syntax(number, "expected a key"))?;
+        if rest.starts_with('[') {
+            let value = self.array(rest, number, depth)?;
+            return Ok((key, value));
+        }
+        let rest = rest
+            .strip_prefix(':')
+            .ok_or_else(|| syntax(number, "expected ':' after key"))?;
+        if rest.is_empty() {
+            // Nested object (possibly empty)
+            let map = match self.peek_depth() {
+                Some(d) if d == depth + 1 => self.object(depth + 1)?,
+                _ => Map::new(),
+            };
+            return Ok((key, Value::Object(map)));
+        }
+        let value = rest
+            .strip_prefix(' ')
+            .ok_or_else(|| syntax(number, "expected a space after ':'"))?;
+        Ok((key, parse_primitive(value, number)?))
+    }
+
+    /// Parse an array from its header ("[N]...") at depth
+    fn array(&mut self, header: &str, number: usize, depth: usize) -> Result<Value, DecodeError> {
+        let close = header
+            .find(']')
+            .ok_or_else(|| syntax(number, "unterminated array length"))?;
+        let len: usize = header[1..close]
+            .parse()
+            .map_err(|_| syntax(number, "invalid array length"))?;
+        let mut rest = &header[close + 1..];
+
+        let mut fields = None;
+        if rest.starts_with('{') {
+            let end = find_unquoted(rest, '}')
+                .ok_or_else(|| syntax(number, "unterminated field list"))?;
+            let names = split_row(&rest[1..end], number)?
+                .into_iter()
+                .map(|name| parse_key_token(&name, number))
+                .collect::<Result<Vec<_>, _>>()?;
+            fields = Some(names);
+            rest = &rest[end + 1..];
+        }
+        let rest = rest
+            .strip_prefix(':')
+            .ok_or_else(|| syntax(number, "expected ':' after array header"))?;
+
+        let items = if let Some(fields) = fields {
+            self.table(&fields, len, number, depth)?
+        } else if let Some(values) = rest.strip_prefix(' ') {
+            split_row(values, number)?
+                .iter()
+                .map(|value| parse_primitive(value, number))
+                .collect::<Result<Vec<_>, _>>()?
+        } else if !rest.is_empty() {
+            return Err(syntax(number, "expected a space after ':'"));
+        } else {
+            self.list(depth + 1)?
+        };
+
+        if items.len() != len {
+            return Err(syntax(
+                number,
+                format!("array declares {} items but has {}", len, items.len()),
+            ));
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn table(
+        &mut self,
+        fields: &[String],
+        len: usize,
+        number: usize,
+        depth: usize,
+    ) -> Result<Vec<Value>, DecodeError> {
+        let mut rows = Vec::with_capacity(len);
+        while self.peek_depth() == Some(depth + 1) {
+            let line = &self.lines[self.pos];
+            self.pos += 1;
+            let values = split_row(line.text, line.number)?;
+            if values.len() != fields.len() {
+                return Err(syntax(
+                    line.number,
+                    format!(
+                        "row has {} values for {} fields",
+                        values.len(),
+                        fields.len()
+                    ),
+                ));
+            }
+            let mut map = Map::new();
+            for (field, value) in fields.iter().zip(values) {
+                map.insert(field.clone(), parse_primitive(&value, line.number)?);
+            }
+            rows.push(Value::Object(map));
+        }
+        if rows.is_empty() && len > 0 {
+            return Err(syntax(number, "missing table rows"));
+        }
+        Ok(rows)
+    }
+
+    /// "- " items at depth
+    fn list(&mut self, depth: usize) -> Result<Vec<Value>, DecodeError> {
+        let mut items = Vec::new();
+        while self.peek_depth() == Some(depth) {
+            let line = &self.lines[self.pos];
+            let (number, text) = (line.number, line.text);
+            let Some(item) = text
+                .strip_prefix("- ")
+                .or(if text == "-" { Some("") } else { None })
+            else {
+                break;
+            };
+            self.pos += 1;
+
+            let value = if item.is_empty() {
+                Value::Object(Map::new())
+            } else if item.starts_with('[') {
+                self.array(item, number, depth)?
+            } else if is_field(item) {
+                // Object item: first field on the hyphen line, the rest one level deeper
+                let (key, value) = self.field(item, number, depth + 1)?;
+                let mut map = Map::new();
+                map.insert(key, value);
+                map.extend(self.object(depth + 1)?);
+                Value::Object(map)
+            } else {
+                parse_primitive(item, number)?
+            };
+            items.push(value);
+        }
+        Ok(items)
+    }
+}
+
+/// Whether a list item's text starts with a key (rather than being a primitive)
+fn is_field(text: &str) -> bool {
+    parse_key(text).is_some_and(|(_, rest)| rest.starts_with(':') || rest.starts_with('['))
+}
+
+/// Split a leading key (quoted or bare) from the rest of the line
+fn parse_key(text: &str) -> Option<(String, &str)> {
+    if text.starts_with('"') {
+        let end = closing_quote(text)?;
+        let key = unescape(&text[1..end])?;
+        return Some((key, &text[end + 1..]));
+    }
+    let end = text.find([':', '['])?;
+    let key = &text[..end];
+    (!key.is_empty() && key.trim() == key).then(|| (key.to_string(), &text[end..]))
+}
+
+fn parse_key_token(token: &str, number: usize) -> Result<String, DecodeError> {
+    match token.strip_prefix('"') {
+        Some(_) => closing_quote(token)
+            .filter(|&end| end == token.len() - 1)
+            .and_then(|end| unescape(&token[1..end]))
+            .ok_or_else(|| syntax(number, "invalid quoted field name")),
+        None => Ok(token.to_string()),
+    }
+}
+
+fn parse_primitive(token: &str, number: usize) -> Result<Value, DecodeError> {
+    if token.starts_with('"') {
+        return closing_quote(token)
+            .filter(|&end| end == token.len() - 1)
+            .and_then(|end| unescape(&token[1..end]))
+            .map(Value::String)
+            .ok_or_else(|| syntax(number, "invalid quoted string"));
+    }
+    Ok(match token {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        "null" => Value::Null,
+        _ if looks_numeric(token) => parse_number(token)
+            .map(Value::Number)
+            .ok_or_else(|| syntax(number, format!("invalid number {}", token)))?,
+        _ => Value::String(token.to_string()),
+    })
+}
+
+fn parse_number(token: &str) -> Option<Number> {
+    if let Ok(n) = token.parse::<u64>() {
+        return Some(n.into());
+    }
+    if let Ok(n) = token.parse::<i64>() {
+        return Some(n.into());
+    }
+    token.parse::<f64>().ok().and_then(Number::from_f64)
+}
+
+/// Split comma-separated values, leaving quoted commas alone
+fn split_row(text: &str, number: usize) -> Result<Vec<String>, DecodeError> {
+    let mut values = Vec::new();
+    let mut current = String::new();
+    let mut chars = text.chars();
+    let mut in_quotes = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                current.push(c);
+            }
+            '\\' if in_quotes => {
+                current.push(c);
+                current.extend(chars.next());
+            }
+            ',' if !in_quotes => values.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(syntax(number, "unterminated string"));
+    }
+    values.push(current);
+    Ok(values)
+}
+
+/// Byte index of the quote closing the string that starts at text[0]
+fn closing_quote(text: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (index, c) in text.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Byte index of the first c outside quotes
+fn find_unquoted(text: &str, target: char) -> Option<usize> {
+    let mut in_quotes = false;
+    let mut escaped = false;
+    for (index, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_quotes => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            c if c == target && !in_quotes => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn unescape(text: &str) -> Option<String> {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        result.push(match chars.next()? {
+            '"' => '"',
+            '\\' => '\\',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            _ => return None,
+        });
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{CallInfo, ExportInfo, FunctionAnnotation, ImportInfo, SignatureInfo};
+    use serde_json::json;
+
+    #[test]
+    fn test_encode_shapes() {
+        let value = json!({
+            "purpose": "Auth, sessions",
+            "tokens": 120,
+            "exports": [{"name": "run", "kind": "fn"}, {"name": "Cfg", "kind": "struct"}],
+            "related": ["a.ts", "b.ts"],
+            "empty": [],
+            "imports": [{"from": "react", "items": ["useState"]}],
+            "meta": {"strict": true, "note": null}
+        });
+        assert_eq!(
+            encode(&value),
+            "purpose: \"Auth, sessions\"\n\
+             tokens: 120\n\
+             exports[2]{name,kind}:\n  run,fn\n  Cfg,struct\n\
+             related[2]: a.ts,b.ts\n\
+             empty[0]:\n\
+             imports[1]:\n  - from: react\n    items[1]: useState\n\
+             meta:\n  strict: true\n  note: null"
+        );
+        assert_eq!(decode(&encode(&value)).unwrap(), value);
+    }
+
+    #[test]
+    fn test_quoting() {
+        for s in [
+            "",
+            " padded",
+            "true",
+            "null",
+            "42",
+            "-1",
+            "05",
+            "1e6",
+            "- item",
+            "a:b",
+            "a,b",
+            "say \"hi\"",
+            "back\\slash",
+            "line\nbreak",
+            "tab\there",
+            "[x]",
+            "{y}",
+        ] {
+            assert!(needs_quotes(s), "{:?} should be quoted", s);
+            let value = json!({ "s": s, "list": [s, "x"] });
+            assert_eq!(decode(&encode(&value)).unwrap(), value, "{:?}", s);
+        }
+        for s in ["plain text", "src/a.ts", "é", "v1.2-beta", "(once)"] {
+            assert!(!needs_quotes(s), "{:?} should not be quoted", s);
+        }
+        assert_eq!(encode_key("imported-by"), "\"imported-by\"");
+        assert_eq!(encode_key("imported_by"), "imported_by");
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert!(decode("a[2]: x").is_err());
+        assert!(decode("a[1]{x,y}:\n  1").is_err());
+        assert!(decode("a: \"open").is_err());
+        assert!(decode("a:\n   b: 1").is_err());
+        assert!(decode("- x").is_err());
+    }
+
+    #[test]
+    fn test_toon_data_round_trip() {
+        let mut data = ToonData::new(
+            "Parses \"quoted\", comma; colon: text".to_string(),
+            1234,
+            vec![ExportInfo {
+                name: "Foo::bar".to_string(),
+                kind: "fn".to_string(),
+            }],
+        );
+        data.imports = Some(vec![ImportInfo {
+            from: "./a,b".to_string(),
+            items: vec![],
+        }]);
+        data.calls = Some(vec![CallInfo {
+            target: "os".to_string(),
+            method: "getenv".to_string(),
+        }]);
+        data.signatures = Some(vec![SignatureInfo {
+            name: "run".to_string(),
+            kind: "fn".to_string(),
+            signature: "fn run(a: i32,\n  b: &str) -> bool".to_string(),
+            start_line: 3,
+            end_line: 9,
+        }]);
+        data.imported_by = Some((0..15).map(|i| format!("f{}.rs", i)).collect());
+        data.gotchas = Some(vec!["true".to_string(), "-".to_string(), "".to_string()]);
+        data.function_annotations = Some(vec![FunctionAnnotation {
+            name: "run".to_string(),
+            invariants: Some(vec!["never panics".to_string()]),
+            gotchas: None,
+            do_not: Some(vec!["a, b".to_string()]),
+            error_handling: None,
+            constraints: None,
+        }]);
+        data.fingerprint = Some("00ff00ff00ff00ff".to_string());
+
+        let encoded = format_toon_strict(&data);
+        assert!(encoded.starts_with("purpose: "));
+        assert!(encoded.contains("imported_by[15]: "));
+        assert!(encoded.contains("function_annotations[1]:\n  - name: run\n"));
+        assert_eq!(parse_toon_strict(&encoded).unwrap(), data);
+    }
+}
@@ -19,17 +19,22 @@
 //!
 //! gotchas:
 //!     - The parse_toon function is lenient and handles missing fields gracefully
+//!     - Round-trips are exact only for what format_toon keeps: text already compressed,
+//!       imported-by/called-by of at most 10, signatures under 150 chars on one line, calls
+//!       grouped by target without duplicates, and no empty lists (they are omitted)
 //!     - Signatures are truncated to 150 characters to prevent excessively long lines
 //!     - imported-by and called-by are truncated to show only first 10 entries
+//!     - fingerprint sits in the middle zone: it is for validate, not for the reader
 //!
 //! flows:
 //!     - format_toon: Build ToonData -> Apply compression -> Format each field -> Join lines
-//!     - parse_toon: Split by lines -> Match field patterns -> Parse field values -> Build ToonData
+//!     - parse_toon: Split by lines -> Match field patterns (indented lines extend signatures,
+//!       files or fn:name blocks) -> Parse field values -> Build ToonData
 
 use crate::formatter::compress::{compress, compress_item};
 use crate::types::{
-    CallInfo, CalledByInfo, ExportInfo, FunctionAnnotation, ImportInfo, SignatureInfo, ToonData,
-    WhenEditingItem,
+    CallInfo, CalledByInfo, ExportInfo, FileSummary, FunctionAnnotation, ImportInfo, SignatureInfo,
+    ToonData, WhenEditingItem,
 };
 
 /// Format ToonData into TOON DOSE file content.
@@ -48,6 +53,13 @@ pub fn format_toon(data: &ToonData) -> String {
         lines.push(format_exports(&data.exports));
     }
 
+    // Files - what each file in a directory summary is for
+    if let Some(ref files) = data.files {
+        if !files.is_empty() {
+            lines.extend(format_files(files));
+        }
+    }
+
     // Signatures - full type information for AI reasoning
     if let Some(ref signatures) = data.signatures {
         if !signatures.is_empty() {
@@ -167,6 +179,11 @@ pub fn format_toon(data: &ToonData) -> String {
         }
     }
 
+    // Source fingerprint, for staleness checks
+    if let Some(ref fingerprint) = data.fingerprint {
+        lines.push(format!("fingerprint: {}", fingerprint));
+    }
+
     // Function-level annotations
     if let Some(ref fn_annotations) = data.function_annotations {
         if !fn_annotations.is_empty() {
@@ -231,30 +248,27 @@ fn format_when_editing(items: &[WhenEditingItem]) -> String {
 }
 
 /// Format calls (outgoing dependencies) in compact single-line format.
+/// Targets and their methods keep the order they are first called in.
 fn format_calls(calls: &[CallInfo]) -> String {
-    use std::collections::HashMap;
-
-    // Group calls by target
-    let mut by_target: HashMap<&str, Vec<&str>> = HashMap::new();
+    // Group calls by target, de-duplicating methods
+    let mut by_target: Vec<(&str, Vec<&str>)> = Vec::new();
     for call in calls {
-        by_target
-            .entry(&call.target)
-            .or_default()
-            .push(&call.method);
+        match by_target
+            .iter_mut()
+            .find(|(target, _)| *target == call.target)
+        {
+            Some((_, methods)) => {
+                if !methods.contains(&call.method.as_str()) {
+                    methods.push(&call.method);
+                }
+            }
+            None => by_target.push((&call.target, vec![&call.method])),
+        }
     }
 
-    // Deduplicate methods
     let items: Vec<String> = by_target
         .iter()
-        .map(|(target, methods)| {
-            let unique: Vec<&str> = methods
-                .iter()
-                .copied()
-                .collect::<std::collections::HashSet<_>>()
-                .into_iter()
-                .collect();
-            format!("{},{}", target, unique.join("|"))
-        })
+        .map(|(target, methods)| format!("{},{}", target, methods.join("|")))
         .collect();
 
     format!(
@@ -310,6 +324,15 @@ fn format_signatures(signatures: &[SignatureInfo]) -> Vec<String> {
     lines
 }
 
+/// Format the files of a directory summary, one per line.
+fn format_files(files: &[FileSummary]) -> Vec<String> {
+    let mut lines = vec![format!("files[{}]:", files.len())];
+    for file in files {
+        lines.push(format!("  {}: {}", file.file, compress(&file.purpose)));
+    }
+    lines
+}
+
 /// Format function-level annotations.
 fn format_function_annotations(annotations: &[FunctionAnnotation]) -> Vec<String> {
     let mut lines = Vec::new();
@@ -361,10 +384,68 @@ fn format_function_annotations(annotations: &[FunctionAnnotation]) -> Vec<String
     lines
 }
 
+/// Names of the fields whose lines differ between two TOON texts, in the order they first
+/// appear (expected first). Indented lines belong to the field above them; each fn:name
+/// annotation is its own field.
+pub fn changed_fields(expected: &str, actual: &str) -> Vec<String> {
+    let expected = field_lines(expected);
+    let actual = field_lines(actual);
+    let mut changed: Vec<String> = Vec::new();
+    for (name, _) in expected.iter().chain(&actual) {
+        if field(&expected, name) != field(&actual, name) && !changed.contains(name) {
+            changed.push(name.clone());
+        }
+    }
+    changed
+}
+
+/// The lines of a field from field_lines output
+fn field<'a>(fields: &'a [(String, Vec<&'a str>)], name: &str) -> Option<&'a Vec<&'a str>> {
+    fields
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, lines)| lines)
+}
+
+/// Split TOON text into (field name, lines) in order
+fn field_lines(content: &str) -> Vec<(String, Vec<&str>)> {
+    let mut fields: Vec<(String, Vec<&str>)> = Vec::new();
+    for line in content.lines().filter(|l| !l.trim().is_empty()) {
+        if line.starts_with(char::is_whitespace) {
+            if let Some((_, lines)) = fields.last_mut() {
+                lines.push(line);
+                continue;
+            }
+        }
+        let name = match line.strip_prefix("fn:") {
+            Some(rest) => format!("fn:{}", rest.split(':').next().unwrap_or(rest)),
+            None => line
+                .split(['[', '{', ':'])
+                .next()
+                .unwrap_or(line)
+                .trim()
+                .to_string(),
+        };
+        fields.push((name, vec![line]));
+    }
+    fields
+}
+
+/// The indented block that lines below a header belong to
+enum Block {
+    None,
+    Signatures,
+    Files,
+    /// The last function annotation
+    Function,
+}
+
 /// Parse TOON content back into ToonData.
-/// Used by validation tool.
+/// Reads every field format_toon writes, including the indented signatures, files and
+/// fn:name blocks.
 pub fn parse_toon(content: &str) -> ToonData {
     let mut data = ToonData::new(String::new(), 0, Vec::new());
+    let mut block = Block::None;
 
     for line in content.lines() {
         let trimmed = line.trim();
@@ -372,6 +453,60 @@ pub fn parse_toon(content: &str) -> ToonData {
             continue;
         }
 
+        // Indented lines continue the block opened above them
+        if line.starts_with(char::is_whitespace) {
+            match block {
+                Block::Signatures => {
+                    if let Some(signature) = parse_signature(trimmed) {
+                        data.signatures.get_or_insert_with(Vec::new).push(signature);
+                    }
+                }
+                Block::Files => {
+                    if let Some((file, purpose)) = trimmed.split_once(':') {
+                        data.files.get_or_insert_with(Vec::new).push(FileSummary {
+                            file: file.trim().to_string(),
+                            purpose: purpose.trim().to_string(),
+                        });
+                    }
+                }
+                Block::Function => {
+                    let annotation = data
+                        .function_annotations
+                        .as_mut()
+                        .and_then(|annotations| annotations.last_mut());
+                    if let Some(annotation) = annotation {
+                        add_annotation_field(annotation, trimmed);
+                    }
+                }
+                Block::None => {}
+            }
+            continue;
+        }
+        block = Block::None;
+
+        // fn:name: field: value, or fn:name: alone to open an indented block
+        if let Some(rest) = trimmed.strip_prefix("fn:") {
+            if let Some((name, field)) = split_annotation(rest) {
+                let mut annotation = FunctionAnnotation {
+                    name: name.to_string(),
+                    invariants: None,
+                    gotchas: None,
+                    do_not: None,
+                    error_handling: None,
+                    constraints: None,
+                };
+                if field.trim().is_empty() {
+                    block = Block::Function;
+                } else {
+                    add_annotation_field(&mut annotation, field.trim());
+                }
+                data.function_annotations
+                    .get_or_insert_with(Vec::new)
+                    .push(annotation);
+            }
+            continue;
+        }
+
         // Parse simple key: value pairs
         if let Some((key, value)) = trimmed.split_once(':') {
             let key = key.trim();
@@ -382,22 +517,23 @@ pub fn parse_toon(content: &str) -> ToonData {
                 "tokens" => {
                     data.tokens = value.trim_start_matches('~').parse().unwrap_or(0);
                 }
-                "ignore" => {
-                    data.ignore = Some(
-                        value
-                            .split(',')
-                            .map(|s| s.trim().to_string())
-                            .filter(|s| !s.is_empty())
-                            .collect(),
-                    );
-                }
+                "ignore" => data.ignore = Some(parse_comma_list(value)),
+                "fingerprint" => data.fingerprint = Some(value.to_string()),
                 _ => {
-                    // Handle other fields with [N] suffix
+                    // Handle other fields with [N] or [N]{columns} suffix
                     let field_name = key.split('[').next().unwrap_or(key);
                     match field_name {
                         "exports" => {
                             data.exports = parse_exports(value);
                         }
+                        "signatures" => block = Block::Signatures,
+                        "files" => block = Block::Files,
+                        "imports" => data.imports = Some(parse_imports(value)),
+                        "calls" => data.calls = Some(parse_calls(value)),
+                        "imported-by" => {
+                            data.imported_by = Some(parse_comma_list(strip_more(value)));
+                        }
+                        "called-by" => data.called_by = Some(parse_called_by(value)),
                         "invariants" | "invariant" => {
                             data.invariants = Some(parse_semicolon_list(value));
                         }
@@ -428,24 +564,7 @@ pub fn parse_toon(content: &str) -> ToonData {
                         "change-impacts" => {
                             data.change_impacts = Some(parse_semicolon_list(value));
                         }
-                        "related" => {
-                            data.related = Some(
-                                value
-                                    .split(',')
-                                    .map(|s| s.trim().to_string())
-                                    .filter(|s| !s.is_empty())
-                                    .collect(),
-                            );
-                        }
-                        "imported-by" => {
-                            data.imported_by = Some(
-                                value
-                                    .split(',')
-                                    .map(|s| s.trim().to_string())
-                                    .filter(|s| !s.is_empty() && !s.starts_with('('))
-                                    .collect(),
-                            );
-                        }
+                        "related" => data.related = Some(parse_comma_list(value)),
                         _ => {}
                     }
                 }
@@ -461,8 +580,11 @@ fn parse_exports(value: &str) -> Vec<ExportInfo> {
 
     // Handle format: Name(kind), Name(kind)
     for item in value.split(", ") {
-        if let Some((name, rest)) = item.split_once('(') {
-            let kind = rest.trim_end_matches(')');
+        if let Some((name, kind)) = item
+            .trim()
+            .strip_suffix(')')
+            .and_then(|i| i.rsplit_once('('))
+        {
             exports.push(ExportInfo {
                 name: name.to_string(),
                 kind: kind.to_string(),
@@ -473,6 +595,125 @@ fn parse_exports(value: &str) -> Vec<ExportInfo> {
     exports
 }
 
+/// Parse one signatures line: name(kind)@start-end: signature. Names may contain ':'
+/// (Rust paths), so the separator is the first ':' that ends a valid head.
+fn parse_signature(line: &str) -> Option<SignatureInfo> {
+    line.match_indices(':').find_map(|(index, _)| {
+        let (export, lines) = line[..index].rsplit_once('@')?;
+        let (name, kind) = export.strip_suffix(')')?.rsplit_once('(')?;
+        let (start_line, end_line) = lines.split_once('-')?;
+        Some(SignatureInfo {
+            name: name.to_string(),
+            kind: kind.to_string(),
+            signature: line[index + 1..].trim().to_string(),
+            start_line: start_line.parse().ok()?,
+            end_line: end_line.parse().ok()?,
+        })
+    })
+}
+
+/// Fields a function annotation line can carry
+const ANNOTATION_FIELDS: [&str; 5] = [
+    "invariants",
+    "gotchas",
+    "do-not",
+    "error-handling",
+    "constraints",
+];
+
+/// Split "name: field: value" or "name:" after fn: at the ':' that ends the name, which
+/// may itself contain ':'
+fn split_annotation(rest: &str) -> Option<(&str, &str)> {
+    rest.match_indices(':').find_map(|(index, _)| {
+        let field = rest[index + 1..].trim();
+        let is_field = field.is_empty()
+            || ANNOTATION_FIELDS
+                .iter()
+                .any(|name| field.strip_prefix(name).is_some_and(|r| r.starts_with(':')));
+        is_field.then(|| (&rest[..index], field))
+    })
+}
+
+/// Parse imports: from,item|item; from,item
+fn parse_imports(value: &str) -> Vec<ImportInfo> {
+    parse_pairs(value)
+        .map(|(from, items)| ImportInfo {
+            from: from.to_string(),
+            items: items
+                .split('|')
+                .filter(|item| !item.is_empty())
+                .map(str::to_string)
+                .collect(),
+        })
+        .collect()
+}
+
+/// Parse calls: target,method|method; target,method
+fn parse_calls(value: &str) -> Vec<CallInfo> {
+    parse_pairs(value)
+        .flat_map(|(target, methods)| {
+            methods
+                .split('|')
+                .filter(|method| !method.is_empty())
+                .map(move |method| CallInfo {
+                    target: target.to_string(),
+                    method: method.to_string(),
+                })
+        })
+        .collect()
+}
+
+/// Parse called-by: from,function; from,function (+N more)
+fn parse_called_by(value: &str) -> Vec<CalledByInfo> {
+    parse_pairs(strip_more(value))
+        .map(|(from, function)| CalledByInfo {
+            from: from.to_string(),
+            function: function.to_string(),
+        })
+        .collect()
+}
+
+/// Split a; separated list of first,rest records
+fn parse_pairs(value: &str) -> impl Iterator<Item = (&str, &str)> {
+    value
+        .split(';')
+        .map(str::trim)
+        .filter_map(|record| record.split_once(','))
+}
+
+/// Drop the " (+N more)" suffix of a truncated list
+fn strip_more(value: &str) -> &str {
+    match value.rfind(" (+") {
+        Some(index) if value.ends_with(" more)") => &value[..index],
+        _ => value,
+    }
+}
+
+fn parse_comma_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Add one "field: value" line to a function annotation
+fn add_annotation_field(annotation: &mut FunctionAnnotation, line: &str) {
+    let Some((field, value)) = line.split_once(':') else {
+        return;
+    };
+    let list = match field.trim() {
+        "invariants" => &mut annotation.invariants,
+        "gotchas" => &mut annotation.gotchas,
+        "do-not" => &mut annotation.do_not,
+        "error-handling" => &mut annotation.error_handling,
+        "constraints" => &mut annotation.constraints,
+        _ => return,
+    };
+    list.get_or_insert_with(Vec::new)
+        .push(value.trim().to_string());
+}
+
 fn parse_semicolon_list(value: &str) -> Vec<String> {
     value
         .split(';')
@@ -503,7 +744,7 @@ mod tests {
 
     /// Comprehensive test covering ALL ToonData fields for formatting.
     /// This single test covers: purpose, tokens, exports, invariants, do_not, when_editing,
-    /// imports, calls, imported_by, called_by, signatures, gotchas, flows, related,
+    /// imports, calls, imported_by, called_by, signatures, files, gotchas, flows, related,
     /// error_handling, constraints, testing, common_mistakes, change_impacts, ignore,
     /// and function_annotations with multiple fields.
     #[test]
@@ -556,6 +797,10 @@ mod tests {
             start_line: 42,
             end_line: 50,
         }]);
+        data.files = Some(vec![FileSummary {
+            file: "provider.ts".to_string(),
+            purpose: "Auth context".to_string(),
+        }]);
         data.gotchas = Some(vec!["Watch for nulls".to_string()]);
         data.flows = Some(vec!["Init -> Process -> Return".to_string()]);
         data.related = Some(vec!["types.ts".to_string()]);
@@ -592,6 +837,7 @@ mod tests {
         assert!(output.contains("imported-by[1]:"));
         assert!(output.contains("called-by[1]:"));
         assert!(output.contains("signatures[1]:"));
+        assert!(output.contains("files[1]:\n  provider.ts: Auth context"));
         assert!(output.contains("gotchas:"));
         assert!(output.contains("flows:"));
         assert!(output.contains("related[1]:"));
@@ -646,6 +892,7 @@ when-editing: !Important; Normal
 related[2]: file1.ts,file2.ts
 imported-by[2]: main.ts,app.ts
 ignore: export-mismatch,token-count
+fingerprint: 0123456789abcdef
 gotchas: Watch out
 error-handling: Throws on invalid; Returns null on empty
 constraints: Max 100 items; Min 1 item
@@ -667,6 +914,7 @@ change-impacts: Breaks API
         assert_eq!(parsed.related.as_ref().unwrap().len(), 2);
         assert_eq!(parsed.imported_by.as_ref().unwrap().len(), 2);
         assert_eq!(parsed.ignore.as_ref().unwrap().len(), 2);
+        assert_eq!(parsed.fingerprint.as_deref(), Some("0123456789abcdef"));
         assert_eq!(parsed.gotchas.as_ref().unwrap().len(), 1);
         // Previously uncovered parse branches
         assert_eq!(parsed.error_handling.as_ref().unwrap().len(), 2);
@@ -698,9 +946,201 @@ change-impacts: Breaks API
         let formatted = format_toon(&data);
         let parsed = parse_toon(&formatted);
 
-        assert_eq!(parsed.purpose, data.purpose);
-        assert_eq!(parsed.tokens, data.tokens);
-        assert_eq!(parsed.exports.len(), data.exports.len());
+        assert_eq!(parsed, data);
+    }
+
+    /// Deterministic xorshift generator for the property-style round-trip test
+    struct Rng(u64);
+
+    impl Rng {
+        fn below(&mut self, n: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % n as u64) as usize
+        }
+
+        fn chance(&mut self) -> bool {
+            self.below(2) == 0
+        }
+
+        fn pick(&mut self, options: &[&str]) -> String {
+            options[self.below(options.len())].to_string()
+        }
+
+        /// 1..=max items
+        fn list<T>(&mut self, max: usize, mut item: impl FnMut(&mut Self) -> T) -> Vec<T> {
+            (0..=self.below(max)).map(|_| item(self)).collect()
+        }
+
+        fn maybe_list<T>(
+            &mut self,
+            max: usize,
+            item: impl FnMut(&mut Self) -> T,
+        ) -> Option<Vec<T>> {
+            self.chance().then(|| self.list(max, item))
+        }
+
+        /// Prose that compress leaves as it is
+        fn text(&mut self) -> String {
+            const WORDS: [&str; 10] = [
+                "cache", "tokens", "expire", "refresh", "(once)", "x:y", "a->b", "50%", "UTF-8",
+                "é",
+            ];
+            self.list(8, |rng| rng.pick(&WORDS)).join(" ")
+        }
+
+        fn path(&mut self) -> String {
+            self.pick(&["src/a.ts", "lib/b.rs", "c.py", "pkg/d.go", "@scope/e"])
+        }
+
+        fn name(&mut self) -> String {
+            self.pick(&["run", "Foo::bar", "useAuth", "Provider", "__init__", "x1"])
+        }
+    }
+
+    fn arbitrary_toon_data(rng: &mut Rng) -> ToonData {
+        let exports = rng.list(5, |rng| ExportInfo {
+            name: rng.name(),
+            kind: rng.pick(&["fn", "class", "const", "hook", "type"]),
+        });
+        let mut data = ToonData::new(rng.text(), rng.below(5000), exports);
+
+        data.when_editing = rng.maybe_list(4, |rng| WhenEditingItem {
+            text: rng.text(),
+            important: rng.chance(),
+        });
+        data.do_not = rng.maybe_list(3, Rng::text);
+        data.imports = rng.maybe_list(4, |rng| ImportInfo {
+            from: rng.path(),
+            items: (0..rng.below(3)).map(|_| rng.name()).collect(),
+        });
+        data.calls = rng.chance().then(|| {
+            // Grouped by target, no duplicates: the form format_toon writes
+            let mut calls = Vec::new();
+            for target in ["./api", "crate::db", "os"] {
+                for method in ["get", "put", "Client::new"] {
+                    if rng.chance() {
+                        calls.push(CallInfo {
+                            target: target.to_string(),
+                            method: method.to_string(),
+                        });
+                    }
+                }
+            }
+            calls
+        });
+        if data.calls.as_ref().is_some_and(Vec::is_empty) {
+            data.calls = None;
+        }
+        data.imported_by = rng.maybe_list(10, Rng::path);
+        data.called_by = rng.maybe_list(10, |rng| CalledByInfo {
+            from: rng.path(),
+            function: rng.name(),
+        });
+        data.signatures = rng.maybe_list(4, |rng| {
+            let start_line = rng.below(500) + 1;
+            SignatureInfo {
+                name: rng.name(),
+                kind: rng.pick(&["fn", "method", "struct"]),
+                signature: rng.pick(&[
+                    "(a: i32) -> Result<(), Error>",
+                    "fn(&self) -> Option<&str>",
+                    "def run(self, *args): ...",
+                    "",
+                ]),
+                start_line,
+                end_line: start_line + rng.below(40),
+            }
+        });
+        data.files = rng.maybe_list(3, |rng| FileSummary {
+            file: rng.pick(&["mod.rs", "index.ts", "_dir.py"]),
+            purpose: rng.text(),
+        });
+        data.invariants = rng.maybe_list(3, Rng::text);
+        data.error_handling = rng.maybe_list(3, Rng::text);
+        data.constraints = rng.maybe_list(3, Rng::text);
+        data.gotchas = rng.maybe_list(3, Rng::text);
+        data.flows = rng.maybe_list(3, Rng::text);
+        data.testing = rng.maybe_list(3, Rng::text);
+        data.common_mistakes = rng.maybe_list(3, Rng::text);
+        data.change_impacts = rng.maybe_list(3, Rng::text);
+        data.related = rng.maybe_list(3, Rng::path);
+        data.function_annotations = rng.maybe_list(3, |rng| {
+            let mut annotation = FunctionAnnotation {
+                name: rng.name(),
+                invariants: rng.maybe_list(2, Rng::text),
+                gotchas: rng.maybe_list(2, Rng::text),
+                do_not: rng.maybe_list(2, Rng::text),
+                error_handling: rng.maybe_list(2, Rng::text),
+                constraints: None,
+            };
+            // An annotation without fields is not written at all
+            if annotation.invariants.is_none() && annotation.gotchas.is_none() {
+                annotation.constraints = Some(vec![rng.text()]);
+            }
+            annotation
+        });
+        data.ignore = rng.maybe_list(2, |rng| rng.pick(&["export-mismatch", "token-count"]));
+        data.fingerprint = rng
+            .chance()
+            .then(|| format!("{:016x}", rng.below(usize::MAX)));
+        data
+    }
+
+    /// parse_toon(format_toon(d)) == d for every field, over many generated ToonData
+    #[test]
+    fn test_round_trip_property() {
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..500 {
+            let data = arbitrary_toon_data(&mut rng);
+            let formatted = format_toon(&data);
+            assert_eq!(
+                parse_toon(&formatted),
+                data,
+                "round trip of:\n{}",
+                formatted
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_truncated_lists() {
+        let parsed = parse_toon(
+            "purpose: P\ntokens: ~1\nimported-by[12]: a.ts,b.ts (+10 more)\ncalled-by[11]: a.ts,run (+10 more)\n",
+        );
+        assert_eq!(parsed.imported_by, Some(vec!["a.ts".into(), "b.ts".into()]));
+        assert_eq!(parsed.called_by.unwrap()[0].function, "run");
+    }
+
+    #[test]
+    fn test_format_calls_keeps_call_order() {
+        let call = |target: &str, method: &str| CallInfo {
+            target: target.to_string(),
+            method: method.to_string(),
+        };
+        let calls = [
+            call("./b", "y"),
+            call("./a", "x"),
+            call("./b", "z"),
+            call("./b", "y"),
+        ];
+        assert_eq!(
+            format_calls(&calls),
+            "calls[2]{target,methods}: ./b,y|z; ./a,x"
+        );
+    }
+
+    #[test]
+    fn test_changed_fields() {
+        let expected = "purpose: A\ntokens: ~10\nsignatures[1]:\n  f(fn)@1-2: () => void\nfn:f: gotchas: x\nimported-by[1]: b.ts\n";
+        let actual = "purpose: A\ntokens: ~12\nsignatures[1]:\n  f(fn)@1-3: () => void\nfn:f: gotchas: x\nrelated[1]: c.ts\n";
+
+        assert_eq!(
+            changed_fields(expected, actual),
+            vec!["tokens", "signatures", "imported-by", "related"]
+        );
+        assert!(changed_fields(expected, expected).is_empty());
     }
 
     /// Edge case: empty content
@@ -20,6 +20,7 @@
 //!     - The lib.rs is separate from main.rs - library consumers get lib, CLI gets main
 //!     - Some types like ToonCommentBlock are only used internally but exported for testing
 
+pub mod cache;
 pub mod cli;
 pub mod commands;
 pub mod config;
@@ -27,16 +28,22 @@ pub mod dependency;
 pub mod exclusion;
 pub mod formatter;
 pub mod parser;
+pub mod pipeline;
+pub mod resolver;
 pub mod types;
 
 // Re-export main types for convenience
+pub use cache::Cache;
 pub use cli::{Cli, Commands, GenerateArgs, StripArgs, ValidateArgs, WatchArgs};
 pub use config::Config;
 pub use dependency::{AffectedFiles, DependencyGraph};
 pub use exclusion::ExclusionConfig;
-pub use formatter::{format_toon, parse_toon};
+pub use formatter::{format_toon, format_toon_strict, parse_toon, parse_toon_strict, ToonFormat};
 pub use parser::{LanguageParser, ParseError, ParserFactory};
+pub use pipeline::ParsedFile;
+pub use resolver::ImportResolver;
 pub use types::{
-    ASTInfo, CallInfo, CalledByInfo, ExportInfo, ExtractedComments, FunctionAnnotation, ImportInfo,
-    SignatureInfo, ToonCommentBlock, ToonData, ValidationResult, WhenEditingItem,
+    ASTInfo, CallInfo, CalledByInfo, ExportInfo, ExtractedComments, FileSummary,
+    FunctionAnnotation, ImportInfo, SignatureInfo, ToonCommentBlock, ToonData, ValidationResult,
+    WhenEditingItem,
 };
@@ -0,0 +1,954 @@
+//! @dose
+//! purpose: This module parses C and C++ source and header files to extract functions,
+//!     classes, structs, enums, namespaces, typedefs, and macros. It uses tree-sitter,
+//!     picking the C grammar for .c files and the C++ grammar for everything else.
+//!
+//! when-editing:
+//!     - !Headers export every non-static declaration; implementation files only export
+//!       non-static function definitions
+//!     - !Quoted includes are emitted as ./relative paths so resolve_import_path maps them to files
+//!     - Declarations are walked through namespaces, extern "C" blocks, templates, and #if/#ifdef bodies
+//!
+//! invariants:
+//!     - static functions and anything in an anonymous namespace are never exported
+//!     - Out-of-line member definitions (Point<T>::length) are never exported; the class declares them
+//!     - A header's implementation files (same stem, .c/.cc/.cpp/.cxx) are offered through paired_files
+//!
+//! do-not:
+//!     - Never parse .c files with the C++ grammar (C code may use new, class, etc. as identifiers)
+//!     - Never treat <system> includes as local files
+//!
+//! gotchas:
+//!     - .h is ambiguous between C and C++ and is parsed with the C++ grammar
+//!     - Quoted includes resolve relative to the including file only, not -I search paths
+//!     - Without symbol tables, calls cannot be attributed to an included header, so none are emitted
+//!     - Class members are private until the first public: section; struct members are public
+//!
+//! flows:
+//!     - Parse: Create tree-sitter parser for the file's dialect, parse source
+//!     - Extract exports: Walk file/namespace scope for non-static declarations
+//!     - Extract imports: #include "x.h" -> ./x.h, #include <x> -> x
+//!     - Inline annotations: // @dose field: value binds to the next function or type
+//!     - Extract signatures: Functions with template params; classes summarize public members
+//!     - Pairing: Generate lists a header's implementation files under related
+
+use crate::parser::{nodes, toon_comment, LanguageParser, ParseError};
+use crate::types::{ASTInfo, ExportInfo, ExtractedComments, ImportInfo, SignatureInfo};
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tree_sitter::{Node, Parser};
+
+/// Header extensions; only headers export declarations without definitions
+const HEADER_EXTENSIONS: &[&str] = &["h", "hh", "hpp", "hxx"];
+
+/// Implementation extensions probed when pairing a header
+const IMPLEMENTATION_EXTENSIONS: &[&str] = &["c", "cc", "cpp", "cxx"];
+
+/// Parser for C and C++ files
+#[derive(Clone)]
+pub struct CppParser;
+
+impl CppParser {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// The C grammar for .c files, the C++ grammar otherwise (including when the path is unknown)
+    fn create_parser(&self, file_path: Option<&Path>) -> Result<Parser, ParseError> {
+        let is_c = file_path
+            .and_then(|p| p.extension())
+            .is_some_and(|ext| ext == "c");
+        let language = if is_c {
+            tree_sitter_c::LANGUAGE
+        } else {
+            tree_sitter_cpp::LANGUAGE
+        };
+
+        let mut parser = Parser::new();
+        parser
+            .set_language(&language.into())
+            .map_err(|e| ParseError::ParseError(e.to_string()))?;
+        Ok(parser)
+    }
+
+    fn extract_exports(&self, root: Node, source: &str, is_header: bool) -> Vec<ExportInfo> {
+        let mut exports = Vec::new();
+        self.for_each_item(root, None, &mut |node, _| {
+            if let Some((name, kind)) = self.exported_item(node, source, is_header) {
+                exports.push(ExportInfo {
+                    name,
+                    kind: kind.to_string(),
+                });
+            }
+        });
+        exports
+    }
+
+    fn extract_signatures(&self, root: Node, source: &str, is_header: bool) -> Vec<SignatureInfo> {
+        let mut signatures = Vec::new();
+        self.for_each_item(root, None, &mut |node, template| {
+            let Some((name, kind)) = self.exported_item(node, source, is_header) else {
+                return;
+            };
+            let signature = match kind {
+                "fn" => Some(self.function_signature(node, template, source)),
+                "class" | "struct" | "union" => self
+                    .record_specifier(node)
+                    .map(|spec| self.record_signature(spec, template, source)),
+                "enum" => self
+                    .record_specifier(node)
+                    .map(|spec| self.enum_signature(spec, source)),
+                "namespace" => Some(self.namespace_signature(node, source, is_header)),
+                "type" => node
+                    .child_by_field_name("type")
+                    .map(|t| format!("= {}", nodes::collapsed_text(t, source))),
+                "macro" => self.macro_signature(node, source),
+                _ => None,
+            };
+            let Some(signature) = signature else {
+                return;
+            };
+            // Templates start at the template keyword
+            let outer = template.and_then(|t| t.parent()).unwrap_or(node);
+            // #define nodes end after their newline, at column 0 of the next line
+            let end = outer.end_position();
+            let end_row = match end.column {
+                0 if end.row > outer.start_position().row => end.row - 1,
+                _ => end.row,
+            };
+            signatures.push(SignatureInfo {
+                name,
+                kind: kind.to_string(),
+                signature,
+                start_line: outer.start_position().row + 1,
+                end_line: end_row + 1,
+            });
+        });
+        signatures
+    }
+
+    /// Visit each declaration at file or namespace scope. Templates are unwrapped and
+    /// their parameter list passed along; extern "C" and #if bodies are transparent.
+    fn for_each_item<'a>(
+        &self,
+        node: Node<'a>,
+        template: Option<Node<'a>>,
+        f: &mut dyn FnMut(Node<'a>, Option<Node<'a>>),
+    ) {
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            match child.kind() {
+                "template_declaration" => {
+                    let params = child.child_by_field_name("parameters");
+                    self.for_each_item(child, params, f);
+                }
+                "namespace_definition" => {
+                    // Anonymous namespaces give their contents internal linkage
+                    if child.child_by_field_name("name").is_none() {
+                        continue;
+                    }
+                    f(child, None);
+                    if let Some(body) = child.child_by_field_name("body") {
+                        self.for_each_item(body, None, f);
+                    }
+                }
+                "linkage_specification" => {
+                    if let Some(body) = child.child_by_field_name("body") {
+                        if body.kind() == "declaration_list" {
+                            self.for_each_item(body, None, f);
+                        } else {
+                            f(body, None);
+                        }
+                    }
+                }
+                "preproc_ifdef" | "preproc_if" | "preproc_else" | "preproc_elif"
+                | "preproc_elifdef" => self.for_each_item(child, None, f),
+                _ => f(child, template),
+            }
+        }
+    }
+
+    /// Name and export kind for a declaration that is visible outside its file
+    fn exported_item(
+        &self,
+        node: Node,
+        source: &str,
+        is_header: bool,
+    ) -> Option<(String, &'static str)> {
+        if self.has_storage_class(node, source, "static") {
+            return None;
+        }
+
+        match node.kind() {
+            "function_definition" => {
+                let name = self.function_name(node, source)?;
+                Some((name, "fn"))
+            }
+            "declaration" if is_header => {
+                if let Some(name) = self.function_name(node, source) {
+                    return Some((name, "fn"));
+                }
+                let declarator = node.child_by_field_name("declarator")?;
+                Some((self.declarator_name(declarator, source)?, "var"))
+            }
+            "class_specifier" | "struct_specifier" | "union_specifier" | "enum_specifier"
+                if is_header && node.child_by_field_name("body").is_some() =>
+            {
+                let name = self.node_text(node.child_by_field_name("name")?, source);
+                let kind = match node.kind() {
+                    "class_specifier" => "class",
+                    "struct_specifier" => "struct",
+                    "union_specifier" => "union",
+                    _ => "enum",
+                };
+                Some((name, kind))
+            }
+            // typedef struct { ... } Coord; exports the struct under its typedef name
+            "type_definition" if is_header => {
+                let name = self.node_text(node.child_by_field_name("declarator")?, source);
+                let kind = match self.record_specifier(node).map(|spec| spec.kind()) {
+                    Some("class_specifier") => "class",
+                    Some("struct_specifier") => "struct",
+                    Some("union_specifier") => "union",
+                    Some("enum_specifier") => "enum",
+                    _ => "type",
+                };
+                Some((name, kind))
+            }
+            "alias_declaration" if is_header => Some((
+                self.node_text(node.child_by_field_name("name")?, source),
+                "type",
+            )),
+            "namespace_definition" if is_header => Some((
+                self.node_text(node.child_by_field_name("name")?, source),
+                "namespace",
+            )),
+            // Object-like macros need a value; bare #defines are include guards and flags
+            "preproc_def" if is_header && node.child_by_field_name("value").is_some() => Some((
+                self.node_text(node.child_by_field_name("name")?, source),
+                "macro",
+            )),
+            "preproc_function_def" if is_header => Some((
+                self.node_text(node.child_by_field_name("name")?, source),
+                "macro",
+            )),
+            _ => None,
+        }
+    }
+
+    fn has_storage_class(&self, node: Node, source: &str, class: &str) -> bool {
+        let mut cursor = node.walk();
+        let found = node.children(&mut cursor).any(|child| {
+            child.kind() == "storage_class_specifier" && self.node_text(child, source) == class
+        });
+        found
+    }
+
+    /// Name of the function a definition or prototype declares, unless it is an
+    /// out-of-line member definition such as Point<T>::length
+    fn function_name(&self, node: Node, source: &str) -> Option<String> {
+        let (function, _) = self.function_declarator(node, source)?;
+        let declarator = function.child_by_field_name("declarator")?;
+        match declarator.kind() {
+            "identifier" | "field_identifier" | "operator_name" | "destructor_name" => {
+                Some(self.node_text(declarator, source))
+            }
+            _ => None,
+        }
+    }
+
+    /// The function_declarator under a declaration, with the pointer/reference
+    /// suffix that belongs to the return type (`char *name()` -> "*")
+    fn function_declarator<'a>(&self, node: Node<'a>, source: &str) -> Option<(Node<'a>, String)> {
+        let mut suffix = String::new();
+        let mut current = node.child_by_field_name("declarator")?;
+        loop {
+            match current.kind() {
+                "function_declarator" => return Some((current, suffix)),
+                "pointer_declarator" => suffix.push('*'),
+                "reference_declarator" => {
+                    let text = self.node_text(current, source);
+                    suffix.push_str(if text.starts_with("&&") { "&&" } else { "&" });
+                }
+                _ => {}
+            }
+            current = match current.child_by_field_name("declarator") {
+                Some(next) => next,
+                // reference_declarator has no declarator field
+                None => current.named_child(current.named_child_count().checked_sub(1)?)?,
+            };
+        }
+    }
+
+    /// Innermost identifier of a declarator chain (`*count = 0` -> count)
+    fn declarator_name(&self, node: Node, source: &str) -> Option<String> {
+        match node.kind() {
+            "identifier" | "field_identifier" | "type_identifier" | "operator_name"
+            | "destructor_name" => Some(self.node_text(node, source)),
+            "qualified_identifier" => {
+                self.declarator_name(node.child_by_field_name("name")?, source)
+            }
+            _ => {
+                let inner = node
+                    .child_by_field_name("declarator")
+                    .or_else(|| node.named_child(0))?;
+                self.declarator_name(inner, source)
+            }
+        }
+    }
+
+    /// `<typename T>(T a, T b) -> T`, `(const Size& size) -> int`, `() -> T const`
+    fn function_signature(&self, node: Node, template: Option<Node>, source: &str) -> String {
+        let type_params = template
+            .map(|t| nodes::collapsed_text(t, source))
+            .unwrap_or_default();
+
+        let Some((function, suffix)) = self.function_declarator(node, source) else {
+            return type_params;
+        };
+        let params = function
+            .child_by_field_name("parameters")
+            .map(|p| nodes::collapsed_text(p, source))
+            .unwrap_or_else(|| "()".to_string());
+
+        // Constructors and destructors have no return type
+        let returns = node
+            .child_by_field_name("type")
+            .map(|t| {
+                let mut cursor = node.walk();
+                let qualifiers: String = node
+                    .children(&mut cursor)
+                    .filter(|c| c.kind() == "type_qualifier" && c.end_byte() <= t.start_byte())
+                    .map(|c| format!("{} ", self.node_text(c, source)))
+                    .collect();
+                format!(
+                    " -> {}{}{}",
+                    qualifiers,
+                    nodes::collapsed_text(t, source),
+                    suffix
+                )
+            })
+            .unwrap_or_default();
+
+        // Trailing method qualifiers: const, noexcept, override
+        let mut cursor = function.walk();
+        let trailing: String = function
+            .children(&mut cursor)
+            .filter(|c| {
+                matches!(
+                    c.kind(),
+                    "type_qualifier" | "noexcept" | "virtual_specifier"
+                )
+            })
+            .map(|c| format!(" {}", self.node_text(c, source)))
+            .collect();
+
+        format!("{}{}{}{}", type_params, params, returns, trailing)
+    }
+
+    /// The class/struct/union/enum specifier with a body, directly or behind a typedef
+    fn record_specifier<'a>(&self, node: Node<'a>) -> Option<Node<'a>> {
+        let spec = match node.kind() {
+            "class_specifier" | "struct_specifier" | "union_specifier" | "enum_specifier" => node,
+            _ => node.child_by_field_name("type")?,
+        };
+        let is_record = matches!(
+            spec.kind(),
+            "class_specifier" | "struct_specifier" | "union_specifier" | "enum_specifier"
+        );
+        (is_record && spec.child_by_field_name("body").is_some()).then_some(spec)
+    }
+
+    /// `<typename T> : public Base<T> { Point(T x, T y); length() -> T const; ... }`
+    fn record_signature(&self, spec: Node, template: Option<Node>, source: &str) -> String {
+        let type_params = template
+            .map(|t| nodes::collapsed_text(t, source))
+            .unwrap_or_default();
+
+        let mut cursor = spec.walk();
+        let bases = spec
+            .children(&mut cursor)
+            .find(|c| c.kind() == "base_class_clause")
+            .map(|b| format!(" {}", nodes::collapsed_text(b, source)))
+            .unwrap_or_default();
+
+        // Class members are private until a public: section; struct and union members are public
+        let mut public = spec.kind() != "class_specifier";
+        let mut members = Vec::new();
+        if let Some(body) = spec.child_by_field_name("body") {
+            let mut cursor = body.walk();
+            for child in body.children(&mut cursor) {
+                if child.kind() == "access_specifier" {
+                    public = self.node_text(child, source).trim() == "public";
+                    continue;
+                }
+                if !public {
+                    continue;
+                }
+                let (member, template) = if child.kind() == "template_declaration" {
+                    let params = child.child_by_field_name("parameters");
+                    match child.named_child(child.named_child_count().saturating_sub(1)) {
+                        Some(inner) => (inner, params),
+                        None => continue,
+                    }
+                } else {
+                    (child, None)
+                };
+                self.push_member(member, template, source, &mut members);
+                if members.len() >= 5 {
+                    members.truncate(5);
+                    members.push("...".to_string());
+                    break;
+                }
+            }
+        }
+
+        let body = if members.is_empty() {
+            String::new()
+        } else {
+            format!(" {{ {} }}", members.join("; "))
+        };
+        format!("{}{}{}", type_params, bases, body)
+            .trim()
+            .to_string()
+    }
+
+    fn push_member(
+        &self,
+        member: Node,
+        template: Option<Node>,
+        source: &str,
+        members: &mut Vec<String>,
+    ) {
+        match member.kind() {
+            "field_declaration" | "declaration" | "function_definition" => {
+                if let Some((function, _)) = self.function_declarator(member, source) {
+                    let Some(name) = function
+                        .child_by_field_name("declarator")
+                        .map(|d| self.node_text(d, source))
+                    else {
+                        return;
+                    };
+                    let prefix = if self.has_storage_class(member, source, "static") {
+                        "static "
+                    } else {
+                        ""
+                    };
+                    members.push(format!(
+                        "{}{}{}",
+                        prefix,
+                        name,
+                        self.function_signature(member, template, source)
+                    ));
+                    return;
+                }
+                let ty = member
+                    .child_by_field_name("type")
+                    .map(|t| nodes::collapsed_text(t, source))
+                    .unwrap_or_default();
+                let mut cursor = member.walk();
+                for declarator in member.children_by_field_name("declarator", &mut cursor) {
+                    if let Some(name) = self.declarator_name(declarator, source) {
+                        members.push(format!("{}: {}", name, ty));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// `{ Circle | Square | Triangle }`
+    fn enum_signature(&self, spec: Node, source: &str) -> String {
+        let mut variants = Vec::new();
+        if let Some(body) = spec.child_by_field_name("body") {
+            let mut cursor = body.walk();
+            for child in body.children(&mut cursor) {
+                if child.kind() == "enumerator" {
+                    if let Some(name_node) = child.child_by_field_name("name") {
+                        variants.push(self.node_text(name_node, source));
+                    }
+                }
+                if variants.len() >= 5 {
+                    variants.push("...".to_string());
+                    break;
+                }
+            }
+        }
+        format!("{{ {} }}", variants.join(" | "))
+    }
+
+    /// `{ Shape; Size; Point; area; ... }` listing what the namespace exports
+    fn namespace_signature(&self, node: Node, source: &str, is_header: bool) -> String {
+        let mut names = Vec::new();
+        if let Some(body) = node.child_by_field_name("body") {
+            self.for_each_item(body, None, &mut |item, _| {
+                if let Some((name, _)) = self.exported_item(item, source, is_header) {
+                    names.push(name);
+                }
+            });
+        }
+        let mut seen = HashSet::new();
+        names.retain(|name| seen.insert(name.clone()));
+        if names.len() > 5 {
+            names.truncate(5);
+            names.push("...".to_string());
+        }
+        format!("{{ {} }}", names.join("; "))
+    }
+
+    /// `(a, b)` for function-like macros, `= value` for object-like ones
+    fn macro_signature(&self, node: Node, source: &str) -> Option<String> {
+        match node.kind() {
+            "preproc_function_def" => node
+                .child_by_field_name("parameters")
+                .map(|p| nodes::collapsed_text(p, source)),
+            _ => node
+                .child_by_field_name("value")
+                .map(|v| format!("= {}", nodes::collapsed_text(v, source))),
+        }
+    }
+
+    /// #include "util/log.h" -> from ./util/log.h; #include <vector> -> from vector
+    fn extract_imports(&self, root: Node, source: &str) -> Vec<ImportInfo> {
+        let mut imports = Vec::new();
+        self.visit_includes(root, source, &mut imports);
+        imports
+    }
+
+    fn visit_includes(&self, node: Node, source: &str, imports: &mut Vec<ImportInfo>) {
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            if child.kind() != "preproc_include" {
+                self.visit_includes(child, source, imports);
+                continue;
+            }
+            let Some(path) = child.child_by_field_name("path") else {
+                continue;
+            };
+            let text = self.node_text(path, source);
+            let from = match path.kind() {
+                // Quoted includes are relative to the including file
+                "string_literal" => {
+                    let inner = text.trim_matches('"');
+                    if inner.starts_with('.') || inner.starts_with('/') {
+                        inner.to_string()
+                    } else {
+                        format!("./{}", inner)
+                    }
+                }
+                "system_lib_string" => text
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string(),
+                _ => continue,
+            };
+            imports.push(ImportInfo {
+                from,
+                items: vec!["*".to_string()],
+            });
+        }
+    }
+
+    fn node_text(&self, node: Node, source: &str) -> String {
+        source[node.start_byte()..node.end_byte()].to_string()
+    }
+}
+
+fn is_header(file_path: &Path) -> bool {
+    file_path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| HEADER_EXTENSIONS.contains(&ext))
+}
+
+impl Default for CppParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguageParser for CppParser {
+    fn language_name(&self) -> &'static str {
+        "cpp"
+    }
+
+    fn file_extensions(&self) -> &[&'static str] {
+        &["c", "h", "cc", "cpp", "hpp"]
+    }
+
+    fn extract_ast_info(&self, source: &str, file_path: &Path) -> Result<ASTInfo, ParseError> {
+        let mut parser = self.create_parser(Some(file_path))?;
+        let tree = parser
+            .parse(source, None)
+            .ok_or_else(|| ParseError::ParseError("Failed to parse source".to_string()))?;
+
+        let root = tree.root_node();
+        let is_header = is_header(file_path);
+
+        let exports = self.extract_exports(root, source, is_header);
+        let imports = self.extract_imports(root, source);
+        let signatures = self.extract_signatures(root, source, is_header);
+
+        let tokens = super::tokens::count_tokens(source);
+
+        Ok(ASTInfo {
+            tokens,
+            exports,
+            imports,
+            calls: Vec::new(),
+            signatures,
+        })
+    }
+
+    fn extract_toon_comments(&self, source: &str) -> Result<ExtractedComments, ParseError> {
+        let mut result = ExtractedComments::default();
+
+        // Find @dose in /** */ or /* */ block comments
+        let block_pattern = Regex::new(r"/\*\*?\s*@dose[\s\S]*?\*/").unwrap();
+        let file_block = block_pattern
+            .find_iter(source)
+            .find(|m| toon_comment::parse_inline_annotation(m.as_str()).is_none());
+
+        if let Some(mat) = file_block {
+            let comment = mat.as_str();
+            let content = comment
+                .trim_start_matches("/*")
+                .trim_start_matches('*')
+                .trim_end_matches("*/");
+
+            let content = content
+                .lines()
+                .map(|line| {
+                    let trimmed = line.trim().trim_start_matches('*').trim();
+                    if trimmed == "@dose" {
+                        ""
+                    } else {
+                        trimmed
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            result.file_block = Some(toon_comment::parse_toon_block(&content));
+        }
+
+        // Inline // @dose invariant: value annotations on the following function or type
+        let mut parser = self.create_parser(None)?;
+        if let Some(tree) = parser.parse(source, None) {
+            result.function_annotations = toon_comment::collect_inline_annotations(
+                tree.root_node(),
+                source,
+                &["comment"],
+                |node| match node.kind() {
+                    "function_definition" | "declaration" | "field_declaration" => {
+                        let (function, _) = self.function_declarator(node, source)?;
+                        let declarator = function.child_by_field_name("declarator")?;
+                        let name = self.declarator_name(declarator, source)?;
+                        // Out-of-line `int Shape::area()` is qualified by its scope, like an
+                        // in-class member; the grammar cannot tell a class scope from a namespace
+                        let scope = (declarator.kind() == "qualified_identifier")
+                            .then(|| declarator.child_by_field_name("scope"))
+                            .flatten()
+                            // a::Shape:: and Shape<T>:: name Shape
+                            .map(|scope| scope.child_by_field_name("name").unwrap_or(scope))
+                            .map(|scope| self.node_text(scope, source));
+                        match scope {
+                            Some(scope) => Some(format!("{}.{}", scope, name)),
+                            None => Some(name),
+                        }
+                    }
+                    "class_specifier" | "struct_specifier" | "union_specifier"
+                    | "enum_specifier"
+                        if node.child_by_field_name("body").is_some() =>
+                    {
+                        node.child_by_field_name("name")
+                            .map(|n| self.node_text(n, source))
+                    }
+                    _ => None,
+                },
+                |node| match node.kind() {
+                    "class_specifier" | "struct_specifier" | "union_specifier"
+                        if node.child_by_field_name("body").is_some() =>
+                    {
+                        node.child_by_field_name("name")
+                            .map(|n| self.node_text(n, source))
+                    }
+                    _ => None,
+                },
+            );
+        }
+
+        Ok(result)
+    }
+
+    fn strip_toon_comments(&self, source: &str, toon_path: &str) -> Result<String, ParseError> {
+        // Replace /** */ and /* */ @dose blocks
+        let block_pattern = Regex::new(r"/\*\*?\s*@dose[\s\S]*?\*/").unwrap();
+        let result = block_pattern
+            .replace_all(source, &format!("// @dose -> {}", toon_path))
+            .to_string();
+
+        Ok(result)
+    }
+
+    fn get_string_ranges(&self, source: &str) -> Result<Vec<(usize, usize)>, ParseError> {
+        let mut parser = self.create_parser(None)?;
+
+        let tree = parser
+            .parse(source, None)
+            .ok_or_else(|| ParseError::ParseError("Failed to parse source".to_string()))?;
+
+        let mut ranges = Vec::new();
+        collect_string_ranges_cpp(&mut tree.walk(), &mut ranges);
+
+        // Preprocessor directives must stay on their own lines, so each directive line
+        // (with continuations) is preserved along with the newline before and after it
+        let mut offset = 0;
+        let mut directive_start: Option<usize> = None;
+        for line in source.split_inclusive('\n') {
+            let start = offset;
+            offset += line.len();
+            if directive_start.is_none() && line.trim_start().starts_with('#') {
+                directive_start = Some(start.saturating_sub(1));
+            }
+            if let Some(begin) = directive_start {
+                if !line.trim_end().ends_with('\\') {
+                    ranges.push((begin, offset));
+                    directive_start = None;
+                }
+            }
+        }
+        ranges.sort();
+        Ok(ranges)
+    }
+
+    fn paired_files(&self, file_path: &Path) -> Vec<PathBuf> {
+        if !is_header(file_path) {
+            return Vec::new();
+        }
+        IMPLEMENTATION_EXTENSIONS
+            .iter()
+            .map(|ext| file_path.with_extension(ext))
+            .collect()
+    }
+}
+
+fn collect_string_ranges_cpp(
+    cursor: &mut tree_sitter::TreeCursor,
+    ranges: &mut Vec<(usize, usize)>,
+) {
+    loop {
+        let node = cursor.node();
+        let kind = node.kind();
+        // C/C++ have string_literal, raw_string_literal (C++11), and char_literal
+        if kind == "string_literal" || kind == "raw_string_literal" || kind == "char_literal" {
+            ranges.push((node.start_byte(), node.end_byte()));
+        }
+        if cursor.goto_first_child() {
+            collect_string_ranges_cpp(cursor, ranges);
+            cursor.goto_parent();
+        }
+        if !cursor.goto_next_sibling() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HPP_FIXTURE: &str = include_str!("../../test_fixtures/sample.hpp");
+    const CPP_FIXTURE: &str = include_str!("../../test_fixtures/sample.cpp");
+
+    #[test]
+    fn test_extract_ast_info_header() {
+        let parser = CppParser::new();
+        let info = parser
+            .extract_ast_info(HPP_FIXTURE, Path::new("sample.hpp"))
+            .unwrap();
+
+        let mut exports: Vec<_> = info
+            .exports
+            .iter()
+            .map(|e| (&e.name[..], &e.kind[..]))
+            .collect();
+        exports.sort();
+        assert_eq!(
+            exports,
+            vec![
+                ("Coord", "struct"),
+                ("Point", "class"),
+                ("SAMPLE_MAX", "macro"),
+                ("SAMPLE_VERSION", "macro"),
+                ("Shape", "enum"),
+                ("Size", "struct"),
+                ("Sizes", "type"),
+                ("area", "fn"),
+                ("geo", "namespace"),
+                ("max_of", "fn"),
+                ("sample_count", "var"),
+                ("sample_init", "fn"),
+                ("shape_name", "fn"),
+            ]
+        );
+
+        let imports: Vec<_> = info.imports.iter().map(|i| &i.from[..]).collect();
+        assert_eq!(imports, vec!["string", "vector", "./util/log.h"]);
+        assert!(info.calls.is_empty());
+    }
+
+    #[test]
+    fn test_extract_ast_info_implementation() {
+        let parser = CppParser::new();
+        let info = parser
+            .extract_ast_info(CPP_FIXTURE, Path::new("sample.cpp"))
+            .unwrap();
+
+        // static functions, anonymous namespaces, and out-of-line members stay private
+        let mut exports: Vec<_> = info.exports.iter().map(|e| &e.name[..]).collect();
+        exports.sort();
+        assert_eq!(exports, vec!["area", "sample_init", "shape_name"]);
+
+        let imports: Vec<_> = info.imports.iter().map(|i| &i.from[..]).collect();
+        assert_eq!(imports, vec!["./sample.hpp", "./util/log.h", "cmath"]);
+    }
+
+    #[test]
+    fn test_extract_signatures() {
+        let parser = CppParser::new();
+        let info = parser
+            .extract_ast_info(HPP_FIXTURE, Path::new("sample.hpp"))
+            .unwrap();
+
+        let sig = |name: &str| {
+            info.signatures
+                .iter()
+                .find(|s| s.name == name)
+                .unwrap_or_else(|| panic!("missing signature for {}", name))
+        };
+
+        let point = sig("Point");
+        assert_eq!(point.kind, "class");
+        assert_eq!(
+            point.signature,
+            "<typename T> : public Base<T> { Point(T x, T y); length() -> T const; static origin() -> Point }"
+        );
+        assert_eq!((point.start_line, point.end_line), (35, 46));
+
+        let max_of = sig("max_of");
+        assert_eq!(max_of.signature, "<typename T>(T a, T b) -> T");
+        assert_eq!((max_of.start_line, max_of.end_line), (51, 54));
+
+        assert_eq!(sig("area").signature, "(const Size& size) -> int");
+        assert_eq!(sig("shape_name").signature, "(Shape shape) -> const char*");
+        assert_eq!(sig("Size").signature, "{ width: int; height: int }");
+        assert_eq!(sig("Coord").signature, "{ lat: double; lng: double }");
+        assert_eq!(sig("Shape").signature, "{ Circle | Square | Triangle }");
+        assert_eq!(sig("Sizes").signature, "= std::vector<Size>");
+        assert_eq!(sig("SAMPLE_MAX").signature, "(a, b)");
+        assert_eq!(sig("SAMPLE_VERSION").signature, "= \"1.0.0\"");
+        let version = sig("SAMPLE_VERSION");
+        assert_eq!((version.start_line, version.end_line), (23, 23));
+        let max = sig("SAMPLE_MAX");
+        assert_eq!((max.start_line, max.end_line), (24, 24));
+
+        let geo = sig("geo");
+        assert_eq!(geo.kind, "namespace");
+        assert_eq!(
+            geo.signature,
+            "{ Shape; Size; Point; area; shape_name; ... }"
+        );
+    }
+
+    #[test]
+    fn test_c_dialect() {
+        let parser = CppParser::new();
+        // `new` and `class` are ordinary identifiers in C
+        let source = r#"#include "list.h"
+
+static int class = 0;
+
+char *list_new(const char *name, ...) {
+    int new = class;
+    return 0;
+}
+"#;
+        let info = parser
+            .extract_ast_info(source, Path::new("list.c"))
+            .unwrap();
+
+        let exports: Vec<_> = info.exports.iter().map(|e| &e.name[..]).collect();
+        assert_eq!(exports, vec!["list_new"]);
+        assert_eq!(info.imports[0].from, "./list.h");
+    }
+
+    #[test]
+    fn test_inline_annotations() {
+        let parser = CppParser::new();
+        let comments = parser.extract_toon_comments(CPP_FIXTURE).unwrap();
+        assert_eq!(
+            comments.file_block.unwrap().purpose.unwrap(),
+            "Sample C++ implementation fixture for testing the luny C/C++ parser."
+        );
+        assert_eq!(
+            comments.function_annotations["area"].invariants,
+            Some(vec!["never returns a negative area".to_string()])
+        );
+
+        // Members are Type.name whether defined in the class or out of line
+        let source = r#"struct Shape {
+    // @dose invariant: positive
+    int area() const;
+};
+
+// @dose gotcha: slow
+int Shape::perimeter() const { return 0; }
+"#;
+        let comments = parser.extract_toon_comments(source).unwrap();
+        assert!(comments.function_annotations["Shape.area"]
+            .invariants
+            .is_some());
+        assert!(comments.function_annotations["Shape.perimeter"]
+            .gotchas
+            .is_some());
+    }
+
+    #[test]
+    fn test_toon_comments() {
+        let parser = CppParser::new();
+        let comments = parser.extract_toon_comments(HPP_FIXTURE).unwrap();
+        let block = comments.file_block.unwrap();
+        assert_eq!(block.purpose.unwrap(), "Sample C++ header fixture for testing the luny C/C++ parser. This file contains classes, structs, enums, templates, and namespaces to verify header extraction works correctly.");
+
+        let stripped = parser
+            .strip_toon_comments(HPP_FIXTURE, "sample.hpp.toon")
+            .unwrap();
+        assert!(stripped.starts_with("// @dose -> sample.hpp.toon"));
+    }
+
+    #[test]
+    fn test_string_ranges_keep_directives() {
+        let parser = CppParser::new();
+        let source = "int x;\n#define A \"a b\"\nconst char* s = \"x  y\";\nint y = 'c';\n";
+        let ranges = parser.get_string_ranges(source).unwrap();
+        let texts: Vec<_> = ranges.iter().map(|(s, e)| &source[*s..*e]).collect();
+        assert_eq!(texts, vec!["\n#define A \"a b\"\n", "\"x  y\"", "'c'"]);
+    }
+
+    #[test]
+    fn test_paired_files() {
+        let parser = CppParser::new();
+        assert_eq!(
+            parser.paired_files(Path::new("src/geo.hpp")),
+            vec![
+                PathBuf::from("src/geo.c"),
+                PathBuf::from("src/geo.cc"),
+                PathBuf::from("src/geo.cpp"),
+                PathBuf::from("src/geo.cxx"),
+            ]
+        );
+        assert!(parser.paired_files(Path::new("src/geo.cpp")).is_empty());
+    }
+}
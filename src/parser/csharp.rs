@@ -21,18 +21,35 @@
 //!     - C# uses explicit visibility modifiers; default is not public
 //!     - Records with primary constructors have special AST structure
 //!     - The @dose block can be in block comments or triple-slash XML comments
+//!     - There is no type resolution: File.Exists() is attributed through a small table of
+//!       framework types, else to the one using namespace in scope, else to the one
+//!       non-System namespace; PascalCase properties and types declared in the file are skipped
 //!
 //! flows:
 //!     - Parse: Create tree-sitter parser, set C# language, parse source
 //!     - Extract exports: Walk AST finding declarations with public modifier
 //!     - Extract imports: Walk AST collecting using_directive nodes
+//!     - Inline annotations: /// @dose field: value binds to the next member or type
+//!     - Extract signatures: Public methods (as Type.Method) with generics/constraints; types summarize public
+//!       constructors, methods, properties, and fields
+//!     - Extract calls: Attribute invocations through using aliases, using static, qualified names
+//!       and type receivers (File.Exists)
 
-use crate::parser::{toon_comment, LanguageParser, ParseError};
-use crate::types::{ASTInfo, ExportInfo, ExtractedComments, ImportInfo};
+use crate::parser::{nodes, toon_comment, LanguageParser, ParseError};
+use crate::types::{ASTInfo, CallInfo, ExportInfo, ExtractedComments, ImportInfo, SignatureInfo};
 use regex::Regex;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use tree_sitter::Parser;
 
+/// Declarations that own members; a member's signature is named after the nearest one
+const TYPE_DECLARATIONS: &[&str] = &[
+    "class_declaration",
+    "struct_declaration",
+    "interface_declaration",
+    "record_declaration",
+];
+
 /// Parser for C# files
 #[derive(Clone)]
 pub struct CSharpParser;
@@ -68,70 +85,58 @@ impl CSharpParser {
             let node = cursor.node();
 
             match node.kind() {
-                "class_declaration" => {
-                    if self.is_public(&node, source) {
-                        if let Some(name_node) = node.child_by_field_name("name") {
-                            let name = self.node_text(name_node, source);
-                            exports.push(ExportInfo {
-                                name,
-                                kind: "class".to_string(),
-                            });
-                        }
+                "class_declaration" if self.is_public(&node, source) => {
+                    if let Some(name_node) = node.child_by_field_name("name") {
+                        let name = self.node_text(name_node, source);
+                        exports.push(ExportInfo {
+                            name,
+                            kind: "class".to_string(),
+                        });
                     }
                 }
-                "interface_declaration" => {
-                    if self.is_public(&node, source) {
-                        if let Some(name_node) = node.child_by_field_name("name") {
-                            let name = self.node_text(name_node, source);
-                            exports.push(ExportInfo {
-                                name,
-                                kind: "interface".to_string(),
-                            });
-                        }
+                "interface_declaration" if self.is_public(&node, source) => {
+                    if let Some(name_node) = node.child_by_field_name("name") {
+                        let name = self.node_text(name_node, source);
+                        exports.push(ExportInfo {
+                            name,
+                            kind: "interface".to_string(),
+                        });
                     }
                 }
-                "struct_declaration" => {
-                    if self.is_public(&node, source) {
-                        if let Some(name_node) = node.child_by_field_name("name") {
-                            let name = self.node_text(name_node, source);
-                            exports.push(ExportInfo {
-                                name,
-                                kind: "struct".to_string(),
-                            });
-                        }
+                "struct_declaration" if self.is_public(&node, source) => {
+                    if let Some(name_node) = node.child_by_field_name("name") {
+                        let name = self.node_text(name_node, source);
+                        exports.push(ExportInfo {
+                            name,
+                            kind: "struct".to_string(),
+                        });
                     }
                 }
-                "enum_declaration" => {
-                    if self.is_public(&node, source) {
-                        if let Some(name_node) = node.child_by_field_name("name") {
-                            let name = self.node_text(name_node, source);
-                            exports.push(ExportInfo {
-                                name,
-                                kind: "enum".to_string(),
-                            });
-                        }
+                "enum_declaration" if self.is_public(&node, source) => {
+                    if let Some(name_node) = node.child_by_field_name("name") {
+                        let name = self.node_text(name_node, source);
+                        exports.push(ExportInfo {
+                            name,
+                            kind: "enum".to_string(),
+                        });
                     }
                 }
-                "method_declaration" => {
-                    if self.is_public(&node, source) {
-                        if let Some(name_node) = node.child_by_field_name("name") {
-                            let name = self.node_text(name_node, source);
-                            exports.push(ExportInfo {
-                                name,
-                                kind: "method".to_string(),
-                            });
-                        }
+                "method_declaration" if self.is_public(&node, source) => {
+                    if let Some(name_node) = node.child_by_field_name("name") {
+                        let name = self.node_text(name_node, source);
+                        exports.push(ExportInfo {
+                            name,
+                            kind: "method".to_string(),
+                        });
                     }
                 }
-                "record_declaration" => {
-                    if self.is_public(&node, source) {
-                        if let Some(name_node) = node.child_by_field_name("name") {
-                            let name = self.node_text(name_node, source);
-                            exports.push(ExportInfo {
-                                name,
-                                kind: "record".to_string(),
-                            });
-                        }
+                "record_declaration" if self.is_public(&node, source) => {
+                    if let Some(name_node) = node.child_by_field_name("name") {
+                        let name = self.node_text(name_node, source);
+                        exports.push(ExportInfo {
+                            name,
+                            kind: "record".to_string(),
+                        });
                     }
                 }
                 _ => {}
@@ -181,18 +186,8 @@ impl CSharpParser {
             let node = cursor.node();
 
             if node.kind() == "using_directive" {
-                // using System.Collections.Generic;
-                let text = self.node_text(node, source);
-                let namespace = text
-                    .trim_start_matches("using")
-                    .trim_end_matches(';')
-                    .trim()
-                    .to_string();
-                if !namespace.is_empty() {
-                    imports.push(ImportInfo {
-                        from: namespace.clone(),
-                        items: vec![namespace],
-                    });
+                if let Some(import) = self.parse_using_directive(node, source) {
+                    imports.push(import);
                 }
             }
 
@@ -207,11 +202,622 @@ impl CSharpParser {
         }
     }
 
+    fn extract_signatures(
+        &self,
+        root: tree_sitter::Node,
+        source: &str,
+        exports: &[ExportInfo],
+    ) -> Vec<SignatureInfo> {
+        let export_names: HashSet<&str> = exports.iter().map(|e| e.name.as_str()).collect();
+        let mut signatures = Vec::new();
+        let mut cursor = root.walk();
+
+        self.visit_signatures(&mut cursor, source, &export_names, &mut signatures);
+        signatures
+    }
+
+    fn visit_signatures(
+        &self,
+        cursor: &mut tree_sitter::TreeCursor,
+        source: &str,
+        export_names: &HashSet<&str>,
+        signatures: &mut Vec<SignatureInfo>,
+    ) {
+        loop {
+            let node = cursor.node();
+
+            // Names are not unique across types (Get on two classes), so the
+            // visibility check is repeated here rather than trusting the name alone
+            let sig = match node.kind() {
+                "method_declaration" if self.is_public(&node, source) => {
+                    self.extract_method_signature(node, source, export_names)
+                }
+                "class_declaration"
+                | "struct_declaration"
+                | "interface_declaration"
+                | "record_declaration"
+                    if self.is_public(&node, source) =>
+                {
+                    self.extract_type_signature(node, source, export_names)
+                }
+                "enum_declaration" if self.is_public(&node, source) => {
+                    self.extract_enum_signature(node, source, export_names)
+                }
+                _ => None,
+            };
+            if let Some(sig) = sig {
+                signatures.push(sig);
+            }
+
+            if cursor.goto_first_child() {
+                self.visit_signatures(cursor, source, export_names, signatures);
+                cursor.goto_parent();
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+
+    fn extract_method_signature(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        export_names: &HashSet<&str>,
+    ) -> Option<SignatureInfo> {
+        let name_node = node.child_by_field_name("name")?;
+        let name = self.node_text(name_node, source);
+
+        if !export_names.contains(name.as_str()) {
+            return None;
+        }
+
+        let kind = if self.has_modifier(&node, source, "async") {
+            "async method"
+        } else {
+            "method"
+        };
+        let start_line = node.start_position().row + 1;
+        let end_line = node.end_position().row + 1;
+
+        // Members are Owner.name, as the fn: annotations name them
+        let owner = nodes::enclosing_name(node, source, |kind| TYPE_DECLARATIONS.contains(&kind));
+        Some(SignatureInfo {
+            name: nodes::qualify(owner, name),
+            kind: kind.to_string(),
+            signature: self.method_signature(node, source),
+            start_line,
+            end_line,
+        })
+    }
+
+    /// `<T>(string id) -> Task<T?> where T : class`
+    fn method_signature(&self, node: tree_sitter::Node, source: &str) -> String {
+        let type_params = nodes::child_of_kind(node, "type_parameter_list")
+            .map(|t| nodes::collapsed_text(t, source))
+            .unwrap_or_default();
+
+        let params = nodes::child_of_kind(node, "parameter_list")
+            .map(|p| nodes::collapsed_text(p, source))
+            .unwrap_or_else(|| "()".to_string());
+
+        let returns = node
+            .child_by_field_name("returns")
+            .map(|r| format!(" -> {}", self.node_text(r, source)))
+            .unwrap_or_default();
+
+        let constraints = self.constraints(node, source);
+
+        format!("{}{}{}{}", type_params, params, returns, constraints)
+    }
+
+    fn extract_type_signature(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        export_names: &HashSet<&str>,
+    ) -> Option<SignatureInfo> {
+        let name_node = node.child_by_field_name("name")?;
+        let name = self.node_text(name_node, source);
+
+        if !export_names.contains(name.as_str()) {
+            return None;
+        }
+
+        let type_params = nodes::child_of_kind(node, "type_parameter_list")
+            .map(|t| nodes::collapsed_text(t, source))
+            .unwrap_or_default();
+
+        // Record primary constructor: record User(string Id, string Name)
+        let primary_ctor = nodes::child_of_kind(node, "parameter_list")
+            .map(|p| nodes::collapsed_text(p, source))
+            .unwrap_or_default();
+
+        let bases = nodes::child_of_kind(node, "base_list")
+            .map(|b| format!(" {}", nodes::collapsed_text(b, source)))
+            .unwrap_or_default();
+
+        let constraints = self.constraints(node, source);
+
+        let is_interface = node.kind() == "interface_declaration";
+        let mut members = Vec::new();
+        if let Some(body) = node.child_by_field_name("body") {
+            for i in 0..body.child_count() {
+                if let Some(child) = body.child(i) {
+                    // Interface members are implicitly public
+                    if !is_interface && !self.is_public(&child, source) {
+                        continue;
+                    }
+                    let member_name = child
+                        .child_by_field_name("name")
+                        .map(|n| self.node_text(n, source));
+                    match (child.kind(), member_name) {
+                        ("constructor_declaration", _) => {
+                            let params = nodes::child_of_kind(child, "parameter_list")
+                                .map(|p| nodes::collapsed_text(p, source))
+                                .unwrap_or_else(|| "()".to_string());
+                            members.push(format!("new{}", params));
+                        }
+                        ("method_declaration", Some(member_name)) => {
+                            members.push(format!(
+                                "{}{}",
+                                member_name,
+                                self.method_signature(child, source)
+                            ));
+                        }
+                        ("property_declaration", Some(member_name)) => {
+                            let ty = child
+                                .child_by_field_name("type")
+                                .map(|t| self.node_text(t, source))
+                                .unwrap_or_default();
+                            members.push(format!("{}: {}", member_name, ty));
+                        }
+                        // public const string Version = "1.0"; public int A, B;
+                        ("field_declaration", _) => {
+                            let Some(declaration) =
+                                nodes::child_of_kind(child, "variable_declaration")
+                            else {
+                                continue;
+                            };
+                            let ty = declaration
+                                .child_by_field_name("type")
+                                .map(|t| self.node_text(t, source))
+                                .unwrap_or_default();
+                            let mut cursor = declaration.walk();
+                            for declarator in declaration.named_children(&mut cursor) {
+                                if let Some(name) = declarator
+                                    .child_by_field_name("name")
+                                    .filter(|_| declarator.kind() == "variable_declarator")
+                                {
+                                    members.push(format!(
+                                        "{}: {}",
+                                        self.node_text(name, source),
+                                        ty
+                                    ));
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            if members.len() > 5 {
+                members.truncate(5);
+                members.push("...".to_string());
+            }
+        }
+
+        let body = if members.is_empty() {
+            String::new()
+        } else {
+            format!(" {{ {} }}", members.join("; "))
+        };
+        let signature = format!(
+            "{}{}{}{}{}",
+            type_params, primary_ctor, bases, constraints, body
+        )
+        .trim()
+        .to_string();
+        let start_line = node.start_position().row + 1;
+        let end_line = node.end_position().row + 1;
+
+        Some(SignatureInfo {
+            name,
+            kind: node.kind().trim_end_matches("_declaration").to_string(),
+            signature,
+            start_line,
+            end_line,
+        })
+    }
+
+    fn extract_enum_signature(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        export_names: &HashSet<&str>,
+    ) -> Option<SignatureInfo> {
+        let name_node = node.child_by_field_name("name")?;
+        let name = self.node_text(name_node, source);
+
+        if !export_names.contains(name.as_str()) {
+            return None;
+        }
+
+        let mut variants = Vec::new();
+        if let Some(body) = node.child_by_field_name("body") {
+            for i in 0..body.child_count() {
+                if let Some(child) = body.child(i) {
+                    if child.kind() == "enum_member_declaration" {
+                        if let Some(name_node) = child.child_by_field_name("name") {
+                            variants.push(self.node_text(name_node, source));
+                        }
+                    }
+                }
+                if variants.len() >= 5 {
+                    variants.push("...".to_string());
+                    break;
+                }
+            }
+        }
+
+        let signature = format!("{{ {} }}", variants.join(" | "));
+        let start_line = node.start_position().row + 1;
+        let end_line = node.end_position().row + 1;
+
+        Some(SignatureInfo {
+            name,
+            kind: "enum".to_string(),
+            signature,
+            start_line,
+            end_line,
+        })
+    }
+
+    /// Generic constraints such as ` where T : class`
+    fn constraints(&self, node: tree_sitter::Node, source: &str) -> String {
+        let mut constraints = String::new();
+        for i in 0..node.child_count() {
+            if let Some(child) = node.child(i) {
+                if child.kind() == "type_parameter_constraints_clause" {
+                    constraints.push(' ');
+                    constraints.push_str(&nodes::collapsed_text(child, source));
+                }
+            }
+        }
+        constraints
+    }
+
+    fn has_modifier(&self, node: &tree_sitter::Node, source: &str, modifier: &str) -> bool {
+        (0..node.child_count()).any(|i| {
+            node.child(i).is_some_and(|child| {
+                child.kind() == "modifier" && self.node_text(child, source) == modifier
+            })
+        })
+    }
+
+    /// using System.IO;                      -> from System.IO, items [System.IO]
+    /// using static System.Math;             -> from System.Math, items [*]
+    /// using Json = Newtonsoft.Json.JsonConvert; -> from Newtonsoft.Json.JsonConvert, items [Json]
+    fn parse_using_directive(&self, node: tree_sitter::Node, source: &str) -> Option<ImportInfo> {
+        let mut is_static = false;
+        let mut target = None;
+
+        for i in 0..node.child_count() {
+            if let Some(child) = node.child(i) {
+                match child.kind() {
+                    "static" => is_static = true,
+                    "qualified_name" | "identifier" | "generic_name"
+                        if node.field_name_for_child(i as u32) != Some("name") =>
+                    {
+                        target = Some(self.node_text(child, source));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let from = target?;
+        let item = if is_static {
+            "*".to_string()
+        } else if let Some(alias) = node.child_by_field_name("name") {
+            self.node_text(alias, source)
+        } else {
+            from.clone()
+        };
+
+        Some(ImportInfo {
+            from,
+            items: vec![item],
+        })
+    }
+
+    fn extract_calls(
+        &self,
+        root: tree_sitter::Node,
+        source: &str,
+        imports: &[ImportInfo],
+    ) -> Vec<CallInfo> {
+        let mut aliases: HashMap<&str, &str> = HashMap::new();
+        let mut namespaces: Vec<&str> = Vec::new();
+        let mut static_classes: Vec<&str> = Vec::new();
+
+        for imp in imports {
+            for item in &imp.items {
+                if item == "*" {
+                    static_classes.push(&imp.from);
+                } else if item == &imp.from {
+                    namespaces.push(&imp.from);
+                } else {
+                    aliases.insert(item, &imp.from);
+                }
+            }
+        }
+        // Longest namespace first so System.Text.Json wins over System
+        namespaces.sort_by_key(|ns| std::cmp::Reverse(ns.len()));
+
+        // Bare calls to methods declared in this file are never static imports
+        let mut local_methods = HashSet::new();
+        self.collect_method_names(&mut root.walk(), source, &mut local_methods);
+        // Receivers named after a type, property or field declared here are not imported types
+        let mut local_names = HashSet::new();
+        self.collect_member_names(&mut root.walk(), source, &mut local_names);
+
+        // Unknown type receivers belong to the only namespace in scope, or else to the only
+        // one that is not the framework's
+        let project: Vec<&str> = namespaces
+            .iter()
+            .copied()
+            .filter(|ns| *ns != "System" && !ns.starts_with("System."))
+            .collect();
+        let type_namespace = match (namespaces.as_slice(), project.as_slice()) {
+            ([only], _) | (_, [only]) => Some(*only),
+            _ => None,
+        };
+
+        let ctx = CallContext {
+            aliases,
+            namespaces,
+            // Bare calls are only attributable when one static class is in scope
+            static_class: match static_classes.as_slice() {
+                [only] => Some(*only),
+                _ => None,
+            },
+            type_namespace,
+            local_methods,
+            local_names,
+        };
+
+        let mut calls: Vec<CallInfo> = Vec::new();
+        let mut seen: HashSet<(String, String)> = HashSet::new();
+        let mut cursor = root.walk();
+
+        self.visit_calls(&mut cursor, source, &ctx, &mut calls, &mut seen);
+        calls
+    }
+
+    fn collect_method_names(
+        &self,
+        cursor: &mut tree_sitter::TreeCursor,
+        source: &str,
+        names: &mut HashSet<String>,
+    ) {
+        loop {
+            let node = cursor.node();
+
+            if node.kind() == "method_declaration" || node.kind() == "local_function_statement" {
+                if let Some(name_node) = node.child_by_field_name("name") {
+                    names.insert(self.node_text(name_node, source));
+                }
+            }
+
+            if cursor.goto_first_child() {
+                self.collect_method_names(cursor, source, names);
+                cursor.goto_parent();
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+
+    /// Types, properties and fields declared in this file
+    fn collect_member_names(
+        &self,
+        cursor: &mut tree_sitter::TreeCursor,
+        source: &str,
+        names: &mut HashSet<String>,
+    ) {
+        loop {
+            let node = cursor.node();
+
+            let name = match node.kind() {
+                "class_declaration"
+                | "struct_declaration"
+                | "interface_declaration"
+                | "record_declaration"
+                | "enum_declaration"
+                | "property_declaration" => node.child_by_field_name("name"),
+                // Fields: field_declaration -> variable_declaration -> variable_declarator
+                "variable_declarator"
+                    if node
+                        .parent()
+                        .and_then(|p| p.parent())
+                        .is_some_and(|p| p.kind() == "field_declaration") =>
+                {
+                    node.child_by_field_name("name")
+                }
+                _ => None,
+            };
+            if let Some(name) = name {
+                names.insert(self.node_text(name, source));
+            }
+
+            if cursor.goto_first_child() {
+                self.collect_member_names(cursor, source, names);
+                cursor.goto_parent();
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+
+    fn visit_calls(
+        &self,
+        cursor: &mut tree_sitter::TreeCursor,
+        source: &str,
+        ctx: &CallContext,
+        calls: &mut Vec<CallInfo>,
+        seen: &mut HashSet<(String, String)>,
+    ) {
+        loop {
+            let node = cursor.node();
+
+            if node.kind() == "invocation_expression" {
+                if let Some(call) = self.parse_invocation(node, source, ctx) {
+                    if seen.insert((call.target.clone(), call.method.clone())) {
+                        calls.push(call);
+                    }
+                }
+            }
+
+            if cursor.goto_first_child() {
+                self.visit_calls(cursor, source, ctx, calls, seen);
+                cursor.goto_parent();
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+
+    fn parse_invocation(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        ctx: &CallContext,
+    ) -> Option<CallInfo> {
+        let function = node.child_by_field_name("function")?;
+
+        match function.kind() {
+            // Abs(x) with `using static System.Math;`
+            "identifier" | "generic_name" => {
+                let method = self.simple_name(function, source)?;
+                if ctx.local_methods.contains(&method) {
+                    return None;
+                }
+                Some(CallInfo {
+                    target: ctx.static_class?.to_string(),
+                    method,
+                })
+            }
+            // Json.Serialize(x) via alias, System.IO.File.Exists(p) fully qualified, or
+            // File.Exists(p) under `using System.IO;`
+            "member_access_expression" => {
+                let method = self.simple_name(function.child_by_field_name("name")?, source)?;
+                let expression = function.child_by_field_name("expression")?;
+                let receiver = self.node_text(expression, source);
+                let root = receiver.split('.').next().unwrap_or(&receiver);
+
+                if let Some(target) = ctx.aliases.get(root) {
+                    return Some(CallInfo {
+                        target: target.to_string(),
+                        method,
+                    });
+                }
+
+                let qualified = ctx.namespaces.iter().copied().find(|ns| {
+                    receiver
+                        .strip_prefix(*ns)
+                        .is_some_and(|rest| rest.starts_with('.'))
+                });
+                let namespace = match qualified {
+                    Some(namespace) => namespace,
+                    None if expression.kind() == "identifier" => {
+                        self.type_namespace(&receiver, ctx)?
+                    }
+                    None => return None,
+                };
+                Some(CallInfo {
+                    target: namespace.to_string(),
+                    method,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Namespace of a bare receiver that names a type: PascalCase, not declared in this
+    /// file, and either a known framework type whose namespace is imported or any other type
+    /// when ctx.type_namespace is unambiguous
+    fn type_namespace<'a>(&self, receiver: &str, ctx: &CallContext<'a>) -> Option<&'a str> {
+        if !receiver.starts_with(|c: char| c.is_ascii_uppercase())
+            || ctx.local_names.contains(receiver)
+        {
+            return None;
+        }
+        match FRAMEWORK_TYPES.iter().find(|(ty, _)| *ty == receiver) {
+            Some((_, namespace)) => ctx.namespaces.iter().copied().find(|ns| ns == namespace),
+            None => ctx.type_namespace,
+        }
+    }
+
+    /// Name without type arguments: Deserialize<T> -> Deserialize
+    fn simple_name(&self, node: tree_sitter::Node, source: &str) -> Option<String> {
+        match node.kind() {
+            "identifier" => Some(self.node_text(node, source)),
+            "generic_name" => {
+                let ident = node.child(0)?;
+                Some(self.node_text(ident, source))
+            }
+            _ => None,
+        }
+    }
+
     fn node_text(&self, node: tree_sitter::Node, source: &str) -> String {
         source[node.start_byte()..node.end_byte()].to_string()
     }
 }
 
+/// Import lookups used while attributing invocations to using directives
+struct CallContext<'a> {
+    aliases: HashMap<&'a str, &'a str>,
+    namespaces: Vec<&'a str>,
+    static_class: Option<&'a str>,
+    /// Namespace for type receivers that are not in FRAMEWORK_TYPES, when unambiguous
+    type_namespace: Option<&'a str>,
+    local_methods: HashSet<String>,
+    local_names: HashSet<String>,
+}
+
+/// Framework types called statically often enough that guessing their namespace from the
+/// usings in scope would mislead
+const FRAMEWORK_TYPES: &[(&str, &str)] = &[
+    ("Activator", "System"),
+    ("Array", "System"),
+    ("Console", "System"),
+    ("Convert", "System"),
+    ("DateTime", "System"),
+    ("Environment", "System"),
+    ("Guid", "System"),
+    ("Math", "System"),
+    ("String", "System"),
+    ("TimeSpan", "System"),
+    ("Directory", "System.IO"),
+    ("File", "System.IO"),
+    ("Path", "System.IO"),
+    ("Enumerable", "System.Linq"),
+    ("Debug", "System.Diagnostics"),
+    ("Process", "System.Diagnostics"),
+    ("JsonSerializer", "System.Text.Json"),
+    ("Regex", "System.Text.RegularExpressions"),
+    ("Task", "System.Threading.Tasks"),
+];
+
 impl Default for CSharpParser {
     fn default() -> Self {
         Self::new()
@@ -237,6 +843,8 @@ impl LanguageParser for CSharpParser {
 
         let exports = self.extract_exports(root, source);
         let imports = self.extract_imports(root, source);
+        let calls = self.extract_calls(root, source, &imports);
+        let signatures = self.extract_signatures(root, source, &exports);
 
         let tokens = super::tokens::count_tokens(source);
 
@@ -244,8 +852,8 @@ impl LanguageParser for CSharpParser {
             tokens,
             exports,
             imports,
-            calls: Vec::new(),
-            signatures: Vec::new(),
+            calls,
+            signatures,
         })
     }
 
@@ -254,8 +862,11 @@ impl LanguageParser for CSharpParser {
 
         // Find @dose in /** */ block comments
         let block_pattern = Regex::new(r"/\*\*[\s\S]*?@dose[\s\S]*?\*/").unwrap();
+        let file_block = block_pattern
+            .find_iter(source)
+            .find(|m| toon_comment::parse_inline_annotation(m.as_str()).is_none());
 
-        if let Some(mat) = block_pattern.find(source) {
+        if let Some(mat) = file_block {
             let comment = mat.as_str();
             let content = comment.trim_start_matches("/**").trim_end_matches("*/");
 
@@ -291,6 +902,36 @@ impl LanguageParser for CSharpParser {
             }
         }
 
+        // Inline /// @dose invariant: value annotations on the following member
+        let mut parser = self.create_parser()?;
+        if let Some(tree) = parser.parse(source, None) {
+            result.function_annotations = toon_comment::collect_inline_annotations(
+                tree.root_node(),
+                source,
+                &["comment"],
+                |node| match node.kind() {
+                    "method_declaration"
+                    | "constructor_declaration"
+                    | "local_function_statement"
+                    | "property_declaration"
+                    | "class_declaration"
+                    | "struct_declaration"
+                    | "interface_declaration"
+                    | "record_declaration"
+                    | "enum_declaration" => node
+                        .child_by_field_name("name")
+                        .map(|n| self.node_text(n, source)),
+                    _ => None,
+                },
+                |node| match node.kind() {
+                    kind if TYPE_DECLARATIONS.contains(&kind) => node
+                        .child_by_field_name("name")
+                        .map(|n| self.node_text(n, source)),
+                    _ => None,
+                },
+            );
+        }
+
         Ok(result)
     }
 
@@ -409,6 +1050,225 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_extract_signatures() {
+        let parser = CSharpParser::new();
+        let info = parser
+            .extract_ast_info(CS_FIXTURE, Path::new("sample.cs"))
+            .unwrap();
+
+        let sig = |name: &str| {
+            info.signatures
+                .iter()
+                .find(|s| s.name == name)
+                .unwrap_or_else(|| panic!("missing signature for {}", name))
+        };
+
+        let get_by_id = sig("UserService.GetByIdAsync");
+        assert_eq!(get_by_id.kind, "async method");
+        assert_eq!(get_by_id.signature, "(string id) -> Task<UserConfig?>");
+        assert_eq!((get_by_id.start_line, get_by_id.end_line), (71, 91));
+
+        assert_eq!(sig("UserService.DeleteAsync").kind, "method");
+        assert_eq!(
+            sig("StringExtensions.Truncate").signature,
+            "(this string str, int maxLength) -> string"
+        );
+
+        let record = sig("UserConfig");
+        assert_eq!(record.kind, "record");
+        assert_eq!(
+            record.signature,
+            "(string Id, string Name, string? Email = null, Dictionary<string, object>? Settings = null)"
+        );
+        assert_eq!((record.start_line, record.end_line), (52, 57));
+
+        let repo = sig("IRepository");
+        assert_eq!(repo.kind, "interface");
+        assert!(repo
+            .signature
+            .starts_with("<T> where T : class { GetByIdAsync(string id) -> Task<T?>;"));
+
+        let service = sig("UserService");
+        assert!(service.signature.starts_with(
+            ": IRepository<UserConfig> { new(string dataDir = \"data\"); GetByIdAsync"
+        ));
+
+        assert_eq!(
+            sig("BaseEntity").signature,
+            "{ Id: string; CreatedAt: DateTime; UpdatedAt: DateTime? }"
+        );
+        assert_eq!(
+            sig("Constants").signature,
+            "{ Version: string; DefaultTimeout: int; MaxRetries: int }"
+        );
+        assert_eq!(
+            sig("UserStatus").signature,
+            "{ Active | Inactive | Pending | Suspended }"
+        );
+
+        // Get/Set on the internal CacheManager are public methods of a
+        // non-public type; they still get method entries, but only once each
+        assert_eq!(
+            info.signatures
+                .iter()
+                .filter(|s| s.name == "CacheManager.Get")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_extract_calls() {
+        let parser = CSharpParser::new();
+        let source = r#"
+using System.IO;
+using static System.Math;
+using Json = Newtonsoft.Json.JsonConvert;
+
+public class Calc
+{
+    public double Run(string p)
+    {
+        var text = Json.SerializeObject(p);
+        System.IO.File.Exists(p);
+        Helper();
+        return Abs(Max(1, 2));
+    }
+
+    private void Helper() {}
+}
+"#;
+        let info = parser
+            .extract_ast_info(source, Path::new("Calc.cs"))
+            .unwrap();
+
+        let mut imports: Vec<_> = info
+            .imports
+            .iter()
+            .map(|i| (&i.from[..], i.items.join("|")))
+            .collect();
+        imports.sort();
+        assert_eq!(
+            imports,
+            vec![
+                ("Newtonsoft.Json.JsonConvert", "Json".to_string()),
+                ("System.IO", "System.IO".to_string()),
+                ("System.Math", "*".to_string()),
+            ]
+        );
+
+        let mut calls: Vec<_> = info
+            .calls
+            .iter()
+            .map(|c| (&c.target[..], &c.method[..]))
+            .collect();
+        calls.sort();
+        assert_eq!(
+            calls,
+            vec![
+                ("Newtonsoft.Json.JsonConvert", "SerializeObject"),
+                ("System.IO", "Exists"),
+                ("System.Math", "Abs"),
+                ("System.Math", "Max"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_calls_on_imported_types() {
+        let parser = CSharpParser::new();
+        let source = r#"
+using System;
+using System.IO;
+using MyApp.Models;
+
+public class Importer
+{
+    public string Name { get; set; }
+    private List<Order> Orders = new();
+
+    public void Run(string p)
+    {
+        if (File.Exists(p)) Console.WriteLine(p);
+        var order = Order.Create(p);
+        Orders.Add(order);
+        Name.Trim();
+        Importer.Reset();
+        order.Save();
+    }
+
+    public static void Reset() {}
+}
+"#;
+        let info = parser
+            .extract_ast_info(source, Path::new("Importer.cs"))
+            .unwrap();
+
+        let mut calls: Vec<_> = info
+            .calls
+            .iter()
+            .map(|c| (&c.target[..], &c.method[..]))
+            .collect();
+        calls.sort();
+        // Framework types map to their own namespace, other types to the one project
+        // namespace; members and types declared here and instance receivers are skipped
+        assert_eq!(
+            calls,
+            vec![
+                ("MyApp.Models", "Create"),
+                ("System", "WriteLine"),
+                ("System.IO", "Exists"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_calls_ambiguous_type_receiver() {
+        let parser = CSharpParser::new();
+        let source = r#"
+using MyApp.Models;
+using MyApp.Services;
+
+public class Checkout
+{
+    public void Run() { Order.Create(); }
+}
+"#;
+        let info = parser
+            .extract_ast_info(source, Path::new("Checkout.cs"))
+            .unwrap();
+        assert!(info.calls.is_empty());
+    }
+
+    #[test]
+    fn test_inline_annotations() {
+        let parser = CSharpParser::new();
+        let source = r#"/** @dose
+purpose: Orders
+*/
+public class OrderService
+{
+    /// @dose invariant: total is never negative
+    [Obsolete]
+    public decimal Total(Order order) => 0;
+
+    /** @dose error-handling: throws on unknown SKU */
+    public void Add(string sku) {}
+}
+"#;
+        let comments = parser.extract_toon_comments(source).unwrap();
+        assert_eq!(comments.file_block.unwrap().purpose.unwrap(), "Orders");
+        assert_eq!(
+            comments.function_annotations["OrderService.Total"].invariants,
+            Some(vec!["total is never negative".to_string()])
+        );
+        assert_eq!(
+            comments.function_annotations["OrderService.Add"].error_handling,
+            Some(vec!["throws on unknown SKU".to_string()])
+        );
+    }
+
     #[test]
     fn test_toon_comments() {
         let parser = CSharpParser::new();
@@ -0,0 +1,871 @@
+//! @dose
+//! purpose: This module parses Elixir source files to extract modules, public functions
+//!     and macros, alias/import/use/require directives, and remote calls. Everything in
+//!     Elixir is a call in the tree-sitter grammar, so definitions are recognized by the
+//!     name of the called macro (defmodule, def, defmacro, ...).
+//!
+//! when-editing:
+//!     - !Functions are identified by name/arity, matching how Elixir itself names them
+//!     - !Nested defmodule names are prefixed with the enclosing module's name
+//!     - !Calls resolve their module through alias/require before being recorded
+//!
+//! invariants:
+//!     - def, defmacro, defguard, and defdelegate export; their p-suffixed forms never do
+//!     - Multi-clause functions export once, with the signature of their first clause
+//!     - Piped calls count the piped value towards their arity
+//!
+//! do-not:
+//!     - Never record local (unqualified) calls unless they come from import ..., only:
+//!     - Never record calls on Erlang modules (:crypto) or __MODULE__
+//!
+//! gotchas:
+//!     - `def foo do` has an identifier head, `def foo(a) do` a call head, and guards
+//!       wrap the head in a `when` binary_operator
+//!     - Single-segment modules (String, Enum) are only recorded when aliased or required,
+//!       which keeps the standard library out of the calls list
+//!     - Default arguments (a \\ 1) define several arities; only the full arity is listed
+//!
+//! flows:
+//!     - Parse: Create tree-sitter parser, set Elixir language, parse source
+//!     - Definitions: Walk defmodule bodies collecting modules and function heads
+//!     - Imports: alias (including the {A, B} multi-alias form), import, use, and require
+//!     - Calls: Remote calls on aliases and imported functions, with arity
+//!     - Comments: @moduledoc with @dose, or a # @dose comment block
+
+use crate::parser::{nodes, toon_comment, LanguageParser, ParseError};
+use crate::types::{ASTInfo, CallInfo, ExportInfo, ExtractedComments, ImportInfo, SignatureInfo};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tree_sitter::{Node, Parser};
+
+/// Parser for Elixir files
+#[derive(Clone)]
+pub struct ElixirParser;
+
+/// A module or function definition found while walking defmodule bodies
+struct Definition<'a> {
+    node: Node<'a>,
+    name: String,
+    kind: &'static str,
+    public: bool,
+    /// Parameter list for functions, e.g. `(id) when is_binary(id)`
+    params: String,
+    /// Enclosing module, for summarizing module members
+    module: Option<String>,
+}
+
+impl ElixirParser {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn create_parser(&self) -> Result<Parser, ParseError> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_elixir::LANGUAGE.into())
+            .map_err(|e| ParseError::ParseError(e.to_string()))?;
+        Ok(parser)
+    }
+
+    fn node_text<'a>(&self, node: Node, source: &'a str) -> &'a str {
+        &source[node.start_byte()..node.end_byte()]
+    }
+
+    /// The macro a call invokes (`def`, `alias`, ...) when its target is a bare identifier
+    fn call_keyword<'a>(&self, node: Node, source: &'a str) -> Option<&'a str> {
+        if node.kind() != "call" {
+            return None;
+        }
+        let target = node.child_by_field_name("target")?;
+        (target.kind() == "identifier").then(|| self.node_text(target, source))
+    }
+
+    fn arguments<'a>(&self, node: Node<'a>) -> Vec<Node<'a>> {
+        let mut cursor = node.walk();
+        let args = node.children(&mut cursor).find(|c| c.kind() == "arguments");
+        args.map(|args| {
+            let mut cursor = args.walk();
+            args.named_children(&mut cursor).collect()
+        })
+        .unwrap_or_default()
+    }
+
+    fn do_block<'a>(&self, node: Node<'a>) -> Option<Node<'a>> {
+        let mut cursor = node.walk();
+        let found = node.children(&mut cursor).find(|c| c.kind() == "do_block");
+        found
+    }
+
+    /// Number of arguments a call passes, counting a do block and a piped value
+    fn call_arity(&self, node: Node, source: &str) -> usize {
+        let mut arity = self.arguments(node).len();
+        if self.do_block(node).is_some() {
+            arity += 1;
+        }
+        let piped = node.parent().is_some_and(|parent| {
+            parent.kind() == "binary_operator"
+                && parent
+                    .child_by_field_name("operator")
+                    .is_some_and(|op| self.node_text(op, source) == "|>")
+                && parent
+                    .child_by_field_name("right")
+                    .is_some_and(|right| right.id() == node.id())
+        });
+        if piped {
+            arity += 1;
+        }
+        arity
+    }
+
+    /// Name, arity, and parameter text of a def head: `foo`, `foo(a, b)`, or `foo(a) when a > 0`
+    fn function_head(&self, head: Node, source: &str) -> Option<(String, usize, String)> {
+        match head.kind() {
+            "identifier" => Some((
+                self.node_text(head, source).to_string(),
+                0,
+                "()".to_string(),
+            )),
+            "call" => {
+                let name = self.node_text(head.child_by_field_name("target")?, source);
+                let params = {
+                    let mut cursor = head.walk();
+                    let args = head.children(&mut cursor).find(|c| c.kind() == "arguments");
+                    args.map(|a| nodes::fold_whitespace(self.node_text(a, source)))
+                        .unwrap_or_else(|| "()".to_string())
+                };
+                Some((name.to_string(), self.arguments(head).len(), params))
+            }
+            "binary_operator" => {
+                let operator = head.child_by_field_name("operator")?;
+                if self.node_text(operator, source) != "when" {
+                    return None;
+                }
+                let (name, arity, params) =
+                    self.function_head(head.child_by_field_name("left")?, source)?;
+                let guard = nodes::fold_whitespace(
+                    self.node_text(head.child_by_field_name("right")?, source),
+                );
+                Some((name, arity, format!("{} when {}", params, guard)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Full name of a defmodule, nested modules included: `MyApp.Accounts.Token`
+    fn module_path(&self, node: Node, source: &str) -> Option<String> {
+        let mut path = vec![self.node_text(*self.arguments(node).first()?, source)];
+        let mut ancestor = node.parent();
+        while let Some(parent) = ancestor {
+            if self.call_keyword(parent, source) == Some("defmodule") {
+                path.push(self.node_text(*self.arguments(parent).first()?, source));
+            }
+            ancestor = parent.parent();
+        }
+        path.reverse();
+        Some(path.join("."))
+    }
+
+    /// Collect modules and function heads, descending into defmodule bodies only
+    fn collect_definitions<'a>(
+        &self,
+        node: Node<'a>,
+        source: &str,
+        module: Option<&str>,
+        definitions: &mut Vec<Definition<'a>>,
+    ) {
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            let Some(keyword) = self.call_keyword(child, source) else {
+                continue;
+            };
+            let args = self.arguments(child);
+            let Some(first) = args.first() else {
+                continue;
+            };
+
+            match keyword {
+                "defmodule" | "defprotocol" => {
+                    let own = self.node_text(*first, source);
+                    let name = match module {
+                        Some(parent) => format!("{}.{}", parent, own),
+                        None => own.to_string(),
+                    };
+                    definitions.push(Definition {
+                        node: child,
+                        name: name.clone(),
+                        kind: if keyword == "defmodule" {
+                            "module"
+                        } else {
+                            "protocol"
+                        },
+                        public: true,
+                        params: String::new(),
+                        module: module.map(str::to_string),
+                    });
+                    if let Some(body) = self.do_block(child) {
+                        self.collect_definitions(body, source, Some(&name), definitions);
+                    }
+                }
+                "def" | "defp" | "defmacro" | "defmacrop" | "defguard" | "defguardp"
+                | "defdelegate" => {
+                    let Some((name, arity, params)) = self.function_head(*first, source) else {
+                        continue;
+                    };
+                    definitions.push(Definition {
+                        node: child,
+                        name: format!("{}/{}", name, arity),
+                        kind: match keyword {
+                            "defmacro" | "defmacrop" => "macro",
+                            "defguard" | "defguardp" => "guard",
+                            _ => "fn",
+                        },
+                        public: !keyword.ends_with('p'),
+                        params,
+                        module: module.map(str::to_string),
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn extract_exports(&self, definitions: &[Definition]) -> Vec<ExportInfo> {
+        let mut seen = HashSet::new();
+        definitions
+            .iter()
+            .filter(|d| d.public && seen.insert(d.name.clone()))
+            .map(|d| ExportInfo {
+                name: d.name.clone(),
+                kind: d.kind.to_string(),
+            })
+            .collect()
+    }
+
+    fn extract_imports(&self, root: Node, source: &str) -> Vec<ImportInfo> {
+        let mut imports = Vec::new();
+        self.visit_imports(root, source, &mut imports);
+        imports
+    }
+
+    fn visit_imports(&self, node: Node, source: &str, imports: &mut Vec<ImportInfo>) {
+        if let Some(keyword) = self.call_keyword(node, source) {
+            if matches!(keyword, "alias" | "import" | "use" | "require") {
+                let args = self.arguments(node);
+                let option = |key: &str| self.keyword_option(&args, key, source);
+                match args.first().map(|a| (a.kind(), *a)) {
+                    // alias MyApp.Accounts.{User, Token}
+                    Some(("dot", dot)) if keyword == "alias" => {
+                        let left = dot.child_by_field_name("left");
+                        let right = dot.child_by_field_name("right");
+                        if let (Some(left), Some(tuple)) = (left, right) {
+                            let prefix = self.node_text(left, source);
+                            let mut cursor = tuple.walk();
+                            for alias in tuple.named_children(&mut cursor) {
+                                let alias = self.node_text(alias, source);
+                                imports.push(ImportInfo {
+                                    from: format!("{}.{}", prefix, alias),
+                                    items: vec![last_segment(alias).to_string()],
+                                });
+                            }
+                        }
+                    }
+                    Some(("alias", module)) => {
+                        let from = self.node_text(module, source).to_string();
+                        let items = match keyword {
+                            "alias" | "require" => vec![option("as")
+                                .map(|a| self.node_text(a, source).to_string())
+                                .unwrap_or_else(|| last_segment(&from).to_string())],
+                            // import Ecto.Query, only: [from: 2]
+                            "import" => option("only")
+                                .map(|only| self.function_list(only, source))
+                                .filter(|items| !items.is_empty())
+                                .unwrap_or_else(|| vec!["*".to_string()]),
+                            _ => vec!["*".to_string()],
+                        };
+                        imports.push(ImportInfo { from, items });
+                    }
+                    _ => {}
+                }
+                return;
+            }
+        }
+
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.visit_imports(child, source, imports);
+        }
+    }
+
+    /// Value of `key:` in a call's trailing keyword list
+    fn keyword_option<'a>(&self, args: &[Node<'a>], key: &str, source: &str) -> Option<Node<'a>> {
+        let keywords = args.iter().find(|a| a.kind() == "keywords")?;
+        let mut cursor = keywords.walk();
+        let pair = keywords.named_children(&mut cursor).find(|pair| {
+            pair.child_by_field_name("key")
+                .is_some_and(|k| self.node_text(k, source).trim().trim_end_matches(':') == key)
+        })?;
+        pair.child_by_field_name("value")
+    }
+
+    /// `[from: 2, where: 3]` -> ["from/2", "where/3"]
+    fn function_list(&self, list: Node, source: &str) -> Vec<String> {
+        let mut items = Vec::new();
+        let mut stack = vec![list];
+        while let Some(node) = stack.pop() {
+            if node.kind() == "pair" {
+                let key = node.child_by_field_name("key");
+                let value = node.child_by_field_name("value");
+                if let (Some(key), Some(value)) = (key, value) {
+                    let name = self.node_text(key, source).trim().trim_end_matches(':');
+                    items.push(format!("{}/{}", name, self.node_text(value, source)));
+                }
+                continue;
+            }
+            let mut cursor = node.walk();
+            let children: Vec<_> = node.named_children(&mut cursor).collect();
+            stack.extend(children.into_iter().rev());
+        }
+        items
+    }
+
+    fn extract_calls(&self, root: Node, source: &str, imports: &[ImportInfo]) -> Vec<CallInfo> {
+        // Alias/require name -> module, and name/arity -> module for import ..., only:
+        let mut aliases: HashMap<&str, &str> = HashMap::new();
+        let mut imported: HashMap<&str, &str> = HashMap::new();
+        for import in imports {
+            for item in &import.items {
+                if item.contains('/') {
+                    imported.insert(item, &import.from);
+                } else if item != "*" {
+                    aliases.insert(item, &import.from);
+                }
+            }
+        }
+
+        let mut calls = Vec::new();
+        let mut seen: HashSet<(String, String)> = HashSet::new();
+        self.visit_calls(root, source, &aliases, &imported, &mut calls, &mut seen);
+        calls
+    }
+
+    fn visit_calls(
+        &self,
+        node: Node,
+        source: &str,
+        aliases: &HashMap<&str, &str>,
+        imported: &HashMap<&str, &str>,
+        calls: &mut Vec<CallInfo>,
+        seen: &mut HashSet<(String, String)>,
+    ) {
+        if node.kind() == "call" {
+            if let Some(call) = self.parse_call(node, source, aliases, imported) {
+                if seen.insert((call.target.clone(), call.method.clone())) {
+                    calls.push(call);
+                }
+            }
+        }
+
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.visit_calls(child, source, aliases, imported, calls, seen);
+        }
+    }
+
+    fn parse_call(
+        &self,
+        node: Node,
+        source: &str,
+        aliases: &HashMap<&str, &str>,
+        imported: &HashMap<&str, &str>,
+    ) -> Option<CallInfo> {
+        let target = node.child_by_field_name("target")?;
+        let arity = self.call_arity(node, source);
+
+        match target.kind() {
+            // Repo.insert(changeset)
+            "dot" => {
+                let module = target.child_by_field_name("left")?;
+                let function = target.child_by_field_name("right")?;
+                if module.kind() != "alias" || function.kind() != "identifier" {
+                    return None;
+                }
+                let module = self.node_text(module, source);
+                let (head, rest) = match module.split_once('.') {
+                    Some((head, rest)) => (head, Some(rest)),
+                    None => (module, None),
+                };
+                let resolved = match (aliases.get(head), rest) {
+                    (Some(full), Some(rest)) => format!("{}.{}", full, rest),
+                    (Some(full), None) => full.to_string(),
+                    // Fully qualified modules are explicit dependencies
+                    (None, Some(_)) => module.to_string(),
+                    (None, None) => return None,
+                };
+                Some(CallInfo {
+                    target: resolved,
+                    method: format!("{}/{}", self.node_text(function, source), arity),
+                })
+            }
+            // from(u in User, ...) after import Ecto.Query, only: [from: 2]
+            "identifier" => {
+                let method = format!("{}/{}", self.node_text(target, source), arity);
+                let module = imported.get(method.as_str())?;
+                Some(CallInfo {
+                    target: module.to_string(),
+                    method,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn extract_signatures(
+        &self,
+        definitions: &[Definition],
+        source: &str,
+        exports: &[ExportInfo],
+    ) -> Vec<SignatureInfo> {
+        let export_names: HashSet<&str> = exports.iter().map(|e| e.name.as_str()).collect();
+        let mut seen = HashSet::new();
+        let mut signatures = Vec::new();
+
+        for definition in definitions {
+            // Functions are Module.name/arity, as the fn: annotations name them
+            let name = match (definition.kind, &definition.module) {
+                ("module" | "protocol", _) | (_, None) => definition.name.clone(),
+                (_, Some(module)) => format!("{}.{}", module, definition.name),
+            };
+            if !definition.public
+                || !export_names.contains(definition.name.as_str())
+                || !seen.insert(name.clone())
+            {
+                continue;
+            }
+
+            let signature = match definition.kind {
+                "module" | "protocol" => self.module_summary(definition, definitions, source),
+                _ => definition.params.clone(),
+            };
+
+            signatures.push(SignatureInfo {
+                name,
+                kind: definition.kind.to_string(),
+                signature,
+                start_line: definition.node.start_position().row + 1,
+                end_line: definition.node.end_position().row + 1,
+            });
+        }
+        signatures
+    }
+
+    /// `{ defstruct [:id]; get/1; put/2 }` for a module's struct and public functions
+    fn module_summary(
+        &self,
+        module: &Definition,
+        definitions: &[Definition],
+        source: &str,
+    ) -> String {
+        let mut members = Vec::new();
+
+        if let Some(body) = self.do_block(module.node) {
+            let mut cursor = body.walk();
+            let defstruct = body
+                .named_children(&mut cursor)
+                .find(|c| self.call_keyword(*c, source) == Some("defstruct"));
+            if let Some(defstruct) = defstruct {
+                members.push(nodes::fold_whitespace(self.node_text(defstruct, source)));
+            }
+        }
+
+        let mut seen = HashSet::new();
+        for member in definitions {
+            if member.module.as_deref() != Some(module.name.as_str())
+                || !member.public
+                || member.kind == "module"
+                || !seen.insert(member.name.as_str())
+            {
+                continue;
+            }
+            members.push(member.name.clone());
+            if members.len() >= 5 {
+                members.push("...".to_string());
+                break;
+            }
+        }
+
+        format!("{{ {} }}", members.join("; "))
+    }
+}
+
+/// `MyApp.Accounts.User` -> `User`
+fn last_segment(module: &str) -> &str {
+    module.rsplit('.').next().unwrap_or(module)
+}
+
+impl Default for ElixirParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguageParser for ElixirParser {
+    fn language_name(&self) -> &'static str {
+        "elixir"
+    }
+
+    fn file_extensions(&self) -> &[&'static str] {
+        &["ex", "exs"]
+    }
+
+    fn extract_ast_info(&self, source: &str, _file_path: &Path) -> Result<ASTInfo, ParseError> {
+        let mut parser = self.create_parser()?;
+        let tree = parser
+            .parse(source, None)
+            .ok_or_else(|| ParseError::ParseError("Failed to parse source".to_string()))?;
+
+        let root = tree.root_node();
+
+        let mut definitions = Vec::new();
+        self.collect_definitions(root, source, None, &mut definitions);
+
+        let exports = self.extract_exports(&definitions);
+        let imports = self.extract_imports(root, source);
+        let calls = self.extract_calls(root, source, &imports);
+        let signatures = self.extract_signatures(&definitions, source, &exports);
+
+        let tokens = super::tokens::count_tokens(source);
+
+        Ok(ASTInfo {
+            tokens,
+            exports,
+            imports,
+            calls,
+            signatures,
+        })
+    }
+
+    fn extract_toon_comments(&self, source: &str) -> Result<ExtractedComments, ParseError> {
+        let mut result = ExtractedComments::default();
+
+        // Find @dose in a @moduledoc heredoc
+        let moduledoc_pattern = Regex::new(r#"@moduledoc\s+(?:~[sS])?"""([\s\S]*?)""""#).unwrap();
+        if let Some(caps) = moduledoc_pattern
+            .captures_iter(source)
+            .find(|caps| caps[1].contains("@dose"))
+        {
+            let content = caps[1]
+                .lines()
+                .map(|line| {
+                    let trimmed = line.trim();
+                    if trimmed == "@dose" {
+                        ""
+                    } else {
+                        trimmed
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            result.file_block = Some(toon_comment::parse_toon_block(&content));
+        }
+
+        // Also check for # @dose comment blocks
+        if result.file_block.is_none() {
+            let comment_pattern =
+                Regex::new(r"(?m)^[ \t]*#\s*@dose[ \t]*\n((?:[ \t]*#[^\n]*\n)*)").unwrap();
+            if let Some(block) = comment_pattern.captures(source).and_then(|c| c.get(1)) {
+                let content = block
+                    .as_str()
+                    .lines()
+                    .map(|line| line.trim().trim_start_matches('#').trim())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                result.file_block = Some(toon_comment::parse_toon_block(&content));
+            }
+        }
+
+        // Inline # @dose invariant: value annotations on the following def
+        let mut parser = self.create_parser()?;
+        if let Some(tree) = parser.parse(source, None) {
+            result.function_annotations = toon_comment::collect_inline_annotations(
+                tree.root_node(),
+                source,
+                &["comment"],
+                |node| match self.call_keyword(node, source)? {
+                    "def" | "defp" | "defmacro" | "defmacrop" | "defguard" | "defguardp"
+                    | "defdelegate" => {
+                        let head = *self.arguments(node).first()?;
+                        self.function_head(head, source)
+                            .map(|(name, arity, _)| format!("{}/{}", name, arity))
+                    }
+                    "defmodule" => self
+                        .arguments(node)
+                        .first()
+                        .map(|n| self.node_text(*n, source).to_string()),
+                    _ => None,
+                },
+                |node| match self.call_keyword(node, source)? {
+                    "defmodule" => self.module_path(node, source),
+                    _ => None,
+                },
+            );
+        }
+
+        Ok(result)
+    }
+
+    fn strip_toon_comments(&self, source: &str, toon_path: &str) -> Result<String, ParseError> {
+        let mut result = source.to_string();
+
+        // Replace @moduledoc heredocs holding a @dose block
+        let moduledoc_pattern =
+            Regex::new(r#"@moduledoc\s+(?:~[sS])?"""[\s\S]*?@dose[\s\S]*?""""#).unwrap();
+        result = moduledoc_pattern
+            .replace_all(&result, format!("# @dose -> {}", toon_path).as_str())
+            .to_string();
+
+        // Replace # @dose comment blocks, keeping their indentation
+        let comment_pattern =
+            Regex::new(r"(?m)^([ \t]*)#\s*@dose[ \t]*\n(?:[ \t]*#[^\n]*\n)*").unwrap();
+        result = comment_pattern
+            .replace_all(
+                &result,
+                format!("${{1}}# @dose -> {}\n", toon_path).as_str(),
+            )
+            .to_string();
+
+        Ok(result)
+    }
+
+    fn get_string_ranges(&self, source: &str) -> Result<Vec<(usize, usize)>, ParseError> {
+        let mut parser = self.create_parser()?;
+
+        let tree = parser
+            .parse(source, None)
+            .ok_or_else(|| ParseError::ParseError("Failed to parse source".to_string()))?;
+
+        let mut ranges = Vec::new();
+        collect_string_ranges_ex(&mut tree.walk(), &mut ranges);
+        Ok(ranges)
+    }
+}
+
+fn collect_string_ranges_ex(
+    cursor: &mut tree_sitter::TreeCursor,
+    ranges: &mut Vec<(usize, usize)>,
+) {
+    loop {
+        let node = cursor.node();
+        let kind = node.kind();
+        // Heredocs are strings too; sigils (~r/.../, ~s"...") keep their delimiters
+        if kind == "string" || kind == "charlist" || kind == "sigil" {
+            ranges.push((node.start_byte(), node.end_byte()));
+        }
+        if cursor.goto_first_child() {
+            collect_string_ranges_ex(cursor, ranges);
+            cursor.goto_parent();
+        }
+        if !cursor.goto_next_sibling() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EX_FIXTURE: &str = include_str!("../../test_fixtures/sample.ex");
+
+    #[test]
+    fn test_extract_exports() {
+        let parser = ElixirParser::new();
+        let info = parser
+            .extract_ast_info(EX_FIXTURE, Path::new("accounts.ex"))
+            .unwrap();
+
+        let exports: Vec<_> = info
+            .exports
+            .iter()
+            .map(|e| (&e.name[..], &e.kind[..]))
+            .collect();
+        assert_eq!(
+            exports,
+            vec![
+                ("MyApp.Accounts", "module"),
+                ("get_user/1", "fn"),
+                ("create_user/1", "fn"),
+                ("delete_user/1", "fn"),
+                ("with_user/2", "macro"),
+                ("MyApp.Accounts.Token", "module"),
+                ("new/1", "fn"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_imports() {
+        let parser = ElixirParser::new();
+        let info = parser
+            .extract_ast_info(EX_FIXTURE, Path::new("accounts.ex"))
+            .unwrap();
+
+        let imports: Vec<_> = info
+            .imports
+            .iter()
+            .map(|i| (&i.from[..], i.items.join(",")))
+            .collect();
+        assert_eq!(
+            imports,
+            vec![
+                ("MyApp.Repo", "Repo".to_string()),
+                ("MyApp.Accounts.User", "User".to_string()),
+                ("MyApp.Accounts.Token", "Token".to_string()),
+                ("Ecto.Query", "from/2".to_string()),
+                ("MyApp.Context", "*".to_string()),
+                ("Logger", "Logger".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_calls() {
+        let parser = ElixirParser::new();
+        let info = parser
+            .extract_ast_info(EX_FIXTURE, Path::new("accounts.ex"))
+            .unwrap();
+
+        let calls: Vec<_> = info
+            .calls
+            .iter()
+            .map(|c| (&c.target[..], &c.method[..]))
+            .collect();
+        // String.downcase is not aliased; :crypto is an Erlang module
+        assert_eq!(
+            calls,
+            vec![
+                ("MyApp.Repo", "get/2"),
+                ("MyApp.Accounts.User", "changeset/2"),
+                ("MyApp.Repo", "insert/1"),
+                ("Logger", "info/1"),
+                ("MyApp.Repo", "delete/1"),
+            ]
+        );
+
+        let source = "defmodule Q do\n  import Ecto.Query, only: [from: 2]\n  alias MyApp.Billing\n\n  def q, do: from(u in \"users\", select: u)\n  def b, do: Billing.Invoice.total(1)\n  def c, do: Phoenix.PubSub.broadcast(MyApp.PubSub, \"t\", :msg)\nend\n";
+        let info = parser.extract_ast_info(source, Path::new("q.ex")).unwrap();
+        let calls: Vec<_> = info
+            .calls
+            .iter()
+            .map(|c| (&c.target[..], &c.method[..]))
+            .collect();
+        assert_eq!(
+            calls,
+            vec![
+                ("Ecto.Query", "from/2"),
+                ("MyApp.Billing.Invoice", "total/1"),
+                ("Phoenix.PubSub", "broadcast/3"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_signatures() {
+        let parser = ElixirParser::new();
+        let info = parser
+            .extract_ast_info(EX_FIXTURE, Path::new("accounts.ex"))
+            .unwrap();
+
+        let sig = |name: &str| {
+            info.signatures
+                .iter()
+                .find(|s| s.name == name)
+                .unwrap_or_else(|| panic!("missing signature for {}", name))
+        };
+
+        assert_eq!(sig("MyApp.Accounts.get_user/1").signature, "(id)");
+        assert_eq!(
+            sig("MyApp.Accounts.create_user/1").signature,
+            "(attrs \\\\ %{})"
+        );
+        assert_eq!(
+            (
+                sig("MyApp.Accounts.create_user/1").start_line,
+                sig("MyApp.Accounts.create_user/1").end_line
+            ),
+            (24, 28)
+        );
+        // First clause wins
+        assert_eq!(
+            sig("MyApp.Accounts.delete_user/1").signature,
+            "(%User{} = user)"
+        );
+        assert_eq!(sig("MyApp.Accounts.with_user/2").kind, "macro");
+        assert_eq!(
+            sig("MyApp.Accounts").signature,
+            "{ get_user/1; create_user/1; delete_user/1; with_user/2 }"
+        );
+        assert_eq!(
+            sig("MyApp.Accounts.Token").signature,
+            "{ defstruct [:value, :user_id]; new/1 }"
+        );
+
+        assert_eq!(sig("MyApp.Accounts.Token.new/1").signature, "(user)");
+
+        let guarded = "defmodule G do\n  def pos(n) when n > 0, do: n\nend\n";
+        let info = parser.extract_ast_info(guarded, Path::new("g.ex")).unwrap();
+        assert_eq!(info.signatures[1].signature, "(n) when n > 0");
+    }
+
+    #[test]
+    fn test_toon_comments() {
+        let parser = ElixirParser::new();
+
+        let comments = parser.extract_toon_comments(EX_FIXTURE).unwrap();
+        let block = comments.file_block.unwrap();
+        assert_eq!(
+            block.purpose.unwrap(),
+            "Sample Elixir fixture for testing the luny Elixir parser. Manages user accounts on top of the Repo."
+        );
+        assert_eq!(
+            block.invariants,
+            Some(vec!["Emails are stored lowercased".to_string()])
+        );
+        assert_eq!(
+            comments.function_annotations["MyApp.Accounts.create_user/1"].invariants,
+            Some(vec!["email is normalized before insert".to_string()])
+        );
+
+        let stripped = parser
+            .strip_toon_comments(EX_FIXTURE, "lib/accounts.ex.toon")
+            .unwrap();
+        assert!(stripped.starts_with(
+            "defmodule MyApp.Accounts do\n  # @dose -> lib/accounts.ex.toon\n\n  alias MyApp.Repo"
+        ));
+
+        let nested = "defmodule A do\n  defmodule B do\n    # @dose invariant: ok\n    def run(x), do: x\n  end\nend\n";
+        let comments = parser.extract_toon_comments(nested).unwrap();
+        assert!(comments.function_annotations.contains_key("A.B.run/1"));
+
+        let source = "# @dose\n# purpose: Release tasks\n\ndefmodule MyApp.Release do\nend\n";
+        let comments = parser.extract_toon_comments(source).unwrap();
+        assert_eq!(
+            comments.file_block.unwrap().purpose.unwrap(),
+            "Release tasks"
+        );
+        let stripped = parser.strip_toon_comments(source, "rel.toon").unwrap();
+        assert_eq!(
+            stripped,
+            "# @dose -> rel.toon\n\ndefmodule MyApp.Release do\nend\n"
+        );
+    }
+
+    #[test]
+    fn test_string_ranges() {
+        let parser = ElixirParser::new();
+        let source = "x = \"a  b\"\ny = ~r/a  b/\nz = 'c  d'\n";
+        let ranges = parser.get_string_ranges(source).unwrap();
+        let texts: Vec<_> = ranges.iter().map(|(s, e)| &source[*s..*e]).collect();
+        assert_eq!(texts, vec!["\"a  b\"", "~r/a  b/", "'c  d'"]);
+    }
+}
@@ -10,7 +10,7 @@
 //!
 //! invariants:
 //!     - Only identifiers starting with uppercase letters are considered exports
-//!     - Method signatures include their receiver type in parentheses
+//!     - Method signatures include their receiver type in parentheses and are named Type.Method
 //!     - Import paths are trimmed of quotes and the package name is the last path segment
 //!
 //! do-not:
@@ -20,6 +20,7 @@
 //! gotchas:
 //!     - Go has no explicit export keyword; capitalization determines visibility
 //!     - The @dose block uses /* */ block comments, not // single-line comments
+//!     - "// @dose: key: value" feeds the file block; "// @dose key: value" annotates a function
 //!     - Receiver types may be pointer types (prefixed with *)
 //!     - Const and var declarations can declare multiple identifiers at once
 //!
@@ -28,6 +29,7 @@
 //!     - Extract exports: Walk AST finding function_declaration, method_declaration,
 //!       type_declaration, const_declaration, var_declaration nodes with uppercase names
 //!     - Extract imports: Walk AST finding import_declaration with import_spec children
+//!     - Inline annotations: // @dose field: value binds to the next func, method, or type
 
 use crate::parser::{toon_comment, LanguageParser, ParseError};
 use crate::types::{
@@ -59,6 +61,41 @@ impl GoParser {
         source[node.start_byte()..node.end_byte()].to_string()
     }
 
+    /// Name an inline annotation binds to: funcs, methods, and type specs
+    fn declaration_name(&self, node: Node, source: &str) -> Option<String> {
+        match node.kind() {
+            "function_declaration" | "type_spec" => node
+                .child_by_field_name("name")
+                .map(|n| self.node_text(n, source)),
+            // Methods are not nested in their type, so the receiver supplies the Type. prefix
+            "method_declaration" => {
+                let name = self.node_text(node.child_by_field_name("name")?, source);
+                match self.receiver_type(node, source) {
+                    Some(receiver) => Some(format!("{}.{}", receiver, name)),
+                    None => Some(name),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Base type name of a method receiver: `(p *Pool[T])` -> Pool
+    fn receiver_type(&self, node: Node, source: &str) -> Option<String> {
+        let receiver = node.child_by_field_name("receiver")?;
+        let mut cursor = receiver.walk();
+        let param = receiver
+            .named_children(&mut cursor)
+            .find(|c| c.kind() == "parameter_declaration")?;
+        let mut ty = param.child_by_field_name("type")?;
+        loop {
+            match ty.kind() {
+                "pointer_type" => ty = ty.named_child(0)?,
+                "generic_type" => ty = ty.child_by_field_name("type")?,
+                _ => return Some(self.node_text(ty, source)),
+            }
+        }
+    }
+
     /// Check if identifier is exported (starts with uppercase)
     fn is_exported(&self, name: &str) -> bool {
         name.chars()
@@ -482,8 +519,9 @@ impl GoParser {
         let start_line = node.start_position().row + 1;
         let end_line = node.end_position().row + 1;
 
+        // Methods are Type.name, as the fn: annotations name them
         Some(SignatureInfo {
-            name,
+            name: self.declaration_name(node, source).unwrap_or(name),
             kind: "method".to_string(),
             signature,
             start_line,
@@ -620,8 +658,11 @@ impl LanguageParser for GoParser {
 
         // Match /* @dose ... */ block comments
         let block_pattern = Regex::new(r"/\*\s*@dose\b([\s\S]*?)\*/").unwrap();
+        let file_block = block_pattern
+            .captures_iter(source)
+            .find(|c| toon_comment::parse_inline_annotation(&c[0]).is_none());
 
-        if let Some(captures) = block_pattern.captures(source) {
+        if let Some(captures) = file_block {
             if let Some(content) = captures.get(1) {
                 comments.file_block = Some(toon_comment::parse_toon_block(content.as_str()));
             }
@@ -642,6 +683,18 @@ impl LanguageParser for GoParser {
             }
         }
 
+        // Match // @dose invariant: value annotations on the following declaration
+        let mut parser = self.create_parser()?;
+        if let Some(tree) = parser.parse(source, None) {
+            comments.function_annotations = toon_comment::collect_inline_annotations(
+                tree.root_node(),
+                source,
+                &["comment"],
+                |node| self.declaration_name(node, source),
+                |_| None,
+            );
+        }
+
         Ok(comments)
     }
 
@@ -777,6 +830,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_method_signatures_named_by_receiver() {
+        let info = GoParser::new()
+            .extract_ast_info(GO_FIXTURE, Path::new("sample.go"))
+            .unwrap();
+
+        let get: Vec<_> = info
+            .signatures
+            .iter()
+            .filter(|s| s.name.ends_with("Get"))
+            .map(|s| (&s.name[..], s.start_line))
+            .collect();
+        assert_eq!(get, vec![("UserService.Get", 84), ("Cache.Get", 273)]);
+    }
+
     #[test]
     fn test_toon_comments() {
         let parser = GoParser::new();
@@ -789,4 +857,32 @@ mod tests {
             .unwrap();
         assert!(stripped.starts_with("// @dose -> sample.go.toon"));
     }
+
+    #[test]
+    fn test_inline_annotations() {
+        let parser = GoParser::new();
+        let source = r#"/* @dose
+purpose: Pool
+*/
+package pool
+
+// @dose invariant: never returns a closed conn
+func (p *Pool) Acquire() (*Conn, error) {
+	return nil, nil
+}
+
+// @dose gotcha: zero value is unusable
+type Pool struct{}
+"#;
+        let comments = parser.extract_toon_comments(source).unwrap();
+        assert_eq!(comments.file_block.unwrap().purpose.unwrap(), "Pool");
+        assert_eq!(
+            comments.function_annotations["Pool.Acquire"].invariants,
+            Some(vec!["never returns a closed conn".to_string()])
+        );
+        assert_eq!(
+            comments.function_annotations["Pool"].gotchas,
+            Some(vec!["zero value is unusable".to_string()])
+        );
+    }
 }
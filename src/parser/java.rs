@@ -0,0 +1,1104 @@
+//! @dose
+//! purpose: This module parses Java source files to extract public classes, interfaces,
+//!     enums, records, annotation types, and methods. It uses tree-sitter for robust
+//!     parsing and respects Java visibility modifiers.
+//!
+//! when-editing:
+//!     - !Only items with the "public" modifier are considered exports
+//!     - !Annotations live inside the modifiers node, so signatures read them from there
+//!     - The is_public() helper checks the modifiers node for the "public" keyword
+//!     - visits_members() decides which nodes the export and signature walks descend into
+//!
+//! invariants:
+//!     - Private, protected, and package-private members are never exported
+//!     - Members are only exported when every enclosing type is public
+//!     - Import declarations are captured as imports (static and wildcard included)
+//!     - Calls are only attributed to classes named by a single-type or static import
+//!
+//! do-not:
+//!     - Never export package-private members (Java's default visibility)
+//!     - Never attribute calls through wildcard package imports; the class is unknown
+//!
+//! gotchas:
+//!     - Interface methods are implicitly public but, like C#, are not exported on their own
+//!     - Variable types are tracked per file, not per scope, so shadowed names can misattribute
+//!     - Text blocks (""" ... """) are string_literal nodes in the grammar
+//!
+//! flows:
+//!     - Parse: Create tree-sitter parser, set Java language, parse source
+//!     - Extract exports: Walk AST finding declarations with public modifier
+//!     - Extract imports: Collect import_declaration nodes (static, wildcard, single-type)
+//!     - Inline annotations: // @dose field: value binds to the next member or type
+//!     - Extract signatures: Public methods (as Type.method) with annotations/generics/throws; types summarize public members
+//!     - Extract calls: Attribute invocations on imported classes, typed variables, and static imports
+
+use crate::parser::{nodes, toon_comment, LanguageParser, ParseError};
+use crate::types::{ASTInfo, CallInfo, ExportInfo, ExtractedComments, ImportInfo, SignatureInfo};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tree_sitter::Parser;
+
+/// Parser for Java files
+#[derive(Clone)]
+pub struct JavaParser;
+
+impl JavaParser {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn create_parser(&self) -> Result<Parser, ParseError> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_java::LANGUAGE.into())
+            .map_err(|e| ParseError::ParseError(e.to_string()))?;
+        Ok(parser)
+    }
+
+    fn extract_exports(&self, root: tree_sitter::Node, source: &str) -> Vec<ExportInfo> {
+        let mut exports = Vec::new();
+        let mut cursor = root.walk();
+
+        self.visit_exports(&mut cursor, source, &mut exports);
+        exports
+    }
+
+    fn visit_exports(
+        &self,
+        cursor: &mut tree_sitter::TreeCursor,
+        source: &str,
+        exports: &mut Vec<ExportInfo>,
+    ) {
+        loop {
+            let node = cursor.node();
+
+            let kind = match node.kind() {
+                "method_declaration" => Some("method"),
+                other => type_kind(other),
+            };
+            if let Some(kind) = kind {
+                if self.is_public(&node, source) {
+                    if let Some(name_node) = node.child_by_field_name("name") {
+                        exports.push(ExportInfo {
+                            name: self.node_text(name_node, source),
+                            kind: kind.to_string(),
+                        });
+                    }
+                }
+            }
+
+            if self.visits_members(&node, source) && cursor.goto_first_child() {
+                self.visit_exports(cursor, source, exports);
+                cursor.goto_parent();
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+
+    /// Whether the export walk goes on into this node: the file, public type declarations,
+    /// and their bodies, so members of a private or package-private type (and of local or
+    /// anonymous classes inside method bodies) are never exported
+    fn visits_members(&self, node: &tree_sitter::Node, source: &str) -> bool {
+        match node.kind() {
+            "program"
+            | "class_body"
+            | "interface_body"
+            | "enum_body"
+            | "enum_body_declarations"
+            | "annotation_type_body" => true,
+            kind => type_kind(kind).is_some() && self.is_public(node, source),
+        }
+    }
+
+    fn is_public(&self, node: &tree_sitter::Node, source: &str) -> bool {
+        self.has_modifier(node, source, "public")
+    }
+
+    fn has_modifier(&self, node: &tree_sitter::Node, source: &str, modifier: &str) -> bool {
+        nodes::child_of_kind(*node, "modifiers").is_some_and(|mods| {
+            (0..mods.child_count()).any(|i| {
+                mods.child(i)
+                    .is_some_and(|child| self.node_text(child, source) == modifier)
+            })
+        })
+    }
+
+    fn extract_imports(&self, root: tree_sitter::Node, source: &str) -> Vec<ImportInfo> {
+        // Imports can only appear at the top of a compilation unit
+        (0..root.child_count())
+            .filter_map(|i| root.child(i))
+            .filter(|child| child.kind() == "import_declaration")
+            .filter_map(|child| self.parse_import_declaration(child, source))
+            .map(|(import, _)| import)
+            .collect()
+    }
+
+    /// import java.util.List;                -> from java.util.List, items [List]
+    /// import java.util.*;                   -> from java.util, items [*]
+    /// import static java.util.Objects.equals; -> from java.util.Objects, items [equals]
+    /// import static java.lang.Math.*;       -> from java.lang.Math, items [*]
+    ///
+    /// The flag reports whether the import was static.
+    fn parse_import_declaration(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+    ) -> Option<(ImportInfo, bool)> {
+        let is_static = nodes::child_of_kind(node, "static").is_some();
+        let is_wildcard = nodes::child_of_kind(node, "asterisk").is_some();
+        let path = (0..node.child_count())
+            .filter_map(|i| node.child(i))
+            .find(|child| child.kind() == "scoped_identifier" || child.kind() == "identifier")
+            .map(|child| self.node_text(child, source))?;
+
+        let import = if is_wildcard {
+            ImportInfo {
+                from: path,
+                items: vec!["*".to_string()],
+            }
+        } else {
+            let (from, item) = match (is_static, path.rsplit_once('.')) {
+                (true, Some((class, member))) => (class.to_string(), member.to_string()),
+                // Single-type imports keep the class in `from` so it can be resolved
+                (false, Some((_, class))) => (path.clone(), class.to_string()),
+                (_, None) => (path.clone(), path.clone()),
+            };
+            ImportInfo {
+                from,
+                items: vec![item],
+            }
+        };
+
+        Some((import, is_static))
+    }
+
+    fn extract_signatures(
+        &self,
+        root: tree_sitter::Node,
+        source: &str,
+        exports: &[ExportInfo],
+    ) -> Vec<SignatureInfo> {
+        let export_names: HashSet<&str> = exports.iter().map(|e| e.name.as_str()).collect();
+        let mut signatures = Vec::new();
+        let mut cursor = root.walk();
+
+        self.visit_signatures(&mut cursor, source, &export_names, &mut signatures);
+        signatures
+    }
+
+    fn visit_signatures(
+        &self,
+        cursor: &mut tree_sitter::TreeCursor,
+        source: &str,
+        export_names: &HashSet<&str>,
+        signatures: &mut Vec<SignatureInfo>,
+    ) {
+        loop {
+            let node = cursor.node();
+
+            // export_names only says some public class declares `save`; a package-private
+            // `save` on a helper class in the same file must still be left out
+            let sig = match node.kind() {
+                "method_declaration" if self.is_public(&node, source) => {
+                    self.extract_method_signature(node, source, export_names)
+                }
+                "enum_declaration" if self.is_public(&node, source) => {
+                    self.extract_enum_signature(node, source, export_names)
+                }
+                kind if type_kind(kind).is_some() && self.is_public(&node, source) => {
+                    self.extract_type_signature(node, source, export_names)
+                }
+                _ => None,
+            };
+            if let Some(sig) = sig {
+                signatures.push(sig);
+            }
+
+            if self.visits_members(&node, source) && cursor.goto_first_child() {
+                self.visit_signatures(cursor, source, export_names, signatures);
+                cursor.goto_parent();
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+
+    fn extract_method_signature(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        export_names: &HashSet<&str>,
+    ) -> Option<SignatureInfo> {
+        let name_node = node.child_by_field_name("name")?;
+        let name = self.node_text(name_node, source);
+
+        if !export_names.contains(name.as_str()) {
+            return None;
+        }
+
+        let kind = if self.has_modifier(&node, source, "static") {
+            "static method"
+        } else {
+            "method"
+        };
+        let start_line = node.start_position().row + 1;
+        let end_line = node.end_position().row + 1;
+
+        // Members are Owner.name, as the fn: annotations name them
+        let owner = nodes::enclosing_name(node, source, |kind| type_kind(kind).is_some());
+        Some(SignatureInfo {
+            name: nodes::qualify(owner, name),
+            kind: kind.to_string(),
+            signature: format!(
+                "{}{}",
+                self.annotations(node, source),
+                self.method_signature(node, source)
+            ),
+            start_line,
+            end_line,
+        })
+    }
+
+    /// `<R>(Function<T, R> mapper) -> List<R> throws IOException`
+    fn method_signature(&self, node: tree_sitter::Node, source: &str) -> String {
+        let type_params = node
+            .child_by_field_name("type_parameters")
+            .map(|t| nodes::collapsed_text(t, source))
+            .unwrap_or_default();
+
+        let params = node
+            .child_by_field_name("parameters")
+            .map(|p| nodes::collapsed_text(p, source))
+            .unwrap_or_else(|| "()".to_string());
+
+        let returns = node
+            .child_by_field_name("type")
+            .map(|r| format!(" -> {}", nodes::collapsed_text(r, source)))
+            .unwrap_or_default();
+
+        let throws = nodes::child_of_kind(node, "throws")
+            .map(|t| format!(" {}", nodes::collapsed_text(t, source)))
+            .unwrap_or_default();
+
+        format!("{}{}{}{}", type_params, params, returns, throws)
+    }
+
+    /// Annotations from the modifiers node, e.g. `@GetMapping("/users") `
+    fn annotations(&self, node: tree_sitter::Node, source: &str) -> String {
+        let Some(mods) = nodes::child_of_kind(node, "modifiers") else {
+            return String::new();
+        };
+        (0..mods.child_count())
+            .filter_map(|i| mods.child(i))
+            .filter(|child| matches!(child.kind(), "annotation" | "marker_annotation"))
+            .map(|child| format!("{} ", nodes::collapsed_text(child, source)))
+            .collect()
+    }
+
+    fn extract_type_signature(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        export_names: &HashSet<&str>,
+    ) -> Option<SignatureInfo> {
+        let name_node = node.child_by_field_name("name")?;
+        let name = self.node_text(name_node, source);
+
+        if !export_names.contains(name.as_str()) {
+            return None;
+        }
+
+        let type_params = node
+            .child_by_field_name("type_parameters")
+            .map(|t| nodes::collapsed_text(t, source))
+            .unwrap_or_default();
+
+        // Record components: record User(String id, String name)
+        let components = node
+            .child_by_field_name("parameters")
+            .map(|p| nodes::collapsed_text(p, source))
+            .unwrap_or_default();
+
+        let mut bases = String::new();
+        for i in 0..node.child_count() {
+            if let Some(child) = node.child(i) {
+                if matches!(
+                    child.kind(),
+                    "superclass" | "super_interfaces" | "extends_interfaces"
+                ) {
+                    bases.push(' ');
+                    bases.push_str(&nodes::collapsed_text(child, source));
+                }
+            }
+        }
+
+        // Interface and annotation members are implicitly public
+        let implicit_public = matches!(
+            node.kind(),
+            "interface_declaration" | "annotation_type_declaration"
+        );
+        let mut members = Vec::new();
+        if let Some(body) = node.child_by_field_name("body") {
+            for i in 0..body.child_count() {
+                if let Some(child) = body.child(i) {
+                    if !implicit_public && !self.is_public(&child, source) {
+                        continue;
+                    }
+                    let member_name = child
+                        .child_by_field_name("name")
+                        .map(|n| self.node_text(n, source));
+                    match (child.kind(), member_name) {
+                        ("constructor_declaration", _) => {
+                            let params = child
+                                .child_by_field_name("parameters")
+                                .map(|p| nodes::collapsed_text(p, source))
+                                .unwrap_or_else(|| "()".to_string());
+                            members.push(format!("new{}", params));
+                        }
+                        ("method_declaration", Some(member_name)) => {
+                            members.push(format!(
+                                "{}{}",
+                                member_name,
+                                self.method_signature(child, source)
+                            ));
+                        }
+                        ("annotation_type_element_declaration", Some(member_name)) => {
+                            let ty = child
+                                .child_by_field_name("type")
+                                .map(|t| nodes::collapsed_text(t, source))
+                                .unwrap_or_default();
+                            members.push(format!("{}() -> {}", member_name, ty));
+                        }
+                        ("field_declaration" | "constant_declaration", _) => {
+                            let ty = child
+                                .child_by_field_name("type")
+                                .map(|t| nodes::collapsed_text(t, source))
+                                .unwrap_or_default();
+                            for j in 0..child.child_count() {
+                                if let Some(declarator) = child.child(j) {
+                                    if declarator.kind() == "variable_declarator" {
+                                        if let Some(field) = declarator.child_by_field_name("name")
+                                        {
+                                            members.push(format!(
+                                                "{}: {}",
+                                                self.node_text(field, source),
+                                                ty
+                                            ));
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                if members.len() >= 5 {
+                    members.truncate(5);
+                    members.push("...".to_string());
+                    break;
+                }
+            }
+        }
+
+        let body = if members.is_empty() {
+            String::new()
+        } else {
+            format!(" {{ {} }}", members.join("; "))
+        };
+        let signature = format!(
+            "{}{}{}{}{}",
+            self.annotations(node, source),
+            type_params,
+            components,
+            bases,
+            body
+        )
+        .trim()
+        .to_string();
+        let start_line = node.start_position().row + 1;
+        let end_line = node.end_position().row + 1;
+
+        Some(SignatureInfo {
+            name,
+            kind: type_kind(node.kind()).unwrap_or("class").to_string(),
+            signature,
+            start_line,
+            end_line,
+        })
+    }
+
+    fn extract_enum_signature(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        export_names: &HashSet<&str>,
+    ) -> Option<SignatureInfo> {
+        let name_node = node.child_by_field_name("name")?;
+        let name = self.node_text(name_node, source);
+
+        if !export_names.contains(name.as_str()) {
+            return None;
+        }
+
+        let mut variants = Vec::new();
+        if let Some(body) = node.child_by_field_name("body") {
+            for i in 0..body.child_count() {
+                if let Some(child) = body.child(i) {
+                    if child.kind() == "enum_constant" {
+                        if let Some(name_node) = child.child_by_field_name("name") {
+                            variants.push(self.node_text(name_node, source));
+                        }
+                    }
+                }
+                if variants.len() >= 5 {
+                    variants.push("...".to_string());
+                    break;
+                }
+            }
+        }
+
+        let signature = format!("{{ {} }}", variants.join(" | "));
+        let start_line = node.start_position().row + 1;
+        let end_line = node.end_position().row + 1;
+
+        Some(SignatureInfo {
+            name,
+            kind: "enum".to_string(),
+            signature,
+            start_line,
+            end_line,
+        })
+    }
+
+    fn extract_calls(&self, root: tree_sitter::Node, source: &str) -> Vec<CallInfo> {
+        let mut classes: HashMap<String, String> = HashMap::new();
+        let mut static_members: HashMap<String, String> = HashMap::new();
+        let mut static_classes: Vec<String> = Vec::new();
+
+        for i in 0..root.child_count() {
+            let Some(child) = root.child(i) else { continue };
+            if child.kind() != "import_declaration" {
+                continue;
+            }
+            let Some((import, is_static)) = self.parse_import_declaration(child, source) else {
+                continue;
+            };
+            for item in import.items {
+                match (is_static, item.as_str()) {
+                    (true, "*") => static_classes.push(import.from.clone()),
+                    (true, _) => {
+                        static_members.insert(item, import.from.clone());
+                    }
+                    // Wildcard package imports name no class we could attribute to
+                    (false, "*") => {}
+                    (false, _) => {
+                        classes.insert(item, import.from.clone());
+                    }
+                }
+            }
+        }
+
+        // A method declared here shadows a static import of the same name, and fields,
+        // locals and parameters typed by an imported class make obj.save() resolvable
+        let mut local_methods = HashSet::new();
+        let mut variables = HashMap::new();
+        self.collect_declarations(
+            &mut root.walk(),
+            source,
+            &classes,
+            &mut local_methods,
+            &mut variables,
+        );
+
+        let ctx = CallContext {
+            classes,
+            static_members,
+            // With two `import static ...*;` lines a bare call could come from either
+            static_class: match static_classes.as_slice() {
+                [only] => Some(only.clone()),
+                _ => None,
+            },
+            variables,
+            local_methods,
+        };
+
+        let mut calls: Vec<CallInfo> = Vec::new();
+        let mut seen: HashSet<(String, String)> = HashSet::new();
+        let mut cursor = root.walk();
+
+        self.visit_calls(&mut cursor, source, &ctx, &mut calls, &mut seen);
+        calls
+    }
+
+    /// Collects method names declared in this file and variables (fields, locals,
+    /// parameters) whose declared type is an imported class
+    fn collect_declarations(
+        &self,
+        cursor: &mut tree_sitter::TreeCursor,
+        source: &str,
+        classes: &HashMap<String, String>,
+        local_methods: &mut HashSet<String>,
+        variables: &mut HashMap<String, String>,
+    ) {
+        loop {
+            let node = cursor.node();
+
+            match node.kind() {
+                "method_declaration" => {
+                    if let Some(name_node) = node.child_by_field_name("name") {
+                        local_methods.insert(self.node_text(name_node, source));
+                    }
+                }
+                "field_declaration" | "local_variable_declaration" | "formal_parameter" => {
+                    let class = node
+                        .child_by_field_name("type")
+                        .and_then(|t| self.type_name(t, source))
+                        .and_then(|t| classes.get(&t));
+                    if let Some(class) = class {
+                        if let Some(name_node) = node.child_by_field_name("name") {
+                            variables.insert(self.node_text(name_node, source), class.clone());
+                        }
+                        for i in 0..node.child_count() {
+                            if let Some(declarator) = node.child(i) {
+                                if declarator.kind() == "variable_declarator" {
+                                    if let Some(name_node) = declarator.child_by_field_name("name")
+                                    {
+                                        variables.insert(
+                                            self.node_text(name_node, source),
+                                            class.clone(),
+                                        );
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+
+            if cursor.goto_first_child() {
+                self.collect_declarations(cursor, source, classes, local_methods, variables);
+                cursor.goto_parent();
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+
+    /// Base type name without type arguments: Map<String, T> -> Map
+    fn type_name(&self, node: tree_sitter::Node, source: &str) -> Option<String> {
+        match node.kind() {
+            "type_identifier" => Some(self.node_text(node, source)),
+            "generic_type" => self.type_name(node.child(0)?, source),
+            _ => None,
+        }
+    }
+
+    fn visit_calls(
+        &self,
+        cursor: &mut tree_sitter::TreeCursor,
+        source: &str,
+        ctx: &CallContext,
+        calls: &mut Vec<CallInfo>,
+        seen: &mut HashSet<(String, String)>,
+    ) {
+        loop {
+            let node = cursor.node();
+
+            let call = match node.kind() {
+                "method_invocation" => self.parse_method_invocation(node, source, ctx),
+                // new HashMap<>() -> java.util.HashMap.new
+                "object_creation_expression" => node
+                    .child_by_field_name("type")
+                    .and_then(|t| self.type_name(t, source))
+                    .and_then(|t| ctx.classes.get(&t))
+                    .map(|class| CallInfo {
+                        target: class.clone(),
+                        method: "new".to_string(),
+                    }),
+                _ => None,
+            };
+            if let Some(call) = call {
+                if seen.insert((call.target.clone(), call.method.clone())) {
+                    calls.push(call);
+                }
+            }
+
+            if cursor.goto_first_child() {
+                self.visit_calls(cursor, source, ctx, calls, seen);
+                cursor.goto_parent();
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+
+    fn parse_method_invocation(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        ctx: &CallContext,
+    ) -> Option<CallInfo> {
+        let method = self.node_text(node.child_by_field_name("name")?, source);
+
+        let Some(object) = node.child_by_field_name("object") else {
+            // requireNonNull(x) with `import static java.util.Objects.requireNonNull;`
+            if ctx.local_methods.contains(&method) {
+                return None;
+            }
+            let target = ctx
+                .static_members
+                .get(&method)
+                .cloned()
+                .or_else(|| ctx.static_class.clone())?;
+            return Some(CallInfo { target, method });
+        };
+
+        let receiver = match object.kind() {
+            "identifier" => self.node_text(object, source),
+            // this.repo.find() resolves through the field's declared type
+            "field_access"
+                if object
+                    .child_by_field_name("object")
+                    .is_some_and(|o| o.kind() == "this") =>
+            {
+                self.node_text(object.child_by_field_name("field")?, source)
+            }
+            _ => return None,
+        };
+
+        // Files.readString(p) on an imported class, or repo.find() on a typed variable
+        let target = ctx
+            .classes
+            .get(&receiver)
+            .or_else(|| ctx.variables.get(&receiver))?;
+        Some(CallInfo {
+            target: target.clone(),
+            method,
+        })
+    }
+
+    fn node_text(&self, node: tree_sitter::Node, source: &str) -> String {
+        source[node.start_byte()..node.end_byte()].to_string()
+    }
+}
+
+/// Export kind for a type declaration node
+fn type_kind(node_kind: &str) -> Option<&'static str> {
+    match node_kind {
+        "class_declaration" => Some("class"),
+        "interface_declaration" => Some("interface"),
+        "enum_declaration" => Some("enum"),
+        "record_declaration" => Some("record"),
+        "annotation_type_declaration" => Some("annotation"),
+        _ => None,
+    }
+}
+
+/// Import lookups used while attributing invocations to import declarations
+struct CallContext {
+    classes: HashMap<String, String>,
+    static_members: HashMap<String, String>,
+    static_class: Option<String>,
+    variables: HashMap<String, String>,
+    local_methods: HashSet<String>,
+}
+
+impl Default for JavaParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguageParser for JavaParser {
+    fn language_name(&self) -> &'static str {
+        "java"
+    }
+
+    fn file_extensions(&self) -> &[&'static str] {
+        &["java"]
+    }
+
+    fn extract_ast_info(&self, source: &str, _file_path: &Path) -> Result<ASTInfo, ParseError> {
+        let mut parser = self.create_parser()?;
+        let tree = parser
+            .parse(source, None)
+            .ok_or_else(|| ParseError::ParseError("Failed to parse source".to_string()))?;
+
+        let root = tree.root_node();
+
+        let exports = self.extract_exports(root, source);
+        let imports = self.extract_imports(root, source);
+        let calls = self.extract_calls(root, source);
+        let signatures = self.extract_signatures(root, source, &exports);
+
+        let tokens = super::tokens::count_tokens(source);
+
+        Ok(ASTInfo {
+            tokens,
+            exports,
+            imports,
+            calls,
+            signatures,
+        })
+    }
+
+    fn extract_toon_comments(&self, source: &str) -> Result<ExtractedComments, ParseError> {
+        let mut result = ExtractedComments::default();
+
+        // Find @dose in /** */ block comments
+        let block_pattern = Regex::new(r"/\*\*[\s\S]*?@dose[\s\S]*?\*/").unwrap();
+        let file_block = block_pattern
+            .find_iter(source)
+            .find(|m| toon_comment::parse_inline_annotation(m.as_str()).is_none());
+
+        if let Some(mat) = file_block {
+            let comment = mat.as_str();
+            let content = comment.trim_start_matches("/**").trim_end_matches("*/");
+
+            let content = content
+                .lines()
+                .map(|line| {
+                    let trimmed = line.trim().trim_start_matches('*').trim();
+                    if trimmed == "@dose" {
+                        ""
+                    } else {
+                        trimmed
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            result.file_block = Some(toon_comment::parse_toon_block(&content));
+        }
+
+        // Inline // @dose invariant: value annotations on the following member
+        let mut parser = self.create_parser()?;
+        if let Some(tree) = parser.parse(source, None) {
+            result.function_annotations = toon_comment::collect_inline_annotations(
+                tree.root_node(),
+                source,
+                &["line_comment", "block_comment"],
+                |node| match node.kind() {
+                    "method_declaration" | "constructor_declaration" => node
+                        .child_by_field_name("name")
+                        .map(|n| self.node_text(n, source)),
+                    kind if type_kind(kind).is_some() => node
+                        .child_by_field_name("name")
+                        .map(|n| self.node_text(n, source)),
+                    _ => None,
+                },
+                |node| {
+                    type_kind(node.kind())?;
+                    node.child_by_field_name("name")
+                        .map(|n| self.node_text(n, source))
+                },
+            );
+        }
+
+        Ok(result)
+    }
+
+    fn strip_toon_comments(&self, source: &str, toon_path: &str) -> Result<String, ParseError> {
+        // Replace /** */ @dose blocks
+        let block_pattern = Regex::new(r"/\*\*[\s\S]*?@dose[\s\S]*?\*/").unwrap();
+        let result = block_pattern
+            .replace_all(source, &format!("// @dose -> {}", toon_path))
+            .to_string();
+
+        Ok(result)
+    }
+
+    fn get_string_ranges(&self, source: &str) -> Result<Vec<(usize, usize)>, ParseError> {
+        let mut parser = self.create_parser()?;
+
+        let tree = parser
+            .parse(source, None)
+            .ok_or_else(|| ParseError::ParseError("Failed to parse source".to_string()))?;
+
+        let mut ranges = Vec::new();
+        collect_string_ranges_java(&mut tree.walk(), &mut ranges);
+        Ok(ranges)
+    }
+}
+
+fn collect_string_ranges_java(
+    cursor: &mut tree_sitter::TreeCursor,
+    ranges: &mut Vec<(usize, usize)>,
+) {
+    loop {
+        let node = cursor.node();
+        let kind = node.kind();
+        // Java has string_literal (text blocks included) and character_literal
+        if kind == "string_literal" || kind == "character_literal" {
+            ranges.push((node.start_byte(), node.end_byte()));
+        }
+        if cursor.goto_first_child() {
+            collect_string_ranges_java(cursor, ranges);
+            cursor.goto_parent();
+        }
+        if !cursor.goto_next_sibling() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JAVA_FIXTURE: &str = include_str!("../../test_fixtures/sample.java");
+
+    #[test]
+    fn test_extract_ast_info() {
+        let parser = JavaParser::new();
+        let info = parser
+            .extract_ast_info(JAVA_FIXTURE, Path::new("sample.java"))
+            .unwrap();
+
+        let mut exports: Vec<_> = info
+            .exports
+            .iter()
+            .map(|e| (&e.name[..], &e.kind[..]))
+            .collect();
+        exports.sort();
+        assert_eq!(
+            exports,
+            vec![
+                ("Audited", "annotation"),
+                ("Repository", "interface"),
+                ("UserConfig", "record"),
+                ("UserService", "class"),
+                ("UserStatus", "enum"),
+                ("compareTo", "method"),
+                ("findAll", "method"),
+                ("findById", "method"),
+                ("mapAll", "method"),
+                ("save", "method"),
+            ]
+        );
+
+        let mut imports: Vec<_> = info
+            .imports
+            .iter()
+            .map(|i| (&i.from[..], i.items.join("|")))
+            .collect();
+        imports.sort();
+        assert_eq!(
+            imports,
+            vec![
+                ("java.io.IOException", "IOException".to_string()),
+                ("java.nio.file.Files", "Files".to_string()),
+                ("java.nio.file.Path", "Path".to_string()),
+                ("java.util.HashMap", "HashMap".to_string()),
+                ("java.util.List", "List".to_string()),
+                ("java.util.Map", "Map".to_string()),
+                ("java.util.Objects", "requireNonNull".to_string()),
+                ("java.util.concurrent", "*".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_signatures() {
+        let parser = JavaParser::new();
+        let info = parser
+            .extract_ast_info(JAVA_FIXTURE, Path::new("sample.java"))
+            .unwrap();
+
+        let sig = |name: &str| {
+            info.signatures
+                .iter()
+                .find(|s| s.name == name)
+                .unwrap_or_else(|| panic!("missing signature for {}", name))
+        };
+
+        let find_by_id = sig("UserService.findById");
+        assert_eq!(find_by_id.kind, "method");
+        assert_eq!(
+            find_by_id.signature,
+            "@Override (String id) -> T throws IOException"
+        );
+        assert_eq!((find_by_id.start_line, find_by_id.end_line), (66, 73));
+
+        assert_eq!(
+            sig("UserService.mapAll").signature,
+            "@SafeVarargs <R>(Function<T, R> mapper, T... users) -> List<R>"
+        );
+
+        let record = sig("UserConfig");
+        assert_eq!(record.kind, "record");
+        assert_eq!(
+            record.signature,
+            "(String id, String name, String email) implements Comparable<UserConfig> { compareTo(UserConfig other) -> int }"
+        );
+        assert_eq!((record.start_line, record.end_line), (46, 54));
+
+        let repo = sig("Repository");
+        assert_eq!(repo.kind, "interface");
+        assert!(repo
+            .signature
+            .starts_with("<T> { findById(String id) -> T throws IOException;"));
+
+        let service = sig("UserService");
+        assert!(service.signature.starts_with(
+            "@Service <T extends UserConfig> extends BaseService implements Repository<T> { new(Path dataDir); findById"
+        ));
+        assert!(!service.signature.contains("validate"));
+
+        assert_eq!(
+            sig("UserStatus").signature,
+            "{ ACTIVE | INACTIVE | PENDING | SUSPENDED }"
+        );
+        assert_eq!(sig("Audited").signature, "{ value() -> String }");
+
+        // clear() on the package-private CacheManager is public but the type is not
+        assert!(info.signatures.iter().all(|s| !s.name.ends_with("clear")));
+    }
+
+    #[test]
+    fn test_members_of_hidden_types() {
+        let source = r#"
+class Hidden {
+    public void leak() {}
+}
+
+public class Outer {
+    public void visible() {
+        Runnable r = new Runnable() {
+            public void run() {}
+        };
+    }
+
+    private static class Priv {
+        public void privM() {}
+    }
+
+    public static class Nested {
+        public void nestedM() {}
+    }
+}
+"#;
+        let info = JavaParser::new()
+            .extract_ast_info(source, Path::new("Outer.java"))
+            .unwrap();
+
+        let exports: Vec<_> = info.exports.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(exports, vec!["Outer", "visible", "Nested", "nestedM"]);
+        let signatures: Vec<_> = info.signatures.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            signatures,
+            vec!["Outer", "Outer.visible", "Nested", "Nested.nestedM"]
+        );
+    }
+
+    #[test]
+    fn test_extract_calls() {
+        let parser = JavaParser::new();
+        let source = r#"
+import java.nio.file.Files;
+import java.util.List;
+import com.acme.repo.UserRepository;
+import static java.util.Objects.requireNonNull;
+import static java.lang.Math.*;
+
+public class Calc {
+    private final UserRepository repo;
+
+    public int run(String p) {
+        requireNonNull(p);
+        Files.exists(p);
+        repo.findById(p);
+        this.repo.save(p);
+        List<String> xs = List.of(p);
+        helper();
+        return abs(max(1, 2));
+    }
+
+    private void helper() {}
+}
+"#;
+        let info = parser
+            .extract_ast_info(source, Path::new("Calc.java"))
+            .unwrap();
+
+        let mut calls: Vec<_> = info
+            .calls
+            .iter()
+            .map(|c| (&c.target[..], &c.method[..]))
+            .collect();
+        calls.sort();
+        assert_eq!(
+            calls,
+            vec![
+                ("com.acme.repo.UserRepository", "findById"),
+                ("com.acme.repo.UserRepository", "save"),
+                ("java.lang.Math", "abs"),
+                ("java.lang.Math", "max"),
+                ("java.nio.file.Files", "exists"),
+                ("java.util.List", "of"),
+                ("java.util.Objects", "requireNonNull"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_inline_annotations() {
+        let parser = JavaParser::new();
+        let source = r#"/** @dose
+purpose: Orders
+*/
+public class OrderService {
+    // @dose invariant: total is never negative
+    @Transactional
+    public long total(Order order) { return 0; }
+
+    /** @dose error-handling: throws on unknown SKU */
+    public void add(String sku) {}
+}
+"#;
+        let comments = parser.extract_toon_comments(source).unwrap();
+        assert_eq!(comments.file_block.unwrap().purpose.unwrap(), "Orders");
+        assert_eq!(
+            comments.function_annotations["OrderService.total"].invariants,
+            Some(vec!["total is never negative".to_string()])
+        );
+        assert_eq!(
+            comments.function_annotations["OrderService.add"].error_handling,
+            Some(vec!["throws on unknown SKU".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_toon_comments() {
+        let parser = JavaParser::new();
+        let comments = parser.extract_toon_comments(JAVA_FIXTURE).unwrap();
+        let block = comments.file_block.unwrap();
+        assert_eq!(block.purpose.unwrap(), "Sample Java fixture for testing the luny Java parser. This file contains various Java constructs including classes, interfaces, records, enums, and annotations to verify extraction works correctly.");
+
+        let stripped = parser
+            .strip_toon_comments(JAVA_FIXTURE, "sample.java.toon")
+            .unwrap();
+        assert!(stripped.starts_with("// @dose -> sample.java.toon"));
+    }
+
+    #[test]
+    fn test_string_ranges() {
+        let parser = JavaParser::new();
+        let source =
+            "class A { String s = \"a b\"; char c = ' '; String t = \"\"\"\n  x\n  \"\"\"; }";
+        let ranges = parser.get_string_ranges(source).unwrap();
+        let texts: Vec<_> = ranges.iter().map(|(s, e)| &source[*s..*e]).collect();
+        assert_eq!(texts, vec!["\"a b\"", "' '", "\"\"\"\n  x\n  \"\"\""]);
+    }
+}
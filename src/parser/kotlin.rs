@@ -0,0 +1,1344 @@
+//! @dose
+//! purpose: This module parses Kotlin source and script files to extract public classes,
+//!     objects, functions, and properties. It uses tree-sitter (tree-sitter-kotlin-ng)
+//!     and treats Kotlin's default visibility as public.
+//!
+//! when-editing:
+//!     - !Kotlin declarations are public unless marked private, internal, or protected
+//!     - !Modifiers and annotations both live in the modifiers node, even on the line above
+//!     - Members of objects and companion objects are exported as Owner.member; class members are only summarized
+//!
+//! invariants:
+//!     - Only file, class, and object bodies are visited; function bodies and initializers never are
+//!     - A private or internal container hides all of its members from exports
+//!     - Calls are only attributed to classes and functions named by a single import
+//!
+//! do-not:
+//!     - Never attribute calls through wildcard imports; the class is unknown
+//!     - Never descend into string nodes when collecting ranges; templates nest strings
+//!
+//! gotchas:
+//!     - The grammar reads Json.decode<User>(s) as comparisons (a < b > (s)), so calls with
+//!       explicit type arguments on a receiver are recovered from that shape
+//!     - Companion object members are exported under the enclosing class name, as call sites spell them
+//!     - Variable types are tracked per file, not per scope, so shadowed names can misattribute
+//!     - Function, receiver, and return types are unnamed children, told apart by position
+//!     - Error recovery can drop whole declarations (a one-line class body followed by a
+//!       companion object); a top-level ERROR makes parse_trees parse each column-0
+//!       declaration on its own instead
+//!
+//! flows:
+//!     - Parse: Create tree-sitter parser, set Kotlin language, parse source
+//!     - Declarations: Walk file, class, and object bodies into qualified declarations
+//!     - Extract imports: import a.b.C [as D] and import a.b.* directives
+//!     - Inline annotations: // @dose field: value binds to the next declaration
+//!     - Extract signatures: Functions render their receiver (String.(x: Int) -> Boolean); types summarize public members
+//!     - Extract calls: Attribute calls on imported classes, typed variables, and imported functions
+
+use crate::parser::{nodes, toon_comment, LanguageParser, ParseError};
+use crate::types::{ASTInfo, CallInfo, ExportInfo, ExtractedComments, ImportInfo, SignatureInfo};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tree_sitter::{Node, Parser, Point, Range, Tree};
+
+/// Parser for Kotlin files
+#[derive(Clone)]
+pub struct KotlinParser;
+
+impl KotlinParser {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn create_parser(&self) -> Result<Parser, ParseError> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_kotlin_ng::LANGUAGE.into())
+            .map_err(|e| ParseError::ParseError(e.to_string()))?;
+        Ok(parser)
+    }
+
+    /// Parse the file, or each top-level declaration on its own when the whole-file parse
+    /// has a top-level ERROR: recovery there can swallow every later declaration
+    fn parse_trees(&self, source: &str) -> Result<Vec<Tree>, ParseError> {
+        let failed = || ParseError::ParseError("Failed to parse source".to_string());
+        let mut parser = self.create_parser()?;
+        let tree = parser.parse(source, None).ok_or_else(failed)?;
+        let root = tree.root_node();
+        if !(0..root.child_count())
+            .filter_map(|i| root.child(i))
+            .any(|child| child.is_error())
+        {
+            return Ok(vec![tree]);
+        }
+
+        // Included ranges keep byte offsets and line numbers relative to the whole file
+        let mut trees = Vec::new();
+        for range in top_level_ranges(source) {
+            parser
+                .set_included_ranges(&[range])
+                .map_err(|e| ParseError::ParseError(e.to_string()))?;
+            trees.push(parser.parse(source, None).ok_or_else(failed)?);
+        }
+        Ok(trees)
+    }
+
+    fn extract_exports(&self, decls: &[Decl]) -> Vec<ExportInfo> {
+        decls
+            .iter()
+            .filter_map(|decl| {
+                decl.export_kind.map(|kind| ExportInfo {
+                    name: decl.name.clone(),
+                    kind: kind.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    fn extract_signatures(&self, decls: &[Decl]) -> Vec<SignatureInfo> {
+        decls
+            .iter()
+            .filter(|decl| decl.export_kind.is_some())
+            .filter_map(|decl| {
+                decl.signature.as_ref().map(|signature| SignatureInfo {
+                    name: decl.name.clone(),
+                    kind: decl.kind.clone(),
+                    signature: signature.clone(),
+                    start_line: decl.start_line,
+                    end_line: decl.end_line,
+                })
+            })
+            .collect()
+    }
+
+    fn extract_imports(&self, roots: &[Node], source: &str) -> Vec<ImportInfo> {
+        // Imports can only appear in the file header
+        roots
+            .iter()
+            .flat_map(|root| (0..root.child_count()).filter_map(|i| root.child(i)))
+            .filter(|child| child.kind() == "import")
+            .filter_map(|child| self.parse_import(child, source))
+            .collect()
+    }
+
+    /// import java.io.File                              -> from java.io.File, items [File]
+    /// import kotlinx.serialization.json.Json as Codec  -> from kotlinx.serialization.json.Json, items [Codec]
+    /// import com.luny.model.*                          -> from com.luny.model, items [*]
+    fn parse_import(&self, node: Node, source: &str) -> Option<ImportInfo> {
+        let from = self.node_text(nodes::child_of_kind(node, "qualified_identifier")?, source);
+
+        let item = if nodes::child_of_kind(node, "*").is_some() {
+            "*".to_string()
+        } else {
+            match nodes::child_of_kind(node, "identifier") {
+                Some(alias) => self.node_text(alias, source),
+                None => from.rsplit('.').next().unwrap_or(&from).to_string(),
+            }
+        };
+
+        Some(ImportInfo {
+            from,
+            items: vec![item],
+        })
+    }
+
+    /// All declarations reachable through file, class, and object bodies, in source order
+    fn extract_declarations(&self, roots: &[Node], source: &str) -> Vec<Decl> {
+        let scope = Scope {
+            prefix: None,
+            kind: ScopeKind::File,
+            exported: true,
+        };
+        let mut decls = Vec::new();
+        for &root in roots {
+            self.visit_members(root, source, &scope, &mut decls);
+        }
+
+        decls.sort_by_key(|decl| decl.start_byte);
+        decls
+    }
+
+    /// Record the declarations directly inside `body`, returning its public member summary
+    fn visit_members(
+        &self,
+        body: Node,
+        source: &str,
+        scope: &Scope,
+        decls: &mut Vec<Decl>,
+    ) -> Vec<String> {
+        let mut summary = Vec::new();
+        for i in 0..body.child_count() {
+            let Some(child) = body.child(i) else { continue };
+            match child.kind() {
+                "function_declaration" => {
+                    self.function_declaration(child, source, scope, &mut summary, decls)
+                }
+                "class_declaration" => {
+                    self.type_declaration(child, source, scope, &mut summary, decls)
+                }
+                "object_declaration" | "companion_object" => {
+                    self.object_declaration(child, source, scope, &mut summary, decls)
+                }
+                "property_declaration" => {
+                    self.property_declaration(child, source, scope, &mut summary, decls)
+                }
+                "type_alias" => self.type_alias(child, source, scope, decls),
+                "secondary_constructor" if is_public(child, source) => {
+                    let params = nodes::child_of_kind(child, "function_value_parameters")
+                        .map(|p| self.params_text(p, source))
+                        .unwrap_or_else(|| "()".to_string());
+                    push_member(&mut summary, format!("constructor{}", params));
+                }
+                _ => {}
+            }
+        }
+        summary
+    }
+
+    fn function_declaration(
+        &self,
+        node: Node,
+        source: &str,
+        scope: &Scope,
+        summary: &mut Vec<String>,
+        decls: &mut Vec<Decl>,
+    ) {
+        let Some(header) = self.function_header(node, source) else {
+            return;
+        };
+        let public = is_public(node, source);
+        if public {
+            push_member(summary, header.render(&header.name));
+        }
+
+        let base = if scope.kind == ScopeKind::File {
+            "fn"
+        } else {
+            "method"
+        };
+        let kind = format!(
+            "{}{}{}",
+            if has_modifier(node, source, "suspend") {
+                "suspend "
+            } else {
+                ""
+            },
+            if header.receiver.is_some() {
+                "extension "
+            } else {
+                ""
+            },
+            base
+        );
+        let exported = scope.exported && public && scope.kind != ScopeKind::Type;
+        decls.push(Decl {
+            name: scope.qualify(&header.name),
+            kind,
+            export_kind: exported.then_some(base),
+            signature: Some(format!(
+                "{}{}",
+                self.annotations(node, source),
+                header.render("")
+            )),
+            ..Decl::at(node)
+        });
+    }
+
+    /// Name, receiver, and types of a function; the receiver and return types are the
+    /// unnamed type children before the name and after the parameters
+    fn function_header(&self, node: Node, source: &str) -> Option<FunctionHeader> {
+        let name_node = node.child_by_field_name("name")?;
+        let params = nodes::child_of_kind(node, "function_value_parameters")?;
+
+        let mut header = FunctionHeader {
+            name: self.node_text(name_node, source),
+            type_params: String::new(),
+            receiver: None,
+            params: self.params_text(params, source),
+            returns: None,
+            constraints: String::new(),
+        };
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            match child.kind() {
+                "modifiers" | "identifier" | "function_value_parameters" | "function_body" => {}
+                "type_parameters" => header.type_params = nodes::collapsed_text(child, source),
+                "type_constraints" => header.constraints = self.constraints(node, source),
+                _ if child.end_byte() <= name_node.start_byte() => {
+                    header.receiver = Some(nodes::collapsed_text(child, source))
+                }
+                _ if child.start_byte() >= params.end_byte() => {
+                    header.returns = Some(nodes::collapsed_text(child, source))
+                }
+                _ => {}
+            }
+        }
+        Some(header)
+    }
+
+    fn type_declaration(
+        &self,
+        node: Node,
+        source: &str,
+        scope: &Scope,
+        summary: &mut Vec<String>,
+        decls: &mut Vec<Decl>,
+    ) {
+        let Some(name_node) = node.child_by_field_name("name") else {
+            return;
+        };
+        let name = self.node_text(name_node, source);
+
+        let has = |m: &str| has_modifier(node, source, m);
+        let (kind, export_kind) = if nodes::child_of_kind(node, "interface").is_some() {
+            if has("sealed") {
+                ("sealed interface", "interface")
+            } else {
+                ("interface", "interface")
+            }
+        } else if has("enum") {
+            ("enum", "enum")
+        } else if has("annotation") {
+            ("annotation", "annotation")
+        } else if has("data") {
+            ("data class", "class")
+        } else if has("sealed") {
+            ("sealed class", "class")
+        } else if has("value") {
+            ("value class", "class")
+        } else {
+            ("class", "class")
+        };
+
+        let qualified = scope.qualify(&name);
+        let public = is_public(node, source);
+        let exported = scope.exported && public;
+
+        let inner = Scope {
+            prefix: Some(&qualified),
+            kind: ScopeKind::Type,
+            exported,
+        };
+        let body = nodes::child_of_kind(node, "class_body")
+            .or_else(|| nodes::child_of_kind(node, "enum_class_body"));
+        let members = body
+            .map(|body| self.visit_members(body, source, &inner, decls))
+            .unwrap_or_default();
+
+        let signature = if kind == "enum" {
+            let mut entries = Vec::new();
+            if let Some(body) = body {
+                let mut cursor = body.walk();
+                for entry in body.named_children(&mut cursor) {
+                    if entry.kind() == "enum_entry" {
+                        if let Some(entry_name) = nodes::child_of_kind(entry, "identifier") {
+                            push_member(&mut entries, self.node_text(entry_name, source));
+                        }
+                    }
+                }
+            }
+            format!("{{ {} }}", entries.join(" | "))
+        } else {
+            // `private constructor(...)` and `@Inject constructor(...)` keep only the parameters
+            let primary_ctor = nodes::child_of_kind(node, "primary_constructor")
+                .and_then(|ctor| nodes::child_of_kind(ctor, "class_parameters"))
+                .map(|p| self.params_text(p, source))
+                .unwrap_or_default();
+            let body = if members.is_empty() {
+                String::new()
+            } else {
+                format!(" {{ {} }}", members.join("; "))
+            };
+            format!(
+                "{}{}{}{}{}{}",
+                self.annotations(node, source),
+                self.child_text(node, "type_parameters", source),
+                primary_ctor,
+                self.supertypes(node, source),
+                self.constraints(node, source),
+                body
+            )
+            .trim()
+            .to_string()
+        };
+
+        if public && scope.kind == ScopeKind::Type {
+            push_member(summary, format!("{} {}", kind, name));
+        }
+        decls.push(Decl {
+            name: qualified,
+            kind: kind.to_string(),
+            export_kind: exported.then_some(export_kind),
+            signature: Some(signature),
+            ..Decl::at(node)
+        });
+    }
+
+    /// `object Name : Base { }` and `companion object { }`
+    fn object_declaration(
+        &self,
+        node: Node,
+        source: &str,
+        scope: &Scope,
+        summary: &mut Vec<String>,
+        decls: &mut Vec<Decl>,
+    ) {
+        let companion = node.kind() == "companion_object";
+        let name = node
+            .child_by_field_name("name")
+            .or_else(|| nodes::child_of_kind(node, "identifier"))
+            .map(|n| self.node_text(n, source));
+        let public = is_public(node, source);
+
+        // Companion members are called through the enclosing class: UserService.create()
+        let qualified = match (&name, companion) {
+            (Some(name), false) => scope.qualify(name),
+            _ => scope.prefix.unwrap_or_default().to_string(),
+        };
+        let exported = scope.exported && public;
+
+        let inner = Scope {
+            prefix: (!qualified.is_empty()).then_some(qualified.as_str()),
+            kind: ScopeKind::Object,
+            exported,
+        };
+        let members = nodes::child_of_kind(node, "class_body")
+            .map(|body| self.visit_members(body, source, &inner, decls))
+            .unwrap_or_default();
+
+        let Some(name) = name.filter(|_| !companion) else {
+            return;
+        };
+        if public && scope.kind == ScopeKind::Type {
+            push_member(summary, format!("object {}", name));
+        }
+        let body = if members.is_empty() {
+            String::new()
+        } else {
+            format!(" {{ {} }}", members.join("; "))
+        };
+        let signature = format!(
+            "{}{}{}",
+            self.annotations(node, source),
+            self.supertypes(node, source),
+            body
+        )
+        .trim()
+        .to_string();
+        decls.push(Decl {
+            name: qualified,
+            kind: "object".to_string(),
+            export_kind: exported.then_some("object"),
+            signature: Some(signature),
+            ..Decl::at(node)
+        });
+    }
+
+    /// `val name: Type = ...`; destructuring declarations name nothing
+    fn property_declaration(
+        &self,
+        node: Node,
+        source: &str,
+        scope: &Scope,
+        summary: &mut Vec<String>,
+        decls: &mut Vec<Decl>,
+    ) {
+        let Some(variable) = nodes::child_of_kind(node, "variable_declaration") else {
+            return;
+        };
+        let Some(name_node) = nodes::child_of_kind(variable, "identifier") else {
+            return;
+        };
+        let name = self.node_text(name_node, source);
+        let ty = variable
+            .named_child(1)
+            .map(|t| nodes::collapsed_text(t, source));
+        // val String.slug: String
+        let receiver = (0..node.named_child_count())
+            .filter_map(|i| node.named_child(i))
+            .take_while(|child| child.id() != variable.id())
+            .find(|child| !matches!(child.kind(), "modifiers" | "type_parameters"))
+            .map(|r| nodes::collapsed_text(r, source));
+
+        let public = is_public(node, source);
+        if public {
+            let head = match &receiver {
+                Some(receiver) => format!("{}.{}", receiver, name),
+                None => name.clone(),
+            };
+            push_member(
+                summary,
+                match &ty {
+                    Some(ty) => format!("{}: {}", head, ty),
+                    None => head,
+                },
+            );
+        }
+
+        let kind = if has_modifier(node, source, "const") {
+            "const"
+        } else if nodes::child_of_kind(node, "var").is_some() {
+            "var"
+        } else {
+            "val"
+        };
+        let exported = scope.exported && public && scope.kind != ScopeKind::Type;
+        decls.push(Decl {
+            name: scope.qualify(&name),
+            kind: kind.to_string(),
+            export_kind: exported.then_some(kind),
+            signature: None,
+            ..Decl::at(node)
+        });
+    }
+
+    /// `typealias UserMap = Map<String, User>`; the name is the alias's `type` field
+    fn type_alias(&self, node: Node, source: &str, scope: &Scope, decls: &mut Vec<Decl>) {
+        let Some(name_node) = node.child_by_field_name("type") else {
+            return;
+        };
+        let Some(aliased) = node.named_child(node.named_child_count().saturating_sub(1)) else {
+            return;
+        };
+        let signature = format!(
+            "{} = {}",
+            self.child_text(node, "type_parameters", source),
+            nodes::collapsed_text(aliased, source)
+        )
+        .trim()
+        .to_string();
+
+        let exported = scope.exported && is_public(node, source);
+        decls.push(Decl {
+            name: scope.qualify(&self.node_text(name_node, source)),
+            kind: "type".to_string(),
+            export_kind: exported.then_some("type"),
+            signature: Some(signature),
+            ..Decl::at(node)
+        });
+    }
+
+    /// ` : Base(), Iface` after a class or object header
+    fn supertypes(&self, node: Node, source: &str) -> String {
+        nodes::child_of_kind(node, "delegation_specifiers")
+            .map(|s| format!(" : {}", nodes::collapsed_text(s, source)))
+            .unwrap_or_default()
+    }
+
+    /// Generic constraints such as ` where T : Comparable<T>`
+    fn constraints(&self, node: Node, source: &str) -> String {
+        nodes::child_of_kind(node, "type_constraints")
+            .map(|c| format!(" {}", nodes::collapsed_text(c, source)))
+            .unwrap_or_default()
+    }
+
+    /// Annotations from the modifiers node, e.g. `@Singleton `
+    fn annotations(&self, node: Node, source: &str) -> String {
+        let Some(mods) = nodes::child_of_kind(node, "modifiers") else {
+            return String::new();
+        };
+        let mut cursor = mods.walk();
+        mods.named_children(&mut cursor)
+            .filter(|child| child.kind() == "annotation")
+            .map(|child| format!("{} ", nodes::collapsed_text(child, source)))
+            .collect()
+    }
+
+    /// Parameter lists without the trailing comma Kotlin allows before `)`
+    fn params_text(&self, node: Node, source: &str) -> String {
+        nodes::collapsed_text(node, source).replace(",)", ")")
+    }
+
+    fn child_text(&self, node: Node, kind: &str, source: &str) -> String {
+        nodes::child_of_kind(node, kind)
+            .map(|child| nodes::collapsed_text(child, source))
+            .unwrap_or_default()
+    }
+
+    fn extract_calls(&self, roots: &[Node], source: &str, imports: &[ImportInfo]) -> Vec<CallInfo> {
+        let mut classes: HashMap<String, String> = HashMap::new();
+        let mut functions: HashMap<String, (String, String)> = HashMap::new();
+
+        for import in imports {
+            for item in &import.items {
+                if item == "*" {
+                    continue;
+                }
+                if item.starts_with(|c: char| c.is_uppercase()) {
+                    classes.insert(item.clone(), import.from.clone());
+                } else if let Some((package, function)) = import.from.rsplit_once('.') {
+                    // import kotlinx.coroutines.launch -> launch { } calls kotlinx.coroutines.launch
+                    functions.insert(item.clone(), (package.to_string(), function.to_string()));
+                }
+            }
+        }
+
+        // Functions declared here shadow imported ones, and parameters, properties, and
+        // locals typed by an imported class make dataDir.mkdirs() resolvable
+        let mut local_functions = HashSet::new();
+        let mut variables = HashMap::new();
+        for root in roots {
+            self.collect_declarations(
+                &mut root.walk(),
+                source,
+                &classes,
+                &mut local_functions,
+                &mut variables,
+            );
+        }
+
+        let ctx = CallContext {
+            classes,
+            functions,
+            variables,
+            local_functions,
+        };
+        let mut calls = Vec::new();
+        let mut seen = HashSet::new();
+        for root in roots {
+            self.visit_calls(&mut root.walk(), source, &ctx, &mut calls, &mut seen);
+        }
+        calls
+    }
+
+    fn collect_declarations(
+        &self,
+        cursor: &mut tree_sitter::TreeCursor,
+        source: &str,
+        classes: &HashMap<String, String>,
+        local_functions: &mut HashSet<String>,
+        variables: &mut HashMap<String, String>,
+    ) {
+        loop {
+            let node = cursor.node();
+
+            match node.kind() {
+                "function_declaration" => {
+                    if let Some(name_node) = node.child_by_field_name("name") {
+                        local_functions.insert(self.node_text(name_node, source));
+                    }
+                }
+                // dataDir: File in parameters, class parameters, and typed properties
+                "parameter" | "class_parameter" | "variable_declaration" => {
+                    let name = nodes::child_of_kind(node, "identifier");
+                    let class = nodes::child_of_kind(node, "user_type")
+                        .and_then(|t| nodes::child_of_kind(t, "identifier"))
+                        .and_then(|t| classes.get(&self.node_text(t, source)));
+                    if let (Some(name), Some(class)) = (name, class) {
+                        variables.insert(self.node_text(name, source), class.clone());
+                    }
+                }
+                // val file = File(path)
+                "property_declaration" => {
+                    let name = nodes::child_of_kind(node, "variable_declaration")
+                        .and_then(|v| nodes::child_of_kind(v, "identifier"));
+                    let class = nodes::child_of_kind(node, "call_expression")
+                        .and_then(|call| call.named_child(0))
+                        .filter(|callee| callee.kind() == "identifier")
+                        .and_then(|callee| classes.get(&self.node_text(callee, source)));
+                    if let (Some(name), Some(class)) = (name, class) {
+                        variables.insert(self.node_text(name, source), class.clone());
+                    }
+                }
+                _ => {}
+            }
+
+            if cursor.goto_first_child() {
+                self.collect_declarations(cursor, source, classes, local_functions, variables);
+                cursor.goto_parent();
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+
+    fn visit_calls(
+        &self,
+        cursor: &mut tree_sitter::TreeCursor,
+        source: &str,
+        ctx: &CallContext,
+        calls: &mut Vec<CallInfo>,
+        seen: &mut HashSet<(String, String)>,
+    ) {
+        loop {
+            let node = cursor.node();
+
+            let callee = match node.kind() {
+                "call_expression" => node.named_child(0),
+                "binary_expression" => self.generic_callee(node, source),
+                _ => None,
+            };
+            if let Some(call) = callee.and_then(|callee| self.parse_call(callee, source, ctx)) {
+                if seen.insert((call.target.clone(), call.method.clone())) {
+                    calls.push(call);
+                }
+            }
+
+            if cursor.goto_first_child() {
+                self.visit_calls(cursor, source, ctx, calls, seen);
+                cursor.goto_parent();
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+
+    /// Callee of `JsonCodec.decodeFromString<User>(json)`, which the grammar parses as
+    /// `(JsonCodec.decodeFromString < User) > (json)`
+    fn generic_callee<'a>(&self, node: Node<'a>, source: &str) -> Option<Node<'a>> {
+        let operator = |n: Node| {
+            n.child_by_field_name("operator")
+                .map(|op| self.node_text(op, source))
+        };
+        let left = node.child_by_field_name("left")?;
+        let right = node.child_by_field_name("right")?;
+        if operator(node)? != ">"
+            || right.kind() != "parenthesized_expression"
+            || left.kind() != "binary_expression"
+            || operator(left)? != "<"
+        {
+            return None;
+        }
+        left.child_by_field_name("left")
+            .filter(|callee| callee.kind() == "navigation_expression")
+    }
+
+    fn parse_call(&self, callee: Node, source: &str, ctx: &CallContext) -> Option<CallInfo> {
+        match callee.kind() {
+            // File(path) constructs an imported class; withContext(io) { } or launch { }
+            // calls an imported function
+            "identifier" => {
+                let name = self.node_text(callee, source);
+                if let Some(class) = ctx.classes.get(&name) {
+                    return Some(CallInfo {
+                        target: class.clone(),
+                        method: "new".to_string(),
+                    });
+                }
+                if ctx.local_functions.contains(&name) {
+                    return None;
+                }
+                ctx.functions
+                    .get(&name)
+                    .map(|(package, function)| CallInfo {
+                        target: package.clone(),
+                        method: function.clone(),
+                    })
+            }
+            // JsonCodec.decodeFromString(x) on an imported class, dataDir?.mkdirs() on a typed variable
+            "navigation_expression" => {
+                let receiver = callee.named_child(0).filter(|r| r.kind() == "identifier")?;
+                let method = callee
+                    .named_child(callee.named_child_count().checked_sub(1)?)
+                    .filter(|m| m.id() != receiver.id() && m.kind() == "identifier")?;
+                let receiver = self.node_text(receiver, source);
+                let target = ctx
+                    .classes
+                    .get(&receiver)
+                    .or_else(|| ctx.variables.get(&receiver))?;
+                Some(CallInfo {
+                    target: target.clone(),
+                    method: self.node_text(method, source),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn node_text(&self, node: Node, source: &str) -> String {
+        source[node.start_byte()..node.end_byte()].to_string()
+    }
+}
+
+/// Import lookups used while attributing calls to import directives
+struct CallContext {
+    classes: HashMap<String, String>,
+    functions: HashMap<String, (String, String)>,
+    variables: HashMap<String, String>,
+    local_functions: HashSet<String>,
+}
+
+/// A declaration found in a file, class, or object body, exported or not
+struct Decl {
+    /// Qualified by enclosing types and objects: UserService.create
+    name: String,
+    kind: String,
+    export_kind: Option<&'static str>,
+    signature: Option<String>,
+    start_byte: usize,
+    start_line: usize,
+    end_line: usize,
+}
+
+impl Decl {
+    /// Position fields for a declaration node, leading annotations included
+    fn at(node: Node) -> Self {
+        Self {
+            name: String::new(),
+            kind: String::new(),
+            export_kind: None,
+            signature: None,
+            start_byte: node.start_byte(),
+            start_line: node.start_position().row + 1,
+            end_line: node.end_position().row + 1,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ScopeKind {
+    File,
+    Object,
+    Type,
+}
+
+/// The body a declaration appears in
+struct Scope<'s> {
+    prefix: Option<&'s str>,
+    kind: ScopeKind,
+    /// Whether members of this body can be exported at all
+    exported: bool,
+}
+
+impl Scope<'_> {
+    fn qualify(&self, name: &str) -> String {
+        match self.prefix {
+            Some(prefix) => format!("{}.{}", prefix, name),
+            None => name.to_string(),
+        }
+    }
+}
+
+/// Pieces of a function header, rendered with or without its name
+struct FunctionHeader {
+    name: String,
+    type_params: String,
+    receiver: Option<String>,
+    params: String,
+    returns: Option<String>,
+    constraints: String,
+}
+
+impl FunctionHeader {
+    /// `<T> List<T>.second() -> T` with a name, `<T> List<T>.() -> T` without
+    fn render(&self, name: &str) -> String {
+        let returns = self
+            .returns
+            .as_ref()
+            .map(|r| format!(" -> {}", r))
+            .unwrap_or_default();
+        let head = match &self.receiver {
+            Some(receiver) if self.type_params.is_empty() => format!("{}.{}", receiver, name),
+            Some(receiver) => format!("{} {}.{}", self.type_params, receiver, name),
+            None => format!("{}{}", name, self.type_params),
+        };
+        format!("{}{}{}{}", head, self.params, returns, self.constraints)
+    }
+}
+
+/// Whether the modifiers node holds `modifier` (suspend, data, const, ...)
+fn has_modifier(node: Node, source: &str, modifier: &str) -> bool {
+    nodes::child_of_kind(node, "modifiers").is_some_and(|mods| {
+        let mut cursor = mods.walk();
+        let found = mods
+            .named_children(&mut cursor)
+            .any(|child| &source[child.start_byte()..child.end_byte()] == modifier);
+        found
+    })
+}
+
+fn is_public(node: Node, source: &str) -> bool {
+    !["private", "internal", "protected"]
+        .iter()
+        .any(|m| has_modifier(node, source, m))
+}
+
+/// Summaries keep five members, then "..."
+fn push_member(summary: &mut Vec<String>, member: String) {
+    match summary.len() {
+        0..5 => summary.push(member),
+        5 => summary.push("...".to_string()),
+        _ => {}
+    }
+}
+
+impl Default for KotlinParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguageParser for KotlinParser {
+    fn language_name(&self) -> &'static str {
+        "kotlin"
+    }
+
+    fn file_extensions(&self) -> &[&'static str] {
+        &["kt", "kts"]
+    }
+
+    fn extract_ast_info(&self, source: &str, _file_path: &Path) -> Result<ASTInfo, ParseError> {
+        let trees = self.parse_trees(source)?;
+        let roots: Vec<Node> = trees.iter().map(Tree::root_node).collect();
+        let decls = self.extract_declarations(&roots, source);
+
+        let exports = self.extract_exports(&decls);
+        let imports = self.extract_imports(&roots, source);
+        let calls = self.extract_calls(&roots, source, &imports);
+        let signatures = self.extract_signatures(&decls);
+
+        let tokens = super::tokens::count_tokens(source);
+
+        Ok(ASTInfo {
+            tokens,
+            exports,
+            imports,
+            calls,
+            signatures,
+        })
+    }
+
+    fn extract_toon_comments(&self, source: &str) -> Result<ExtractedComments, ParseError> {
+        let mut result = ExtractedComments::default();
+
+        // Find @dose in /** */ block comments
+        let block_pattern = Regex::new(r"/\*\*[\s\S]*?@dose[\s\S]*?\*/").unwrap();
+        let file_block = block_pattern
+            .find_iter(source)
+            .find(|m| toon_comment::parse_inline_annotation(m.as_str()).is_none());
+
+        if let Some(mat) = file_block {
+            let comment = mat.as_str();
+            let content = comment.trim_start_matches("/**").trim_end_matches("*/");
+
+            let content = content
+                .lines()
+                .map(|line| {
+                    let trimmed = line.trim().trim_start_matches('*').trim();
+                    if trimmed == "@dose" {
+                        ""
+                    } else {
+                        trimmed
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            result.file_block = Some(toon_comment::parse_toon_block(&content));
+        }
+
+        // Inline // @dose invariant: value annotations on the following declaration
+        for tree in self.parse_trees(source)? {
+            let annotations = toon_comment::collect_inline_annotations(
+                tree.root_node(),
+                source,
+                &["line_comment", "block_comment"],
+                |node| match node.kind() {
+                    "function_declaration" | "class_declaration" | "object_declaration" => node
+                        .child_by_field_name("name")
+                        .map(|n| self.node_text(n, source)),
+                    "property_declaration" => nodes::child_of_kind(node, "variable_declaration")
+                        .and_then(|v| nodes::child_of_kind(v, "identifier"))
+                        .map(|n| self.node_text(n, source)),
+                    "type_alias" => node
+                        .child_by_field_name("type")
+                        .map(|n| self.node_text(n, source)),
+                    _ => None,
+                },
+                |node| match node.kind() {
+                    "class_declaration" | "object_declaration" => node
+                        .child_by_field_name("name")
+                        .map(|n| self.node_text(n, source)),
+                    // Companion members belong to the class: UserService.create
+                    "companion_object" => node
+                        .parent()
+                        .and_then(|body| body.parent())
+                        .and_then(|owner| owner.child_by_field_name("name"))
+                        .map(|n| self.node_text(n, source)),
+                    _ => None,
+                },
+            );
+            result.function_annotations.extend(annotations);
+        }
+
+        Ok(result)
+    }
+
+    fn strip_toon_comments(&self, source: &str, toon_path: &str) -> Result<String, ParseError> {
+        // Replace /** */ @dose blocks
+        let block_pattern = Regex::new(r"/\*\*[\s\S]*?@dose[\s\S]*?\*/").unwrap();
+        let result = block_pattern
+            .replace_all(source, &format!("// @dose -> {}", toon_path))
+            .to_string();
+
+        Ok(result)
+    }
+
+    fn get_string_ranges(&self, source: &str) -> Result<Vec<(usize, usize)>, ParseError> {
+        let mut ranges = Vec::new();
+        for tree in self.parse_trees(source)? {
+            collect_string_ranges_kotlin(&mut tree.walk(), &mut ranges);
+        }
+        Ok(ranges)
+    }
+}
+
+/// One range per top-level declaration: each starts at a line that begins in column 0,
+/// unless the line above is a comment or annotation that belongs to it
+fn top_level_ranges(source: &str) -> Vec<Range> {
+    let annotation_line = Regex::new(r"^(@[\w.:]+(\([^)]*\))?\s*)+$").unwrap();
+    let mut starts = vec![(0, Point::new(0, 0))];
+    let mut attached = false;
+    let mut offset = 0;
+    for (row, line) in source.split_inclusive('\n').enumerate() {
+        if row > 0
+            && !attached
+            && line.starts_with(|c: char| c.is_alphabetic() || c == '@' || c == '/')
+        {
+            starts.push((offset, Point::new(row, 0)));
+        }
+        let trimmed = line.trim();
+        if !trimmed.is_empty() {
+            attached = trimmed.starts_with(['/', '*']) || annotation_line.is_match(trimmed);
+        }
+        offset += line.len();
+    }
+
+    let last_line = source.rsplit('\n').next().unwrap_or_default();
+    let end = Point::new(source.matches('\n').count(), last_line.len());
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, &(start_byte, start_point))| {
+            let (end_byte, end_point) = starts.get(i + 1).copied().unwrap_or((source.len(), end));
+            Range {
+                start_byte,
+                end_byte,
+                start_point,
+                end_point,
+            }
+        })
+        .collect()
+}
+
+fn collect_string_ranges_kotlin(
+    cursor: &mut tree_sitter::TreeCursor,
+    ranges: &mut Vec<(usize, usize)>,
+) {
+    loop {
+        let node = cursor.node();
+        let kind = node.kind();
+        // Strings inside ${} templates are already covered by the outer literal
+        if matches!(
+            kind,
+            "string_literal" | "multiline_string_literal" | "character_literal"
+        ) {
+            ranges.push((node.start_byte(), node.end_byte()));
+        } else if cursor.goto_first_child() {
+            collect_string_ranges_kotlin(cursor, ranges);
+            cursor.goto_parent();
+        }
+        if !cursor.goto_next_sibling() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KT_FIXTURE: &str = include_str!("../../test_fixtures/sample.kt");
+
+    #[test]
+    fn test_extract_ast_info() {
+        let parser = KotlinParser::new();
+        let info = parser
+            .extract_ast_info(KT_FIXTURE, Path::new("sample.kt"))
+            .unwrap();
+
+        let mut exports: Vec<_> = info
+            .exports
+            .iter()
+            .map(|e| (&e.name[..], &e.kind[..]))
+            .collect();
+        exports.sort();
+        assert_eq!(
+            exports,
+            vec![
+                ("DEFAULT_TIMEOUT", "val"),
+                ("Repository", "interface"),
+                ("Result", "class"),
+                ("Result.Failure", "class"),
+                ("Result.Loading", "object"),
+                ("Result.Success", "class"),
+                ("UserConfig", "class"),
+                ("UserMap", "type"),
+                ("UserRegistry", "object"),
+                ("UserRegistry.register", "method"),
+                ("UserService", "class"),
+                ("UserService.DEFAULT_DIR", "const"),
+                ("UserService.create", "method"),
+                ("UserStatus", "enum"),
+                ("VERSION", "const"),
+                ("loadAll", "fn"),
+                ("second", "fn"),
+                ("toSlug", "fn"),
+            ]
+        );
+
+        let mut imports: Vec<_> = info
+            .imports
+            .iter()
+            .map(|i| (&i.from[..], i.items.join("|")))
+            .collect();
+        imports.sort();
+        assert_eq!(
+            imports,
+            vec![
+                ("com.luny.model", "*".to_string()),
+                ("java.io.File", "File".to_string()),
+                ("kotlinx.coroutines.Dispatchers", "Dispatchers".to_string()),
+                ("kotlinx.coroutines.withContext", "withContext".to_string()),
+                ("kotlinx.serialization.json.Json", "JsonCodec".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_signatures() {
+        let parser = KotlinParser::new();
+        let info = parser
+            .extract_ast_info(KT_FIXTURE, Path::new("sample.kt"))
+            .unwrap();
+
+        let sig = |name: &str| {
+            info.signatures
+                .iter()
+                .find(|s| s.name == name)
+                .unwrap_or_else(|| panic!("missing signature for {}", name))
+        };
+
+        let second = sig("second");
+        assert_eq!(second.kind, "extension fn");
+        assert_eq!(second.signature, "<T> List<T>.() -> T");
+
+        let to_slug = sig("toSlug");
+        assert_eq!(
+            to_slug.signature,
+            "String.(separator: Char = '-') -> String"
+        );
+        assert_eq!((to_slug.start_line, to_slug.end_line), (103, 104));
+
+        let load_all = sig("loadAll");
+        assert_eq!(load_all.kind, "suspend fn");
+        assert_eq!(
+            load_all.signature,
+            "(service: UserService, ids: List<String>) -> List<UserConfig>"
+        );
+        assert_eq!((load_all.start_line, load_all.end_line), (106, 108));
+
+        assert_eq!(
+            sig("UserService.create").signature,
+            "(dir: String = DEFAULT_DIR) -> UserService"
+        );
+
+        let config = sig("UserConfig");
+        assert_eq!(config.kind, "data class");
+        assert_eq!(
+            config.signature,
+            "(val id: String, val name: String, val email: String? = null) { displayName() -> String }"
+        );
+        assert_eq!((config.start_line, config.end_line), (43, 49));
+
+        let result = sig("Result");
+        assert_eq!(result.kind, "sealed class");
+        assert_eq!(
+            result.signature,
+            "<out T> { data class Success; data class Failure; object Loading }"
+        );
+        assert_eq!(
+            sig("Result.Success").signature,
+            "<T>(val value: T) : Result<T>()"
+        );
+
+        let service = sig("UserService");
+        assert_eq!(
+            service.signature,
+            "@Singleton (private val dataDir: File) : Repository<UserConfig> { findById(id: String) -> UserConfig?; save(entity: UserConfig) -> Boolean }"
+        );
+        assert_eq!(
+            sig("Repository").signature,
+            "<T> { findById(id: String) -> T?; save(entity: T) -> Boolean }"
+        );
+        assert_eq!(
+            sig("UserStatus").signature,
+            "{ ACTIVE | INACTIVE | PENDING | SUSPENDED }"
+        );
+        assert_eq!(
+            sig("UserRegistry").signature,
+            "{ register(service: UserService) }"
+        );
+        assert_eq!(sig("UserMap").signature, "= Map<String, UserConfig>");
+    }
+
+    #[test]
+    fn test_extract_calls() {
+        let parser = KotlinParser::new();
+        let info = parser
+            .extract_ast_info(KT_FIXTURE, Path::new("sample.kt"))
+            .unwrap();
+
+        let mut calls: Vec<_> = info
+            .calls
+            .iter()
+            .map(|c| (&c.target[..], &c.method[..]))
+            .collect();
+        calls.sort();
+        assert_eq!(
+            calls,
+            vec![
+                ("java.io.File", "mkdirs"),
+                ("java.io.File", "new"),
+                ("kotlinx.coroutines", "withContext"),
+                ("kotlinx.serialization.json.Json", "decodeFromString"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_headers() {
+        let parser = KotlinParser::new();
+        let source = r#"
+@JvmInline
+value class Id(val raw: String)
+
+class Box<T : Any> private constructor(val v: T) where T : Comparable<T> {
+    constructor(x: Int) : this(x as T)
+    val String.size: Int get() = length
+    private var count: Int = 0
+
+    companion object Factory {
+        fun <T : Comparable<T>> of(v: T): Box<T> = Box(v)
+    }
+}
+
+fun <T> maxOf(a: T, b: T): T where T : Comparable<T> = if (a > b) a else b
+"#;
+        let info = parser
+            .extract_ast_info(source, Path::new("Box.kt"))
+            .unwrap();
+
+        let sig = |name: &str| {
+            info.signatures
+                .iter()
+                .find(|s| s.name == name)
+                .unwrap_or_else(|| panic!("missing signature for {}", name))
+        };
+
+        let id = sig("Id");
+        assert_eq!(id.kind, "value class");
+        assert_eq!(id.signature, "@JvmInline (val raw: String)");
+        assert_eq!((id.start_line, id.end_line), (2, 3));
+
+        assert_eq!(
+            sig("Box").signature,
+            "<T : Any>(val v: T) where T : Comparable<T> { constructor(x: Int); String.size: Int }"
+        );
+        assert_eq!(
+            sig("Box.of").signature,
+            "<T : Comparable<T>>(v: T) -> Box<T>"
+        );
+        assert_eq!(
+            sig("maxOf").signature,
+            "<T>(a: T, b: T) -> T where T : Comparable<T>"
+        );
+    }
+
+    #[test]
+    fn test_extract_after_recovery() {
+        // A one-line private class body followed by a companion object makes tree-sitter
+        // recover by dropping the second class; each declaration is then parsed alone
+        let source = "private class Hidden { fun leak() {} }\nclass C {\n  companion object { fun make() = C() }\n}\n";
+        let info = KotlinParser::new()
+            .extract_ast_info(source, Path::new("Recovery.kt"))
+            .unwrap();
+
+        let exports: Vec<_> = info
+            .exports
+            .iter()
+            .map(|e| (e.name.as_str(), e.kind.as_str()))
+            .collect();
+        assert_eq!(exports, vec![("C", "class"), ("C.make", "method")]);
+        let lines: Vec<_> = info
+            .signatures
+            .iter()
+            .map(|s| (s.name.as_str(), s.start_line, s.end_line))
+            .collect();
+        assert_eq!(lines, vec![("C", 2, 4), ("C.make", 3, 3)]);
+    }
+
+    #[test]
+    fn test_inline_annotations() {
+        let parser = KotlinParser::new();
+        let source = r#"/** @dose
+purpose: Orders
+*/
+class OrderService {
+    // @dose invariant: total is never negative
+    @Transactional
+    fun total(order: Order): Long = 0
+
+    /** @dose error-handling: throws on unknown SKU */
+    fun add(sku: String) {}
+}
+
+// @dose gotcha: blocks the calling thread
+fun String.loadBlocking() = Unit
+"#;
+        let comments = parser.extract_toon_comments(source).unwrap();
+        assert_eq!(comments.file_block.unwrap().purpose.unwrap(), "Orders");
+        assert_eq!(
+            comments.function_annotations["OrderService.total"].invariants,
+            Some(vec!["total is never negative".to_string()])
+        );
+        assert_eq!(
+            comments.function_annotations["OrderService.add"].error_handling,
+            Some(vec!["throws on unknown SKU".to_string()])
+        );
+        assert_eq!(
+            comments.function_annotations["loadBlocking"].gotchas,
+            Some(vec!["blocks the calling thread".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_toon_comments() {
+        let parser = KotlinParser::new();
+        let comments = parser.extract_toon_comments(KT_FIXTURE).unwrap();
+        let block = comments.file_block.unwrap();
+        assert_eq!(block.purpose.unwrap(), "Sample Kotlin fixture for testing the luny Kotlin parser. This file contains various Kotlin constructs including data and sealed classes, objects, companion objects, and extension functions to verify extraction works correctly.");
+
+        let stripped = parser
+            .strip_toon_comments(KT_FIXTURE, "sample.kt.toon")
+            .unwrap();
+        assert!(stripped.starts_with("// @dose -> sample.kt.toon"));
+    }
+
+    #[test]
+    fn test_string_ranges() {
+        let parser = KotlinParser::new();
+        let source = "val s = \"a ${b + \"}\"} c\"\nval c = ' '\nval r = \"\"\"\n  x \" y\n\"\"\"";
+        let ranges = parser.get_string_ranges(source).unwrap();
+        let texts: Vec<_> = ranges.iter().map(|(s, e)| &source[*s..*e]).collect();
+        assert_eq!(
+            texts,
+            vec!["\"a ${b + \"}\"} c\"", "' '", "\"\"\"\n  x \" y\n\"\"\""]
+        );
+    }
+}
@@ -21,32 +21,60 @@
 //! gotchas:
 //!     - The TypeScript parser handles both .ts/.tsx and .js/.jsx extensions
 //!     - Extensions are stored without the leading dot (e.g., "ts" not ".ts")
+//!     - from_config lets luny.toml remap built-in extensions; new() never reads config
+//!     - Files without an extension are matched by their #! line, which costs a file read;
+//!       callers apply exclusions first, and each path is read once until its mtime changes
 //!
 //! flows:
-//!     - Get parser: Call get_parser() with a Path, it extracts extension and looks up parser
+//!     - Configure: Call from_config() to add [[language]] tables from luny.toml
+//!     - Get parser: Call get_parser() with a Path, it extracts extension (or the #!
+//!       interpreter for extensionless scripts) and looks up parser
 //!     - Check support: Call is_supported() to verify a file type is handled
 //!     - List extensions: Call supported_extensions() to get all registered extensions
 
+mod cpp;
 mod csharp;
+mod elixir;
 mod go;
+mod java;
+mod kotlin;
+mod nodes;
+mod php;
 mod python;
+mod query;
 mod ruby;
 mod rust;
+mod sfc;
+mod shell;
+mod swift;
 mod tokens;
 pub mod toon_comment;
 mod typescript;
 
+use crate::config::Config;
 use crate::types::{ASTInfo, ExtractedComments};
 use std::collections::HashMap;
-use std::path::Path;
-use std::sync::Arc;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
 use thiserror::Error;
 
+pub use cpp::CppParser;
 pub use csharp::CSharpParser;
+pub use elixir::ElixirParser;
 pub use go::GoParser;
+pub use java::JavaParser;
+pub use kotlin::KotlinParser;
+pub use php::PhpParser;
 pub use python::PythonParser;
+pub use query::QueryParser;
 pub use ruby::RubyParser;
 pub use rust::RustParser;
+pub use sfc::SfcParser;
+pub use shell::ShellParser;
+pub use swift::SwiftParser;
 pub use typescript::TypeScriptParser;
 
 #[derive(Error, Debug)]
@@ -79,11 +107,19 @@ pub trait LanguageParser: Send + Sync {
     /// Get byte ranges of string literals in source (for minification)
     /// Returns Vec of (start_byte, end_byte) ranges
     fn get_string_ranges(&self, source: &str) -> Result<Vec<(usize, usize)>, ParseError>;
+
+    /// Candidate companion files (e.g. a header's implementation) to list under related.
+    /// Paths need not exist; callers keep only the ones that do.
+    fn paired_files(&self, _file_path: &Path) -> Vec<PathBuf> {
+        Vec::new()
+    }
 }
 
 /// Factory for creating language parsers
 pub struct ParserFactory {
     parsers: HashMap<String, Arc<dyn LanguageParser>>,
+    /// #! lookups for extensionless files, with the mtime they were read at
+    shebangs: Mutex<HashMap<PathBuf, (SystemTime, Option<&'static str>)>>,
 }
 
 impl ParserFactory {
@@ -126,15 +162,131 @@ impl ParserFactory {
             parsers.insert(ext.to_string(), Arc::clone(&rust_parser));
         }
 
-        Self { parsers }
+        // Java parser
+        let java_parser: Arc<dyn LanguageParser> = Arc::new(JavaParser::new());
+        for ext in java_parser.file_extensions() {
+            parsers.insert(ext.to_string(), Arc::clone(&java_parser));
+        }
+
+        // Kotlin parser
+        let kotlin_parser: Arc<dyn LanguageParser> = Arc::new(KotlinParser::new());
+        for ext in kotlin_parser.file_extensions() {
+            parsers.insert(ext.to_string(), Arc::clone(&kotlin_parser));
+        }
+
+        // C/C++ parser
+        let cpp_parser: Arc<dyn LanguageParser> = Arc::new(CppParser::new());
+        for ext in cpp_parser.file_extensions() {
+            parsers.insert(ext.to_string(), Arc::clone(&cpp_parser));
+        }
+
+        // PHP parser
+        let php_parser: Arc<dyn LanguageParser> = Arc::new(PhpParser::new());
+        for ext in php_parser.file_extensions() {
+            parsers.insert(ext.to_string(), Arc::clone(&php_parser));
+        }
+
+        // Swift parser
+        let swift_parser: Arc<dyn LanguageParser> = Arc::new(SwiftParser::new());
+        for ext in swift_parser.file_extensions() {
+            parsers.insert(ext.to_string(), Arc::clone(&swift_parser));
+        }
+
+        // Elixir parser
+        let ex_parser: Arc<dyn LanguageParser> = Arc::new(ElixirParser::new());
+        for ext in ex_parser.file_extensions() {
+            parsers.insert(ext.to_string(), Arc::clone(&ex_parser));
+        }
+
+        // Shell parser
+        let sh_parser: Arc<dyn LanguageParser> = Arc::new(ShellParser::new());
+        for ext in sh_parser.file_extensions() {
+            parsers.insert(ext.to_string(), Arc::clone(&sh_parser));
+        }
+
+        // Vue, Svelte, and Astro component parser
+        let sfc_parser: Arc<dyn LanguageParser> = Arc::new(SfcParser::new());
+        for ext in sfc_parser.file_extensions() {
+            parsers.insert(ext.to_string(), Arc::clone(&sfc_parser));
+        }
+
+        Self {
+            parsers,
+            shebangs: Mutex::default(),
+        }
+    }
+
+    /// Create a factory with the built-in parsers plus the [[language]] tables from luny.toml.
+    /// Config entries win over built-in extensions; invalid entries are skipped with a warning.
+    pub fn from_config(config: &Config) -> Self {
+        let mut factory = Self::new();
+
+        for language in &config.languages {
+            let parser: Arc<dyn LanguageParser> = if language.queries.is_empty() {
+                // Dialect mapping: reuse the built-in parser for this grammar
+                let builtin = query::builtin_extension(&language.grammar)
+                    .and_then(|ext| factory.parsers.get(ext));
+                match builtin {
+                    Some(parser) => Arc::clone(parser),
+                    None => {
+                        eprintln!(
+                            "Warning: No built-in parser for grammar '{}' in luny.toml",
+                            language.grammar
+                        );
+                        continue;
+                    }
+                }
+            } else {
+                match QueryParser::new(language) {
+                    Ok(parser) => Arc::new(parser),
+                    Err(e) => {
+                        eprintln!("Warning: Skipping [[language]] in luny.toml: {}", e);
+                        continue;
+                    }
+                }
+            };
+
+            for ext in &language.extensions {
+                let ext = ext.trim_start_matches('.');
+                factory.parsers.insert(ext.to_string(), Arc::clone(&parser));
+            }
+        }
+
+        factory
     }
 
     /// Get parser for a file path based on extension
     pub fn get_parser(&self, file_path: &Path) -> Option<&dyn LanguageParser> {
-        let ext = file_path.extension()?.to_str()?;
+        let ext = match file_path.extension() {
+            Some(ext) => ext.to_str()?,
+            None => self.cached_shebang_extension(file_path)?,
+        };
         self.parsers.get(ext).map(|p| p.as_ref())
     }
 
+    /// shebang_extension, read once per path and again only after the file is modified
+    fn cached_shebang_extension(&self, file_path: &Path) -> Option<&'static str> {
+        let modified = fs::metadata(file_path).and_then(|m| m.modified()).ok()?;
+        let cached = self
+            .shebangs
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(file_path)
+            .copied();
+        if let Some((read_at, ext)) = cached {
+            if read_at == modified {
+                return ext;
+            }
+        }
+
+        let ext = shebang_extension(file_path);
+        self.shebangs
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(file_path.to_path_buf(), (modified, ext));
+        ext
+    }
+
     /// Get parser by extension string
     pub fn get_parser_by_ext(&self, ext: &str) -> Option<&dyn LanguageParser> {
         self.parsers.get(ext).map(|p| p.as_ref())
@@ -142,11 +294,7 @@ impl ParserFactory {
 
     /// Check if a file extension is supported
     pub fn is_supported(&self, file_path: &Path) -> bool {
-        file_path
-            .extension()
-            .and_then(|e| e.to_str())
-            .map(|ext| self.parsers.contains_key(ext))
-            .unwrap_or(false)
+        self.get_parser(file_path).is_some()
     }
 
     /// Get all supported extensions
@@ -155,6 +303,26 @@ impl ParserFactory {
     }
 }
 
+/// Extension standing in for an extensionless script's #! interpreter
+fn shebang_extension(file_path: &Path) -> Option<&'static str> {
+    let file = File::open(file_path).ok()?;
+    let mut first_line = String::new();
+    BufReader::new(file.take(256))
+        .read_line(&mut first_line)
+        .ok()?;
+
+    // #!/bin/bash, #!/usr/bin/env bash, #!/usr/bin/env -S bash -e
+    let mut words = first_line.strip_prefix("#!")?.split_whitespace();
+    let mut interpreter = words.next()?.rsplit('/').next()?;
+    if interpreter == "env" {
+        interpreter = words.find(|w| !w.starts_with('-'))?;
+    }
+    match interpreter {
+        "sh" | "bash" => Some("sh"),
+        _ => None,
+    }
+}
+
 impl Default for ParserFactory {
     fn default() -> Self {
         Self::new()
@@ -165,6 +333,92 @@ impl Default for ParserFactory {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parser_factory_from_config() {
+        let config: Config = toml::from_str(
+            r##"
+[[language]]
+grammar = "javascript"
+extensions = ["mjs", ".cjs"]
+
+[[language]]
+grammar = "python"
+extensions = ["pyi"]
+
+[[language]]
+name = "starlark"
+grammar = "python"
+extensions = ["bzl"]
+comments = { line = "#" }
+
+[language.queries]
+exports = "(function_definition name: (identifier) @name) @fn"
+
+[[language]]
+grammar = "cobol"
+extensions = ["cbl"]
+"##,
+        )
+        .unwrap();
+        let factory = ParserFactory::from_config(&config);
+
+        let language = |file: &str| {
+            factory
+                .get_parser(Path::new(file))
+                .map(|p| p.language_name())
+        };
+        assert_eq!(language("a.mjs"), Some("typescript"));
+        assert_eq!(language("a.cjs"), Some("typescript"));
+        assert_eq!(language("a.pyi"), Some("python"));
+        assert_eq!(language("BUILD.bzl"), Some("starlark"));
+        assert_eq!(language("a.cbl"), None);
+        // Built-in parsers are still registered
+        assert_eq!(language("a.py"), Some("python"));
+    }
+
+    #[test]
+    fn test_shebang_detection() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let write = |name: &str, content: &str| {
+            let path = temp_dir.path().join(name);
+            std::fs::write(&path, content).unwrap();
+            path
+        };
+        let factory = ParserFactory::new();
+
+        for shebang in [
+            "#!/bin/bash\n",
+            "#!/bin/sh\n",
+            "#!/usr/bin/env -S bash -e\n",
+        ] {
+            let script = write("deploy", shebang);
+            assert_eq!(
+                factory.get_parser(&script).map(|p| p.language_name()),
+                Some("shell")
+            );
+        }
+        assert!(!factory.is_supported(&write("tool", "#!/usr/bin/env node\n")));
+        assert!(!factory.is_supported(&write("LICENSE", "MIT License\n")));
+    }
+
+    #[test]
+    fn test_shebang_rejects_binary_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let binary = temp_dir.path().join("app");
+        let mut content = vec![0x7f, b'E', b'L', b'F', 0xff, 0xfe, 0x00];
+        content.extend(std::iter::repeat_n(0x90, 4096));
+        std::fs::write(&binary, content).unwrap();
+        let factory = ParserFactory::new();
+
+        assert!(!factory.is_supported(&binary));
+        // The cached answer is reused for the same file
+        assert!(!factory.is_supported(&binary));
+        assert!(factory.get_parser(&binary).is_none());
+        // Missing files are rejected without being cached
+        assert!(!factory.is_supported(&temp_dir.path().join("missing")));
+        assert_eq!(factory.shebangs.lock().unwrap().len(), 1);
+    }
+
     #[test]
     fn test_parser_factory() {
         let factory = ParserFactory::new();
@@ -233,6 +487,73 @@ mod tests {
                 .language_name(),
             "rust"
         );
+        assert_eq!(
+            factory
+                .get_parser(Path::new("test.java"))
+                .unwrap()
+                .language_name(),
+            "java"
+        );
+        assert_eq!(
+            factory
+                .get_parser(Path::new("test.kt"))
+                .unwrap()
+                .language_name(),
+            "kotlin"
+        );
+        assert_eq!(
+            factory
+                .get_parser(Path::new("build.gradle.kts"))
+                .unwrap()
+                .language_name(),
+            "kotlin"
+        );
+        assert_eq!(
+            factory
+                .get_parser(Path::new("test.c"))
+                .unwrap()
+                .language_name(),
+            "cpp"
+        );
+        assert_eq!(
+            factory
+                .get_parser(Path::new("test.hpp"))
+                .unwrap()
+                .language_name(),
+            "cpp"
+        );
+        assert_eq!(
+            factory
+                .get_parser(Path::new("test.php"))
+                .unwrap()
+                .language_name(),
+            "php"
+        );
+        assert_eq!(
+            factory
+                .get_parser(Path::new("test.swift"))
+                .unwrap()
+                .language_name(),
+            "swift"
+        );
+        for file in ["test.ex", "test.exs"] {
+            assert_eq!(
+                factory.get_parser(Path::new(file)).unwrap().language_name(),
+                "elixir"
+            );
+        }
+        for file in ["test.sh", "test.bash"] {
+            assert_eq!(
+                factory.get_parser(Path::new(file)).unwrap().language_name(),
+                "shell"
+            );
+        }
+        for file in ["test.vue", "test.svelte", "test.astro"] {
+            assert_eq!(
+                factory.get_parser(Path::new(file)).unwrap().language_name(),
+                "sfc"
+            );
+        }
 
         // Verify unsupported extensions return None
         assert!(factory.get_parser(Path::new("test.json")).is_none());
@@ -246,7 +567,11 @@ mod tests {
         exts.sort();
         assert_eq!(
             exts,
-            vec!["cs", "go", "js", "jsx", "py", "rb", "rs", "ts", "tsx"]
+            vec![
+                "astro", "bash", "c", "cc", "cpp", "cs", "ex", "exs", "go", "h", "hpp", "java",
+                "js", "jsx", "kt", "kts", "php", "py", "rb", "rs", "sh", "svelte", "swift", "ts",
+                "tsx", "vue"
+            ]
         );
     }
 }
@@ -0,0 +1,64 @@
+//! Shared tree-sitter node helpers used by the signature extractors.
+//!
+//! Parsers that render declarations as one-line signatures (C#, C++, Java, PHP,
+//! Swift) all need the same child lookup, whitespace folding, and member naming.
+
+use tree_sitter::Node;
+
+/// First direct child of the given kind, named or anonymous (`static`, `modifiers`)
+pub fn child_of_kind<'a>(node: Node<'a>, kind: &str) -> Option<Node<'a>> {
+    (0..node.child_count())
+        .filter_map(|i| node.child(i))
+        .find(|child| child.kind() == kind)
+}
+
+/// Name of the nearest ancestor whose kind `is_owner` accepts, from its `name` field
+pub fn enclosing_name(node: Node, source: &str, is_owner: impl Fn(&str) -> bool) -> Option<String> {
+    let mut ancestor = node.parent();
+    while let Some(parent) = ancestor {
+        if is_owner(parent.kind()) {
+            let name = parent.child_by_field_name("name")?;
+            return Some(source[name.start_byte()..name.end_byte()].to_string());
+        }
+        ancestor = parent.parent();
+    }
+    None
+}
+
+/// `Owner.member` for a member of a named type, the bare name otherwise
+pub fn qualify(owner: Option<String>, name: String) -> String {
+    match owner {
+        Some(owner) => format!("{}.{}", owner, name),
+        None => name,
+    }
+}
+
+/// Node text with line breaks and indentation folded to single spaces
+pub fn collapsed_text(node: Node, source: &str) -> String {
+    collapse(&source[node.start_byte()..node.end_byte()])
+}
+
+/// Fold whitespace runs to single spaces and drop the space left inside parentheses
+/// by a parameter list that was split across lines
+pub fn collapse(text: &str) -> String {
+    fold_whitespace(text).replace("( ", "(").replace(" )", ")")
+}
+
+/// Fold whitespace runs, line breaks included, to single spaces
+pub fn fold_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collapse() {
+        assert_eq!(
+            collapse("(\n    int a,\n    int b\n)"),
+            "(int a, int b)".to_string()
+        );
+        assert_eq!(fold_whitespace("do\n  ( x )"), "do ( x )".to_string());
+    }
+}
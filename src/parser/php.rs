@@ -0,0 +1,1185 @@
+//! @dose
+//! purpose: This module parses PHP source files to extract classes, interfaces, traits,
+//!     enums, functions, and public methods. It uses tree-sitter for robust parsing and
+//!     resolves class names through the file's namespace and use statements.
+//!
+//! when-editing:
+//!     - !Members without a visibility modifier are public, so only private/protected hide them
+//!     - !Class names resolve like PHP does: use alias, then leading backslash, then current namespace
+//!     - The is_public() helper checks the visibility_modifier child
+//!
+//! invariants:
+//!     - Private and protected methods are never exported
+//!     - Every use clause (grouped, aliased, function, const) becomes its own import
+//!     - Static calls (Foo::bar()) are attributed to the fully qualified class name
+//!
+//! do-not:
+//!     - Never export __construct; it appears as new(...) in the class signature instead
+//!     - Never attribute self::, static::, or parent:: calls; they stay inside the class
+//!
+//! gotchas:
+//!     - Only the first namespace in a file is used to qualify unimported names
+//!     - Interface methods are implicitly public but, like Java, are not exported on their own
+//!     - Variable types are tracked per file, not per scope, so reused $names can misattribute
+//!     - Inline HTML outside <?php ?> tags is kept verbatim by minification
+//!
+//! flows:
+//!     - Parse: Create tree-sitter parser, set PHP language, parse source
+//!     - Extract exports: Walk AST for class-likes, functions, and public methods
+//!     - Extract imports: Collect namespace_use_declaration clauses with their aliases
+//!     - Inline annotations: // @dose field: value binds to the next method, function, or type
+//!     - Extract signatures: Typed params and return types, methods as Type.method; types summarize public members
+//!     - Extract calls: Static calls, new, typed $variables/$this->props, and imported functions
+
+use crate::parser::{nodes, toon_comment, LanguageParser, ParseError};
+use crate::types::{ASTInfo, CallInfo, ExportInfo, ExtractedComments, ImportInfo, SignatureInfo};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tree_sitter::Parser;
+
+/// Parser for PHP files
+#[derive(Clone)]
+pub struct PhpParser;
+
+impl PhpParser {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn create_parser(&self) -> Result<Parser, ParseError> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_php::LANGUAGE_PHP.into())
+            .map_err(|e| ParseError::ParseError(e.to_string()))?;
+        Ok(parser)
+    }
+
+    fn extract_exports(&self, root: tree_sitter::Node, source: &str) -> Vec<ExportInfo> {
+        let mut exports = Vec::new();
+        let mut cursor = root.walk();
+
+        self.visit_exports(&mut cursor, source, &mut exports);
+        exports
+    }
+
+    fn visit_exports(
+        &self,
+        cursor: &mut tree_sitter::TreeCursor,
+        source: &str,
+        exports: &mut Vec<ExportInfo>,
+    ) {
+        loop {
+            let node = cursor.node();
+
+            let kind = match node.kind() {
+                "function_definition" => Some("fn"),
+                "method_declaration" if self.is_exported_method(node, source) => Some("method"),
+                other => type_kind(other),
+            };
+            if let Some(kind) = kind {
+                if let Some(name_node) = node.child_by_field_name("name") {
+                    exports.push(ExportInfo {
+                        name: self.node_text(name_node, source),
+                        kind: kind.to_string(),
+                    });
+                }
+            }
+
+            if cursor.goto_first_child() {
+                self.visit_exports(cursor, source, exports);
+                cursor.goto_parent();
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+
+    /// Public methods of classes, traits, and enums (interfaces only declare them)
+    fn is_exported_method(&self, node: tree_sitter::Node, source: &str) -> bool {
+        let in_interface = node
+            .parent()
+            .and_then(|body| body.parent())
+            .is_some_and(|owner| owner.kind() == "interface_declaration");
+        let is_constructor = node
+            .child_by_field_name("name")
+            .is_some_and(|n| self.node_text(n, source) == "__construct");
+        !in_interface && !is_constructor && self.is_public(&node, source)
+    }
+
+    /// Members default to public when no visibility modifier is given
+    fn is_public(&self, node: &tree_sitter::Node, source: &str) -> bool {
+        nodes::child_of_kind(*node, "visibility_modifier")
+            .is_none_or(|v| self.node_text(v, source) == "public")
+    }
+
+    fn is_static(&self, node: &tree_sitter::Node) -> bool {
+        nodes::child_of_kind(*node, "static_modifier").is_some()
+    }
+
+    fn extract_imports(&self, root: tree_sitter::Node, source: &str) -> Vec<ImportInfo> {
+        self.use_clauses(root, source)
+            .into_iter()
+            .map(|clause| {
+                let from = match clause.kind {
+                    // use function App\Support\format_money; -> from App\Support
+                    UseKind::Function | UseKind::Const => clause
+                        .path
+                        .rsplit_once('\\')
+                        .map(|(namespace, _)| namespace.to_string())
+                        .unwrap_or_else(|| clause.path.clone()),
+                    UseKind::Class => clause.path.clone(),
+                };
+                ImportInfo {
+                    from,
+                    items: vec![clause.alias],
+                }
+            })
+            .collect()
+    }
+
+    /// use App\Models\Invoice;                      -> App\Models\Invoice as Invoice
+    /// use App\Models\{Customer, Payment as Pay};   -> one clause per name
+    /// use function App\Support\format_money;       -> function App\Support\format_money
+    fn use_clauses(&self, root: tree_sitter::Node, source: &str) -> Vec<UseClause> {
+        let mut clauses = Vec::new();
+        let mut cursor = root.walk();
+        self.visit_use_declarations(&mut cursor, source, &mut clauses);
+        clauses
+    }
+
+    fn visit_use_declarations(
+        &self,
+        cursor: &mut tree_sitter::TreeCursor,
+        source: &str,
+        clauses: &mut Vec<UseClause>,
+    ) {
+        loop {
+            let node = cursor.node();
+
+            if node.kind() == "namespace_use_declaration" {
+                let declaration_kind = use_kind(node, source);
+                // Group prefix: use App\Models\{...}
+                let prefix =
+                    nodes::child_of_kind(node, "namespace_name").map(|p| self.node_text(p, source));
+                let group = node.child_by_field_name("body").unwrap_or(node);
+
+                for i in 0..group.child_count() {
+                    let Some(clause) = group.child(i) else {
+                        continue;
+                    };
+                    if clause.kind() != "namespace_use_clause" {
+                        continue;
+                    }
+                    let Some(name) = (0..clause.child_count())
+                        .filter_map(|j| clause.child(j))
+                        .find(|c| matches!(c.kind(), "qualified_name" | "name"))
+                    else {
+                        continue;
+                    };
+                    let name = self.node_text(name, source);
+                    let path = match &prefix {
+                        Some(prefix) => format!("{}\\{}", prefix, name),
+                        None => name,
+                    };
+                    let path = path.trim_start_matches('\\').to_string();
+                    let alias = clause
+                        .child_by_field_name("alias")
+                        .map(|a| self.node_text(a, source))
+                        .unwrap_or_else(|| path.rsplit('\\').next().unwrap_or(&path).to_string());
+                    clauses.push(UseClause {
+                        kind: use_kind(clause, source)
+                            .or(declaration_kind)
+                            .unwrap_or(UseKind::Class),
+                        path,
+                        alias,
+                    });
+                }
+                // Use declarations never nest
+            } else if cursor.goto_first_child() {
+                self.visit_use_declarations(cursor, source, clauses);
+                cursor.goto_parent();
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+
+    fn extract_signatures(
+        &self,
+        root: tree_sitter::Node,
+        source: &str,
+        exports: &[ExportInfo],
+    ) -> Vec<SignatureInfo> {
+        let export_names: HashSet<&str> = exports.iter().map(|e| e.name.as_str()).collect();
+        let mut signatures = Vec::new();
+        let mut cursor = root.walk();
+
+        self.visit_signatures(&mut cursor, source, &export_names, &mut signatures);
+        signatures
+    }
+
+    fn visit_signatures(
+        &self,
+        cursor: &mut tree_sitter::TreeCursor,
+        source: &str,
+        export_names: &HashSet<&str>,
+        signatures: &mut Vec<SignatureInfo>,
+    ) {
+        loop {
+            let node = cursor.node();
+
+            // Methods are public unless marked otherwise, but interface methods and
+            // constructors are described by their type, so each method is checked itself
+            let sig = match node.kind() {
+                "function_definition" => {
+                    self.extract_function_signature(node, source, export_names)
+                }
+                "method_declaration" if self.is_exported_method(node, source) => {
+                    self.extract_function_signature(node, source, export_names)
+                }
+                "enum_declaration" => self.extract_enum_signature(node, source, export_names),
+                kind if type_kind(kind).is_some() => {
+                    self.extract_type_signature(node, source, export_names)
+                }
+                _ => None,
+            };
+            if let Some(sig) = sig {
+                signatures.push(sig);
+            }
+
+            if cursor.goto_first_child() {
+                self.visit_signatures(cursor, source, export_names, signatures);
+                cursor.goto_parent();
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+
+    fn extract_function_signature(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        export_names: &HashSet<&str>,
+    ) -> Option<SignatureInfo> {
+        let name_node = node.child_by_field_name("name")?;
+        let name = self.node_text(name_node, source);
+
+        if !export_names.contains(name.as_str()) {
+            return None;
+        }
+
+        let kind = match node.kind() {
+            "function_definition" => "fn",
+            _ if self.is_static(&node) => "static method",
+            _ => "method",
+        };
+        let start_line = node.start_position().row + 1;
+        let end_line = node.end_position().row + 1;
+
+        // Methods are Owner.name, as the fn: annotations name them; functions stay global
+        let owner = match node.kind() {
+            "method_declaration" => {
+                nodes::enclosing_name(node, source, |kind| type_kind(kind).is_some())
+            }
+            _ => None,
+        };
+        Some(SignatureInfo {
+            name: nodes::qualify(owner, name),
+            kind: kind.to_string(),
+            signature: format!(
+                "{}{}",
+                self.attributes(node, source),
+                self.function_signature(node, source)
+            ),
+            start_line,
+            end_line,
+        })
+    }
+
+    /// `(int $amount, ?string $currency = null) -> bool`
+    fn function_signature(&self, node: tree_sitter::Node, source: &str) -> String {
+        let params = node
+            .child_by_field_name("parameters")
+            .map(|p| nodes::collapsed_text(p, source))
+            .unwrap_or_else(|| "()".to_string());
+
+        let returns = node
+            .child_by_field_name("return_type")
+            .map(|r| format!(" -> {}", nodes::collapsed_text(r, source)))
+            .unwrap_or_default();
+
+        format!("{}{}", params, returns)
+    }
+
+    /// Attributes such as `#[Route('/users')] `
+    fn attributes(&self, node: tree_sitter::Node, source: &str) -> String {
+        node.child_by_field_name("attributes")
+            .map(|a| format!("{} ", nodes::collapsed_text(a, source)))
+            .unwrap_or_default()
+    }
+
+    fn extract_type_signature(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        export_names: &HashSet<&str>,
+    ) -> Option<SignatureInfo> {
+        let name_node = node.child_by_field_name("name")?;
+        let name = self.node_text(name_node, source);
+
+        if !export_names.contains(name.as_str()) {
+            return None;
+        }
+
+        // final/abstract/readonly class
+        let mut modifiers = String::new();
+        for i in 0..node.child_count() {
+            if let Some(child) = node.child(i) {
+                if matches!(
+                    child.kind(),
+                    "final_modifier" | "abstract_modifier" | "readonly_modifier"
+                ) {
+                    modifiers.push(' ');
+                    modifiers.push_str(&self.node_text(child, source));
+                }
+            }
+        }
+
+        let mut bases = String::new();
+        for i in 0..node.child_count() {
+            if let Some(child) = node.child(i) {
+                if matches!(child.kind(), "base_clause" | "class_interface_clause") {
+                    bases.push(' ');
+                    bases.push_str(&nodes::collapsed_text(child, source));
+                }
+            }
+        }
+
+        // An interface lists every method it requires, whatever visibility is spelled out
+        let implicit_public = node.kind() == "interface_declaration";
+        let mut members = Vec::new();
+        if let Some(body) = node.child_by_field_name("body") {
+            for i in 0..body.child_count() {
+                if let Some(child) = body.child(i) {
+                    // Traits are listed with the bases: use LogsActivity
+                    if child.kind() == "use_declaration" {
+                        bases.push(' ');
+                        bases.push_str(nodes::collapsed_text(child, source).trim_end_matches(';'));
+                        continue;
+                    }
+                    if !implicit_public && !self.is_public(&child, source) {
+                        continue;
+                    }
+                    self.push_member(child, source, &mut members);
+                }
+                if members.len() >= 5 {
+                    members.truncate(5);
+                    members.push("...".to_string());
+                    break;
+                }
+            }
+        }
+
+        let body = if members.is_empty() {
+            String::new()
+        } else {
+            format!(" {{ {} }}", members.join("; "))
+        };
+        let signature = format!(
+            "{}{}{}{}",
+            self.attributes(node, source).trim_end(),
+            modifiers,
+            bases,
+            body
+        )
+        .trim()
+        .to_string();
+        let start_line = node.start_position().row + 1;
+        let end_line = node.end_position().row + 1;
+
+        Some(SignatureInfo {
+            name,
+            kind: type_kind(node.kind()).unwrap_or("class").to_string(),
+            signature,
+            start_line,
+            end_line,
+        })
+    }
+
+    fn push_member(&self, member: tree_sitter::Node, source: &str, members: &mut Vec<String>) {
+        match member.kind() {
+            "method_declaration" => {
+                let Some(name) = member
+                    .child_by_field_name("name")
+                    .map(|n| self.node_text(n, source))
+                else {
+                    return;
+                };
+                let signature = self.function_signature(member, source);
+                if name == "__construct" {
+                    members.push(format!("new{}", signature));
+                } else if self.is_static(&member) {
+                    members.push(format!("static {}{}", name, signature));
+                } else {
+                    members.push(format!("{}{}", name, signature));
+                }
+            }
+            "property_declaration" => {
+                let ty = member
+                    .child_by_field_name("type")
+                    .map(|t| format!(": {}", nodes::collapsed_text(t, source)))
+                    .unwrap_or_default();
+                for i in 0..member.child_count() {
+                    if let Some(element) = member.child(i) {
+                        if element.kind() == "property_element" {
+                            if let Some(name) = element.child_by_field_name("name") {
+                                members.push(format!("{}{}", self.node_text(name, source), ty));
+                            }
+                        }
+                    }
+                }
+            }
+            "const_declaration" => {
+                for i in 0..member.child_count() {
+                    if let Some(element) = member.child(i) {
+                        if element.kind() == "const_element" {
+                            if let Some(name) = nodes::child_of_kind(element, "name") {
+                                members.push(format!("const {}", self.node_text(name, source)));
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// `: string { Draft | Sent | Paid }`
+    fn extract_enum_signature(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        export_names: &HashSet<&str>,
+    ) -> Option<SignatureInfo> {
+        let name_node = node.child_by_field_name("name")?;
+        let name = self.node_text(name_node, source);
+
+        if !export_names.contains(name.as_str()) {
+            return None;
+        }
+
+        // Backed enums: enum Status: string
+        let backing = (0..node.child_count())
+            .filter_map(|i| node.child(i))
+            .find(|c| c.kind() == "primitive_type")
+            .map(|t| format!(": {} ", self.node_text(t, source)))
+            .unwrap_or_default();
+
+        let mut variants = Vec::new();
+        if let Some(body) = node.child_by_field_name("body") {
+            for i in 0..body.child_count() {
+                if let Some(child) = body.child(i) {
+                    if child.kind() == "enum_case" {
+                        if let Some(name_node) = child.child_by_field_name("name") {
+                            variants.push(self.node_text(name_node, source));
+                        }
+                    }
+                }
+                if variants.len() >= 5 {
+                    variants.push("...".to_string());
+                    break;
+                }
+            }
+        }
+
+        let signature = format!("{}{{ {} }}", backing, variants.join(" | "));
+        let start_line = node.start_position().row + 1;
+        let end_line = node.end_position().row + 1;
+
+        Some(SignatureInfo {
+            name,
+            kind: "enum".to_string(),
+            signature,
+            start_line,
+            end_line,
+        })
+    }
+
+    fn extract_calls(&self, root: tree_sitter::Node, source: &str) -> Vec<CallInfo> {
+        let namespace = (0..root.child_count())
+            .filter_map(|i| root.child(i))
+            .find(|c| c.kind() == "namespace_definition")
+            .and_then(|ns| ns.child_by_field_name("name"))
+            .map(|n| self.node_text(n, source));
+
+        let mut classes: HashMap<String, String> = HashMap::new();
+        let mut functions: HashMap<String, String> = HashMap::new();
+        for clause in self.use_clauses(root, source) {
+            match clause.kind {
+                UseKind::Class => {
+                    classes.insert(clause.alias, clause.path);
+                }
+                UseKind::Function => {
+                    if let Some((ns, _)) = clause.path.rsplit_once('\\') {
+                        functions.insert(clause.alias, ns.to_string());
+                    }
+                }
+                UseKind::Const => {}
+            }
+        }
+
+        let mut ctx = CallContext {
+            namespace,
+            classes,
+            functions,
+            variables: HashMap::new(),
+        };
+        let mut variables = HashMap::new();
+        self.collect_variables(&mut root.walk(), source, &ctx, &mut variables);
+        ctx.variables = variables;
+
+        let mut calls: Vec<CallInfo> = Vec::new();
+        let mut seen: HashSet<(String, String)> = HashSet::new();
+        let mut cursor = root.walk();
+
+        self.visit_calls(&mut cursor, source, &ctx, &mut calls, &mut seen);
+        calls
+    }
+
+    /// Collects $variables and properties whose declared type is a class:
+    /// parameters (including promoted constructor properties) and typed properties
+    fn collect_variables(
+        &self,
+        cursor: &mut tree_sitter::TreeCursor,
+        source: &str,
+        ctx: &CallContext,
+        variables: &mut HashMap<String, String>,
+    ) {
+        loop {
+            let node = cursor.node();
+
+            match node.kind() {
+                "simple_parameter" | "property_promotion_parameter" | "variadic_parameter" => {
+                    let class = node
+                        .child_by_field_name("type")
+                        .and_then(|t| self.type_name(t, source))
+                        .map(|t| ctx.resolve_class(&t));
+                    let name = node
+                        .child_by_field_name("name")
+                        .and_then(|n| nodes::child_of_kind(n, "name"));
+                    if let (Some(class), Some(name)) = (class, name) {
+                        variables.insert(self.node_text(name, source), class);
+                    }
+                }
+                "property_declaration" => {
+                    let class = node
+                        .child_by_field_name("type")
+                        .and_then(|t| self.type_name(t, source))
+                        .map(|t| ctx.resolve_class(&t));
+                    if let Some(class) = class {
+                        for i in 0..node.child_count() {
+                            let name = node
+                                .child(i)
+                                .filter(|e| e.kind() == "property_element")
+                                .and_then(|e| e.child_by_field_name("name"))
+                                .and_then(|n| nodes::child_of_kind(n, "name"));
+                            if let Some(name) = name {
+                                variables.insert(self.node_text(name, source), class.clone());
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+
+            if cursor.goto_first_child() {
+                self.collect_variables(cursor, source, ctx, variables);
+                cursor.goto_parent();
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+
+    /// Class named by a type: Invoice, ?Invoice, \App\Invoice (never int/string/array)
+    fn type_name(&self, node: tree_sitter::Node, source: &str) -> Option<String> {
+        match node.kind() {
+            "named_type" => self.type_name(node.named_child(0)?, source),
+            "optional_type" => self.type_name(node.named_child(0)?, source),
+            "name" | "qualified_name" => Some(self.node_text(node, source)),
+            _ => None,
+        }
+    }
+
+    fn visit_calls(
+        &self,
+        cursor: &mut tree_sitter::TreeCursor,
+        source: &str,
+        ctx: &CallContext,
+        calls: &mut Vec<CallInfo>,
+        seen: &mut HashSet<(String, String)>,
+    ) {
+        loop {
+            let node = cursor.node();
+
+            let call = match node.kind() {
+                // Invoice::query() -> App\Models\Invoice.query
+                "scoped_call_expression" => {
+                    let scope = node.child_by_field_name("scope");
+                    let method = node.child_by_field_name("name");
+                    match (scope, method) {
+                        (Some(scope), Some(method))
+                            if matches!(scope.kind(), "name" | "qualified_name") =>
+                        {
+                            Some(CallInfo {
+                                target: ctx.resolve_class(&self.node_text(scope, source)),
+                                method: self.node_text(method, source),
+                            })
+                        }
+                        _ => None,
+                    }
+                }
+                // $this->payments->capture() or $invoice->total() on a typed variable
+                "member_call_expression" | "nullsafe_member_call_expression" => {
+                    self.parse_member_call(node, source, ctx)
+                }
+                // format_money() with `use function App\Support\format_money;`
+                "function_call_expression" => self.parse_function_call(node, source, ctx),
+                // new Invoice() -> App\Models\Invoice.new
+                "object_creation_expression" => (0..node.child_count())
+                    .filter_map(|i| node.child(i))
+                    .find(|c| matches!(c.kind(), "name" | "qualified_name"))
+                    .map(|class| CallInfo {
+                        target: ctx.resolve_class(&self.node_text(class, source)),
+                        method: "new".to_string(),
+                    }),
+                _ => None,
+            };
+            if let Some(call) = call {
+                if seen.insert((call.target.clone(), call.method.clone())) {
+                    calls.push(call);
+                }
+            }
+
+            if cursor.goto_first_child() {
+                self.visit_calls(cursor, source, ctx, calls, seen);
+                cursor.goto_parent();
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+
+    fn parse_member_call(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        ctx: &CallContext,
+    ) -> Option<CallInfo> {
+        let method = self.node_text(node.child_by_field_name("name")?, source);
+        let object = node.child_by_field_name("object")?;
+
+        let receiver = match object.kind() {
+            "variable_name" => self.node_text(nodes::child_of_kind(object, "name")?, source),
+            // $this->payments resolves through the property's declared type
+            "member_access_expression"
+                if object
+                    .child_by_field_name("object")
+                    .is_some_and(|o| self.node_text(o, source) == "$this") =>
+            {
+                self.node_text(object.child_by_field_name("name")?, source)
+            }
+            _ => return None,
+        };
+
+        let target = ctx.variables.get(&receiver)?;
+        Some(CallInfo {
+            target: target.clone(),
+            method,
+        })
+    }
+
+    fn parse_function_call(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        ctx: &CallContext,
+    ) -> Option<CallInfo> {
+        let function = node.child_by_field_name("function")?;
+        match function.kind() {
+            "name" => {
+                let name = self.node_text(function, source);
+                let target = ctx.functions.get(&name)?;
+                Some(CallInfo {
+                    target: target.clone(),
+                    method: name,
+                })
+            }
+            // \App\Support\format_money() names its namespace explicitly
+            "qualified_name" => {
+                let path = self.node_text(function, source);
+                let (namespace, name) = path.trim_start_matches('\\').rsplit_once('\\')?;
+                Some(CallInfo {
+                    target: namespace.to_string(),
+                    method: name.to_string(),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn node_text(&self, node: tree_sitter::Node, source: &str) -> String {
+        source[node.start_byte()..node.end_byte()].to_string()
+    }
+}
+
+/// Export kind for a class-like declaration node
+fn type_kind(node_kind: &str) -> Option<&'static str> {
+    match node_kind {
+        "class_declaration" => Some("class"),
+        "interface_declaration" => Some("interface"),
+        "trait_declaration" => Some("trait"),
+        "enum_declaration" => Some("enum"),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy)]
+enum UseKind {
+    Class,
+    Function,
+    Const,
+}
+
+/// The `function`/`const` keyword on a use declaration or a single clause
+fn use_kind(node: tree_sitter::Node, source: &str) -> Option<UseKind> {
+    node.child_by_field_name("type")
+        .map(|t| match &source[t.start_byte()..t.end_byte()] {
+            "function" => UseKind::Function,
+            "const" => UseKind::Const,
+            _ => UseKind::Class,
+        })
+}
+
+/// One imported name: the fully qualified path and the local name it is used by
+struct UseClause {
+    kind: UseKind,
+    path: String,
+    alias: String,
+}
+
+/// Name lookups used while attributing calls to classes and namespaces
+struct CallContext {
+    namespace: Option<String>,
+    classes: HashMap<String, String>,
+    functions: HashMap<String, String>,
+    variables: HashMap<String, String>,
+}
+
+impl CallContext {
+    /// Resolve a class name the way PHP does: \Fully\Qualified as written, an imported
+    /// alias (first segment), otherwise relative to the current namespace
+    fn resolve_class(&self, name: &str) -> String {
+        if let Some(absolute) = name.strip_prefix('\\') {
+            return absolute.to_string();
+        }
+        let (first, rest) = match name.split_once('\\') {
+            Some((first, rest)) => (first, Some(rest)),
+            None => (name, None),
+        };
+        if let Some(imported) = self.classes.get(first) {
+            return match rest {
+                Some(rest) => format!("{}\\{}", imported, rest),
+                None => imported.clone(),
+            };
+        }
+        match &self.namespace {
+            Some(namespace) => format!("{}\\{}", namespace, name),
+            None => name.to_string(),
+        }
+    }
+}
+
+impl Default for PhpParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguageParser for PhpParser {
+    fn language_name(&self) -> &'static str {
+        "php"
+    }
+
+    fn file_extensions(&self) -> &[&'static str] {
+        &["php"]
+    }
+
+    fn extract_ast_info(&self, source: &str, _file_path: &Path) -> Result<ASTInfo, ParseError> {
+        let mut parser = self.create_parser()?;
+        let tree = parser
+            .parse(source, None)
+            .ok_or_else(|| ParseError::ParseError("Failed to parse source".to_string()))?;
+
+        let root = tree.root_node();
+
+        let exports = self.extract_exports(root, source);
+        let imports = self.extract_imports(root, source);
+        let calls = self.extract_calls(root, source);
+        let signatures = self.extract_signatures(root, source, &exports);
+
+        let tokens = super::tokens::count_tokens(source);
+
+        Ok(ASTInfo {
+            tokens,
+            exports,
+            imports,
+            calls,
+            signatures,
+        })
+    }
+
+    fn extract_toon_comments(&self, source: &str) -> Result<ExtractedComments, ParseError> {
+        let mut result = ExtractedComments::default();
+
+        // Find @dose in /** */ docblocks
+        let block_pattern = Regex::new(r"/\*\*[\s\S]*?@dose[\s\S]*?\*/").unwrap();
+        let file_block = block_pattern
+            .find_iter(source)
+            .find(|m| toon_comment::parse_inline_annotation(m.as_str()).is_none());
+
+        if let Some(mat) = file_block {
+            let comment = mat.as_str();
+            let content = comment.trim_start_matches("/**").trim_end_matches("*/");
+
+            let content = content
+                .lines()
+                .map(|line| {
+                    let trimmed = line.trim().trim_start_matches('*').trim();
+                    if trimmed == "@dose" {
+                        ""
+                    } else {
+                        trimmed
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            result.file_block = Some(toon_comment::parse_toon_block(&content));
+        }
+
+        // Inline // @dose invariant: value (or # @dose) annotations on the following member
+        let mut parser = self.create_parser()?;
+        if let Some(tree) = parser.parse(source, None) {
+            result.function_annotations = toon_comment::collect_inline_annotations(
+                tree.root_node(),
+                source,
+                &["comment"],
+                |node| match node.kind() {
+                    "method_declaration" | "function_definition" => node
+                        .child_by_field_name("name")
+                        .map(|n| self.node_text(n, source)),
+                    kind if type_kind(kind).is_some() => node
+                        .child_by_field_name("name")
+                        .map(|n| self.node_text(n, source)),
+                    _ => None,
+                },
+                |node| {
+                    type_kind(node.kind())?;
+                    node.child_by_field_name("name")
+                        .map(|n| self.node_text(n, source))
+                },
+            );
+        }
+
+        Ok(result)
+    }
+
+    fn strip_toon_comments(&self, source: &str, toon_path: &str) -> Result<String, ParseError> {
+        // Replace /** */ @dose blocks
+        let block_pattern = Regex::new(r"/\*\*[\s\S]*?@dose[\s\S]*?\*/").unwrap();
+        let result = block_pattern
+            .replace_all(source, &format!("// @dose -> {}", toon_path))
+            .to_string();
+
+        Ok(result)
+    }
+
+    fn get_string_ranges(&self, source: &str) -> Result<Vec<(usize, usize)>, ParseError> {
+        let mut parser = self.create_parser()?;
+
+        let tree = parser
+            .parse(source, None)
+            .ok_or_else(|| ParseError::ParseError("Failed to parse source".to_string()))?;
+
+        let mut ranges = Vec::new();
+        collect_string_ranges_php(&mut tree.walk(), &mut ranges);
+        Ok(ranges)
+    }
+}
+
+fn collect_string_ranges_php(
+    cursor: &mut tree_sitter::TreeCursor,
+    ranges: &mut Vec<(usize, usize)>,
+) {
+    loop {
+        let node = cursor.node();
+        let kind = node.kind();
+        // PHP has 'string', "encapsed_string", heredoc, and nowdoc literals; inline
+        // HTML outside the PHP tags is a text node and must also survive verbatim
+        if matches!(
+            kind,
+            "string" | "encapsed_string" | "heredoc" | "nowdoc" | "text"
+        ) {
+            ranges.push((node.start_byte(), node.end_byte()));
+        } else if cursor.goto_first_child() {
+            collect_string_ranges_php(cursor, ranges);
+            cursor.goto_parent();
+        }
+        if !cursor.goto_next_sibling() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PHP_FIXTURE: &str = include_str!("../../test_fixtures/sample.php");
+
+    #[test]
+    fn test_extract_ast_info() {
+        let parser = PhpParser::new();
+        let info = parser
+            .extract_ast_info(PHP_FIXTURE, Path::new("sample.php"))
+            .unwrap();
+
+        let mut exports: Vec<_> = info
+            .exports
+            .iter()
+            .map(|e| (&e.name[..], &e.kind[..]))
+            .collect();
+        exports.sort();
+        assert_eq!(
+            exports,
+            vec![
+                ("Billable", "interface"),
+                ("InvoiceService", "class"),
+                ("InvoiceStatus", "enum"),
+                ("LogsActivity", "trait"),
+                ("charge", "method"),
+                ("find", "method"),
+                ("label", "method"),
+                ("summarize", "fn"),
+                ("total", "method"),
+            ]
+        );
+
+        let imports: Vec<_> = info
+            .imports
+            .iter()
+            .map(|i| (&i.from[..], i.items.join("|")))
+            .collect();
+        assert_eq!(
+            imports,
+            vec![
+                ("App\\Models\\Invoice", "Invoice".to_string()),
+                ("App\\Models\\Customer", "Customer".to_string()),
+                ("App\\Models\\Payment", "PaymentModel".to_string()),
+                ("Illuminate\\Support\\Facades\\Log", "Log".to_string()),
+                ("Illuminate\\Support\\Str", "Str".to_string()),
+                ("App\\Support", "format_money".to_string()),
+                ("App\\Support", "CURRENCY".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_signatures() {
+        let parser = PhpParser::new();
+        let info = parser
+            .extract_ast_info(PHP_FIXTURE, Path::new("sample.php"))
+            .unwrap();
+
+        let sig = |name: &str| {
+            info.signatures
+                .iter()
+                .find(|s| s.name == name)
+                .unwrap_or_else(|| panic!("missing signature for {}", name))
+        };
+
+        let charge = sig("InvoiceService.charge");
+        assert_eq!(charge.kind, "method");
+        assert_eq!(
+            charge.signature,
+            "(int $amount, ?string $currency = null) -> bool"
+        );
+        assert_eq!((charge.start_line, charge.end_line), (72, 76));
+
+        let find = sig("InvoiceService.find");
+        assert_eq!(find.kind, "static method");
+        assert_eq!(find.signature, "(string|int $id) -> ?Invoice");
+
+        let summarize = sig("summarize");
+        assert_eq!(summarize.kind, "fn");
+        assert_eq!(summarize.signature, "(Invoice ...$invoices) -> string");
+
+        let service = sig("InvoiceService");
+        assert_eq!(service.kind, "class");
+        assert_eq!(
+            service.signature,
+            "#[Service] final implements Billable use LogsActivity { const MAX_LINES; new(private readonly PaymentModel $payments); charge(int $amount, ?string $currency = null) -> bool; static find(string|int $id) -> ?Invoice; total(Invoice $invoice) -> float; ... }"
+        );
+        assert_eq!((service.start_line, service.end_line), (59, 98));
+
+        assert_eq!(
+            sig("Billable").signature,
+            "{ charge(int $amount, ?string $currency = null) -> bool }"
+        );
+        assert_eq!(
+            sig("InvoiceStatus").signature,
+            ": string { Draft | Sent | Paid }"
+        );
+        assert_eq!(sig("LogsActivity").kind, "trait");
+
+        // charge appears on the interface and the class; only the class method is exported
+        assert_eq!(
+            info.signatures
+                .iter()
+                .filter(|s| s.name.ends_with("charge"))
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_extract_calls() {
+        let parser = PhpParser::new();
+        let info = parser
+            .extract_ast_info(PHP_FIXTURE, Path::new("sample.php"))
+            .unwrap();
+
+        let mut calls: Vec<_> = info
+            .calls
+            .iter()
+            .map(|c| (&c.target[..], &c.method[..]))
+            .collect();
+        calls.sort();
+        assert_eq!(
+            calls,
+            vec![
+                ("App\\Models\\Invoice", "query"),
+                ("App\\Models\\Invoice", "sum"),
+                ("App\\Models\\Payment", "capture"),
+                ("App\\Support", "format_money"),
+                ("Illuminate\\Support\\Facades\\Log", "info"),
+                ("Illuminate\\Support\\Str", "title"),
+                ("InvalidArgumentException", "new"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_calls_resolve_through_namespace() {
+        let parser = PhpParser::new();
+        let source = r#"<?php
+namespace App\Http;
+
+use App\Services;
+
+class Controller {
+    public function show(?Services\Mailer $mailer) {
+        Request::capture();
+        Services\Queue::push();
+        self::helper();
+        parent::boot();
+        $mailer?->send();
+        \Carbon\now();
+        return new Response();
+    }
+}
+"#;
+        let info = parser
+            .extract_ast_info(source, Path::new("Controller.php"))
+            .unwrap();
+
+        let mut calls: Vec<_> = info
+            .calls
+            .iter()
+            .map(|c| (&c.target[..], &c.method[..]))
+            .collect();
+        calls.sort();
+        assert_eq!(
+            calls,
+            vec![
+                ("App\\Http\\Request", "capture"),
+                ("App\\Http\\Response", "new"),
+                ("App\\Services\\Mailer", "send"),
+                ("App\\Services\\Queue", "push"),
+                ("Carbon", "now"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_inline_annotations() {
+        let parser = PhpParser::new();
+        let source = r#"<?php
+/** @dose
+purpose: Orders
+*/
+class OrderService {
+    // @dose invariant: total is never negative
+    public function total(Order $order): int { return 0; }
+
+    # @dose error-handling: throws on unknown SKU
+    public function add(string $sku): void {}
+}
+"#;
+        let comments = parser.extract_toon_comments(source).unwrap();
+        assert_eq!(comments.file_block.unwrap().purpose.unwrap(), "Orders");
+        assert_eq!(
+            comments.function_annotations["OrderService.total"].invariants,
+            Some(vec!["total is never negative".to_string()])
+        );
+        assert_eq!(
+            comments.function_annotations["OrderService.add"].error_handling,
+            Some(vec!["throws on unknown SKU".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_toon_comments() {
+        let parser = PhpParser::new();
+        let comments = parser.extract_toon_comments(PHP_FIXTURE).unwrap();
+        let block = comments.file_block.unwrap();
+        assert_eq!(block.purpose.unwrap(), "Sample PHP fixture for testing the luny PHP parser. This file contains classes, traits, interfaces, enums, and functions to verify extraction works correctly.");
+
+        let stripped = parser
+            .strip_toon_comments(PHP_FIXTURE, "sample.php.toon")
+            .unwrap();
+        assert!(stripped.starts_with("<?php\n// @dose -> sample.php.toon"));
+    }
+
+    #[test]
+    fn test_string_ranges() {
+        let parser = PhpParser::new();
+        let source = "<?php $a = 'a b'; $b = \"x {$a}\"; ?>\n<p>  hi  </p>\n";
+        let ranges = parser.get_string_ranges(source).unwrap();
+        let texts: Vec<_> = ranges.iter().map(|(s, e)| &source[*s..*e]).collect();
+        assert_eq!(texts, vec!["'a b'", "\"x {$a}\"", "<p>  hi  </p>\n"]);
+    }
+}
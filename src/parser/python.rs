@@ -7,9 +7,10 @@
 //!     - !Functions and classes starting with underscore are considered private
 //!     - !Decorated functions and classes need special handling via decorated_definition nodes
 //!     - Only top-level definitions (depth <= 1) are considered exports
+//!     - !When __all__ is present it alone decides exports, including underscore names it lists
 //!
 //! invariants:
-//!     - Private items (names starting with _) are never included in exports
+//!     - Private items (names starting with _) are never included in exports unless listed in __all__
 //!     - All imports from the same module are grouped into a single ImportInfo
 //!     - The parser returns empty vectors for missing information rather than erroring
 //!
@@ -21,17 +22,22 @@
 //!     - Python has no explicit export syntax; all public top-level items are exports
 //!     - The @dose block can be in either triple-quoted docstrings or hash comments
 //!     - Relative imports use dots and need special handling in import_prefix nodes
+//!     - Signature ranges start at the first decorator, not at the def/class line
 //!
 //! flows:
 //!     - Parse: Create tree-sitter parser, set Python language, parse source
 //!     - Extract exports: Walk AST at depth 0-1, collect function_definition and class_definition
 //!     - Extract imports: Walk AST collecting import_statement and import_from_statement nodes
+//!     - Inline annotations: # @dose field: value binds to the next def (Class.method) or class
+//!     - Extract calls: Attribute call sites to modules via locally bound import names
+//!     - Extract signatures: Top-level defs and classes; public methods become Class.method entries
 
 use crate::parser::{toon_comment, LanguageParser, ParseError};
-use crate::types::{ASTInfo, ExportInfo, ExtractedComments, ImportInfo};
+use crate::types::{ASTInfo, CallInfo, ExportInfo, ExtractedComments, ImportInfo, SignatureInfo};
 use regex::Regex;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
-use tree_sitter::Parser;
+use tree_sitter::{Node, Parser};
 
 /// Parser for Python files
 #[derive(Clone)]
@@ -51,6 +57,23 @@ impl PythonParser {
     }
 
     fn extract_exports(&self, root: tree_sitter::Node, source: &str) -> Vec<ExportInfo> {
+        // An explicit __all__ is the module's public API, overriding conventions
+        if let Some(names) = self.extract_dunder_all(root, source) {
+            let kinds = self.top_level_kinds(root, source);
+            return names
+                .into_iter()
+                .map(|name| {
+                    // Names that are neither defined nor imported here usually come
+                    // from a star import
+                    let kind = kinds
+                        .get(&name)
+                        .cloned()
+                        .unwrap_or_else(|| "reexport".to_string());
+                    ExportInfo { name, kind }
+                })
+                .collect();
+        }
+
         let mut exports = Vec::new();
         let mut cursor = root.walk();
 
@@ -58,6 +81,108 @@ impl PythonParser {
         exports
     }
 
+    /// Names listed in top-level `__all__ = [...]` / `__all__ += (...)`, in order
+    fn extract_dunder_all(&self, root: Node, source: &str) -> Option<Vec<String>> {
+        let mut names: Option<Vec<String>> = None;
+        let mut cursor = root.walk();
+
+        for statement in root.children(&mut cursor) {
+            if statement.kind() != "expression_statement" {
+                continue;
+            }
+            let Some(assignment) = statement.named_child(0) else {
+                continue;
+            };
+            if !matches!(assignment.kind(), "assignment" | "augmented_assignment") {
+                continue;
+            }
+            let is_dunder_all = assignment
+                .child_by_field_name("left")
+                .is_some_and(|left| self.node_text(left, source) == "__all__");
+            let Some(right) = assignment.child_by_field_name("right") else {
+                continue;
+            };
+            if !is_dunder_all || !matches!(right.kind(), "list" | "tuple") {
+                continue;
+            }
+
+            let list = names.get_or_insert_with(Vec::new);
+            if assignment.kind() == "assignment" {
+                list.clear();
+            }
+            let mut inner = right.walk();
+            for element in right.named_children(&mut inner) {
+                if element.kind() != "string" {
+                    continue;
+                }
+                let name = self
+                    .node_text(element, source)
+                    .trim_matches(|c| c == '"' || c == '\'')
+                    .to_string();
+                if !name.is_empty() && !list.contains(&name) {
+                    list.push(name);
+                }
+            }
+        }
+
+        names
+    }
+
+    /// Kind of every name bound at module level, including private ones, so
+    /// that `__all__` entries can be classified.
+    fn top_level_kinds(&self, root: Node, source: &str) -> HashMap<String, String> {
+        let mut kinds = HashMap::new();
+        let mut cursor = root.walk();
+
+        for node in root.children(&mut cursor) {
+            let (definition, _) = self.unwrap_decorated(node, source);
+            match definition.kind() {
+                "function_definition" | "class_definition" => {
+                    if let Some(name_node) = definition.child_by_field_name("name") {
+                        let kind = if definition.kind() == "class_definition" {
+                            "class"
+                        } else {
+                            "fn"
+                        };
+                        kinds.insert(self.node_text(name_node, source), kind.to_string());
+                    }
+                }
+                "expression_statement" => {
+                    let left = definition
+                        .named_child(0)
+                        .filter(|a| a.kind() == "assignment")
+                        .and_then(|a| a.child_by_field_name("left"))
+                        .filter(|l| l.kind() == "identifier");
+                    if let Some(left) = left {
+                        let name = self.node_text(left, source);
+                        let is_constant = name
+                            .chars()
+                            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+                        let kind = if is_constant { "const" } else { "var" };
+                        kinds.entry(name).or_insert_with(|| kind.to_string());
+                    }
+                }
+                "import_statement" | "import_from_statement" => {
+                    let mut inner = definition.walk();
+                    for child in definition.children_by_field_name("name", &mut inner) {
+                        let bound = match child.kind() {
+                            "aliased_import" => child.child_by_field_name("alias"),
+                            _ => Some(child),
+                        };
+                        if let Some(bound) = bound {
+                            let name = self.node_text(bound, source);
+                            let name = name.split('.').next().unwrap_or(&name).to_string();
+                            kinds.entry(name).or_insert_with(|| "reexport".to_string());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        kinds
+    }
+
     fn visit_exports(
         &self,
         cursor: &mut tree_sitter::TreeCursor,
@@ -158,21 +283,33 @@ impl PythonParser {
 
             match node.kind() {
                 "import_statement" => {
-                    // import foo, bar
-                    let mut items = Vec::new();
+                    // import foo, bar / import numpy as np
+                    // Items hold the name bound locally so calls can be attributed
                     for i in 0..node.child_count() {
                         if let Some(child) = node.child(i) {
-                            if child.kind() == "dotted_name" {
-                                items.push(self.node_text(child, source));
+                            match child.kind() {
+                                "dotted_name" => {
+                                    let name = self.node_text(child, source);
+                                    imports.push(ImportInfo {
+                                        from: name.clone(),
+                                        items: vec![name],
+                                    });
+                                }
+                                "aliased_import" => {
+                                    if let (Some(name), Some(alias)) = (
+                                        child.child_by_field_name("name"),
+                                        child.child_by_field_name("alias"),
+                                    ) {
+                                        imports.push(ImportInfo {
+                                            from: self.node_text(name, source),
+                                            items: vec![self.node_text(alias, source)],
+                                        });
+                                    }
+                                }
+                                _ => {}
                             }
                         }
                     }
-                    if !items.is_empty() {
-                        imports.push(ImportInfo {
-                            from: items[0].clone(),
-                            items,
-                        });
-                    }
                 }
                 "import_from_statement" => {
                     // from foo import bar, baz
@@ -182,10 +319,12 @@ impl PythonParser {
                     for i in 0..node.child_count() {
                         if let Some(child) = node.child(i) {
                             match child.kind() {
-                                "dotted_name" | "relative_import" => {
-                                    if from.is_empty() {
-                                        from = self.node_text(child, source);
-                                    }
+                                "dotted_name" | "relative_import" if from.is_empty() => {
+                                    from = self.node_text(child, source);
+                                }
+                                // Imported names are dotted_name nodes after the module
+                                "dotted_name" => {
+                                    items.push(self.node_text(child, source));
                                 }
                                 "import_prefix" => {
                                     from = self.node_text(child, source);
@@ -221,6 +360,430 @@ impl PythonParser {
         }
     }
 
+    fn extract_calls(&self, root: Node, source: &str, imports: &[ImportInfo]) -> Vec<CallInfo> {
+        // Map locally bound names to their source module and the name they were imported
+        // as. For `import os.path` the bound name is `os`, so only the first segment is used.
+        let mut import_map: HashMap<String, (String, String)> = HashMap::new();
+        for imp in imports {
+            for item in &imp.items {
+                let name = item.split('.').next().unwrap_or(item);
+                import_map.insert(name.to_string(), (imp.from.clone(), name.to_string()));
+            }
+        }
+
+        let mut cursor = root.walk();
+        self.collect_from_import_aliases(&mut cursor, source, &mut import_map);
+
+        let mut calls: Vec<CallInfo> = Vec::new();
+        let mut seen: HashSet<(String, String)> = HashSet::new();
+        let mut cursor = root.walk();
+
+        self.visit_calls(&mut cursor, source, &import_map, &mut calls, &mut seen);
+        calls
+    }
+
+    /// `from x import y as z` keeps `y` in the import items, but call sites use `z`;
+    /// calls through `z` are recorded as calls to `y`, the name x exports.
+    fn collect_from_import_aliases(
+        &self,
+        cursor: &mut tree_sitter::TreeCursor,
+        source: &str,
+        import_map: &mut HashMap<String, (String, String)>,
+    ) {
+        loop {
+            let node = cursor.node();
+
+            if node.kind() == "import_from_statement" {
+                if let Some(module) = node.child_by_field_name("module_name") {
+                    let from = self.node_text(module, source);
+                    for i in 0..node.child_count() {
+                        if let Some(child) = node.child(i) {
+                            if child.kind() == "aliased_import" {
+                                if let (Some(name), Some(alias)) = (
+                                    child.child_by_field_name("name"),
+                                    child.child_by_field_name("alias"),
+                                ) {
+                                    import_map.insert(
+                                        self.node_text(alias, source),
+                                        (from.clone(), self.node_text(name, source)),
+                                    );
+                                }
+                            }
+                        }
+                    }
+                }
+            } else if cursor.goto_first_child() {
+                self.collect_from_import_aliases(cursor, source, import_map);
+                cursor.goto_parent();
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+
+    fn visit_calls(
+        &self,
+        cursor: &mut tree_sitter::TreeCursor,
+        source: &str,
+        import_map: &HashMap<String, (String, String)>,
+        calls: &mut Vec<CallInfo>,
+        seen: &mut HashSet<(String, String)>,
+    ) {
+        loop {
+            let node = cursor.node();
+
+            if node.kind() == "call" {
+                if let Some(call) = self.parse_call(node, source, import_map) {
+                    if seen.insert((call.target.clone(), call.method.clone())) {
+                        calls.push(call);
+                    }
+                }
+            }
+
+            if cursor.goto_first_child() {
+                self.visit_calls(cursor, source, import_map, calls, seen);
+                cursor.goto_parent();
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+
+    fn parse_call(
+        &self,
+        node: Node,
+        source: &str,
+        import_map: &HashMap<String, (String, String)>,
+    ) -> Option<CallInfo> {
+        let function = node.child_by_field_name("function")?;
+
+        match function.kind() {
+            // Direct call: foo()
+            "identifier" => {
+                let (target, imported) = import_map.get(&self.node_text(function, source))?;
+                Some(CallInfo {
+                    target: target.clone(),
+                    method: imported.clone(),
+                })
+            }
+            // Attribute call: mod.foo() or os.path.join()
+            "attribute" => {
+                let method = self.node_text(function.child_by_field_name("attribute")?, source);
+                let root =
+                    self.get_root_identifier(function.child_by_field_name("object")?, source)?;
+                let (target, _) = import_map.get(&root)?;
+                Some(CallInfo {
+                    target: target.clone(),
+                    method,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn get_root_identifier(&self, node: Node, source: &str) -> Option<String> {
+        match node.kind() {
+            "identifier" => Some(self.node_text(node, source)),
+            "attribute" => self.get_root_identifier(node.child_by_field_name("object")?, source),
+            _ => None,
+        }
+    }
+
+    fn extract_signatures(
+        &self,
+        root: Node,
+        source: &str,
+        exports: &[ExportInfo],
+    ) -> Vec<SignatureInfo> {
+        let export_names: HashSet<&str> = exports.iter().map(|e| e.name.as_str()).collect();
+        let mut signatures = Vec::new();
+        let mut cursor = root.walk();
+
+        // Only top-level definitions are exports, so no recursion is needed
+        for node in root.children(&mut cursor) {
+            let (definition, decorators) = self.unwrap_decorated(node, source);
+
+            match definition.kind() {
+                "function_definition" => {
+                    if let Some(sig) = self.extract_function_signature(
+                        node,
+                        definition,
+                        &decorators,
+                        source,
+                        &export_names,
+                    ) {
+                        signatures.push(sig);
+                    }
+                }
+                "class_definition" => {
+                    self.extract_class_signatures(
+                        node,
+                        definition,
+                        &decorators,
+                        source,
+                        &export_names,
+                        &mut signatures,
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        signatures
+    }
+
+    /// Returns the inner definition of a decorated_definition along with its
+    /// decorator names (arguments and module prefixes stripped).
+    fn unwrap_decorated<'a>(&self, node: Node<'a>, source: &str) -> (Node<'a>, Vec<String>) {
+        if node.kind() != "decorated_definition" {
+            return (node, Vec::new());
+        }
+
+        let mut decorators = Vec::new();
+        for i in 0..node.child_count() {
+            if let Some(child) = node.child(i) {
+                if child.kind() == "decorator" {
+                    let text = self.node_text(child, source);
+                    let name = text.trim_start_matches('@').trim();
+                    let name = name.split('(').next().unwrap_or(name).trim();
+                    decorators.push(name.to_string());
+                }
+            }
+        }
+
+        let definition = node.child_by_field_name("definition").unwrap_or(node);
+        (definition, decorators)
+    }
+
+    fn extract_function_signature(
+        &self,
+        outer: Node,
+        node: Node,
+        decorators: &[String],
+        source: &str,
+        export_names: &HashSet<&str>,
+    ) -> Option<SignatureInfo> {
+        let name_node = node.child_by_field_name("name")?;
+        let name = self.node_text(name_node, source);
+
+        if !export_names.contains(name.as_str()) {
+            return None;
+        }
+
+        let kind = if self.is_async(node) {
+            "async fn".to_string()
+        } else {
+            "fn".to_string()
+        };
+
+        // Arbitrary decorators wrap the function, so keep them visible
+        let prefix: String = decorators.iter().map(|d| format!("@{} ", d)).collect();
+        let signature = format!("{}{}", prefix, self.function_signature(node, source, false));
+        let start_line = outer.start_position().row + 1;
+        let end_line = outer.end_position().row + 1;
+
+        Some(SignatureInfo {
+            name,
+            kind,
+            signature,
+            start_line,
+            end_line,
+        })
+    }
+
+    /// Pushes the class signature followed by one entry per public method,
+    /// named `Class.method` so line ranges can be looked up individually.
+    fn extract_class_signatures(
+        &self,
+        outer: Node,
+        node: Node,
+        decorators: &[String],
+        source: &str,
+        export_names: &HashSet<&str>,
+        signatures: &mut Vec<SignatureInfo>,
+    ) {
+        let Some(name_node) = node.child_by_field_name("name") else {
+            return;
+        };
+        let name = self.node_text(name_node, source);
+
+        if !export_names.contains(name.as_str()) {
+            return;
+        }
+
+        let is_dataclass = decorators
+            .iter()
+            .any(|d| d.rsplit('.').next() == Some("dataclass"));
+
+        let bases = node
+            .child_by_field_name("superclasses")
+            .map(|b| self.node_text(b, source))
+            .unwrap_or_default();
+
+        let mut init = String::new();
+        let mut fields = Vec::new();
+        let mut methods = Vec::new();
+        let mut method_signatures = Vec::new();
+
+        if let Some(body) = node.child_by_field_name("body") {
+            let mut cursor = body.walk();
+            for member in body.children(&mut cursor) {
+                let (definition, member_decorators) = self.unwrap_decorated(member, source);
+
+                match definition.kind() {
+                    "function_definition" => {
+                        let Some(method_name_node) = definition.child_by_field_name("name") else {
+                            continue;
+                        };
+                        let method_name = self.node_text(method_name_node, source);
+
+                        if method_name == "__init__" {
+                            init = self.format_parameters(definition, source, true);
+                            continue;
+                        }
+                        if method_name.starts_with('_')
+                            || member_decorators
+                                .iter()
+                                .any(|d| d.ends_with(".setter") || d.ends_with(".deleter"))
+                        {
+                            continue;
+                        }
+
+                        let method_sig = self.function_signature(definition, source, true);
+                        if methods.len() < 5 {
+                            methods.push(format!("{}{}", method_name, method_sig));
+                        } else if methods.len() == 5 {
+                            methods.push("...".to_string());
+                        }
+
+                        method_signatures.push(SignatureInfo {
+                            name: format!("{}.{}", name, method_name),
+                            kind: self.method_kind(definition, &member_decorators),
+                            signature: method_sig,
+                            start_line: member.start_position().row + 1,
+                            end_line: member.end_position().row + 1,
+                        });
+                    }
+                    "expression_statement" if is_dataclass => {
+                        // Dataclass fields: `name: type [= default]`
+                        if let Some(assignment) = definition.named_child(0) {
+                            if let (Some(left), Some(ty)) = (
+                                assignment.child_by_field_name("left"),
+                                assignment.child_by_field_name("type"),
+                            ) {
+                                if fields.len() < 5 {
+                                    fields.push(format!(
+                                        "{}: {}",
+                                        self.node_text(left, source),
+                                        self.node_text(ty, source)
+                                    ));
+                                } else if fields.len() == 5 {
+                                    fields.push("...".to_string());
+                                }
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let mut parts = Vec::new();
+        if !bases.is_empty() {
+            parts.push(bases);
+        }
+        if !init.is_empty() {
+            parts.push(format!("__init__{}", init));
+        }
+        if !fields.is_empty() {
+            parts.push(format!("{{ {} }}", fields.join(", ")));
+        }
+        if !methods.is_empty() {
+            parts.push(format!("{{ {} }}", methods.join("; ")));
+        }
+
+        let kind = if is_dataclass { "dataclass" } else { "class" };
+
+        signatures.push(SignatureInfo {
+            name,
+            kind: kind.to_string(),
+            signature: parts.join(" "),
+            start_line: outer.start_position().row + 1,
+            end_line: outer.end_position().row + 1,
+        });
+        signatures.extend(method_signatures);
+    }
+
+    fn method_kind(&self, node: Node, decorators: &[String]) -> String {
+        for decorator in decorators {
+            let short = decorator.rsplit('.').next().unwrap_or(decorator);
+            match short {
+                "property" | "cached_property" => return "property".to_string(),
+                "staticmethod" | "classmethod" | "abstractmethod" => return short.to_string(),
+                _ => {}
+            }
+        }
+
+        if self.is_async(node) {
+            "async method".to_string()
+        } else {
+            "method".to_string()
+        }
+    }
+
+    /// `(params) -> return_type`, with the receiver dropped for methods.
+    fn function_signature(&self, node: Node, source: &str, is_method: bool) -> String {
+        let params = self.format_parameters(node, source, is_method);
+        match node.child_by_field_name("return_type") {
+            Some(ret) => format!("{} -> {}", params, self.node_text(ret, source)),
+            None => params,
+        }
+    }
+
+    fn format_parameters(&self, node: Node, source: &str, is_method: bool) -> String {
+        let Some(params) = node.child_by_field_name("parameters") else {
+            return "()".to_string();
+        };
+
+        let mut cursor = params.walk();
+        let mut parts: Vec<String> = params
+            .named_children(&mut cursor)
+            .filter(|p| p.kind() != "comment")
+            .map(|p| self.node_text(p, source))
+            .collect();
+
+        if is_method && matches!(parts.first().map(String::as_str), Some("self" | "cls")) {
+            parts.remove(0);
+        }
+
+        format!("({})", parts.join(", "))
+    }
+
+    /// Name an inline annotation binds to. Methods use `Class.method`, matching
+    /// the names in signatures.
+    fn declaration_name(&self, node: Node, source: &str) -> Option<String> {
+        if !matches!(node.kind(), "function_definition" | "class_definition") {
+            return None;
+        }
+        Some(self.node_text(node.child_by_field_name("name")?, source))
+    }
+
+    /// Class name, for qualifying methods as Class.method
+    fn owner_name(&self, node: Node, source: &str) -> Option<String> {
+        if node.kind() != "class_definition" {
+            return None;
+        }
+        Some(self.node_text(node.child_by_field_name("name")?, source))
+    }
+
+    fn is_async(&self, node: Node) -> bool {
+        node.child(0).is_some_and(|c| c.kind() == "async")
+    }
+
     fn node_text(&self, node: tree_sitter::Node, source: &str) -> String {
         source[node.start_byte()..node.end_byte()].to_string()
     }
@@ -251,6 +814,8 @@ impl LanguageParser for PythonParser {
 
         let exports = self.extract_exports(root, source);
         let imports = self.extract_imports(root, source);
+        let calls = self.extract_calls(root, source, &imports);
+        let signatures = self.extract_signatures(root, source, &exports);
 
         let tokens = super::tokens::count_tokens(source);
 
@@ -258,8 +823,8 @@ impl LanguageParser for PythonParser {
             tokens,
             exports,
             imports,
-            calls: Vec::new(),
-            signatures: Vec::new(),
+            calls,
+            signatures,
         })
     }
 
@@ -308,6 +873,18 @@ impl LanguageParser for PythonParser {
             }
         }
 
+        // Inline # @dose invariant: value annotations on the following def/class
+        let mut parser = self.create_parser()?;
+        if let Some(tree) = parser.parse(source, None) {
+            result.function_annotations = toon_comment::collect_inline_annotations(
+                tree.root_node(),
+                source,
+                &["comment"],
+                |node| self.declaration_name(node, source),
+                |node| self.owner_name(node, source),
+            );
+        }
+
         Ok(result)
     }
 
@@ -417,6 +994,204 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_inline_annotations() {
+        let parser = PythonParser::new();
+        let source = r#"
+class ConnectionPool:
+    # @dose invariant: always returns a valid connection or raises
+    async def acquire(self):
+        pass
+
+    # @dose gotcha: must be called even if acquire() raised
+    @staticmethod
+    def release(conn):
+        pass
+
+# @dose do-not: call from a signal handler
+def shutdown():
+    pass
+"#;
+        let comments = parser.extract_toon_comments(source).unwrap();
+        assert!(comments.file_block.is_none());
+
+        let annotations = &comments.function_annotations;
+        assert_eq!(
+            annotations["ConnectionPool.acquire"].invariants,
+            Some(vec![
+                "always returns a valid connection or raises".to_string()
+            ])
+        );
+        assert_eq!(
+            annotations["ConnectionPool.release"].gotchas,
+            Some(vec!["must be called even if acquire() raised".to_string()])
+        );
+        assert_eq!(
+            annotations["shutdown"].do_not,
+            Some(vec!["call from a signal handler".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_dunder_all_exports() {
+        let parser = PythonParser::new();
+        let source = r#"
+from .models import User, Group as G
+from .helpers import *
+
+__all__ = ["User", "G", "build", "_compat", "MAX_SIZE", "Registry"]
+__all__ += ["helper_fn"]
+
+MAX_SIZE = 10
+
+def build():
+    pass
+
+def _compat():
+    pass
+
+def not_listed():
+    pass
+
+class Registry:
+    pass
+"#;
+        let info = parser
+            .extract_ast_info(source, Path::new("pkg/__init__.py"))
+            .unwrap();
+
+        let exports: Vec<_> = info
+            .exports
+            .iter()
+            .map(|e| (&e.name[..], &e.kind[..]))
+            .collect();
+        assert_eq!(
+            exports,
+            vec![
+                ("User", "reexport"),
+                ("G", "reexport"),
+                ("build", "fn"),
+                ("_compat", "fn"),
+                ("MAX_SIZE", "const"),
+                ("Registry", "class"),
+                ("helper_fn", "reexport"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_calls() {
+        let parser = PythonParser::new();
+        let info = parser
+            .extract_ast_info(PY_FIXTURE, Path::new("sample.py"))
+            .unwrap();
+
+        let mut calls: Vec<_> = info
+            .calls
+            .iter()
+            .map(|c| (&c.target[..], &c.method[..]))
+            .collect();
+        calls.sort();
+        assert_eq!(
+            calls,
+            vec![
+                ("asyncio", "sleep"),
+                ("dataclasses", "field"),
+                ("functools", "wraps"),
+                ("json", "dump"),
+                ("json", "load"),
+                ("os", "exists"),
+                ("os", "join"),
+                ("typing", "TypeVar"),
+                ("uuid", "uuid4"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_calls_with_aliases() {
+        let parser = PythonParser::new();
+        let source = "import numpy as np\nfrom collections import OrderedDict as OD, deque\n\n\
+                      np.zeros(3)\nOD()\ndeque()\n";
+        let info = parser
+            .extract_ast_info(source, Path::new("alias.py"))
+            .unwrap();
+
+        let calls: Vec<_> = info
+            .calls
+            .iter()
+            .map(|c| (&c.target[..], &c.method[..]))
+            .collect();
+        // Aliased calls record the imported name, which is what collections exports
+        assert_eq!(
+            calls,
+            vec![
+                ("numpy", "zeros"),
+                ("collections", "OrderedDict"),
+                ("collections", "deque"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_signatures() {
+        let parser = PythonParser::new();
+        let info = parser
+            .extract_ast_info(PY_FIXTURE, Path::new("sample.py"))
+            .unwrap();
+
+        let sig = |name: &str| {
+            info.signatures
+                .iter()
+                .find(|s| s.name == name)
+                .unwrap_or_else(|| panic!("missing signature for {}", name))
+        };
+
+        let create_user = sig("create_user");
+        assert_eq!(create_user.kind, "fn");
+        assert_eq!(
+            create_user.signature,
+            "(name: str, email: Optional[str] = None) -> UserConfig"
+        );
+        assert_eq!((create_user.start_line, create_user.end_line), (128, 135));
+
+        let fetch = sig("fetch_user_async");
+        assert_eq!(fetch.kind, "async fn");
+        assert_eq!(fetch.signature, "(user_id: str) -> Optional[UserConfig]");
+
+        // Decorator lines are part of the range
+        let validate = sig("validate_email");
+        assert_eq!(validate.signature, "@log_calls (email: str) -> bool");
+        assert_eq!(validate.start_line, 115);
+
+        let config = sig("UserConfig");
+        assert_eq!(config.kind, "dataclass");
+        assert_eq!(
+            config.signature,
+            "{ id: str, name: str, email: Optional[str], settings: Dict[str, Any] }"
+        );
+
+        let service = sig("UserService");
+        assert_eq!(service.kind, "class");
+        assert_eq!(
+            service.signature,
+            "(BaseService[UserConfig]) __init__(data_dir: str = \"data\") { get(id: str) -> Optional[UserConfig]; save(user: UserConfig) -> bool }"
+        );
+        assert_eq!((service.start_line, service.end_line), (77, 111));
+
+        let get = sig("UserService.get");
+        assert_eq!(get.kind, "method");
+        assert_eq!((get.start_line, get.end_line), (84, 96));
+        assert_eq!(sig("BaseService.get").kind, "abstractmethod");
+
+        // Private methods and private functions never get signatures
+        assert!(info
+            .signatures
+            .iter()
+            .all(|s| !s.name.contains("_validate")));
+        assert!(info.signatures.iter().all(|s| s.name != "_internal_helper"));
+    }
+
     #[test]
     fn test_toon_comments() {
         let parser = PythonParser::new();
@@ -0,0 +1,742 @@
+//! @dose
+//! purpose: This module implements QueryParser, a language parser configured entirely from
+//!     a [[language]] table in luny.toml. Exports, imports, calls, signatures, and string
+//!     literals come from tree-sitter queries run against a grammar compiled into luny.
+//!
+//! when-editing:
+//!     - !Capture names are the contract with luny.toml; renaming one breaks user configs
+//!     - !Queries are compiled once in QueryParser::new so bad configs fail at startup
+//!     - New grammars must be added to both grammar() and builtin_extension()
+//!
+//! invariants:
+//!     - Every query listed in QueryConfig is checked for its required captures
+//!     - Calls are only recorded when they resolve to an import, like the built-in parsers
+//!     - Signatures are filtered to exported names whenever an exports query is set
+//!
+//! do-not:
+//!     - Never panic on a user query; surface ParseError so the factory can skip the entry
+//!     - Never leak config strings per call; intern() leaks each distinct string once
+//!
+//! gotchas:
+//!     - Captures starting with an underscore (@_fn) only feed predicates and are ignored
+//!     - The LanguageParser trait hands out &'static str, hence the interning
+//!     - A match without a kind capture exports with kind "export"
+//!     - Inline annotations bind to the next node with a name field whose kind mentions
+//!       function or method, which fits most grammars but not all
+//!
+//! flows:
+//!     - Configure: ParserFactory::from_config builds one QueryParser per [[language]] with queries
+//!     - Parse: Run each configured query over the tree and map captures to ASTInfo fields
+//!     - Comments: Find the @dose block using the configured line/block delimiters
+
+use crate::config::{CommentSyntax, LanguageConfig};
+use crate::parser::{toon_comment, LanguageParser, ParseError};
+use crate::types::{ASTInfo, CallInfo, ExportInfo, ExtractedComments, ImportInfo, SignatureInfo};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Mutex;
+use streaming_iterator::StreamingIterator;
+use tree_sitter::{Language, Node, Parser, Query, QueryCursor};
+
+/// Capture names with a fixed meaning; any other capture names a kind
+const ROLE_CAPTURES: &[&str] = &["name", "body", "from", "item", "target", "method"];
+
+/// Comment node kinds across the compiled-in grammars
+const COMMENT_KINDS: &[&str] = &[
+    "comment",
+    "line_comment",
+    "block_comment",
+    "multiline_comment",
+];
+
+/// Look up a grammar compiled into luny by name
+pub fn grammar(name: &str) -> Option<Language> {
+    let language = match name {
+        "typescript" => tree_sitter_typescript::LANGUAGE_TYPESCRIPT,
+        "tsx" => tree_sitter_typescript::LANGUAGE_TSX,
+        "javascript" => tree_sitter_javascript::LANGUAGE,
+        "python" => tree_sitter_python::LANGUAGE,
+        "ruby" => tree_sitter_ruby::LANGUAGE,
+        "c_sharp" | "csharp" => tree_sitter_c_sharp::LANGUAGE,
+        "go" => tree_sitter_go::LANGUAGE,
+        "rust" => tree_sitter_rust::LANGUAGE,
+        "java" => tree_sitter_java::LANGUAGE,
+        "c" => tree_sitter_c::LANGUAGE,
+        "cpp" => tree_sitter_cpp::LANGUAGE,
+        "php" => tree_sitter_php::LANGUAGE_PHP,
+        "swift" => tree_sitter_swift::LANGUAGE,
+        "elixir" => tree_sitter_elixir::LANGUAGE,
+        "bash" => tree_sitter_bash::LANGUAGE,
+        _ => return None,
+    };
+    Some(language.into())
+}
+
+/// Extension handled by the built-in parser for a grammar, used to map dialects onto it
+pub fn builtin_extension(grammar: &str) -> Option<&'static str> {
+    Some(match grammar {
+        "typescript" => "ts",
+        "tsx" => "tsx",
+        "javascript" => "js",
+        "python" => "py",
+        "ruby" => "rb",
+        "c_sharp" | "csharp" => "cs",
+        "go" => "go",
+        "rust" => "rs",
+        "java" => "java",
+        "c" => "c",
+        "cpp" => "cpp",
+        "php" => "php",
+        "swift" => "swift",
+        "elixir" => "ex",
+        "bash" => "sh",
+        _ => return None,
+    })
+}
+
+/// Leak each distinct config string once so it can be handed out as &'static str
+fn intern(value: &str) -> &'static str {
+    static INTERNED: Lazy<Mutex<HashSet<&'static str>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+    let mut interned = INTERNED.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(existing) = interned.get(value) {
+        return existing;
+    }
+    let leaked: &'static str = Box::leak(value.to_string().into_boxed_str());
+    interned.insert(leaked);
+    leaked
+}
+
+/// Parser driven by tree-sitter queries from luny.toml
+pub struct QueryParser {
+    name: &'static str,
+    extensions: Vec<&'static str>,
+    language: Language,
+    comments: CommentSyntax,
+    exports: Option<Query>,
+    imports: Option<Query>,
+    calls: Option<Query>,
+    signatures: Option<Query>,
+    strings: Option<Query>,
+}
+
+/// One query match: role captures by name, plus the kind capture if any
+struct Captures<'tree> {
+    roles: HashMap<&'static str, Vec<Node<'tree>>>,
+    kind: Option<(String, Node<'tree>)>,
+}
+
+impl<'tree> Captures<'tree> {
+    fn first(&self, role: &str) -> Option<Node<'tree>> {
+        self.roles
+            .get(role)
+            .and_then(|nodes| nodes.first().copied())
+    }
+}
+
+impl QueryParser {
+    pub fn new(config: &LanguageConfig) -> Result<Self, ParseError> {
+        let language = grammar(&config.grammar)
+            .ok_or_else(|| ParseError::UnsupportedLanguage(config.grammar.clone()))?;
+        let name = config.name.as_deref().unwrap_or(&config.grammar);
+
+        let compile = |field: &str, source: &Option<String>, required: &[&str]| {
+            let Some(source) = source else {
+                return Ok(None);
+            };
+            let query = Query::new(&language, source).map_err(|e| {
+                ParseError::ParseError(format!("Invalid {} query for {}: {}", field, name, e))
+            })?;
+            for capture in required {
+                if !query.capture_names().contains(capture) {
+                    return Err(ParseError::ParseError(format!(
+                        "The {} query for {} must capture @{}",
+                        field, name, capture
+                    )));
+                }
+            }
+            Ok(Some(query))
+        };
+        let queries = &config.queries;
+
+        Ok(Self {
+            name: intern(name),
+            extensions: config.extensions.iter().map(|e| intern(e)).collect(),
+            exports: compile("exports", &queries.exports, &["name"])?,
+            imports: compile("imports", &queries.imports, &["from"])?,
+            calls: compile("calls", &queries.calls, &["method"])?,
+            signatures: compile("signatures", &queries.signatures, &["name"])?,
+            strings: compile("strings", &queries.strings, &[])?,
+            comments: config.comments.clone(),
+            language,
+        })
+    }
+
+    fn create_parser(&self) -> Result<Parser, ParseError> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&self.language)
+            .map_err(|e| ParseError::ParseError(e.to_string()))?;
+        Ok(parser)
+    }
+
+    /// Run a query and collect each match's captures by role
+    fn run<'tree>(&self, query: &Query, root: Node<'tree>, source: &str) -> Vec<Captures<'tree>> {
+        let names = query.capture_names();
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(query, root, source.as_bytes());
+        let mut results = Vec::new();
+
+        while let Some(m) = matches.next() {
+            let mut captures = Captures {
+                roles: HashMap::new(),
+                kind: None,
+            };
+            for capture in m.captures {
+                let name = names[capture.index as usize];
+                // @_name captures only feed predicates like #eq?
+                if name.starts_with('_') {
+                    continue;
+                }
+                match ROLE_CAPTURES.iter().find(|role| **role == name) {
+                    Some(role) => captures.roles.entry(*role).or_default().push(capture.node),
+                    None => {
+                        captures
+                            .kind
+                            .get_or_insert((name.to_string(), capture.node));
+                    }
+                }
+            }
+            results.push(captures);
+        }
+        results
+    }
+
+    fn extract_exports(&self, root: Node, source: &str) -> Vec<ExportInfo> {
+        let Some(query) = &self.exports else {
+            return Vec::new();
+        };
+        let mut exports = Vec::new();
+        let mut seen = HashSet::new();
+
+        for captures in self.run(query, root, source) {
+            let Some(name) = captures.first("name") else {
+                continue;
+            };
+            let name = node_text(name, source).to_string();
+            if seen.insert(name.clone()) {
+                let kind = captures
+                    .kind
+                    .map(|(kind, _)| kind)
+                    .unwrap_or_else(|| "export".to_string());
+                exports.push(ExportInfo { name, kind });
+            }
+        }
+        exports
+    }
+
+    fn extract_imports(&self, root: Node, source: &str) -> Vec<ImportInfo> {
+        let Some(query) = &self.imports else {
+            return Vec::new();
+        };
+        let mut imports: Vec<ImportInfo> = Vec::new();
+
+        for captures in self.run(query, root, source) {
+            let Some(from) = captures.first("from") else {
+                continue;
+            };
+            let from = unquote(node_text(from, source));
+            let items: Vec<String> = captures
+                .roles
+                .get("item")
+                .map(|nodes| {
+                    nodes
+                        .iter()
+                        .map(|n| unquote(node_text(*n, source)))
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            // One statement often yields a match per imported item
+            let import = match imports.iter_mut().find(|i| i.from == from) {
+                Some(existing) => existing,
+                None => {
+                    imports.push(ImportInfo {
+                        from,
+                        items: Vec::new(),
+                    });
+                    imports.last_mut().unwrap()
+                }
+            };
+            for item in items {
+                if !import.items.contains(&item) {
+                    import.items.push(item);
+                }
+            }
+        }
+
+        for import in &mut imports {
+            if import.items.is_empty() {
+                import.items.push("*".to_string());
+            }
+        }
+        imports
+    }
+
+    fn extract_calls(&self, root: Node, source: &str, imports: &[ImportInfo]) -> Vec<CallInfo> {
+        let Some(query) = &self.calls else {
+            return Vec::new();
+        };
+
+        // Imported names and bare module names resolve to their import
+        let mut import_map: HashMap<&str, &str> = HashMap::new();
+        for import in imports {
+            // "net/http" -> http, "./util.js" -> util, "os.path" -> os
+            let last = import
+                .from
+                .rsplit(['/', ':', '\\'])
+                .next()
+                .unwrap_or_default();
+            let module = last.split('.').next().unwrap_or_default();
+            import_map.insert(module, &import.from);
+            import_map.insert(&import.from, &import.from);
+            for item in import.items.iter().filter(|i| *i != "*") {
+                import_map.insert(item, &import.from);
+            }
+        }
+
+        let mut calls = Vec::new();
+        let mut seen: HashSet<(String, String)> = HashSet::new();
+        for captures in self.run(query, root, source) {
+            let Some(method) = captures.first("method") else {
+                continue;
+            };
+            let method = node_text(method, source);
+            let target = match captures.first("target") {
+                Some(target) => import_map.get(node_text(target, source)),
+                None => import_map.get(method),
+            };
+            if let Some(target) = target {
+                let key = (target.to_string(), method.to_string());
+                if seen.insert(key.clone()) {
+                    calls.push(CallInfo {
+                        target: key.0,
+                        method: key.1,
+                    });
+                }
+            }
+        }
+        calls
+    }
+
+    fn extract_signatures(
+        &self,
+        root: Node,
+        source: &str,
+        exports: &[ExportInfo],
+    ) -> Vec<SignatureInfo> {
+        let Some(query) = &self.signatures else {
+            return Vec::new();
+        };
+        let export_names: HashSet<&str> = exports.iter().map(|e| e.name.as_str()).collect();
+        let mut signatures = Vec::new();
+
+        for captures in self.run(query, root, source) {
+            let Some(name_node) = captures.first("name") else {
+                continue;
+            };
+            let name = node_text(name_node, source);
+            if self.exports.is_some() && !export_names.contains(name) {
+                continue;
+            }
+            let body = captures.first("body");
+            let (kind, declaration) = match captures.kind {
+                Some((kind, node)) => (kind, node),
+                None => ("fn".to_string(), name_node.parent().unwrap_or(name_node)),
+            };
+
+            // Everything between the name and the body, e.g. "(a, b) -> int"
+            let end = body
+                .map(|b| b.start_byte())
+                .unwrap_or(declaration.end_byte());
+            let start = name_node.end_byte().min(end);
+            let signature = source[start..end]
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .trim_end_matches(['{', ':', '=', ' '])
+                .to_string();
+
+            signatures.push(SignatureInfo {
+                name: name.to_string(),
+                kind,
+                signature,
+                start_line: declaration.start_position().row + 1,
+                end_line: declaration.end_position().row + 1,
+            });
+        }
+        signatures
+    }
+
+    /// Byte span and content of the file-level @dose block, from a block comment or a
+    /// run of line comments
+    fn file_block(&self, source: &str) -> Option<(usize, usize, String)> {
+        if let Some((open, close)) = &self.comments.block {
+            let pattern = format!(
+                r"{}[\s\S]*?@dose[\s\S]*?{}",
+                regex::escape(open),
+                regex::escape(close)
+            );
+            let block_pattern = Regex::new(&pattern).ok()?;
+            let found = block_pattern
+                .find_iter(source)
+                .find(|m| toon_comment::parse_inline_annotation(m.as_str()).is_none());
+            if let Some(m) = found {
+                let inner = &m.as_str()[open.len()..m.as_str().len() - close.len()];
+                let content = inner
+                    .lines()
+                    .map(|line| line.trim().trim_start_matches('*').trim())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                return Some((m.start(), m.end(), strip_marker(&content)));
+            }
+        }
+
+        let prefix = self.comments.line.as_deref()?;
+        let mut offset = 0;
+        let mut run: Option<(usize, usize, Vec<&str>)> = None;
+        for line in source.split_inclusive('\n') {
+            let trimmed = line.trim();
+            let body = trimmed.strip_prefix(prefix);
+            match (&mut run, body) {
+                (Some((_, end, lines)), Some(body)) => {
+                    lines.push(body.trim());
+                    *end = offset + line.trim_end_matches(['\n', '\r']).len();
+                }
+                (Some(_), None) => break,
+                (None, Some(body))
+                    if body.contains("@dose")
+                        && toon_comment::parse_inline_annotation(trimmed).is_none() =>
+                {
+                    let start = offset + (line.len() - line.trim_start().len());
+                    let end = offset + line.trim_end_matches(['\n', '\r']).len();
+                    run = Some((start, end, vec![body.trim()]));
+                }
+                _ => {}
+            }
+            offset += line.len();
+        }
+        run.map(|(start, end, lines)| (start, end, strip_marker(&lines.join("\n"))))
+    }
+
+    /// Stub left where the file block was
+    fn stub(&self, toon_path: &str) -> String {
+        match (&self.comments.line, &self.comments.block) {
+            (Some(line), _) => format!("{} @dose -> {}", line, toon_path),
+            (None, Some((open, close))) => format!("{} @dose -> {} {}", open, toon_path, close),
+            (None, None) => String::new(),
+        }
+    }
+}
+
+fn node_text<'a>(node: Node, source: &'a str) -> &'a str {
+    &source[node.start_byte()..node.end_byte()]
+}
+
+/// `"./util"` -> `./util`, `<stdio.h>` -> `stdio.h`
+fn unquote(text: &str) -> String {
+    text.trim_matches(|c| matches!(c, '"' | '\'' | '`' | '<' | '>'))
+        .to_string()
+}
+
+/// Drop the @dose marker from block content
+fn strip_marker(content: &str) -> String {
+    content
+        .lines()
+        .map(|line| line.trim().trim_start_matches("@dose").trim())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl LanguageParser for QueryParser {
+    fn language_name(&self) -> &'static str {
+        self.name
+    }
+
+    fn file_extensions(&self) -> &[&'static str] {
+        &self.extensions
+    }
+
+    fn extract_ast_info(&self, source: &str, _file_path: &Path) -> Result<ASTInfo, ParseError> {
+        let mut parser = self.create_parser()?;
+        let tree = parser
+            .parse(source, None)
+            .ok_or_else(|| ParseError::ParseError("Failed to parse source".to_string()))?;
+
+        let root = tree.root_node();
+
+        let exports = self.extract_exports(root, source);
+        let imports = self.extract_imports(root, source);
+        let calls = self.extract_calls(root, source, &imports);
+        let signatures = self.extract_signatures(root, source, &exports);
+
+        let tokens = super::tokens::count_tokens(source);
+
+        Ok(ASTInfo {
+            tokens,
+            exports,
+            imports,
+            calls,
+            signatures,
+        })
+    }
+
+    fn extract_toon_comments(&self, source: &str) -> Result<ExtractedComments, ParseError> {
+        let mut result = ExtractedComments::default();
+
+        if let Some((_, _, content)) = self.file_block(source) {
+            result.file_block = Some(toon_comment::parse_toon_block(&content));
+        }
+
+        let mut parser = self.create_parser()?;
+        if let Some(tree) = parser.parse(source, None) {
+            result.function_annotations = toon_comment::collect_inline_annotations(
+                tree.root_node(),
+                source,
+                COMMENT_KINDS,
+                |node| {
+                    let kind = node.kind();
+                    if !kind.contains("function") && !kind.contains("method") {
+                        return None;
+                    }
+                    node.child_by_field_name("name")
+                        .map(|n| node_text(n, source).to_string())
+                },
+                |node| {
+                    let kind = node.kind();
+                    if !["class", "struct", "interface", "trait", "impl"]
+                        .iter()
+                        .any(|owner| kind.contains(owner))
+                    {
+                        return None;
+                    }
+                    node.child_by_field_name("name")
+                        .map(|n| node_text(n, source).to_string())
+                },
+            );
+        }
+
+        Ok(result)
+    }
+
+    fn strip_toon_comments(&self, source: &str, toon_path: &str) -> Result<String, ParseError> {
+        Ok(match self.file_block(source) {
+            Some((start, end, _)) => format!(
+                "{}{}{}",
+                &source[..start],
+                self.stub(toon_path),
+                &source[end..]
+            ),
+            None => source.to_string(),
+        })
+    }
+
+    fn get_string_ranges(&self, source: &str) -> Result<Vec<(usize, usize)>, ParseError> {
+        let Some(query) = &self.strings else {
+            return Ok(Vec::new());
+        };
+        let mut parser = self.create_parser()?;
+        let tree = parser
+            .parse(source, None)
+            .ok_or_else(|| ParseError::ParseError("Failed to parse source".to_string()))?;
+
+        let mut ranges = Vec::new();
+        let mut cursor = QueryCursor::new();
+        let mut captures = cursor.captures(query, tree.root_node(), source.as_bytes());
+        while let Some((m, index)) = captures.next() {
+            let node = m.captures[*index].node;
+            ranges.push((node.start_byte(), node.end_byte()));
+        }
+        Ok(ranges)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::QueryConfig;
+
+    /// Python re-targeted through queries, as a user would write it in luny.toml
+    fn starlark() -> QueryParser {
+        QueryParser::new(&LanguageConfig {
+            name: Some("starlark".to_string()),
+            grammar: "python".to_string(),
+            extensions: vec!["bzl".to_string()],
+            comments: CommentSyntax {
+                line: Some("#".to_string()),
+                block: None,
+            },
+            queries: QueryConfig {
+                exports: Some(
+                    r#"(module (function_definition name: (identifier) @name) @fn)
+                       (module (expression_statement (assignment left: (identifier) @name) @const))"#
+                        .to_string(),
+                ),
+                imports: Some(
+                    r#"(call function: (identifier) @_load
+                         arguments: (argument_list . (string) @from (string) @item)
+                         (#eq? @_load "load"))"#
+                        .to_string(),
+                ),
+                calls: Some(
+                    r#"(call function: (identifier) @method)
+                       (call function: (attribute object: (identifier) @target attribute: (identifier) @method))"#
+                        .to_string(),
+                ),
+                signatures: Some(
+                    "(function_definition name: (identifier) @name body: (block) @body) @fn"
+                        .to_string(),
+                ),
+                strings: Some("(string) @string".to_string()),
+            },
+        })
+        .unwrap()
+    }
+
+    const SOURCE: &str = r#"# @dose
+# purpose: Build rules for the web bundle
+# gotchas:
+#     - Loaded by every BUILD file
+
+load("//tools:defs.bzl", "node_binary", "npm")
+
+BUNDLE_NAME = "web"
+
+# @dose invariant: outputs are hermetic
+def web_bundle(name, srcs = []):
+    node_binary(name = name, srcs = srcs)
+    npm.install(name)
+"#;
+
+    #[test]
+    fn test_query_ast_info() {
+        let parser = starlark();
+        assert_eq!(parser.language_name(), "starlark");
+        assert_eq!(parser.file_extensions(), &["bzl"]);
+
+        let info = parser
+            .extract_ast_info(SOURCE, Path::new("defs.bzl"))
+            .unwrap();
+
+        let exports: Vec<_> = info
+            .exports
+            .iter()
+            .map(|e| (&e.name[..], &e.kind[..]))
+            .collect();
+        assert_eq!(
+            exports,
+            vec![("BUNDLE_NAME", "const"), ("web_bundle", "fn")]
+        );
+
+        assert_eq!(info.imports.len(), 1);
+        assert_eq!(info.imports[0].from, "//tools:defs.bzl");
+        assert_eq!(info.imports[0].items, vec!["node_binary", "npm"]);
+
+        let calls: Vec<_> = info
+            .calls
+            .iter()
+            .map(|c| (&c.target[..], &c.method[..]))
+            .collect();
+        assert_eq!(
+            calls,
+            vec![
+                ("//tools:defs.bzl", "node_binary"),
+                ("//tools:defs.bzl", "install")
+            ]
+        );
+
+        assert_eq!(info.signatures.len(), 1);
+        let sig = &info.signatures[0];
+        assert_eq!(sig.name, "web_bundle");
+        assert_eq!(sig.kind, "fn");
+        assert_eq!(sig.signature, "(name, srcs = [])");
+        assert_eq!((sig.start_line, sig.end_line), (11, 13));
+    }
+
+    #[test]
+    fn test_query_comments_and_strip() {
+        let parser = starlark();
+
+        let comments = parser.extract_toon_comments(SOURCE).unwrap();
+        let block = comments.file_block.unwrap();
+        assert_eq!(block.purpose.unwrap(), "Build rules for the web bundle");
+        assert_eq!(
+            block.gotchas,
+            Some(vec!["Loaded by every BUILD file".to_string()])
+        );
+        assert_eq!(
+            comments.function_annotations["web_bundle"].invariants,
+            Some(vec!["outputs are hermetic".to_string()])
+        );
+
+        let stripped = parser.strip_toon_comments(SOURCE, "defs.bzl.toon").unwrap();
+        assert!(stripped.starts_with("# @dose -> defs.bzl.toon\n\nload("));
+        assert!(stripped.contains("# @dose invariant: outputs are hermetic"));
+
+        let ranges = parser.get_string_ranges(SOURCE).unwrap();
+        assert!(ranges
+            .iter()
+            .any(|(s, e)| &SOURCE[*s..*e] == "\"//tools:defs.bzl\""));
+    }
+
+    #[test]
+    fn test_block_comment_dose() {
+        let parser = QueryParser::new(&LanguageConfig {
+            grammar: "javascript".to_string(),
+            extensions: vec!["es6".to_string()],
+            queries: QueryConfig {
+                exports: Some(
+                    "(export_statement declaration: (function_declaration name: (identifier) @name) @fn)"
+                        .to_string(),
+                ),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(parser.language_name(), "javascript");
+
+        let source = "/* @dose\npurpose: Helpers\n\ninvariants:\n    - Pure\n*/\nexport function id(x) { return x; }\n";
+        let comments = parser.extract_toon_comments(source).unwrap();
+        assert_eq!(comments.file_block.unwrap().purpose.unwrap(), "Helpers");
+        assert_eq!(
+            parser.strip_toon_comments(source, "a.es6.toon").unwrap(),
+            "// @dose -> a.es6.toon\nexport function id(x) { return x; }\n"
+        );
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let config = |grammar: &str, exports: &str| LanguageConfig {
+            grammar: grammar.to_string(),
+            queries: QueryConfig {
+                exports: Some(exports.to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            QueryParser::new(&config("cobol", "(x) @name")),
+            Err(ParseError::UnsupportedLanguage(_))
+        ));
+        assert!(QueryParser::new(&config("python", "(not_a_node) @name")).is_err());
+        let missing_name = QueryParser::new(&config("python", "(function_definition) @fn"));
+        assert!(missing_name
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("must capture @name"));
+    }
+}
@@ -5,31 +5,42 @@
 //!
 //! when-editing:
 //!     - !Methods starting with underscore are considered private by convention
-//!     - !Singleton methods (self.method) are extracted as class_method kind
+//!     - !Singleton methods (self.method, class << self) are extracted as class_method kind
+//!     - !Visibility sections and per-method calls (private :foo) decide method exports
 //!     - Modules and classes are always exported
 //!
 //! invariants:
-//!     - Private methods (underscore prefix) are excluded from exports
+//!     - Private methods (private section, private :foo, or underscore prefix) are excluded
+//!     - Protected methods export as protected_method, module_function methods as module_function
+//!     - Exports and signatures come from the same walk, so a signature has its export's kind
 //!     - Require, require_relative, include, and extend statements are all captured as imports
 //!     - The parser handles both instance methods and class (singleton) methods
 //!
 //! do-not:
 //!     - Never treat instance variables as exports
 //!     - Never extract methods defined inside other methods
+//!     - Never carry a visibility section from one class body into a nested class
 //!
 //! gotchas:
 //!     - Ruby has no explicit export mechanism; all defined items are potentially public
 //!     - The @dose block can be in =begin/=end blocks or hash comment blocks
-//!     - Singleton methods have "self." prefix in the exported name
+//!     - Singleton methods have "self." prefix in the exported name; signatures name every
+//!       member Owner.member instead, as fn: annotations do
+//!     - Call targets are inferred by name convention (JSON <-> 'json'), not by resolving requires
 //!
 //! flows:
 //!     - Parse: Create tree-sitter parser, set Ruby language, parse source
-//!     - Extract exports: Walk AST collecting method, singleton_method, class, and module nodes
+//!     - Declarations: Walk each body in order, tracking the current visibility section, into
+//!       exports and signatures
 //!     - Extract imports: Walk AST finding call nodes for require/include/extend
+//!     - Inline annotations: # @dose field: value binds to the next def, class, or module
+//!     - Signatures: Method parameter lists verbatim; classes/modules list their exported methods
+//!     - Extract calls: Match constant receivers against required paths
 
 use crate::parser::{toon_comment, LanguageParser, ParseError};
-use crate::types::{ASTInfo, ExportInfo, ExtractedComments, ImportInfo};
+use crate::types::{ASTInfo, CallInfo, ExportInfo, ExtractedComments, ImportInfo, SignatureInfo};
 use regex::Regex;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use tree_sitter::Parser;
 
@@ -50,75 +61,255 @@ impl RubyParser {
         Ok(parser)
     }
 
-    fn extract_exports(&self, root: tree_sitter::Node, source: &str) -> Vec<ExportInfo> {
+    /// Exports and signatures from one walk, so both see the same visibility and kind
+    fn extract_declarations(
+        &self,
+        root: tree_sitter::Node,
+        source: &str,
+    ) -> (Vec<ExportInfo>, Vec<SignatureInfo>) {
         let mut exports = Vec::new();
-        let mut cursor = root.walk();
-
-        self.visit_exports(&mut cursor, source, &mut exports);
-        exports
+        let mut signatures = Vec::new();
+        self.visit_body(root, source, None, None, &mut exports, &mut signatures);
+        (exports, signatures)
     }
 
-    fn visit_exports(
+    /// Walks the statements of a program/class/module body in order, tracking
+    /// the current visibility section (`private`, `protected`, `module_function`).
+    /// `owner` names the enclosing class or module, which qualifies member signatures.
+    /// `class_overrides` is set when walking a `class << self` body, whose methods
+    /// can be hidden by `private_class_method` in the enclosing class.
+    /// Returns the owner's member summary: the exported methods, with their parameters.
+    fn visit_body(
         &self,
-        cursor: &mut tree_sitter::TreeCursor,
+        body: tree_sitter::Node,
         source: &str,
+        owner: Option<&str>,
+        class_overrides: Option<&HashMap<String, Visibility>>,
         exports: &mut Vec<ExportInfo>,
-    ) {
-        loop {
-            let node = cursor.node();
-
-            match node.kind() {
-                "method" => {
-                    if let Some(name_node) = node.child_by_field_name("name") {
-                        let name = self.node_text(name_node, source);
-                        // Skip private methods (by convention, methods starting with _)
-                        if !name.starts_with('_') {
-                            exports.push(ExportInfo {
-                                name,
-                                kind: "method".to_string(),
-                            });
+        signatures: &mut Vec<SignatureInfo>,
+    ) -> Vec<String> {
+        let singleton = class_overrides.is_some();
+        let mut overrides = class_overrides.cloned().unwrap_or_default();
+        overrides.extend(self.collect_visibility_overrides(body, source, singleton));
+        let mut section = Visibility::Public;
+        let mut summary = Vec::new();
+
+        for i in 0..body.child_count() {
+            let Some(child) = body.child(i) else {
+                continue;
+            };
+
+            match child.kind() {
+                // Bare `private` / `protected` / `public` / `module_function`
+                "identifier" => {
+                    if let Some(visibility) =
+                        Visibility::from_keyword(&self.node_text(child, source))
+                    {
+                        section = visibility;
+                    }
+                }
+                // `private def foo ... end` applies to the inline definition only;
+                // `private :foo` is handled by collect_visibility_overrides
+                "call" => {
+                    let Some(visibility) = child
+                        .child_by_field_name("method")
+                        .and_then(|m| Visibility::from_keyword(&self.node_text(m, source)))
+                    else {
+                        continue;
+                    };
+                    if let Some(args) = child.child_by_field_name("arguments") {
+                        for j in 0..args.child_count() {
+                            if let Some(arg) = args.child(j) {
+                                if arg.kind() == "method" || arg.kind() == "singleton_method" {
+                                    let method = self
+                                        .export_method(arg, source, owner, singleton, visibility);
+                                    record_method(method, &mut summary, exports, signatures);
+                                }
+                            }
                         }
                     }
                 }
+                "method" => {
+                    let prefix = if singleton { "self." } else { "" };
+                    let visibility = child
+                        .child_by_field_name("name")
+                        .and_then(|n| {
+                            let name = format!("{}{}", prefix, self.node_text(n, source));
+                            overrides.get(&name).copied()
+                        })
+                        .unwrap_or(section);
+                    let method = self.export_method(child, source, owner, singleton, visibility);
+                    record_method(method, &mut summary, exports, signatures);
+                }
                 "singleton_method" => {
-                    if let Some(name_node) = node.child_by_field_name("name") {
-                        let name = self.node_text(name_node, source);
-                        exports.push(ExportInfo {
-                            name: format!("self.{}", name),
-                            kind: "class_method".to_string(),
-                        });
-                    }
+                    let visibility = child
+                        .child_by_field_name("name")
+                        .and_then(|n| overrides.get(&format!("self.{}", self.node_text(n, source))))
+                        .copied()
+                        .unwrap_or(Visibility::Public);
+                    let method = self.export_method(child, source, owner, singleton, visibility);
+                    record_method(method, &mut summary, exports, signatures);
                 }
-                "class" => {
-                    if let Some(name_node) = node.child_by_field_name("name") {
-                        let name = self.node_text(name_node, source);
-                        exports.push(ExportInfo {
-                            name,
-                            kind: "class".to_string(),
-                        });
+                "class" | "module" => {
+                    let Some(name_node) = child.child_by_field_name("name") else {
+                        continue;
+                    };
+                    let name = self.node_text(name_node, source);
+                    exports.push(ExportInfo {
+                        name: name.clone(),
+                        kind: child.kind().to_string(),
+                    });
+
+                    // The container's signature comes before its members; its summary is
+                    // filled in once the body has been walked
+                    let index = signatures.len();
+                    signatures.push(SignatureInfo {
+                        name: name.clone(),
+                        kind: child.kind().to_string(),
+                        signature: String::new(),
+                        start_line: child.start_position().row + 1,
+                        end_line: child.end_position().row + 1,
+                    });
+                    let mut members = match child.child_by_field_name("body") {
+                        Some(inner) => {
+                            self.visit_body(inner, source, Some(&name), None, exports, signatures)
+                        }
+                        None => Vec::new(),
+                    };
+                    if members.len() > 5 {
+                        members.truncate(5);
+                        members.push("...".to_string());
                     }
+
+                    let superclass = child
+                        .child_by_field_name("superclass")
+                        .map(|s| self.node_text(s, source))
+                        .unwrap_or_default();
+                    signatures[index].signature = if members.is_empty() {
+                        superclass
+                    } else {
+                        format!("{} {{ {} }}", superclass, members.join("; "))
+                            .trim()
+                            .to_string()
+                    };
                 }
-                "module" => {
-                    if let Some(name_node) = node.child_by_field_name("name") {
-                        let name = self.node_text(name_node, source);
-                        exports.push(ExportInfo {
-                            name,
-                            kind: "module".to_string(),
-                        });
+                // class << self ... end
+                "singleton_class" => {
+                    if let Some(inner) = child.child_by_field_name("body") {
+                        summary.extend(self.visit_body(
+                            inner,
+                            source,
+                            owner,
+                            Some(&overrides),
+                            exports,
+                            signatures,
+                        ));
                     }
                 }
                 _ => {}
             }
+        }
+        summary
+    }
 
-            if cursor.goto_first_child() {
-                self.visit_exports(cursor, source, exports);
-                cursor.goto_parent();
+    /// Per-method visibility set after the fact: `private :foo, :bar`,
+    /// `private_class_method :create`, `public :baz`.
+    fn collect_visibility_overrides(
+        &self,
+        body: tree_sitter::Node,
+        source: &str,
+        singleton: bool,
+    ) -> HashMap<String, Visibility> {
+        let instance_prefix = if singleton { "self." } else { "" };
+        let mut overrides = HashMap::new();
+
+        for i in 0..body.child_count() {
+            let Some(child) = body.child(i) else {
+                continue;
+            };
+            if child.kind() != "call" {
+                continue;
             }
-
-            if !cursor.goto_next_sibling() {
-                break;
+            let Some(method) = child.child_by_field_name("method") else {
+                continue;
+            };
+            let method = self.node_text(method, source);
+            let (visibility, prefix) = match method.as_str() {
+                "private_class_method" => (Visibility::Private, "self."),
+                "public_class_method" => (Visibility::Public, "self."),
+                other => match Visibility::from_keyword(other) {
+                    Some(visibility) => (visibility, instance_prefix),
+                    None => continue,
+                },
+            };
+
+            if let Some(args) = child.child_by_field_name("arguments") {
+                for j in 0..args.child_count() {
+                    if let Some(arg) = args.child(j) {
+                        if arg.kind() == "simple_symbol" || arg.kind() == "string" {
+                            let name = self
+                                .node_text(arg, source)
+                                .trim_start_matches(':')
+                                .trim_matches('"')
+                                .trim_matches('\'')
+                                .to_string();
+                            overrides.insert(format!("{}{}", prefix, name), visibility);
+                        }
+                    }
+                }
             }
         }
+
+        overrides
+    }
+
+    /// Export and signature of a method, or None when it is not exported
+    fn export_method(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        owner: Option<&str>,
+        singleton: bool,
+        visibility: Visibility,
+    ) -> Option<(ExportInfo, SignatureInfo)> {
+        let name_node = node.child_by_field_name("name")?;
+        let name = self.node_text(name_node, source);
+
+        // Skip private methods (by convention, methods starting with _)
+        if name.starts_with('_') {
+            return None;
+        }
+
+        let is_class_method = singleton || node.kind() == "singleton_method";
+        let kind = match visibility {
+            Visibility::Private => return None,
+            Visibility::Protected => "protected_method",
+            Visibility::ModuleFunction => "module_function",
+            Visibility::Public if is_class_method => "class_method",
+            Visibility::Public => "method",
+        };
+        let export_name = if is_class_method {
+            format!("self.{}", name)
+        } else {
+            name.clone()
+        };
+
+        // Members are Owner.name, as the fn: annotations name them
+        let signature = SignatureInfo {
+            name: match owner {
+                Some(owner) => format!("{}.{}", owner, name),
+                None => export_name.clone(),
+            },
+            kind: kind.to_string(),
+            signature: self.method_params(node, source),
+            start_line: node.start_position().row + 1,
+            end_line: node.end_position().row + 1,
+        };
+        let export = ExportInfo {
+            name: export_name,
+            kind: kind.to_string(),
+        };
+        Some((export, signature))
     }
 
     fn extract_imports(&self, root: tree_sitter::Node, source: &str) -> Vec<ImportInfo> {
@@ -180,11 +371,153 @@ impl RubyParser {
         }
     }
 
+    fn extract_calls(
+        &self,
+        root: tree_sitter::Node,
+        source: &str,
+        imports: &[ImportInfo],
+    ) -> Vec<CallInfo> {
+        // Ruby has no import bindings, so constants are matched against require
+        // paths by convention: require 'file_utils' / 'fileutils' defines FileUtils.
+        let mut import_map: HashMap<String, String> = HashMap::new();
+        for imp in imports {
+            let mut segments = imp.from.split(['/', ':']).filter(|s| !s.is_empty());
+            if let Some(first) = segments.next() {
+                import_map
+                    .entry(normalize_constant(first))
+                    .or_insert_with(|| imp.from.clone());
+            }
+            if let Some(last) = imp.from.rsplit(['/', ':']).next() {
+                import_map.insert(normalize_constant(last), imp.from.clone());
+            }
+        }
+
+        let mut calls: Vec<CallInfo> = Vec::new();
+        let mut seen: HashSet<(String, String)> = HashSet::new();
+        let mut cursor = root.walk();
+
+        self.visit_calls(&mut cursor, source, &import_map, &mut calls, &mut seen);
+        calls
+    }
+
+    fn visit_calls(
+        &self,
+        cursor: &mut tree_sitter::TreeCursor,
+        source: &str,
+        import_map: &HashMap<String, String>,
+        calls: &mut Vec<CallInfo>,
+        seen: &mut HashSet<(String, String)>,
+    ) {
+        loop {
+            let node = cursor.node();
+
+            if node.kind() == "call" {
+                if let Some(call) = self.parse_call(node, source, import_map) {
+                    if seen.insert((call.target.clone(), call.method.clone())) {
+                        calls.push(call);
+                    }
+                }
+            }
+
+            if cursor.goto_first_child() {
+                self.visit_calls(cursor, source, import_map, calls, seen);
+                cursor.goto_parent();
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+
+    fn parse_call(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        import_map: &HashMap<String, String>,
+    ) -> Option<CallInfo> {
+        // Only constant receivers can be attributed: JSON.parse, Foo::Bar.baz
+        let receiver = node.child_by_field_name("receiver")?;
+        let method = node.child_by_field_name("method")?;
+        let constant = self.get_root_constant(receiver, source)?;
+
+        let target = import_map.get(&normalize_constant(&constant))?;
+        Some(CallInfo {
+            target: target.clone(),
+            method: self.node_text(method, source),
+        })
+    }
+
+    fn get_root_constant(&self, node: tree_sitter::Node, source: &str) -> Option<String> {
+        match node.kind() {
+            "constant" => Some(self.node_text(node, source)),
+            "scope_resolution" => {
+                let scope = node.child_by_field_name("scope")?;
+                self.get_root_constant(scope, source)
+            }
+            _ => None,
+        }
+    }
+
+    /// Parameter list with keyword args, splats and block params kept verbatim
+    fn method_params(&self, node: tree_sitter::Node, source: &str) -> String {
+        let Some(params) = node.child_by_field_name("parameters") else {
+            return "()".to_string();
+        };
+        let text = self
+            .node_text(params, source)
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        // `def foo a, b` is legal Ruby; normalize to the parenthesized form
+        if text.starts_with('(') {
+            text
+        } else {
+            format!("({})", text)
+        }
+    }
+
     fn node_text(&self, node: tree_sitter::Node, source: &str) -> String {
         source[node.start_byte()..node.end_byte()].to_string()
     }
 }
 
+/// Record an exported method, adding `name(params)` to its owner's member summary
+fn record_method(
+    method: Option<(ExportInfo, SignatureInfo)>,
+    summary: &mut Vec<String>,
+    exports: &mut Vec<ExportInfo>,
+    signatures: &mut Vec<SignatureInfo>,
+) {
+    if let Some((export, signature)) = method {
+        summary.push(format!("{}{}", export.name, signature.signature));
+        exports.push(export);
+        signatures.push(signature);
+    }
+}
+
+/// Method visibility as set by a section keyword or a per-method call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Visibility {
+    Public,
+    Protected,
+    Private,
+    /// `module_function`: callable as `Mod.name`, private as an instance method
+    ModuleFunction,
+}
+
+impl Visibility {
+    fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword {
+            "public" => Some(Self::Public),
+            "protected" => Some(Self::Protected),
+            "private" => Some(Self::Private),
+            "module_function" => Some(Self::ModuleFunction),
+            _ => None,
+        }
+    }
+}
+
 impl Default for RubyParser {
     fn default() -> Self {
         Self::new()
@@ -208,8 +541,9 @@ impl LanguageParser for RubyParser {
 
         let root = tree.root_node();
 
-        let exports = self.extract_exports(root, source);
+        let (exports, signatures) = self.extract_declarations(root, source);
         let imports = self.extract_imports(root, source);
+        let calls = self.extract_calls(root, source, &imports);
 
         let tokens = super::tokens::count_tokens(source);
 
@@ -217,8 +551,8 @@ impl LanguageParser for RubyParser {
             tokens,
             exports,
             imports,
-            calls: Vec::new(),
-            signatures: Vec::new(),
+            calls,
+            signatures,
         })
     }
 
@@ -266,6 +600,28 @@ impl LanguageParser for RubyParser {
             }
         }
 
+        // Inline # @dose invariant: value annotations on the following def
+        let mut parser = self.create_parser()?;
+        if let Some(tree) = parser.parse(source, None) {
+            result.function_annotations = toon_comment::collect_inline_annotations(
+                tree.root_node(),
+                source,
+                &["comment"],
+                |node| match node.kind() {
+                    "method" | "singleton_method" | "class" | "module" => node
+                        .child_by_field_name("name")
+                        .map(|n| self.node_text(n, source)),
+                    _ => None,
+                },
+                |node| match node.kind() {
+                    "class" | "module" => node
+                        .child_by_field_name("name")
+                        .map(|n| self.node_text(n, source)),
+                    _ => None,
+                },
+            );
+        }
+
         Ok(result)
     }
 
@@ -303,6 +659,15 @@ impl LanguageParser for RubyParser {
     }
 }
 
+/// Lowercase with underscores removed, so `FileUtils`, `fileutils` and
+/// `file_utils` all compare equal.
+fn normalize_constant(name: &str) -> String {
+    name.chars()
+        .filter(|c| *c != '_')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
 fn collect_string_ranges_rb(
     cursor: &mut tree_sitter::TreeCursor,
     ranges: &mut Vec<(usize, usize)>,
@@ -361,6 +726,192 @@ mod tests {
         assert_eq!(imports, vec!["fileutils", "helper", "json"]);
     }
 
+    #[test]
+    fn test_extract_calls() {
+        let parser = RubyParser::new();
+        let info = parser
+            .extract_ast_info(RB_FIXTURE, Path::new("sample.rb"))
+            .unwrap();
+
+        let mut calls: Vec<_> = info
+            .calls
+            .iter()
+            .map(|c| (&c.target[..], &c.method[..]))
+            .collect();
+        calls.sort();
+        assert_eq!(calls, vec![("fileutils", "mkdir_p"), ("json", "parse")]);
+
+        let source = "require 'active_support/core_ext'\n\nActiveSupport::Inflector.camelize('a')\nLogger.new\n";
+        let info = parser
+            .extract_ast_info(source, Path::new("mixins.rb"))
+            .unwrap();
+        let calls: Vec<_> = info
+            .calls
+            .iter()
+            .map(|c| (&c.target[..], &c.method[..]))
+            .collect();
+        assert_eq!(calls, vec![("active_support/core_ext", "camelize")]);
+    }
+
+    #[test]
+    fn test_visibility_exports() {
+        let parser = RubyParser::new();
+        let info = parser
+            .extract_ast_info(RB_FIXTURE, Path::new("sample.rb"))
+            .unwrap();
+
+        let kind_of = |name: &str| {
+            info.exports
+                .iter()
+                .find(|e| e.name == name)
+                .map(|e| e.kind.as_str())
+        };
+
+        assert_eq!(kind_of("all_users"), Some("method"));
+        assert_eq!(kind_of("cache_get"), Some("protected_method"));
+        assert_eq!(kind_of("camelize"), Some("module_function"));
+        assert_eq!(kind_of("self.create"), Some("class_method"));
+        assert_eq!(kind_of("validate!"), None);
+
+        let source = r#"
+class Widget
+  def render; end
+  def helper; end
+  private :helper
+
+  private def secret; end
+
+  class << self
+    def build; end
+    def cached; end
+    def internal; end
+    private :internal
+  end
+  private_class_method :cached
+
+  def self.load; end
+
+  class Inner
+    def visible; end
+  end
+
+  private
+
+  def hidden; end
+end
+"#;
+        let info = parser
+            .extract_ast_info(source, Path::new("widget.rb"))
+            .unwrap();
+        let mut exports: Vec<_> = info
+            .exports
+            .iter()
+            .map(|e| (&e.name[..], &e.kind[..]))
+            .collect();
+        exports.sort();
+        assert_eq!(
+            exports,
+            vec![
+                ("Inner", "class"),
+                ("Widget", "class"),
+                ("render", "method"),
+                ("self.build", "class_method"),
+                ("self.load", "class_method"),
+                ("visible", "method"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_signatures() {
+        let parser = RubyParser::new();
+        let info = parser
+            .extract_ast_info(RB_FIXTURE, Path::new("sample.rb"))
+            .unwrap();
+
+        let sig = |name: &str| {
+            info.signatures
+                .iter()
+                .find(|s| s.name == name)
+                .unwrap_or_else(|| panic!("missing signature for {}", name))
+        };
+
+        let init = sig("UserConfig.initialize");
+        assert_eq!(init.kind, "method");
+        assert_eq!(init.signature, "(id:, name:, email: nil, settings: {})");
+        assert_eq!((init.start_line, init.end_line), (59, 64));
+
+        let from_json = sig("UserConfig.from_json");
+        assert_eq!(from_json.kind, "class_method");
+        assert_eq!(from_json.signature, "(json_str)");
+
+        assert_eq!(sig("UserConfig.to_h").signature, "()");
+        assert_eq!(sig("UserConfig.to_json").signature, "(*args)");
+
+        // Members are qualified by their owner and share the export's kind
+        assert_eq!(sig("BaseService.get").signature, "(id)");
+        assert_eq!(sig("UserService.get").start_line, 109);
+        assert_eq!(sig("BaseService.cache_get").kind, "protected_method");
+        assert_eq!(sig("StringUtils.camelize").kind, "module_function");
+        let mut names: Vec<_> = info.signatures.iter().map(|s| &s.name).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), info.signatures.len());
+
+        let config = sig("UserConfig");
+        assert_eq!(config.kind, "class");
+        assert_eq!(
+            config.signature,
+            "{ initialize(id:, name:, email: nil, settings: {}); to_h(); to_json(*args); self.from_json(json_str) }"
+        );
+        assert_eq!((config.start_line, config.end_line), (56, 78));
+
+        let service = sig("UserService");
+        assert!(service
+            .signature
+            .starts_with("< BaseService { get(id); save(user);"));
+
+        let loggable = sig("Loggable");
+        assert_eq!(loggable.kind, "module");
+        assert_eq!(loggable.signature, "{ log(message); log_error(message) }");
+
+        let source = "def run(a, *rest, key: 1, **opts, &block)\nend\n";
+        let info = parser
+            .extract_ast_info(source, Path::new("run.rb"))
+            .unwrap();
+        assert_eq!(
+            info.signatures[0].signature,
+            "(a, *rest, key: 1, **opts, &block)"
+        );
+    }
+
+    #[test]
+    fn test_inline_annotations() {
+        let parser = RubyParser::new();
+        let source = r#"
+class Session
+  # @dose invariant: token is refreshed before expiry
+  def token
+  end
+
+  class << self
+    # @dose gotcha: memoized per process
+    def current
+    end
+  end
+end
+"#;
+        let comments = parser.extract_toon_comments(source).unwrap();
+        assert_eq!(
+            comments.function_annotations["Session.token"].invariants,
+            Some(vec!["token is refreshed before expiry".to_string()])
+        );
+        assert_eq!(
+            comments.function_annotations["Session.current"].gotchas,
+            Some(vec!["memoized per process".to_string()])
+        );
+    }
+
     #[test]
     fn test_toon_comments() {
         let parser = RubyParser::new();
@@ -11,6 +11,7 @@
 //! invariants:
 //!     - Private items (no pub modifier) are never exported
 //!     - Method exports include their receiver type in the kind field
+//!     - Method signatures are named Type.method after their impl or trait, like fn: annotations
 //!     - Macro definitions are always exported (they have no visibility modifier)
 //!
 //! do-not:
@@ -27,6 +28,7 @@
 //!     - Parse: Create tree-sitter parser, set Rust language, parse source
 //!     - Extract exports: Walk AST finding items with visibility_modifier nodes
 //!     - Extract imports: Walk AST collecting use_declaration nodes with complex patterns
+//!     - Inline annotations: /// @dose field: value binds to the next fn, impl, or type item
 
 use crate::parser::{toon_comment, LanguageParser, ParseError};
 use crate::types::{ASTInfo, CallInfo, ExportInfo, ExtractedComments, ImportInfo, SignatureInfo};
@@ -86,98 +88,82 @@ impl RustParser {
             let node = cursor.node();
 
             match node.kind() {
-                "function_item" => {
-                    if self.is_public(node) {
-                        if let Some(name_node) = node.child_by_field_name("name") {
-                            let name = self.node_text(name_node, source);
-                            let kind = if name.starts_with("test_") {
-                                "test".to_string()
-                            } else {
-                                "fn".to_string()
-                            };
-                            exports.push(ExportInfo { name, kind });
-                        }
+                "function_item" if self.is_public(node) => {
+                    if let Some(name_node) = node.child_by_field_name("name") {
+                        let name = self.node_text(name_node, source);
+                        let kind = if name.starts_with("test_") {
+                            "test".to_string()
+                        } else {
+                            "fn".to_string()
+                        };
+                        exports.push(ExportInfo { name, kind });
                     }
                 }
-                "struct_item" => {
-                    if self.is_public(node) {
-                        if let Some(name_node) = node.child_by_field_name("name") {
-                            let name = self.node_text(name_node, source);
-                            exports.push(ExportInfo {
-                                name,
-                                kind: "struct".to_string(),
-                            });
-                        }
+                "struct_item" if self.is_public(node) => {
+                    if let Some(name_node) = node.child_by_field_name("name") {
+                        let name = self.node_text(name_node, source);
+                        exports.push(ExportInfo {
+                            name,
+                            kind: "struct".to_string(),
+                        });
                     }
                 }
-                "enum_item" => {
-                    if self.is_public(node) {
-                        if let Some(name_node) = node.child_by_field_name("name") {
-                            let name = self.node_text(name_node, source);
-                            exports.push(ExportInfo {
-                                name,
-                                kind: "enum".to_string(),
-                            });
-                        }
+                "enum_item" if self.is_public(node) => {
+                    if let Some(name_node) = node.child_by_field_name("name") {
+                        let name = self.node_text(name_node, source);
+                        exports.push(ExportInfo {
+                            name,
+                            kind: "enum".to_string(),
+                        });
                     }
                 }
-                "trait_item" => {
-                    if self.is_public(node) {
-                        if let Some(name_node) = node.child_by_field_name("name") {
-                            let name = self.node_text(name_node, source);
-                            exports.push(ExportInfo {
-                                name,
-                                kind: "trait".to_string(),
-                            });
-                        }
+                "trait_item" if self.is_public(node) => {
+                    if let Some(name_node) = node.child_by_field_name("name") {
+                        let name = self.node_text(name_node, source);
+                        exports.push(ExportInfo {
+                            name,
+                            kind: "trait".to_string(),
+                        });
                     }
                 }
-                "type_item" => {
-                    if self.is_public(node) {
-                        if let Some(name_node) = node.child_by_field_name("name") {
-                            let name = self.node_text(name_node, source);
-                            exports.push(ExportInfo {
-                                name,
-                                kind: "type".to_string(),
-                            });
-                        }
+                "type_item" if self.is_public(node) => {
+                    if let Some(name_node) = node.child_by_field_name("name") {
+                        let name = self.node_text(name_node, source);
+                        exports.push(ExportInfo {
+                            name,
+                            kind: "type".to_string(),
+                        });
                     }
                 }
-                "const_item" => {
-                    if self.is_public(node) {
-                        if let Some(name_node) = node.child_by_field_name("name") {
-                            let name = self.node_text(name_node, source);
-                            exports.push(ExportInfo {
-                                name,
-                                kind: "const".to_string(),
-                            });
-                        }
+                "const_item" if self.is_public(node) => {
+                    if let Some(name_node) = node.child_by_field_name("name") {
+                        let name = self.node_text(name_node, source);
+                        exports.push(ExportInfo {
+                            name,
+                            kind: "const".to_string(),
+                        });
                     }
                 }
-                "static_item" => {
-                    if self.is_public(node) {
-                        if let Some(name_node) = node.child_by_field_name("name") {
-                            let name = self.node_text(name_node, source);
-                            exports.push(ExportInfo {
-                                name,
-                                kind: "static".to_string(),
-                            });
-                        }
+                "static_item" if self.is_public(node) => {
+                    if let Some(name_node) = node.child_by_field_name("name") {
+                        let name = self.node_text(name_node, source);
+                        exports.push(ExportInfo {
+                            name,
+                            kind: "static".to_string(),
+                        });
                     }
                 }
                 "impl_item" => {
                     // Check for pub methods in impl blocks
                     self.extract_impl_exports(node, source, exports);
                 }
-                "mod_item" => {
-                    if self.is_public(node) {
-                        if let Some(name_node) = node.child_by_field_name("name") {
-                            let name = self.node_text(name_node, source);
-                            exports.push(ExportInfo {
-                                name,
-                                kind: "mod".to_string(),
-                            });
-                        }
+                "mod_item" if self.is_public(node) => {
+                    if let Some(name_node) = node.child_by_field_name("name") {
+                        let name = self.node_text(name_node, source);
+                        exports.push(ExportInfo {
+                            name,
+                            kind: "mod".to_string(),
+                        });
                     }
                 }
                 "macro_definition" => {
@@ -421,6 +407,59 @@ impl RustParser {
         }
     }
 
+    /// Name an inline annotation binds to: fn/struct/enum/trait/type/mod items by
+    /// name, impl blocks by their self type without generics (`impl<K> Cache<K>` -> Cache)
+    fn declaration_name(&self, node: Node, source: &str) -> Option<String> {
+        match node.kind() {
+            "function_item"
+            | "function_signature_item"
+            | "struct_item"
+            | "enum_item"
+            | "trait_item"
+            | "type_item"
+            | "mod_item" => node
+                .child_by_field_name("name")
+                .map(|n| self.node_text(n, source)),
+            "impl_item" => self.owner_name(node, source),
+            _ => None,
+        }
+    }
+
+    /// Type an impl or trait body belongs to, for qualifying methods as Type.method
+    fn owner_name(&self, node: Node, source: &str) -> Option<String> {
+        match node.kind() {
+            "trait_item" => node
+                .child_by_field_name("name")
+                .map(|n| self.node_text(n, source)),
+            "impl_item" => {
+                let ty = node.child_by_field_name("type")?;
+                let base = if ty.kind() == "generic_type" {
+                    ty.child_by_field_name("type").unwrap_or(ty)
+                } else {
+                    ty
+                };
+                Some(self.node_text(base, source))
+            }
+            _ => None,
+        }
+    }
+
+    /// Type of the impl or trait a function is defined in; None for free functions,
+    /// including those nested in a method body
+    fn enclosing_owner(&self, node: Node, source: &str) -> Option<String> {
+        let mut ancestor = node.parent();
+        while let Some(parent) = ancestor {
+            if parent.kind() == "function_item" {
+                return None;
+            }
+            if let Some(owner) = self.owner_name(parent, source) {
+                return Some(owner);
+            }
+            ancestor = parent.parent();
+        }
+        None
+    }
+
     fn extract_signatures(
         &self,
         root: Node,
@@ -518,6 +557,11 @@ impl RustParser {
         let start_line = node.start_position().row + 1;
         let end_line = node.end_position().row + 1;
 
+        // Methods are Type.name, as the fn: annotations name them
+        let name = match self.enclosing_owner(node, source) {
+            Some(owner) => format!("{}.{}", owner, name),
+            None => name,
+        };
         Some(SignatureInfo {
             name,
             kind: "fn".to_string(),
@@ -779,10 +823,14 @@ impl LanguageParser for RustParser {
     fn extract_toon_comments(&self, source: &str) -> Result<ExtractedComments, ParseError> {
         let mut comments = ExtractedComments::default();
 
-        // Match /*! @dose ... */ or /** @dose ... */ block comments
+        // Match /*! @dose ... */ or /** @dose ... */ block comments, skipping
+        // single-line /** @dose invariant: ... */ annotations on items
         let block_pattern = Regex::new(r"/\*[!\*]?\s*@dose\b([\s\S]*?)\*/").unwrap();
+        let file_block = block_pattern
+            .captures_iter(source)
+            .find(|c| toon_comment::parse_inline_annotation(&c[0]).is_none());
 
-        if let Some(captures) = block_pattern.captures(source) {
+        if let Some(captures) = file_block {
             if let Some(content) = captures.get(1) {
                 comments.file_block = Some(toon_comment::parse_toon_block(content.as_str()));
             }
@@ -790,7 +838,10 @@ impl LanguageParser for RustParser {
 
         // Match //! @dose or /// @dose doc comments
         let doc_pattern = Regex::new(r"(?m)^[ \t]*//[!/]\s*@dose\b(.*)$").unwrap();
-        if let Some(captures) = doc_pattern.captures(source) {
+        let doc_block = doc_pattern
+            .captures_iter(source)
+            .find(|c| toon_comment::parse_inline_annotation(&c[0]).is_none());
+        if let Some(captures) = doc_block {
             if let Some(content) = captures.get(1) {
                 // Collect subsequent doc comment lines
                 let start = captures.get(0).unwrap().end();
@@ -815,6 +866,18 @@ impl LanguageParser for RustParser {
             }
         }
 
+        // Inline /// @dose invariant: ... annotations bind to the next item
+        let mut parser = self.create_parser()?;
+        if let Some(tree) = parser.parse(source, None) {
+            comments.function_annotations = toon_comment::collect_inline_annotations(
+                tree.root_node(),
+                source,
+                &["line_comment", "block_comment"],
+                |node| self.declaration_name(node, source),
+                |node| self.owner_name(node, source),
+            );
+        }
+
         Ok(comments)
     }
 
@@ -951,6 +1014,26 @@ mod tests {
         assert!(import_items.contains(&"Arc"));
     }
 
+    #[test]
+    fn test_method_signatures_named_by_type() {
+        let info = RustParser::new()
+            .extract_ast_info(RS_FIXTURE, Path::new("sample.rs"))
+            .unwrap();
+
+        let mut names: Vec<_> = info
+            .signatures
+            .iter()
+            .filter(|s| s.name.ends_with(".new") || s.name == "new")
+            .map(|s| &s.name[..])
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec!["Cache.new", "UserConfig.new", "UserService.new"]
+        );
+        assert!(info.signatures.iter().any(|s| s.name == "create_user"));
+    }
+
     #[test]
     fn test_toon_comments() {
         let parser = RustParser::new();
@@ -963,4 +1046,37 @@ mod tests {
             .unwrap();
         assert!(stripped.starts_with("// @dose -> sample.rs.toon"));
     }
+
+    #[test]
+    fn test_inline_annotations() {
+        let parser = RustParser::new();
+        let source = r#"//! @dose
+//! purpose: Cache module
+
+/// @dose constraint: single writer
+impl<K, V> Cache<K, V> {
+    /// @dose invariant: returns None for expired entries
+    /// @dose gotcha: clones the value
+    #[inline]
+    pub fn get(&self, key: &K) -> Option<V> {
+        None
+    }
+}
+"#;
+        let comments = parser.extract_toon_comments(source).unwrap();
+        assert_eq!(
+            comments.file_block.unwrap().purpose.unwrap(),
+            "Cache module"
+        );
+
+        let get = &comments.function_annotations["Cache.get"];
+        assert_eq!(
+            get.invariants,
+            Some(vec!["returns None for expired entries".to_string()])
+        );
+        assert_eq!(get.gotchas, Some(vec!["clones the value".to_string()]));
+
+        let cache = &comments.function_annotations["Cache"];
+        assert_eq!(cache.constraints, Some(vec!["single writer".to_string()]));
+    }
 }
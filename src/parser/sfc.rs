@@ -0,0 +1,668 @@
+//! @dose
+//! purpose: This module parses Vue, Svelte, and Astro single-file components. It finds
+//!     the component's script blocks, hands them to the TypeScript parser for exports,
+//!     imports, and calls, and adds the component itself plus its props and emits.
+//!
+//! when-editing:
+//!     - !Script blocks are parsed in place: everything else is blanked to spaces with newlines
+//!       kept, so TypeScript line numbers and byte offsets already match the original file
+//!     - !The component is exported under the file stem; `export default` is not listed separately
+//!     - Props and emits are recorded as signatures named after the component
+//!
+//! invariants:
+//!     - Byte offsets of the masked source equal those of the original source
+//!     - Svelte instance-script exports are props, not module exports
+//!     - @dose in a script block wins over a template <!-- @dose --> comment
+//!
+//! do-not:
+//!     - Never minify template or style markup; only script contents may be collapsed
+//!     - Never parse Astro <script> tags as the component script; they run in the browser
+//!
+//! gotchas:
+//!     - extract_toon_comments has no file path, so a file starting with --- is treated as
+//!       Astro frontmatter and any other file as <script> blocks
+//!     - <script setup> and <script> share one TypeScript parse, as they share one module in Vue
+//!     - Template expressions are not parsed, so calls made only from the template are missed
+//!
+//! flows:
+//!     - Split: Find <script> blocks (Vue/Svelte) or the --- frontmatter (Astro)
+//!     - Delegate: Blank everything outside the blocks and run the TypeScript parser
+//!     - Component: Export the file stem as a component; drop default and Svelte prop exports
+//!     - Props/emits: defineProps/defineEmits, props/emits options, export let, $props(),
+//!       createEventDispatcher, and Astro's Props type
+//!     - Comments: @dose from the scripts, falling back to an HTML comment in the template
+
+use crate::parser::{nodes, toon_comment, LanguageParser, ParseError, TypeScriptParser};
+use crate::types::{ASTInfo, ExportInfo, ExtractedComments, SignatureInfo};
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::Path;
+use tree_sitter::{Node, Parser};
+
+/// Parser for Vue, Svelte, and Astro components
+#[derive(Clone)]
+pub struct SfcParser {
+    typescript: TypeScriptParser,
+}
+
+/// Byte range of a script block's content
+struct ScriptBlock {
+    start: usize,
+    end: usize,
+    /// lang="tsx"/"jsx" needs the TSX grammar
+    jsx: bool,
+    /// Svelte <script context="module">: exports here are real module exports
+    module: bool,
+    /// Svelte instance script: `export let` declares a prop
+    props_by_export: bool,
+}
+
+impl SfcParser {
+    pub fn new() -> Self {
+        Self {
+            typescript: TypeScriptParser::new(),
+        }
+    }
+
+    /// Astro frontmatter when the file opens with ---, otherwise every <script> block
+    fn script_blocks(&self, source: &str) -> Vec<ScriptBlock> {
+        let frontmatter = Regex::new(r"\A\s*---[ \t]*\r?\n").unwrap();
+        if let Some(open) = frontmatter.find(source) {
+            let close = Regex::new(r"(?m)^---[ \t]*\r?$").unwrap();
+            let end = close
+                .find_at(source, open.end())
+                .map(|m| m.start())
+                .unwrap_or(source.len());
+            return vec![ScriptBlock {
+                start: open.end(),
+                end,
+                jsx: false,
+                module: false,
+                props_by_export: false,
+            }];
+        }
+
+        let script = Regex::new(r"(?is)<script\b([^>]*)>(.*?)</script\s*>").unwrap();
+        let lang = Regex::new(r#"(?i)\blang\s*=\s*["']?(\w+)"#).unwrap();
+        let data_type = Regex::new(r#"(?i)\btype\s*=\s*["']?([\w/+.-]+)"#).unwrap();
+        let module = Regex::new(r#"(?i)\bcontext\s*=\s*["']module["']|\bmodule\b"#).unwrap();
+
+        script
+            .captures_iter(source)
+            .filter_map(|caps| {
+                let attrs = caps.get(1).map_or("", |m| m.as_str());
+                let content = caps.get(2)?;
+                // JSON-LD, templates, and other non-script payloads
+                let is_script = data_type.captures(attrs).is_none_or(|t| {
+                    let t = t[1].to_ascii_lowercase();
+                    t == "module" || t.contains("javascript") || t.contains("typescript")
+                });
+                if !is_script {
+                    return None;
+                }
+                let jsx = lang
+                    .captures(attrs)
+                    .is_some_and(|l| matches!(&l[1], "tsx" | "jsx"));
+                Some(ScriptBlock {
+                    start: content.start(),
+                    end: content.end(),
+                    jsx,
+                    module: module.is_match(attrs),
+                    props_by_export: false,
+                })
+            })
+            .collect()
+    }
+
+    /// The source with everything outside the script blocks replaced by spaces.
+    /// Newlines are kept and every byte maps to one byte, so rows and offsets survive.
+    fn mask(&self, source: &str, blocks: &[ScriptBlock]) -> String {
+        let mut masked: Vec<u8> = source
+            .bytes()
+            .map(|b| if b == b'\n' || b == b'\r' { b } else { b' ' })
+            .collect();
+        for block in blocks {
+            masked[block.start..block.end]
+                .copy_from_slice(&source.as_bytes()[block.start..block.end]);
+        }
+        // Blocks hold whole UTF-8 sequences and everything else is ASCII
+        String::from_utf8(masked).unwrap_or_default()
+    }
+
+    fn parse_typescript(&self, masked: &str, jsx: bool) -> Option<tree_sitter::Tree> {
+        let mut parser = Parser::new();
+        let language = if jsx {
+            tree_sitter_typescript::LANGUAGE_TSX
+        } else {
+            tree_sitter_typescript::LANGUAGE_TYPESCRIPT
+        };
+        parser.set_language(&language.into()).ok()?;
+        parser.parse(masked, None)
+    }
+
+    /// Props and emits signatures, plus the names of Svelte instance-script exports
+    /// (which are props rather than module exports)
+    fn component_api(
+        &self,
+        root: Node,
+        source: &str,
+        blocks: &[ScriptBlock],
+        component: &str,
+    ) -> (Vec<SignatureInfo>, HashSet<String>) {
+        let mut api = ComponentApi::default();
+        self.visit_component_api(root, source, blocks, &mut api);
+
+        let mut signatures = Vec::new();
+        // export let label: string; export let count = 0;
+        if !api.export_lets.is_empty() {
+            let first = api.export_lets.first().map_or(0, |(_, s, _)| *s);
+            let last = api.export_lets.last().map_or(0, |(_, _, e)| *e);
+            let fields: Vec<&str> = api.export_lets.iter().map(|(f, _, _)| f.as_str()).collect();
+            api.props
+                .get_or_insert((format!("{{ {} }}", fields.join("; ")), first, last));
+        }
+        for (kind, found) in [("props", api.props), ("emits", api.emits)] {
+            if let Some((signature, start_line, end_line)) = found {
+                signatures.push(SignatureInfo {
+                    name: component.to_string(),
+                    kind: kind.to_string(),
+                    signature,
+                    start_line,
+                    end_line,
+                });
+            }
+        }
+        (signatures, api.instance_exports)
+    }
+
+    fn visit_component_api(
+        &self,
+        node: Node,
+        source: &str,
+        blocks: &[ScriptBlock],
+        api: &mut ComponentApi,
+    ) {
+        let lines = |n: Node| (n.start_position().row + 1, n.end_position().row + 1);
+
+        match node.kind() {
+            "call_expression" => {
+                let callee = node
+                    .child_by_field_name("function")
+                    .map(|f| node_text(f, source))
+                    .unwrap_or_default();
+                let (start_line, end_line) = lines(node);
+                let declared = || {
+                    // defineProps<{ id: string }>() or defineProps({ id: String })
+                    node.child_by_field_name("type_arguments")
+                        .map(|t| {
+                            strip_angle_brackets(&nodes::fold_whitespace(node_text(t, source)))
+                        })
+                        .or_else(|| {
+                            node.child_by_field_name("arguments")
+                                .and_then(|a| a.named_child(0))
+                                .map(|a| nodes::fold_whitespace(node_text(a, source)))
+                        })
+                };
+                match callee {
+                    "defineProps" | "$props" if api.props.is_none() => {
+                        // $props() is typed by its declaration: let { a }: Props = $props()
+                        let signature = declared().or_else(|| {
+                            let declarator = node.parent()?;
+                            let typed = declarator.child_by_field_name("type").map(|t| {
+                                nodes::fold_whitespace(node_text(t, source))
+                                    .trim_start_matches(':')
+                                    .trim()
+                                    .to_string()
+                            });
+                            typed.or_else(|| {
+                                declarator
+                                    .child_by_field_name("name")
+                                    .map(|n| nodes::fold_whitespace(node_text(n, source)))
+                            })
+                        });
+                        if let Some(signature) = signature {
+                            api.props = Some((signature, start_line, end_line));
+                        }
+                    }
+                    "defineEmits" | "createEventDispatcher" if api.emits.is_none() => {
+                        if let Some(signature) = declared() {
+                            api.emits = Some((signature, start_line, end_line));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            // export default { props: {...}, emits: [...] } / defineComponent({ ... })
+            "pair" => {
+                let key = node
+                    .child_by_field_name("key")
+                    .map(|k| node_text(k, source).trim_matches(|c| c == '"' || c == '\''))
+                    .unwrap_or_default();
+                let in_component_options = node
+                    .parent()
+                    .and_then(|object| object.parent())
+                    .is_some_and(|owner| {
+                        owner.kind() == "export_statement"
+                            || (owner.kind() == "arguments"
+                                && owner
+                                    .parent()
+                                    .and_then(|call| call.child_by_field_name("function"))
+                                    .is_some_and(|f| node_text(f, source) == "defineComponent"))
+                    });
+                if in_component_options {
+                    let value = node
+                        .child_by_field_name("value")
+                        .map(|v| nodes::fold_whitespace(node_text(v, source)));
+                    let (start_line, end_line) = lines(node);
+                    match (key, value) {
+                        ("props", Some(value)) if api.props.is_none() => {
+                            api.props = Some((value, start_line, end_line));
+                        }
+                        ("emits", Some(value)) if api.emits.is_none() => {
+                            api.emits = Some((value, start_line, end_line));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            // Astro: interface Props { ... } / type Props = { ... }
+            "interface_declaration" | "type_alias_declaration"
+                if node
+                    .child_by_field_name("name")
+                    .is_some_and(|n| node_text(n, source) == "Props") =>
+            {
+                let body = node
+                    .child_by_field_name("body")
+                    .or_else(|| node.child_by_field_name("value"));
+                if let Some(body) = body {
+                    let (start_line, end_line) = lines(node);
+                    api.props_type = Some((
+                        nodes::fold_whitespace(node_text(body, source)),
+                        start_line,
+                        end_line,
+                    ));
+                }
+            }
+            // Svelte 4: export let label: string; in the instance script
+            "export_statement" => {
+                let in_instance = blocks.iter().any(|b| {
+                    b.props_by_export && b.start <= node.start_byte() && node.end_byte() <= b.end
+                });
+                let declaration = node.child_by_field_name("declaration");
+                if let (true, Some(declaration)) = (in_instance, declaration) {
+                    let mut cursor = declaration.walk();
+                    for declarator in declaration.named_children(&mut cursor) {
+                        if declarator.kind() != "variable_declarator" {
+                            continue;
+                        }
+                        let Some(name) = declarator.child_by_field_name("name") else {
+                            continue;
+                        };
+                        let name = node_text(name, source).to_string();
+                        // Props with a default value are optional
+                        let optional = if declarator.child_by_field_name("value").is_some() {
+                            "?"
+                        } else {
+                            ""
+                        };
+                        let ty = declarator
+                            .child_by_field_name("type")
+                            .map(|t| nodes::fold_whitespace(node_text(t, source)))
+                            .unwrap_or_default();
+                        let (start_line, end_line) = lines(node);
+                        api.export_lets.push((
+                            format!("{}{}{}", name, optional, ty),
+                            start_line,
+                            end_line,
+                        ));
+                        api.instance_exports.insert(name);
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.visit_component_api(child, source, blocks, api);
+        }
+
+        // Astro's Props type only applies when nothing declares props directly
+        if node.parent().is_none() && api.props.is_none() {
+            api.props = api.props_type.take();
+        }
+    }
+}
+
+/// Props and emits found while walking the component's scripts
+#[derive(Default)]
+struct ComponentApi {
+    props: Option<(String, usize, usize)>,
+    emits: Option<(String, usize, usize)>,
+    props_type: Option<(String, usize, usize)>,
+    export_lets: Vec<(String, usize, usize)>,
+    instance_exports: HashSet<String>,
+}
+
+fn node_text<'a>(node: Node, source: &'a str) -> &'a str {
+    &source[node.start_byte()..node.end_byte()]
+}
+
+/// `<{ id: string }>` -> `{ id: string }`
+fn strip_angle_brackets(text: &str) -> String {
+    text.strip_prefix('<')
+        .and_then(|t| t.strip_suffix('>'))
+        .unwrap_or(text)
+        .trim()
+        .to_string()
+}
+
+impl Default for SfcParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguageParser for SfcParser {
+    fn language_name(&self) -> &'static str {
+        "sfc"
+    }
+
+    fn file_extensions(&self) -> &[&'static str] {
+        &["vue", "svelte", "astro"]
+    }
+
+    fn extract_ast_info(&self, source: &str, file_path: &Path) -> Result<ASTInfo, ParseError> {
+        let mut blocks = self.script_blocks(source);
+        if file_path.extension().is_some_and(|e| e == "svelte") {
+            for block in &mut blocks {
+                block.props_by_export = !block.module;
+            }
+        }
+        let masked = self.mask(source, &blocks);
+        let jsx = blocks.iter().any(|b| b.jsx);
+
+        // The TypeScript parser picks its grammar from the extension
+        let script_path = Path::new(if jsx { "component.tsx" } else { "component.ts" });
+        let mut info = self.typescript.extract_ast_info(&masked, script_path)?;
+        info.tokens = super::tokens::count_tokens(source);
+
+        let component = file_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "Component".to_string());
+
+        let tree = self
+            .parse_typescript(&masked, jsx)
+            .ok_or_else(|| ParseError::ParseError("Failed to parse source".to_string()))?;
+        let (api_signatures, instance_exports) =
+            self.component_api(tree.root_node(), &masked, &blocks, &component);
+
+        info.exports
+            .retain(|e| e.name != "default" && !instance_exports.contains(&e.name));
+        info.exports.insert(
+            0,
+            ExportInfo {
+                name: component,
+                kind: "component".to_string(),
+            },
+        );
+        info.signatures
+            .retain(|s| !instance_exports.contains(&s.name));
+        info.signatures.extend(api_signatures);
+
+        Ok(info)
+    }
+
+    fn extract_toon_comments(&self, source: &str) -> Result<ExtractedComments, ParseError> {
+        let blocks = self.script_blocks(source);
+        let masked = self.mask(source, &blocks);
+        let mut result = self.typescript.extract_toon_comments(&masked)?;
+
+        // Fall back to <!-- @dose ... --> in the template
+        if result.file_block.is_none() {
+            let html_pattern = Regex::new(r"<!--\s*@dose([\s\S]*?)-->").unwrap();
+            if let Some(caps) = html_pattern.captures(source) {
+                let content = caps[1]
+                    .lines()
+                    .map(|line| line.trim())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                result.file_block = Some(toon_comment::parse_toon_block(&content));
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn strip_toon_comments(&self, source: &str, toon_path: &str) -> Result<String, ParseError> {
+        let result = self.typescript.strip_toon_comments(source, toon_path)?;
+
+        // Replace <!-- @dose --> template comments
+        let html_pattern = Regex::new(r"<!--\s*@dose[\s\S]*?-->").unwrap();
+        Ok(html_pattern
+            .replace_all(&result, format!("<!-- @dose -> {} -->", toon_path).as_str())
+            .to_string())
+    }
+
+    fn get_string_ranges(&self, source: &str) -> Result<Vec<(usize, usize)>, ParseError> {
+        let blocks = self.script_blocks(source);
+        let masked = self.mask(source, &blocks);
+
+        // Template and style markup is kept verbatim; script strings come from TypeScript
+        let mut ranges: Vec<(usize, usize)> = self
+            .typescript
+            .get_string_ranges(&masked)?
+            .into_iter()
+            .filter(|(start, end)| blocks.iter().any(|b| b.start <= *start && *end <= b.end))
+            .collect();
+        let mut offset = 0;
+        for block in &blocks {
+            ranges.push((offset, block.start));
+            offset = block.end;
+        }
+        ranges.push((offset, source.len()));
+        ranges.sort();
+        Ok(ranges)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VUE_FIXTURE: &str = include_str!("../../test_fixtures/sample.vue");
+    const SVELTE_FIXTURE: &str = include_str!("../../test_fixtures/sample.svelte");
+
+    #[test]
+    fn test_vue_ast_info() {
+        let parser = SfcParser::new();
+        let info = parser
+            .extract_ast_info(VUE_FIXTURE, Path::new("src/UserCard.vue"))
+            .unwrap();
+
+        let exports: Vec<_> = info
+            .exports
+            .iter()
+            .map(|e| (&e.name[..], &e.kind[..]))
+            .collect();
+        assert_eq!(
+            exports,
+            vec![
+                ("UserCard", "component"),
+                ("CARD_VERSION", "const"),
+                ("formatName", "fn"),
+            ]
+        );
+
+        let imports: Vec<_> = info.imports.iter().map(|i| &i.from[..]).collect();
+        assert_eq!(imports, vec!["vue", "@/stores/user", "./api"]);
+        assert!(info
+            .calls
+            .iter()
+            .any(|c| c.target == "./api" && c.method == "saveUser"));
+
+        let sig = |kind: &str| {
+            info.signatures
+                .iter()
+                .find(|s| s.kind == kind)
+                .unwrap_or_else(|| panic!("missing {} signature", kind))
+        };
+
+        // Line numbers point into the .vue file, not the script block
+        let format_name = sig("fn");
+        assert_eq!(format_name.name, "formatName");
+        assert_eq!((format_name.start_line, format_name.end_line), (21, 23));
+
+        let props = sig("props");
+        assert_eq!(props.name, "UserCard");
+        assert_eq!(props.signature, "{ userId: string; compact?: boolean; }");
+        assert_eq!((props.start_line, props.end_line), (31, 34));
+
+        let emits = sig("emits");
+        assert_eq!(emits.signature, "{ (e: 'saved', id: string): void; }");
+        assert_eq!((emits.start_line, emits.end_line), (36, 38));
+    }
+
+    #[test]
+    fn test_svelte_ast_info() {
+        let parser = SfcParser::new();
+        let info = parser
+            .extract_ast_info(SVELTE_FIXTURE, Path::new("Counter.svelte"))
+            .unwrap();
+
+        // export let in the instance script declares props, not module exports
+        let exports: Vec<_> = info
+            .exports
+            .iter()
+            .map(|e| (&e.name[..], &e.kind[..]))
+            .collect();
+        assert_eq!(exports, vec![("Counter", "component"), ("preload", "fn")]);
+
+        let props = info.signatures.iter().find(|s| s.kind == "props").unwrap();
+        assert_eq!(props.signature, "{ label: string; count? }");
+        assert_eq!((props.start_line, props.end_line), (17, 18));
+
+        let emits = info.signatures.iter().find(|s| s.kind == "emits").unwrap();
+        assert_eq!(emits.signature, "{ change: number }");
+
+        assert!(info
+            .calls
+            .iter()
+            .any(|c| c.target == "../lib/math" && c.method == "clamp"));
+    }
+
+    #[test]
+    fn test_svelte_runes_and_options_api() {
+        let parser = SfcParser::new();
+        let runes = r#"<script lang="ts">
+  let { title, done = false }: { title: string; done?: boolean } = $props();
+</script>
+"#;
+        let info = parser
+            .extract_ast_info(runes, Path::new("Todo.svelte"))
+            .unwrap();
+        let props = info.signatures.iter().find(|s| s.kind == "props").unwrap();
+        assert_eq!(props.signature, "{ title: string; done?: boolean }");
+
+        let options = r#"<template><p>{{ msg }}</p></template>
+<script>
+export default {
+  props: { msg: String },
+  emits: ['close'],
+};
+</script>
+"#;
+        let info = parser
+            .extract_ast_info(options, Path::new("Hello.vue"))
+            .unwrap();
+        let sigs: Vec<_> = info
+            .signatures
+            .iter()
+            .map(|s| (&s.kind[..], &s.signature[..], s.start_line))
+            .collect();
+        assert_eq!(
+            sigs,
+            vec![("props", "{ msg: String }", 4), ("emits", "['close']", 5)]
+        );
+    }
+
+    #[test]
+    fn test_astro_frontmatter() {
+        let parser = SfcParser::new();
+        let source = r#"---
+/** @dose
+purpose: Page layout
+
+gotchas:
+    - The <script> below runs in the browser
+*/
+import Header from '../components/Header.astro';
+
+interface Props {
+  title: string;
+}
+
+const { title } = Astro.props;
+---
+<html>
+  <Header title={title} />
+  <script>console.log("client only");</script>
+</html>
+"#;
+        let info = parser
+            .extract_ast_info(source, Path::new("Layout.astro"))
+            .unwrap();
+        assert_eq!(info.exports[0].name, "Layout");
+        assert_eq!(info.imports[0].from, "../components/Header.astro");
+        assert!(info.calls.is_empty());
+
+        let props = info.signatures.iter().find(|s| s.kind == "props").unwrap();
+        assert_eq!(props.signature, "{ title: string; }");
+        assert_eq!((props.start_line, props.end_line), (10, 12));
+
+        let comments = parser.extract_toon_comments(source).unwrap();
+        assert_eq!(comments.file_block.unwrap().purpose.unwrap(), "Page layout");
+    }
+
+    #[test]
+    fn test_toon_comments() {
+        let parser = SfcParser::new();
+
+        // Template comment
+        let comments = parser.extract_toon_comments(VUE_FIXTURE).unwrap();
+        let block = comments.file_block.unwrap();
+        assert_eq!(block.purpose.unwrap(), "Sample Vue fixture for testing the luny single-file component parser. This component has a template, a plain script, and a script setup block.");
+        assert_eq!(
+            comments.function_annotations["save"].invariants,
+            Some(vec![
+                "only emits saved after the API call resolves".to_string()
+            ])
+        );
+
+        let stripped = parser
+            .strip_toon_comments(VUE_FIXTURE, "src/UserCard.vue.toon")
+            .unwrap();
+        assert!(stripped.starts_with("<!-- @dose -> src/UserCard.vue.toon -->\n<template>"));
+
+        // Script comment
+        let comments = parser.extract_toon_comments(SVELTE_FIXTURE).unwrap();
+        let block = comments.file_block.unwrap();
+        assert_eq!(
+            block.invariants,
+            Some(vec!["count never goes below zero".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_string_ranges_keep_markup() {
+        let parser = SfcParser::new();
+        let source = "<p>\n  hi\n</p>\n<script>\nconst a = 'x  y';\n</script>\n";
+        let ranges = parser.get_string_ranges(source).unwrap();
+        let texts: Vec<_> = ranges.iter().map(|(s, e)| &source[*s..*e]).collect();
+        assert!(texts.contains(&"<p>\n  hi\n</p>\n<script>"));
+        assert!(texts.contains(&"'x  y'"));
+        assert!(texts.contains(&"</script>\n"));
+        assert!(!texts.iter().any(|t| t.contains("const")));
+    }
+}
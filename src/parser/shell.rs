@@ -0,0 +1,686 @@
+//! @dose
+//! purpose: This module parses Bash and POSIX shell scripts to extract function
+//!     definitions, sourced files, and the external commands a script runs. Extensionless
+//!     scripts reach this parser through their #! line (see ParserFactory).
+//!
+//! when-editing:
+//!     - !Calls are external commands: builtins and functions defined in the file are skipped
+//!     - !Sourced paths are made relative to the script, since that is how ops scripts
+//!       locate their libraries ($SCRIPT_DIR/lib.sh, $(dirname "$0")/lib.sh)
+//!     - Functions starting with an underscore are private by convention
+//!
+//! invariants:
+//!     - A call's target is the command and its method the subcommand (kubectl apply),
+//!       or the command again when the first argument is not a plain word
+//!     - Wrappers (sudo, env, exec, nohup, command, time, nice, xargs) are looked through
+//!     - Function signatures list positional parameters, named after the locals they feed
+//!
+//! do-not:
+//!     - Never record commands whose name is itself an expansion ($CMD args)
+//!     - Never treat the #! line as part of the @dose block
+//!
+//! gotchas:
+//!     - A leading $SCRIPT_DIR-style expansion (anything naming DIR, ROOT, BASH_SOURCE,
+//!       or $0) is taken to be the script's directory; other expansions are not imported
+//!     - Commands provided by sourced libraries look external, as the library is not read
+//!
+//! flows:
+//!     - Parse: Create tree-sitter parser, set Bash language, parse source
+//!     - Exports: function_definition nodes outside other functions
+//!     - Imports: source and . commands
+//!     - Calls: Every other command node, unwrapped and filtered
+//!     - Comments: A # @dose comment block, plus inline # @dose annotations on functions
+
+use crate::parser::{toon_comment, LanguageParser, ParseError};
+use crate::types::{ASTInfo, CallInfo, ExportInfo, ExtractedComments, ImportInfo, SignatureInfo};
+use regex::Regex;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use tree_sitter::{Node, Parser};
+
+/// Shell builtins and keywords that are not dependencies on other tools
+const BUILTINS: &[&str] = &[
+    ".",
+    ":",
+    "[",
+    "[[",
+    "alias",
+    "bg",
+    "break",
+    "builtin",
+    "caller",
+    "cd",
+    "compgen",
+    "complete",
+    "continue",
+    "declare",
+    "dirs",
+    "disown",
+    "echo",
+    "enable",
+    "eval",
+    "exit",
+    "export",
+    "false",
+    "fg",
+    "getopts",
+    "hash",
+    "help",
+    "history",
+    "jobs",
+    "kill",
+    "let",
+    "local",
+    "logout",
+    "mapfile",
+    "popd",
+    "printf",
+    "pushd",
+    "pwd",
+    "read",
+    "readarray",
+    "readonly",
+    "return",
+    "set",
+    "shift",
+    "shopt",
+    "source",
+    "test",
+    "times",
+    "trap",
+    "true",
+    "type",
+    "typeset",
+    "ulimit",
+    "umask",
+    "unalias",
+    "unset",
+    "wait",
+];
+
+/// Commands that run another command given as an argument
+const WRAPPERS: &[&str] = &[
+    "sudo", "env", "exec", "nohup", "command", "time", "nice", "xargs",
+];
+
+/// Parser for shell scripts
+#[derive(Clone)]
+pub struct ShellParser;
+
+impl ShellParser {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn create_parser(&self) -> Result<Parser, ParseError> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_bash::LANGUAGE.into())
+            .map_err(|e| ParseError::ParseError(e.to_string()))?;
+        Ok(parser)
+    }
+
+    fn node_text<'a>(&self, node: Node, source: &'a str) -> &'a str {
+        &source[node.start_byte()..node.end_byte()]
+    }
+
+    /// Function definitions, not descending into function bodies
+    fn collect_functions<'a>(&self, node: Node<'a>, functions: &mut Vec<Node<'a>>) {
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            if child.kind() == "function_definition" {
+                functions.push(child);
+            } else {
+                self.collect_functions(child, functions);
+            }
+        }
+    }
+
+    fn function_name<'a>(&self, node: Node, source: &'a str) -> Option<&'a str> {
+        node.child_by_field_name("name")
+            .map(|n| self.node_text(n, source))
+    }
+
+    fn extract_exports(&self, functions: &[Node], source: &str) -> Vec<ExportInfo> {
+        let mut seen = HashSet::new();
+        functions
+            .iter()
+            .filter_map(|f| self.function_name(*f, source))
+            .filter(|name| !name.starts_with('_') && seen.insert(*name))
+            .map(|name| ExportInfo {
+                name: name.to_string(),
+                kind: "fn".to_string(),
+            })
+            .collect()
+    }
+
+    /// Command name and arguments of a command node
+    fn command_parts<'a>(
+        &self,
+        node: Node<'a>,
+        source: &'a str,
+    ) -> Option<(&'a str, Vec<Node<'a>>)> {
+        let name = node.child_by_field_name("name")?;
+        let text = self.node_text(name, source);
+        let mut cursor = node.walk();
+        let args = node
+            .children_by_field_name("argument", &mut cursor)
+            .collect();
+        Some((text, args))
+    }
+
+    fn extract_imports(&self, root: Node, source: &str) -> Vec<ImportInfo> {
+        let mut imports: Vec<ImportInfo> = Vec::new();
+        let mut stack = vec![root];
+
+        while let Some(node) = stack.pop() {
+            if node.kind() == "command" {
+                if let Some((name, args)) = self.command_parts(node, source) {
+                    if name == "source" || name == "." {
+                        let from = args.first().and_then(|arg| {
+                            script_relative(&unquote(self.node_text(*arg, source)))
+                        });
+                        if let Some(from) = from {
+                            if !imports.iter().any(|i| i.from == from) {
+                                imports.push(ImportInfo {
+                                    from,
+                                    items: vec!["*".to_string()],
+                                });
+                            }
+                        }
+                        continue;
+                    }
+                }
+            }
+            let mut cursor = node.walk();
+            let children: Vec<_> = node.named_children(&mut cursor).collect();
+            stack.extend(children.into_iter().rev());
+        }
+        imports
+    }
+
+    fn extract_calls(&self, root: Node, source: &str, functions: &[Node]) -> Vec<CallInfo> {
+        let local: HashSet<&str> = functions
+            .iter()
+            .filter_map(|f| self.function_name(*f, source))
+            .collect();
+        let mut calls = Vec::new();
+        let mut seen: HashSet<(String, String)> = HashSet::new();
+        let mut stack = vec![root];
+
+        while let Some(node) = stack.pop() {
+            if node.kind() == "command" {
+                if let Some(call) = self.parse_command(node, source, &local) {
+                    if seen.insert((call.target.clone(), call.method.clone())) {
+                        calls.push(call);
+                    }
+                }
+            }
+            let mut cursor = node.walk();
+            let children: Vec<_> = node.named_children(&mut cursor).collect();
+            stack.extend(children.into_iter().rev());
+        }
+        calls
+    }
+
+    fn parse_command(&self, node: Node, source: &str, local: &HashSet<&str>) -> Option<CallInfo> {
+        let (name, args) = self.command_parts(node, source)?;
+        let mut words: Vec<(&str, bool)> = std::iter::once((name, true))
+            .chain(
+                args.iter()
+                    .map(|a| (self.node_text(*a, source), a.kind() == "word")),
+            )
+            .collect();
+
+        // sudo -u deploy kubectl ... -> kubectl ...
+        while let Some(&(wrapper, _)) = words.first() {
+            if !WRAPPERS.contains(&wrapper) {
+                break;
+            }
+            let mut rest = 1;
+            while let Some(&(word, _)) = words.get(rest) {
+                if wrapper == "sudo" && matches!(word, "-u" | "-g" | "-C" | "-h" | "-p") {
+                    rest += 2;
+                } else if word.starts_with('-') || (wrapper == "env" && word.contains('=')) {
+                    rest += 1;
+                } else {
+                    break;
+                }
+            }
+            words.drain(..rest.min(words.len()));
+        }
+
+        let (command, _) = *words.first()?;
+        if command.contains('$')
+            || command.contains('`')
+            || BUILTINS.contains(&command)
+            || local.contains(command)
+        {
+            return None;
+        }
+        // /usr/bin/curl -> curl; ./bin/tool stays a path
+        let command = match command.strip_prefix('/') {
+            Some(path) => path.rsplit('/').next().unwrap_or(path),
+            None => command,
+        };
+
+        let subcommand = words
+            .get(1)
+            .filter(|(word, plain)| *plain && is_subcommand(word))
+            .map(|(word, _)| *word)
+            .unwrap_or(command);
+
+        Some(CallInfo {
+            target: command.to_string(),
+            method: subcommand.to_string(),
+        })
+    }
+
+    fn extract_signatures(
+        &self,
+        functions: &[Node],
+        source: &str,
+        exports: &[ExportInfo],
+    ) -> Vec<SignatureInfo> {
+        let export_names: HashSet<&str> = exports.iter().map(|e| e.name.as_str()).collect();
+        let mut seen = HashSet::new();
+        let mut signatures = Vec::new();
+
+        for function in functions {
+            let Some(name) = self.function_name(*function, source) else {
+                continue;
+            };
+            if !export_names.contains(name) || !seen.insert(name) {
+                continue;
+            }
+
+            signatures.push(SignatureInfo {
+                name: name.to_string(),
+                kind: "fn".to_string(),
+                signature: self.positional_params(*function, source),
+                start_line: function.start_position().row + 1,
+                end_line: function.end_position().row + 1,
+            });
+        }
+        signatures
+    }
+
+    /// `(service, version)` from `local service="$1"; local version="$2"`, `($1, ...)` otherwise
+    fn positional_params(&self, function: Node, source: &str) -> String {
+        let mut names: BTreeMap<usize, String> = BTreeMap::new();
+        let mut highest = 0;
+        let mut variadic = false;
+        let mut stack = vec![function];
+
+        while let Some(node) = stack.pop() {
+            match node.kind() {
+                "variable_name" | "special_variable_name" => {
+                    let text = self.node_text(node, source);
+                    if let Ok(index) = text.parse::<usize>() {
+                        highest = highest.max(index);
+                    } else if text == "@" || text == "*" {
+                        variadic = true;
+                    }
+                }
+                // local service="$1"
+                "variable_assignment" => {
+                    let name = node.child_by_field_name("name");
+                    let value = node.child_by_field_name("value");
+                    if let (Some(name), Some(value)) = (name, value) {
+                        let value = unquote(self.node_text(value, source));
+                        let index = value
+                            .strip_prefix("${")
+                            .and_then(|v| v.strip_suffix('}'))
+                            .or_else(|| value.strip_prefix('$'))
+                            .and_then(|v| v.parse::<usize>().ok());
+                        if let Some(index) = index {
+                            names
+                                .entry(index)
+                                .or_insert_with(|| self.node_text(name, source).to_string());
+                        }
+                    }
+                }
+                _ => {}
+            }
+            let mut cursor = node.walk();
+            stack.extend(node.named_children(&mut cursor));
+        }
+
+        let mut params: Vec<String> = (1..=highest)
+            .map(|i| names.remove(&i).unwrap_or_else(|| format!("${}", i)))
+            .collect();
+        if variadic {
+            params.push("...".to_string());
+        }
+        format!("({})", params.join(", "))
+    }
+}
+
+/// `"$SCRIPT_DIR/lib.sh"` -> `$SCRIPT_DIR/lib.sh`
+fn unquote(text: &str) -> String {
+    text.trim_matches(|c| c == '"' || c == '\'').to_string()
+}
+
+/// Plain lowercase words like `apply` or `rollout`, as opposed to flags, paths, and values
+fn is_subcommand(word: &str) -> bool {
+    let mut chars = word.chars();
+    chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+/// A sourced path relative to the script: `$DIR/lib.sh` and `lib.sh` become `./lib.sh`.
+/// Returns None when the path depends on other expansions.
+fn script_relative(path: &str) -> Option<String> {
+    let path = if let Some(rest) = path.strip_prefix('$') {
+        // Skip the leading $VAR, ${...}, or $(...) expansion
+        let end = match rest.chars().next()? {
+            '{' => rest.find('}')? + 1,
+            '(' => matching_paren(rest)? + 1,
+            _ => rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len()),
+        };
+        if !names_script_dir(&rest[..end]) {
+            return None;
+        }
+        rest[end..].strip_prefix('/')?
+    } else {
+        path
+    };
+
+    if path.is_empty() || path.contains('$') || path.contains('`') {
+        return None;
+    }
+    if path.starts_with('/') || path.starts_with("./") || path.starts_with("../") {
+        Some(path.to_string())
+    } else {
+        Some(format!("./{}", path))
+    }
+}
+
+/// Whether an expansion plausibly holds the script's directory:
+/// $SCRIPT_DIR, ${ROOT}, ${BASH_SOURCE%/*}, $(dirname "$0"), ...
+fn names_script_dir(expansion: &str) -> bool {
+    let upper = expansion.to_ascii_uppercase();
+    ["DIR", "ROOT", "BASH_SOURCE", "$0"]
+        .iter()
+        .any(|hint| upper.contains(hint))
+}
+
+/// Byte index of the `)` closing the `(` at the start of `text`
+fn matching_paren(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+impl Default for ShellParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguageParser for ShellParser {
+    fn language_name(&self) -> &'static str {
+        "shell"
+    }
+
+    fn file_extensions(&self) -> &[&'static str] {
+        &["sh", "bash"]
+    }
+
+    fn extract_ast_info(&self, source: &str, _file_path: &Path) -> Result<ASTInfo, ParseError> {
+        let mut parser = self.create_parser()?;
+        let tree = parser
+            .parse(source, None)
+            .ok_or_else(|| ParseError::ParseError("Failed to parse source".to_string()))?;
+
+        let root = tree.root_node();
+
+        let mut functions = Vec::new();
+        self.collect_functions(root, &mut functions);
+
+        let exports = self.extract_exports(&functions, source);
+        let imports = self.extract_imports(root, source);
+        let calls = self.extract_calls(root, source, &functions);
+        let signatures = self.extract_signatures(&functions, source, &exports);
+
+        let tokens = super::tokens::count_tokens(source);
+
+        Ok(ASTInfo {
+            tokens,
+            exports,
+            imports,
+            calls,
+            signatures,
+        })
+    }
+
+    fn extract_toon_comments(&self, source: &str) -> Result<ExtractedComments, ParseError> {
+        let mut result = ExtractedComments::default();
+
+        // Find a # @dose comment block
+        let comment_pattern = Regex::new(r"(?m)^#[ \t]*@dose[ \t]*\n((?:#[^\n]*\n)*)").unwrap();
+        if let Some(block) = comment_pattern.captures(source).and_then(|c| c.get(1)) {
+            let content = block
+                .as_str()
+                .lines()
+                .map(|line| line.trim_start_matches('#').trim())
+                .collect::<Vec<_>>()
+                .join("\n");
+            result.file_block = Some(toon_comment::parse_toon_block(&content));
+        }
+
+        // Inline # @dose invariant: value annotations on the following function
+        let mut parser = self.create_parser()?;
+        if let Some(tree) = parser.parse(source, None) {
+            result.function_annotations = toon_comment::collect_inline_annotations(
+                tree.root_node(),
+                source,
+                &["comment"],
+                |node| {
+                    (node.kind() == "function_definition")
+                        .then(|| self.function_name(node, source).map(str::to_string))
+                        .flatten()
+                },
+                |_| None,
+            );
+        }
+
+        Ok(result)
+    }
+
+    fn strip_toon_comments(&self, source: &str, toon_path: &str) -> Result<String, ParseError> {
+        // Replace # @dose comment blocks
+        let comment_pattern = Regex::new(r"(?m)^#[ \t]*@dose[ \t]*\n(?:#[^\n]*\n)*").unwrap();
+        Ok(comment_pattern
+            .replace_all(source, format!("# @dose -> {}\n", toon_path).as_str())
+            .to_string())
+    }
+
+    fn get_string_ranges(&self, source: &str) -> Result<Vec<(usize, usize)>, ParseError> {
+        let mut parser = self.create_parser()?;
+
+        let tree = parser
+            .parse(source, None)
+            .ok_or_else(|| ParseError::ParseError("Failed to parse source".to_string()))?;
+
+        let mut ranges = Vec::new();
+        collect_string_ranges_sh(&mut tree.walk(), &mut ranges);
+        Ok(ranges)
+    }
+}
+
+fn collect_string_ranges_sh(
+    cursor: &mut tree_sitter::TreeCursor,
+    ranges: &mut Vec<(usize, usize)>,
+) {
+    loop {
+        let node = cursor.node();
+        let kind = node.kind();
+        if matches!(
+            kind,
+            "string" | "raw_string" | "ansi_c_string" | "translated_string"
+        ) {
+            ranges.push((node.start_byte(), node.end_byte()));
+        }
+        // Heredocs are data: keep everything between the markers, leading indentation included
+        if kind == "heredoc_redirect" {
+            let mut children = node.walk();
+            let children: Vec<_> = node.children(&mut children).collect();
+            let start = children.iter().find(|c| c.kind() == "heredoc_start");
+            let end = children.iter().find(|c| c.kind() == "heredoc_end");
+            if let (Some(start), Some(end)) = (start, end) {
+                ranges.push((start.end_byte(), end.start_byte()));
+            }
+        }
+        if cursor.goto_first_child() {
+            collect_string_ranges_sh(cursor, ranges);
+            cursor.goto_parent();
+        }
+        if !cursor.goto_next_sibling() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SH_FIXTURE: &str = include_str!("../../test_fixtures/sample.sh");
+
+    #[test]
+    fn test_extract_ast_info() {
+        let parser = ShellParser::new();
+        let info = parser
+            .extract_ast_info(SH_FIXTURE, Path::new("deploy.sh"))
+            .unwrap();
+
+        let exports: Vec<_> = info.exports.iter().map(|e| &e.name[..]).collect();
+        assert_eq!(exports, vec!["deploy", "rollback", "main"]);
+
+        let imports: Vec<_> = info.imports.iter().map(|i| &i.from[..]).collect();
+        assert_eq!(imports, vec!["./lib/common.sh", "./lib/log.sh"]);
+    }
+
+    #[test]
+    fn test_extract_calls() {
+        let parser = ShellParser::new();
+        let info = parser
+            .extract_ast_info(SH_FIXTURE, Path::new("deploy.sh"))
+            .unwrap();
+
+        let calls: Vec<_> = info
+            .calls
+            .iter()
+            .map(|c| (&c.target[..], &c.method[..]))
+            .collect();
+        assert_eq!(
+            calls,
+            vec![
+                ("dirname", "dirname"),
+                ("log_info", "log_info"),
+                ("kubectl", "apply"),
+                ("kubectl", "rollout"),
+                ("curl", "curl"),
+                ("jq", "jq"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_signatures() {
+        let parser = ShellParser::new();
+        let info = parser
+            .extract_ast_info(SH_FIXTURE, Path::new("deploy.sh"))
+            .unwrap();
+
+        let sigs: Vec<_> = info
+            .signatures
+            .iter()
+            .map(|s| (&s.name[..], &s.signature[..], s.start_line, s.end_line))
+            .collect();
+        assert_eq!(
+            sigs,
+            vec![
+                ("deploy", "(service, version)", 21, 28),
+                ("rollback", "(service)", 30, 33),
+                ("main", "($1, $2, $3)", 39, 45),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_source_paths() {
+        assert_eq!(script_relative("lib.sh").as_deref(), Some("./lib.sh"));
+        assert_eq!(script_relative("../lib.sh").as_deref(), Some("../lib.sh"));
+        assert_eq!(
+            script_relative("/etc/profile").as_deref(),
+            Some("/etc/profile")
+        );
+        assert_eq!(script_relative("$DIR/lib.sh").as_deref(), Some("./lib.sh"));
+        assert_eq!(
+            script_relative("${BASH_SOURCE%/*}/lib/a.sh").as_deref(),
+            Some("./lib/a.sh")
+        );
+        assert_eq!(
+            script_relative("$(dirname \"$0\")/lib.sh").as_deref(),
+            Some("./lib.sh")
+        );
+        assert_eq!(script_relative(".env.sh").as_deref(), Some("./.env.sh"));
+        assert_eq!(script_relative("$HOME/.bashrc"), None);
+        assert_eq!(script_relative("$DIR/$ENV.sh"), None);
+        assert_eq!(script_relative("$CONFIG"), None);
+    }
+
+    #[test]
+    fn test_toon_comments() {
+        let parser = ShellParser::new();
+
+        let comments = parser.extract_toon_comments(SH_FIXTURE).unwrap();
+        let block = comments.file_block.unwrap();
+        assert_eq!(
+            block.purpose.unwrap(),
+            "Sample shell fixture for testing the luny shell parser. Deploys a service to the cluster."
+        );
+        assert_eq!(
+            block.gotchas,
+            Some(vec![
+                "Requires kubectl to point at the right context".to_string()
+            ])
+        );
+        assert_eq!(
+            comments.function_annotations["deploy"].invariants,
+            Some(vec!["never deploys an untagged image".to_string()])
+        );
+
+        let stripped = parser
+            .strip_toon_comments(SH_FIXTURE, "scripts/deploy.sh.toon")
+            .unwrap();
+        assert!(stripped.starts_with(
+            "#!/usr/bin/env bash\n# @dose -> scripts/deploy.sh.toon\n\nset -euo pipefail"
+        ));
+        assert!(stripped.contains("# @dose invariant: never deploys an untagged image"));
+    }
+
+    #[test]
+    fn test_string_ranges() {
+        let parser = ShellParser::new();
+        let source = "echo 'a  b' \"c  d\"\ncat <<EOF\n  indented\nEOF\n";
+        let ranges = parser.get_string_ranges(source).unwrap();
+        let texts: Vec<_> = ranges.iter().map(|(s, e)| &source[*s..*e]).collect();
+        assert_eq!(texts, vec!["'a  b'", "\"c  d\"", "\n  indented\n"]);
+    }
+}
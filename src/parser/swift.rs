@@ -0,0 +1,839 @@
+//! @dose
+//! purpose: This module parses Swift source files to extract public and open structs,
+//!     classes, actors, enums, protocols, extensions, functions, and computed properties.
+//!     It uses tree-sitter for robust parsing and respects Swift access levels.
+//!
+//! when-editing:
+//!     - !Only public and open declarations are exports; Swift's default access is internal
+//!     - !Members of a public extension default to public; members of other types do not
+//!     - Signatures are the source text between the name and the body, so labels, generic
+//!       constraints, async/throws, and where clauses come through as written
+//!
+//! invariants:
+//!     - private, fileprivate, and internal declarations are never exported
+//!     - Members of a non-exported type are never exported, even if marked public
+//!     - Every import declaration is captured, including @testable and kind-qualified imports
+//!
+//! do-not:
+//!     - Never treat private(set) as the declaration's access level; it only restricts the setter
+//!     - Never export stored properties of a type on their own; they appear in the type signature
+//!
+//! gotchas:
+//!     - tree-sitter-swift uses class_declaration for class, struct, enum, actor, and extension;
+//!       declaration_kind tells them apart
+//!     - Protocol requirements are implicitly public but, like Java, are not exported on their own
+//!     - Imports name modules, not files, so no calls are attributed
+//!
+//! flows:
+//!     - Parse: Create tree-sitter parser, set Swift language, parse source
+//!     - Collect: Walk types and extensions, tracking whether members default to public
+//!     - Extract imports: import M -> M [*]; import struct M.Type -> M [Type]
+//!     - Inline annotations: /// @dose field: value binds to the next declaration
+//!     - Extract signatures: Functions keep labels/generics/effects, members as Type.member;
+//!       types summarize public members
+
+use crate::parser::{nodes, toon_comment, LanguageParser, ParseError};
+use crate::types::{ASTInfo, ExportInfo, ExtractedComments, ImportInfo, SignatureInfo};
+use regex::Regex;
+use std::path::Path;
+use tree_sitter::{Node, Parser};
+
+/// Parser for Swift files
+#[derive(Clone)]
+pub struct SwiftParser;
+
+/// A public declaration found while walking the file
+struct Decl<'a> {
+    node: Node<'a>,
+    name: String,
+    kind: &'static str,
+    /// Enclosing type or extended type name, for members
+    owner: Option<String>,
+}
+
+impl SwiftParser {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn create_parser(&self) -> Result<Parser, ParseError> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_swift::LANGUAGE.into())
+            .map_err(|e| ParseError::ParseError(e.to_string()))?;
+        Ok(parser)
+    }
+
+    /// Public declarations in source order. `owner` names the enclosing type or extension;
+    /// `default_public` is set inside public extensions, where members without an access
+    /// modifier inherit public.
+    fn collect_decls<'a>(
+        &self,
+        node: Node<'a>,
+        source: &str,
+        owner: Option<&str>,
+        default_public: bool,
+        decls: &mut Vec<Decl<'a>>,
+    ) {
+        let in_type = owner.is_some();
+        let owner_name = owner.map(str::to_string);
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            let exported = match self.access_level(child, source) {
+                Some(level) => level == "public" || level == "open",
+                None => default_public,
+            };
+
+            match child.kind() {
+                "class_declaration" => {
+                    let Some(name_node) = child.child_by_field_name("name") else {
+                        continue;
+                    };
+                    let name = nodes::collapsed_text(name_node, source);
+                    let declaration_kind = self.declaration_kind(child, source);
+                    let is_extension = declaration_kind == "extension";
+                    if exported {
+                        decls.push(Decl {
+                            node: child,
+                            name: name.clone(),
+                            kind: type_kind(&declaration_kind),
+                            owner: owner_name.clone(),
+                        });
+                    }
+                    // Extensions are walked even when internal, since members can opt
+                    // into public; members of an internal type stay internal
+                    if exported || is_extension {
+                        if let Some(body) = child.child_by_field_name("body") {
+                            let members_public = is_extension && exported;
+                            self.collect_decls(body, source, Some(&name), members_public, decls);
+                        }
+                    }
+                }
+                "protocol_declaration" if exported => {
+                    if let Some(name_node) = child.child_by_field_name("name") {
+                        decls.push(Decl {
+                            node: child,
+                            name: self.node_text(name_node, source),
+                            kind: "protocol",
+                            owner: owner_name.clone(),
+                        });
+                    }
+                }
+                "function_declaration" if exported => {
+                    if let Some(name_node) = child.child_by_field_name("name") {
+                        decls.push(Decl {
+                            node: child,
+                            name: self.node_text(name_node, source),
+                            kind: if in_type { "method" } else { "fn" },
+                            owner: owner_name.clone(),
+                        });
+                    }
+                }
+                "property_declaration" if exported => {
+                    let computed = child.child_by_field_name("computed_value").is_some();
+                    // Stored properties of a type only appear in the type's signature
+                    if in_type && !computed {
+                        continue;
+                    }
+                    let kind = match (in_type, self.mutability(child, source).as_str()) {
+                        (true, _) => "property",
+                        (false, "let") => "let",
+                        (false, _) => "var",
+                    };
+                    if let Some(name) = self.property_name(child, source) {
+                        decls.push(Decl {
+                            node: child,
+                            name,
+                            kind,
+                            owner: owner_name.clone(),
+                        });
+                    }
+                }
+                "typealias_declaration" if exported => {
+                    if let Some(name_node) = child.child_by_field_name("name") {
+                        decls.push(Decl {
+                            node: child,
+                            name: self.node_text(name_node, source),
+                            kind: "type",
+                            owner: owner_name.clone(),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// The declaration's access level, ignoring setter-only modifiers like private(set)
+    fn access_level(&self, node: Node, source: &str) -> Option<String> {
+        let modifiers = nodes::child_of_kind(node, "modifiers")?;
+        let mut cursor = modifiers.walk();
+        let level = modifiers
+            .children(&mut cursor)
+            .filter(|m| m.kind() == "visibility_modifier")
+            .map(|m| self.node_text(m, source))
+            .find(|text| !text.contains('('));
+        level
+    }
+
+    fn has_modifier(&self, node: Node, source: &str, modifier: &str) -> bool {
+        nodes::child_of_kind(node, "modifiers").is_some_and(|mods| {
+            let mut cursor = mods.walk();
+            let found = mods
+                .children(&mut cursor)
+                .any(|m| self.node_text(m, source) == modifier);
+            found
+        })
+    }
+
+    /// class, struct, enum, actor, or extension
+    fn declaration_kind(&self, node: Node, source: &str) -> String {
+        node.child_by_field_name("declaration_kind")
+            .map(|k| self.node_text(k, source))
+            .unwrap_or_else(|| "class".to_string())
+    }
+
+    /// let or var
+    fn mutability(&self, node: Node, source: &str) -> String {
+        nodes::child_of_kind(node, "value_binding_pattern")
+            .and_then(|p| p.child_by_field_name("mutability"))
+            .map(|m| self.node_text(m, source))
+            .unwrap_or_else(|| "var".to_string())
+    }
+
+    fn property_name(&self, node: Node, source: &str) -> Option<String> {
+        node.child_by_field_name("name")
+            .map(|pattern| nodes::collapsed_text(pattern, source))
+    }
+
+    fn extract_imports(&self, root: Node, source: &str) -> Vec<ImportInfo> {
+        let mut cursor = root.walk();
+        let imports = root
+            .named_children(&mut cursor)
+            .filter(|child| child.kind() == "import_declaration")
+            .filter_map(|child| self.parse_import(child, source))
+            .collect();
+        imports
+    }
+
+    /// import Foundation           -> from Foundation, items [*]
+    /// import struct SwiftUI.Color -> from SwiftUI, items [Color]
+    fn parse_import(&self, node: Node, source: &str) -> Option<ImportInfo> {
+        let path = self.node_text(nodes::child_of_kind(node, "identifier")?, source);
+        let has_kind = (0..node.child_count())
+            .filter_map(|i| node.child(i))
+            .any(|c| {
+                matches!(
+                    c.kind(),
+                    "struct" | "class" | "enum" | "protocol" | "func" | "var" | "let" | "typealias"
+                )
+            });
+
+        match (has_kind, path.rsplit_once('.')) {
+            (true, Some((module, item))) => Some(ImportInfo {
+                from: module.to_string(),
+                items: vec![item.to_string()],
+            }),
+            _ => Some(ImportInfo {
+                from: path,
+                items: vec!["*".to_string()],
+            }),
+        }
+    }
+
+    fn extract_signatures(&self, decls: &[Decl], source: &str) -> Vec<SignatureInfo> {
+        decls
+            .iter()
+            .filter_map(|decl| {
+                let node = decl.node;
+                let (kind, signature) = match node.kind() {
+                    "function_declaration" => {
+                        let is_static = self.has_modifier(node, source, "static")
+                            || self.has_modifier(node, source, "class");
+                        let kind = match decl.kind {
+                            "method" if is_static => "static method",
+                            kind => kind,
+                        };
+                        let name_node = node.child_by_field_name("name")?;
+                        (
+                            kind,
+                            format!(
+                                "{}{}",
+                                self.attributes(node, source),
+                                self.header(node, name_node, source)
+                            ),
+                        )
+                    }
+                    "class_declaration" | "protocol_declaration" => {
+                        (decl.kind, self.type_signature(node, decl.kind, source))
+                    }
+                    // : TimeInterval
+                    "property_declaration" => {
+                        let ty = nodes::child_of_kind(node, "type_annotation")?;
+                        (decl.kind, nodes::collapsed_text(ty, source))
+                    }
+                    // = [String: UserConfig]
+                    "typealias_declaration" => {
+                        let name_node = node.child_by_field_name("name")?;
+                        (decl.kind, self.header(node, name_node, source))
+                    }
+                    _ => return None,
+                };
+                // Members are Owner.name, as the fn: annotations name them; nested types
+                // keep their own name
+                let name = match node.kind() {
+                    "class_declaration" | "protocol_declaration" => decl.name.clone(),
+                    _ => nodes::qualify(decl.owner.clone(), decl.name.clone()),
+                };
+                Some(SignatureInfo {
+                    name,
+                    kind: kind.to_string(),
+                    signature,
+                    start_line: node.start_position().row + 1,
+                    end_line: node.end_position().row + 1,
+                })
+            })
+            .collect()
+    }
+
+    /// `<Store: Repository>: NSObject where Store.Entity == UserConfig { init(store: Store); ... }`
+    fn type_signature(&self, node: Node, kind: &str, source: &str) -> String {
+        let header = node
+            .child_by_field_name("name")
+            .map(|name| self.header(node, name, source))
+            .unwrap_or_default();
+
+        let mut members = Vec::new();
+        if let Some(body) = node.child_by_field_name("body") {
+            // Protocol requirements are as visible as the protocol; public extension
+            // members default to public
+            let default_public = kind == "protocol"
+                || (kind == "extension"
+                    && matches!(
+                        self.access_level(node, source).as_deref(),
+                        Some("public" | "open")
+                    ));
+            let mut cursor = body.walk();
+            for child in body.named_children(&mut cursor) {
+                if kind == "enum" && child.kind() == "enum_entry" {
+                    members.push(self.enum_entry(child, source));
+                } else {
+                    let public = match self.access_level(child, source) {
+                        Some(level) => level == "public" || level == "open",
+                        None => default_public,
+                    };
+                    if public {
+                        if let Some(member) = self.member_summary(child, source) {
+                            members.push(member);
+                        }
+                    }
+                }
+                if members.len() >= 5 {
+                    members.truncate(5);
+                    members.push("...".to_string());
+                    break;
+                }
+            }
+        }
+
+        let separator = if kind == "enum" { " | " } else { "; " };
+        let body = if members.is_empty() {
+            String::new()
+        } else {
+            format!(" {{ {} }}", members.join(separator))
+        };
+        format!("{}{}{}", self.attributes(node, source), header, body)
+            .trim()
+            .to_string()
+    }
+
+    /// `pending(reason: String)` or `active`
+    fn enum_entry(&self, node: Node, source: &str) -> String {
+        let name = node
+            .child_by_field_name("name")
+            .map(|n| self.node_text(n, source))
+            .unwrap_or_default();
+        let data = node
+            .child_by_field_name("data_contents")
+            .map(|d| nodes::collapsed_text(d, source))
+            .unwrap_or_default();
+        format!("{}{}", name, data)
+    }
+
+    fn member_summary(&self, node: Node, source: &str) -> Option<String> {
+        match node.kind() {
+            "function_declaration" | "protocol_function_declaration" => {
+                let name_node = node.child_by_field_name("name")?;
+                let prefix = if self.has_modifier(node, source, "static")
+                    || self.has_modifier(node, source, "class")
+                {
+                    "static "
+                } else {
+                    ""
+                };
+                Some(format!(
+                    "{}{}{}",
+                    prefix,
+                    self.node_text(name_node, source),
+                    self.header(node, name_node, source)
+                ))
+            }
+            "init_declaration" | "subscript_declaration" => {
+                let keyword = nodes::child_of_kind(node, "init")
+                    .or_else(|| nodes::child_of_kind(node, "subscript"))?;
+                Some(format!(
+                    "{}{}",
+                    self.node_text(keyword, source),
+                    self.header(node, keyword, source)
+                ))
+            }
+            // var name: String
+            "property_declaration" => {
+                let name = self.property_name(node, source)?;
+                let ty = nodes::child_of_kind(node, "type_annotation")
+                    .map(|t| nodes::collapsed_text(t, source))
+                    .unwrap_or_default();
+                Some(format!("{} {}{}", self.mutability(node, source), name, ty))
+            }
+            "protocol_property_declaration" | "associatedtype_declaration" => {
+                Some(nodes::collapsed_text(node, source))
+            }
+            _ => None,
+        }
+    }
+
+    /// Source text between `after` and the declaration's body: parameters with labels,
+    /// generic clauses, effects, return type, and where clauses exactly as written
+    fn header(&self, node: Node, after: Node, source: &str) -> String {
+        let end = node
+            .child_by_field_name("body")
+            .or_else(|| nodes::child_of_kind(node, "computed_property"))
+            .map(|b| b.start_byte())
+            .unwrap_or_else(|| node.end_byte());
+        let text = &source[after.end_byte()..end.max(after.end_byte())];
+        nodes::collapse(text)
+    }
+
+    /// Attributes such as `@discardableResult ` or `@MainActor `
+    fn attributes(&self, node: Node, source: &str) -> String {
+        let Some(mods) = nodes::child_of_kind(node, "modifiers") else {
+            return String::new();
+        };
+        let mut cursor = mods.walk();
+        let attributes = mods
+            .children(&mut cursor)
+            .filter(|child| child.kind() == "attribute")
+            .map(|child| format!("{} ", nodes::collapsed_text(child, source)))
+            .collect();
+        attributes
+    }
+
+    fn node_text(&self, node: Node, source: &str) -> String {
+        source[node.start_byte()..node.end_byte()].to_string()
+    }
+}
+
+/// Export kind for a class_declaration's declaration_kind
+fn type_kind(declaration_kind: &str) -> &'static str {
+    match declaration_kind {
+        "struct" => "struct",
+        "enum" => "enum",
+        "actor" => "actor",
+        "extension" => "extension",
+        _ => "class",
+    }
+}
+
+impl Default for SwiftParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguageParser for SwiftParser {
+    fn language_name(&self) -> &'static str {
+        "swift"
+    }
+
+    fn file_extensions(&self) -> &[&'static str] {
+        &["swift"]
+    }
+
+    fn extract_ast_info(&self, source: &str, _file_path: &Path) -> Result<ASTInfo, ParseError> {
+        let mut parser = self.create_parser()?;
+        let tree = parser
+            .parse(source, None)
+            .ok_or_else(|| ParseError::ParseError("Failed to parse source".to_string()))?;
+
+        let root = tree.root_node();
+
+        let mut decls = Vec::new();
+        self.collect_decls(root, source, None, false, &mut decls);
+
+        let exports = decls
+            .iter()
+            .map(|decl| ExportInfo {
+                name: decl.name.clone(),
+                kind: decl.kind.to_string(),
+            })
+            .collect();
+        let imports = self.extract_imports(root, source);
+        let signatures = self.extract_signatures(&decls, source);
+
+        let tokens = super::tokens::count_tokens(source);
+
+        Ok(ASTInfo {
+            tokens,
+            exports,
+            imports,
+            calls: Vec::new(),
+            signatures,
+        })
+    }
+
+    fn extract_toon_comments(&self, source: &str) -> Result<ExtractedComments, ParseError> {
+        let mut result = ExtractedComments::default();
+
+        // Find @dose in /** */ block comments
+        let block_pattern = Regex::new(r"/\*\*[\s\S]*?@dose[\s\S]*?\*/").unwrap();
+        let file_block = block_pattern
+            .find_iter(source)
+            .find(|m| toon_comment::parse_inline_annotation(m.as_str()).is_none());
+
+        if let Some(mat) = file_block {
+            let comment = mat.as_str();
+            let content = comment.trim_start_matches("/**").trim_end_matches("*/");
+
+            let content = content
+                .lines()
+                .map(|line| {
+                    let trimmed = line.trim().trim_start_matches('*').trim();
+                    if trimmed == "@dose" {
+                        ""
+                    } else {
+                        trimmed
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            result.file_block = Some(toon_comment::parse_toon_block(&content));
+        }
+
+        // Also check for /// @dose doc comments
+        if result.file_block.is_none() {
+            let doc_pattern = Regex::new(r"///\s*@dose\s*\n((?:\s*///[^\n]*\n)*)").unwrap();
+            if let Some(caps) = doc_pattern.captures(source) {
+                if let Some(block) = caps.get(1) {
+                    let content = block
+                        .as_str()
+                        .lines()
+                        .map(|line| line.trim().trim_start_matches('/').trim_end())
+                        .map(|line| line.strip_prefix(' ').unwrap_or(line))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    result.file_block = Some(toon_comment::parse_toon_block(&content));
+                }
+            }
+        }
+
+        // Inline /// @dose invariant: value annotations on the following declaration
+        let mut parser = self.create_parser()?;
+        if let Some(tree) = parser.parse(source, None) {
+            result.function_annotations = toon_comment::collect_inline_annotations(
+                tree.root_node(),
+                source,
+                &["comment", "multiline_comment"],
+                |node| match node.kind() {
+                    "function_declaration"
+                    | "protocol_function_declaration"
+                    | "class_declaration"
+                    | "protocol_declaration"
+                    | "typealias_declaration" => node
+                        .child_by_field_name("name")
+                        .map(|n| nodes::collapsed_text(n, source)),
+                    "property_declaration" => self.property_name(node, source),
+                    "init_declaration" => Some("init".to_string()),
+                    _ => None,
+                },
+                |node| match node.kind() {
+                    "class_declaration" | "protocol_declaration" => node
+                        .child_by_field_name("name")
+                        .map(|n| nodes::collapsed_text(n, source)),
+                    _ => None,
+                },
+            );
+        }
+
+        Ok(result)
+    }
+
+    fn strip_toon_comments(&self, source: &str, toon_path: &str) -> Result<String, ParseError> {
+        let mut result = source.to_string();
+
+        // Replace /** */ @dose blocks
+        let block_pattern = Regex::new(r"/\*\*[\s\S]*?@dose[\s\S]*?\*/").unwrap();
+        result = block_pattern
+            .replace_all(&result, &format!("// @dose -> {}", toon_path))
+            .to_string();
+
+        // Remove /// @dose comment blocks
+        let doc_pattern = Regex::new(r"///\s*@dose[^\n]*\n(?:///[^\n]*\n)*").unwrap();
+        result = doc_pattern
+            .replace_all(&result, &format!("// @dose -> {}\n", toon_path))
+            .to_string();
+
+        Ok(result)
+    }
+
+    fn get_string_ranges(&self, source: &str) -> Result<Vec<(usize, usize)>, ParseError> {
+        let mut parser = self.create_parser()?;
+
+        let tree = parser
+            .parse(source, None)
+            .ok_or_else(|| ParseError::ParseError("Failed to parse source".to_string()))?;
+
+        let mut ranges = Vec::new();
+        collect_string_ranges_swift(&mut tree.walk(), &mut ranges);
+        Ok(ranges)
+    }
+}
+
+fn collect_string_ranges_swift(
+    cursor: &mut tree_sitter::TreeCursor,
+    ranges: &mut Vec<(usize, usize)>,
+) {
+    loop {
+        let node = cursor.node();
+        let kind = node.kind();
+        // Swift has "line", """multi-line""", and #"raw"# string literals
+        if matches!(
+            kind,
+            "line_string_literal" | "multi_line_string_literal" | "raw_string_literal"
+        ) {
+            ranges.push((node.start_byte(), node.end_byte()));
+        } else if cursor.goto_first_child() {
+            collect_string_ranges_swift(cursor, ranges);
+            cursor.goto_parent();
+        }
+        if !cursor.goto_next_sibling() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SWIFT_FIXTURE: &str = include_str!("../../test_fixtures/sample.swift");
+
+    #[test]
+    fn test_extract_ast_info() {
+        let parser = SwiftParser::new();
+        let info = parser
+            .extract_ast_info(SWIFT_FIXTURE, Path::new("sample.swift"))
+            .unwrap();
+
+        let mut exports: Vec<_> = info
+            .exports
+            .iter()
+            .map(|e| (&e.name[..], &e.kind[..]))
+            .collect();
+        exports.sort();
+        assert_eq!(
+            exports,
+            vec![
+                ("Array", "extension"),
+                ("Repository", "protocol"),
+                ("UserConfig", "struct"),
+                ("UserMap", "type"),
+                ("UserService", "class"),
+                ("UserStatus", "enum"),
+                ("defaultTimeout", "let"),
+                ("displayName", "property"),
+                ("loadAll", "fn"),
+                ("save", "method"),
+                ("slugified", "method"),
+                ("sortedByName", "method"),
+                ("user", "method"),
+            ]
+        );
+
+        let imports: Vec<_> = info
+            .imports
+            .iter()
+            .map(|i| (&i.from[..], i.items.join("|")))
+            .collect();
+        assert_eq!(
+            imports,
+            vec![
+                ("Foundation", "*".to_string()),
+                ("Combine", "*".to_string()),
+                ("CoreModels", "*".to_string()),
+                ("SwiftUI", "Color".to_string()),
+            ]
+        );
+        assert!(info.calls.is_empty());
+    }
+
+    #[test]
+    fn test_extract_signatures() {
+        let parser = SwiftParser::new();
+        let info = parser
+            .extract_ast_info(SWIFT_FIXTURE, Path::new("sample.swift"))
+            .unwrap();
+
+        let sig = |name: &str| {
+            info.signatures
+                .iter()
+                .find(|s| s.name == name)
+                .unwrap_or_else(|| panic!("missing signature for {}", name))
+        };
+
+        let user = sig("UserService.user");
+        assert_eq!(user.kind, "method");
+        assert_eq!(
+            user.signature,
+            "(withId id: String) async throws -> UserConfig?"
+        );
+        assert_eq!((user.start_line, user.end_line), (62, 67));
+
+        assert_eq!(
+            sig("UserService.save").signature,
+            "@discardableResult (_ user: UserConfig, overwrite: Bool = false) async throws -> Bool"
+        );
+
+        let load_all = sig("loadAll");
+        assert_eq!(load_all.kind, "fn");
+        assert_eq!(
+            load_all.signature,
+            "<S: Sequence>(_ ids: S, from service: UserService<InMemoryStore>) async -> [UserConfig] where S.Element == String"
+        );
+
+        let service = sig("UserService");
+        assert_eq!(service.kind, "class");
+        assert_eq!(
+            service.signature,
+            "<Store: Repository>: NSObject where Store.Entity == UserConfig { init(store: Store); user(withId id: String) async throws -> UserConfig?; save(_ user: UserConfig, overwrite: Bool = false) async throws -> Bool }"
+        );
+        assert_eq!((service.start_line, service.end_line), (54, 84));
+
+        assert_eq!(
+            sig("UserConfig").signature,
+            ": Codable, Equatable { let id: String; var name: String; var displayName: String; init(id: String, name: String) }"
+        );
+        assert_eq!(
+            sig("UserStatus").signature,
+            ": String, Codable { active | inactive | pending(reason: String) }"
+        );
+        assert_eq!(
+            sig("Repository").signature,
+            "{ associatedtype Entity; find(byId id: String) async throws -> Entity?; save(_ entity: Entity) async throws }"
+        );
+        assert_eq!(
+            sig("Array").signature,
+            "where Element == UserConfig { sortedByName() -> [UserConfig] }"
+        );
+        assert_eq!(sig("UserConfig.displayName").signature, ": String");
+        assert_eq!(sig("defaultTimeout").signature, ": TimeInterval");
+        assert_eq!(sig("UserMap").signature, "= [String: UserConfig]");
+    }
+
+    #[test]
+    fn test_access_levels() {
+        let parser = SwiftParser::new();
+        let source = r#"
+public actor Cache {
+    public static func make() -> Cache { Cache() }
+    public private(set) var count: Int = 0
+    public class var shared: Int { 1 }
+    fileprivate func reset() {}
+    public struct Entry {}
+}
+
+struct Internal {
+    public func leaked() {}
+}
+"#;
+        let info = parser
+            .extract_ast_info(source, Path::new("Cache.swift"))
+            .unwrap();
+
+        let exports: Vec<_> = info
+            .exports
+            .iter()
+            .map(|e| (&e.name[..], &e.kind[..]))
+            .collect();
+        assert_eq!(
+            exports,
+            vec![
+                ("Cache", "actor"),
+                ("make", "method"),
+                ("shared", "property"),
+                ("Entry", "struct"),
+            ]
+        );
+
+        let make = info
+            .signatures
+            .iter()
+            .find(|s| s.name == "Cache.make")
+            .unwrap();
+        assert_eq!(make.kind, "static method");
+        let cache = info.signatures.iter().find(|s| s.name == "Cache").unwrap();
+        assert_eq!(
+            cache.signature,
+            "{ static make() -> Cache; var count: Int; var shared: Int }"
+        );
+    }
+
+    #[test]
+    fn test_inline_annotations() {
+        let parser = SwiftParser::new();
+        let source = r#"/// @dose
+/// purpose: Orders
+///
+/// gotchas:
+///     - Totals are in cents
+
+public struct OrderService {
+    /// @dose invariant: total is never negative
+    public func total(_ order: Order) -> Int { 0 }
+}
+"#;
+        let comments = parser.extract_toon_comments(source).unwrap();
+        let block = comments.file_block.unwrap();
+        assert_eq!(block.purpose.unwrap(), "Orders");
+        assert_eq!(block.gotchas, Some(vec!["Totals are in cents".to_string()]));
+        assert_eq!(
+            comments.function_annotations["OrderService.total"].invariants,
+            Some(vec!["total is never negative".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_toon_comments() {
+        let parser = SwiftParser::new();
+        let comments = parser.extract_toon_comments(SWIFT_FIXTURE).unwrap();
+        let block = comments.file_block.unwrap();
+        assert_eq!(block.purpose.unwrap(), "Sample Swift fixture for testing the luny Swift parser. This file contains structs, classes, enums, protocols, and extensions to verify extraction works correctly.");
+
+        let stripped = parser
+            .strip_toon_comments(SWIFT_FIXTURE, "sample.swift.toon")
+            .unwrap();
+        assert!(stripped.starts_with("// @dose -> sample.swift.toon"));
+    }
+
+    #[test]
+    fn test_string_ranges() {
+        let parser = SwiftParser::new();
+        let source = "let a = \"a b\"\nlet r = #\"raw  x\"#\nlet m = \"\"\"\n  multi\n  \"\"\"\n";
+        let ranges = parser.get_string_ranges(source).unwrap();
+        let texts: Vec<_> = ranges.iter().map(|(s, e)| &source[*s..*e]).collect();
+        assert_eq!(
+            texts,
+            vec!["\"a b\"", "#\"raw  x\"#", "\"\"\"\n  multi\n  \"\"\""]
+        );
+    }
+}
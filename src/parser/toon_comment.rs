@@ -3,7 +3,18 @@
 //! This module extracts the common parsing code that was duplicated across
 //! TypeScript, Python, Ruby, C#, Go, and Rust parsers.
 
-use crate::types::{ToonCommentBlock, WhenEditingItem};
+use crate::types::{FunctionAnnotation, ToonCommentBlock, WhenEditingItem};
+use std::collections::HashMap;
+use tree_sitter::Node;
+
+/// Fields accepted in single-line `@dose field: value` annotations.
+const INLINE_FIELDS: &[&str] = &[
+    "invariant",
+    "gotcha",
+    "do-not",
+    "constraint",
+    "error-handling",
+];
 
 /// Normalize a section name: lowercase, spaces to dashes, strip trailing colon.
 fn normalize_section(s: &str) -> String {
@@ -112,7 +123,7 @@ pub fn save_section(block: &mut ToonCommentBlock, section: Option<&str>, items:
     };
 
     // Normalize: already lowercase with dashes, just handle singular/plural
-    let normalized = section.trim_end_matches('s');
+    let normalized = singular(section);
 
     match normalized {
         "purpose" => {
@@ -148,6 +159,166 @@ pub fn save_section(block: &mut ToonCommentBlock, section: Option<&str>, items:
     }
 }
 
+/// Parse a single-line inline annotation from raw comment text, delimiters included:
+/// `/// @dose invariant: x`, `# @dose gotcha: y`, `/** @dose do-not: z */`.
+/// Returns the singular field name and its value; multi-line blocks return None.
+pub fn parse_inline_annotation(comment: &str) -> Option<(String, String)> {
+    let comment = comment.trim();
+    if comment.contains('\n') {
+        return None;
+    }
+
+    let rest = comment[comment.find("@dose")? + "@dose".len()..].trim();
+    let rest = rest.trim_end_matches("*/").trim();
+    let (field, value) = rest.split_once(':')?;
+
+    // Accept plural spellings (invariants:, gotchas:) as well
+    let field = field.trim().to_lowercase();
+    let field = singular(&field);
+    let value = value.trim();
+
+    if !INLINE_FIELDS.contains(&field) || value.is_empty() {
+        return None;
+    }
+
+    Some((field.to_string(), value.to_string()))
+}
+
+/// Drop one plural 's' (gotchas -> gotcha); only one, so a doubled 's' is not a field name
+fn singular(field: &str) -> &str {
+    field.strip_suffix('s').unwrap_or(field)
+}
+
+/// Add an inline annotation value to the entry for `name`, creating it if needed.
+pub fn add_function_annotation(
+    annotations: &mut HashMap<String, FunctionAnnotation>,
+    name: &str,
+    field: &str,
+    value: String,
+) {
+    let annotation = annotations
+        .entry(name.to_string())
+        .or_insert_with(|| FunctionAnnotation {
+            name: name.to_string(),
+            invariants: None,
+            gotchas: None,
+            do_not: None,
+            error_handling: None,
+            constraints: None,
+        });
+
+    let list = match field {
+        "invariant" => &mut annotation.invariants,
+        "gotcha" => &mut annotation.gotchas,
+        "do-not" => &mut annotation.do_not,
+        "constraint" => &mut annotation.constraints,
+        "error-handling" => &mut annotation.error_handling,
+        _ => return,
+    };
+    list.get_or_insert_with(Vec::new).push(value);
+}
+
+/// Collect inline annotations from comment nodes and bind each to the first
+/// declaration that starts after the comment, as named by `declaration_name`.
+///
+/// Binding by position rather than by sibling keeps this grammar-agnostic:
+/// attributes, decorators, and grammars that hoist leading comments out of
+/// the block (tree-sitter-python) all work the same way.
+///
+/// Members are named `Owner.name` after the nearest enclosing type, as named by
+/// `owner_name` (classes, structs, traits, impls, modules that hold functions), so
+/// `get` on two types does not collide. Declarations nested in a function keep
+/// their bare name.
+pub fn collect_inline_annotations<F, G>(
+    root: Node,
+    source: &str,
+    comment_kinds: &[&str],
+    declaration_name: F,
+    owner_name: G,
+) -> HashMap<String, FunctionAnnotation>
+where
+    F: Fn(Node) -> Option<String>,
+    G: Fn(Node) -> Option<String>,
+{
+    let mut comments = Vec::new();
+    collect_comment_nodes(root, comment_kinds, &mut comments);
+
+    let mut annotations = HashMap::new();
+    for comment in comments {
+        let text = &source[comment.start_byte()..comment.end_byte()];
+        let Some((field, value)) = parse_inline_annotation(text) else {
+            continue;
+        };
+        if let Some((node, name)) =
+            find_declaration_after(root, comment.end_byte(), &declaration_name)
+        {
+            let name = match enclosing_owner(node, &declaration_name, &owner_name) {
+                Some(owner) => format!("{}.{}", owner, name),
+                None => name,
+            };
+            add_function_annotation(&mut annotations, &name, &field, value);
+        }
+    }
+    annotations
+}
+
+/// Name of the type a declaration belongs to: the nearest ancestor that is an owner,
+/// unless a non-owner declaration (a function) comes first
+fn enclosing_owner<F, G>(node: Node, declaration_name: &F, owner_name: &G) -> Option<String>
+where
+    F: Fn(Node) -> Option<String>,
+    G: Fn(Node) -> Option<String>,
+{
+    let mut ancestor = node.parent();
+    while let Some(node) = ancestor {
+        if let Some(owner) = owner_name(node) {
+            return Some(owner);
+        }
+        if declaration_name(node).is_some() {
+            return None;
+        }
+        ancestor = node.parent();
+    }
+    None
+}
+
+fn collect_comment_nodes<'a>(node: Node<'a>, kinds: &[&str], out: &mut Vec<Node<'a>>) {
+    if kinds.contains(&node.kind()) {
+        out.push(node);
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_comment_nodes(child, kinds, out);
+    }
+}
+
+fn find_declaration_after<'a, F>(
+    node: Node<'a>,
+    pos: usize,
+    declaration_name: &F,
+) -> Option<(Node<'a>, String)>
+where
+    F: Fn(Node) -> Option<String>,
+{
+    // Subtrees that end before the comment cannot contain the declaration
+    if node.end_byte() <= pos {
+        return None;
+    }
+    if node.start_byte() >= pos {
+        if let Some(name) = declaration_name(node) {
+            return Some((node, name));
+        }
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if let Some(found) = find_declaration_after(child, pos, declaration_name) {
+            return Some(found);
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -183,4 +354,27 @@ invariant: Must hold
         assert_eq!(block.do_not, Some(vec!["Never do this".to_string()]));
         assert_eq!(block.invariants, Some(vec!["Must hold".to_string()]));
     }
+
+    #[test]
+    fn test_parse_inline_annotation() {
+        assert_eq!(
+            parse_inline_annotation("/// @dose invariant: never empty"),
+            Some(("invariant".to_string(), "never empty".to_string()))
+        );
+        assert_eq!(
+            parse_inline_annotation("# @dose gotchas: blocks the loop\n"),
+            Some(("gotcha".to_string(), "blocks the loop".to_string()))
+        );
+        assert_eq!(
+            parse_inline_annotation("/** @dose do-not: call twice */"),
+            Some(("do-not".to_string(), "call twice".to_string()))
+        );
+        // File-level blocks and unknown fields are not inline annotations
+        assert_eq!(parse_inline_annotation("//! @dose"), None);
+        assert_eq!(parse_inline_annotation("// @dose purpose: Module"), None);
+        assert_eq!(parse_inline_annotation("/* @dose\ninvariant: x\n*/"), None);
+        // Only one trailing 's' is a plural
+        assert_eq!(parse_inline_annotation("// @dose gotchass: x"), None);
+        assert_eq!(parse_inline_annotation("// @dose invariantsss: x"), None);
+    }
 }
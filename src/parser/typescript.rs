@@ -33,9 +33,7 @@
 //!     - Extract signatures: For each export, build signature from params and return type
 
 use crate::parser::{toon_comment, LanguageParser, ParseError};
-use crate::types::{
-    ASTInfo, CallInfo, ExportInfo, ExtractedComments, FunctionAnnotation, ImportInfo, SignatureInfo,
-};
+use crate::types::{ASTInfo, CallInfo, ExportInfo, ExtractedComments, ImportInfo, SignatureInfo};
 use regex::Regex;
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
@@ -1024,91 +1022,41 @@ impl TypeScriptParser {
         source[node.start_byte()..node.end_byte()].to_string()
     }
 
-    /// Find the name of the next function/export declaration after a given byte position
-    fn find_next_function_name(
-        &self,
-        root: &Node,
-        source: &str,
-        after_pos: usize,
-    ) -> Option<String> {
-        let mut cursor = root.walk();
-        self.find_next_function_recursive(&mut cursor, source, after_pos)
-    }
-
-    fn find_next_function_recursive(
-        &self,
-        cursor: &mut tree_sitter::TreeCursor,
-        source: &str,
-        after_pos: usize,
-    ) -> Option<String> {
-        loop {
-            let node = cursor.node();
-
-            // Check if this node starts after our position and is a function/export
-            if node.start_byte() >= after_pos {
-                let kind = node.kind();
-                if kind == "function_declaration"
-                    || kind == "export_statement"
-                    || kind == "lexical_declaration"
-                {
-                    // Extract the function/variable name
-                    return self.extract_declaration_name(&node, source);
-                }
-            }
-
-            // Recurse into children
-            if cursor.goto_first_child() {
-                if let Some(name) = self.find_next_function_recursive(cursor, source, after_pos) {
-                    return Some(name);
-                }
-                cursor.goto_parent();
-            }
-
-            if !cursor.goto_next_sibling() {
-                return None;
-            }
+    /// Name of a declaration that inline annotations bind to
+    fn declaration_name(&self, node: Node, source: &str) -> Option<String> {
+        match node.kind() {
+            "function_declaration"
+            | "generator_function_declaration"
+            | "class_declaration"
+            | "abstract_class_declaration"
+            | "interface_declaration"
+            | "type_alias_declaration"
+            | "enum_declaration"
+            | "method_definition"
+            | "method_signature"
+            | "abstract_method_signature" => node
+                .child_by_field_name("name")
+                .map(|n| self.node_text(n, source)),
+            // const handler = () => ...; destructuring patterns have no single name
+            "variable_declarator" => node
+                .child_by_field_name("name")
+                .filter(|n| n.kind() == "identifier")
+                .map(|n| self.node_text(n, source)),
+            _ => None,
         }
     }
 
-    fn extract_declaration_name(&self, node: &Node, source: &str) -> Option<String> {
-        let kind = node.kind();
-
-        if kind == "function_declaration" {
-            // Look for the identifier child
-            for i in 0..node.child_count() {
-                if let Some(child) = node.child(i) {
-                    if child.kind() == "identifier" {
-                        return Some(self.node_text(child, source));
-                    }
-                }
-            }
-        } else if kind == "export_statement" {
-            // Look for function_declaration or variable_declaration inside
-            for i in 0..node.child_count() {
-                if let Some(child) = node.child(i) {
-                    if let Some(name) = self.extract_declaration_name(&child, source) {
-                        return Some(name);
-                    }
-                }
-            }
-        } else if kind == "lexical_declaration" {
-            // Look for variable_declarator -> identifier
-            for i in 0..node.child_count() {
-                if let Some(child) = node.child(i) {
-                    if child.kind() == "variable_declarator" {
-                        for j in 0..child.child_count() {
-                            if let Some(grandchild) = child.child(j) {
-                                if grandchild.kind() == "identifier" {
-                                    return Some(self.node_text(grandchild, source));
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+    /// Class or interface name, for qualifying members as Class.method
+    fn owner_name(&self, node: Node, source: &str) -> Option<String> {
+        match node.kind() {
+            "class_declaration"
+            | "abstract_class_declaration"
+            | "class"
+            | "interface_declaration" => node
+                .child_by_field_name("name")
+                .map(|n| self.node_text(n, source)),
+            _ => None,
         }
-
-        None
     }
 }
 
@@ -1194,84 +1142,16 @@ impl LanguageParser for TypeScriptParser {
             }
         }
 
-        // Find inline @dose comments (single-line annotations for specific functions)
-        // Pattern matches: /** @dose field: value */ or // @dose field: value
-        let inline_pattern = Regex::new(
-            r"(?:/\*\*\s*@dose\s+(invariant|gotcha|do-not|constraint|error-handling):\s*([^*]+)\s*\*/|//\s*@dose\s+(invariant|gotcha|do-not|constraint|error-handling):\s*(.+))"
-        ).unwrap();
-
-        // Collect all inline annotations with their positions
-        let mut inline_annotations: Vec<(usize, String, String)> = Vec::new();
-        for cap in inline_pattern.captures_iter(source) {
-            let pos = cap.get(0).unwrap().end();
-            let (field, value) = if let Some(f) = cap.get(1) {
-                (
-                    f.as_str().to_string(),
-                    cap.get(2).unwrap().as_str().trim().to_string(),
-                )
-            } else {
-                (
-                    cap.get(3).unwrap().as_str().to_string(),
-                    cap.get(4).unwrap().as_str().trim().to_string(),
-                )
-            };
-            inline_annotations.push((pos, field, value));
-        }
-
-        if !inline_annotations.is_empty() {
-            // Parse source with tree-sitter to find function names
-            let mut parser = Parser::new();
-            let language = tree_sitter_typescript::LANGUAGE_TYPESCRIPT;
-            parser.set_language(&language.into()).ok();
-
-            if let Some(tree) = parser.parse(source, None) {
-                let root = tree.root_node();
-
-                for (pos, field, value) in inline_annotations {
-                    // Find the next function/export after this position
-                    if let Some(func_name) = self.find_next_function_name(&root, source, pos) {
-                        let annotation = result
-                            .function_annotations
-                            .entry(func_name.clone())
-                            .or_insert_with(|| FunctionAnnotation {
-                                name: func_name,
-                                invariants: None,
-                                gotchas: None,
-                                do_not: None,
-                                error_handling: None,
-                                constraints: None,
-                            });
-
-                        match field.as_str() {
-                            "invariant" => {
-                                annotation
-                                    .invariants
-                                    .get_or_insert_with(Vec::new)
-                                    .push(value);
-                            }
-                            "gotcha" => {
-                                annotation.gotchas.get_or_insert_with(Vec::new).push(value);
-                            }
-                            "do-not" => {
-                                annotation.do_not.get_or_insert_with(Vec::new).push(value);
-                            }
-                            "constraint" => {
-                                annotation
-                                    .constraints
-                                    .get_or_insert_with(Vec::new)
-                                    .push(value);
-                            }
-                            "error-handling" => {
-                                annotation
-                                    .error_handling
-                                    .get_or_insert_with(Vec::new)
-                                    .push(value);
-                            }
-                            _ => {}
-                        }
-                    }
-                }
-            }
+        // Inline // @dose invariant: value annotations on the following declaration
+        let mut parser = self.create_parser(false)?;
+        if let Some(tree) = parser.parse(source, None) {
+            result.function_annotations = toon_comment::collect_inline_annotations(
+                tree.root_node(),
+                source,
+                &["comment"],
+                |node| self.declaration_name(node, source),
+                |node| self.owner_name(node, source),
+            );
         }
 
         Ok(result)
@@ -1420,4 +1300,37 @@ mod tests {
         assert!(stripped.contains("// @dose"));
         assert!(stripped.contains("sample.ts.toon"));
     }
+
+    #[test]
+    fn test_inline_annotations() {
+        let parser = TypeScriptParser::new();
+        let source = r#"
+export class Cache {
+  /** @dose invariant: returns undefined for expired entries */
+  get(key: string) {}
+}
+
+export class Store {
+  // @dose gotcha: hits the network
+  get(key: string) {}
+}
+
+// @dose do-not: call before init
+export const handler = async () => {};
+"#;
+        let comments = parser.extract_toon_comments(source).unwrap();
+        let annotations = &comments.function_annotations;
+        assert_eq!(
+            annotations["Cache.get"].invariants,
+            Some(vec!["returns undefined for expired entries".to_string()])
+        );
+        assert_eq!(
+            annotations["Store.get"].gotchas,
+            Some(vec!["hits the network".to_string()])
+        );
+        assert_eq!(
+            annotations["handler"].do_not,
+            Some(vec!["call before init".to_string()])
+        );
+    }
 }
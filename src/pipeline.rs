@@ -0,0 +1,431 @@
+//! @dose
+//! purpose: The generation pipeline shared by generate, watch and validate --fix. Each source
+//!     file is read and parsed once into a ParsedFile; the dependency graph is built from those,
+//!     and each ParsedFile plus its graph entries becomes the ToonData that gets formatted.
+//!
+//! when-editing:
+//!     - !Every command that writes a .toon must go through ParsedFile::toon_data so generate,
+//!       watch and validate --fix produce identical output for the same tree
+//!     - Graph keys are ParsedFile::relative (root-relative, forward slashes); import and call
+//!       targets come from ImportResolver in the same form
+//!
+//! invariants:
+//!     - A ParsedFile holds everything derived from the source text: nothing downstream
+//!       re-reads or re-parses the file
+//!     - parse_cached only reuses a cache entry whose content hash matches the file on disk
+//!     - imported-by and called-by are sorted and de-duplicated
+//!     - parse_files and build_graph run on the current rayon pool but return results in input
+//!       order, so output never depends on the thread count
+//!
+//! do-not:
+//!     - Never build a DependencyGraph by hand in a command; use build_graph or
+//!       DependencyGraph::update_file with ParsedFile::edges
+//!
+//! flows:
+//!     - Parse: parse_files -> ParsedFile::parse_cached (read, AST, @dose comments, paired files)
+//!     - Graph: resolve_edges(files, resolver, cache) -> graph_from_edges -> DependencyGraph
+//!     - Output: ParsedFile::toon_data(graph) -> ToonFormat::format -> toon_path
+
+use crate::cache::{content_hash, Cache};
+use crate::config::FileThresholds;
+use crate::dependency::{normalize_separators, DependencyGraph};
+use crate::parser::ParserFactory;
+use crate::resolver::ImportResolver;
+use crate::types::{ASTInfo, ExtractedComments, ToonData};
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A source file read and parsed once: everything the pipeline needs from its text
+#[derive(Debug, Clone)]
+pub struct ParsedFile {
+    /// Absolute path of the source file
+    pub path: PathBuf,
+    /// Path relative to root with forward slashes; the file's dependency graph key
+    pub relative: String,
+    /// language_name of the parser that handled the file
+    pub language: &'static str,
+    /// Structural data extracted from the AST
+    pub ast_info: ASTInfo,
+    /// @dose blocks extracted from comments
+    pub comments: ExtractedComments,
+    /// Root-relative companion files the parser pairs with this one (e.g. a header's .cpp)
+    pub paired: Vec<String>,
+    /// cache::content_hash of the source text
+    pub hash: String,
+}
+
+/// A file's resolved graph edges: import targets and (call target, method) pairs
+pub type Edges = (Vec<String>, Vec<(String, String)>);
+
+impl ParsedFile {
+    /// Read and parse a source file under root
+    pub fn parse(path: &Path, root: &Path, factory: &ParserFactory) -> Result<Self> {
+        Self::parse_cached(path, root, factory, &Cache::default())
+    }
+
+    /// Read a source file under root, reusing the cached parse when its content is unchanged
+    pub fn parse_cached(
+        path: &Path,
+        root: &Path,
+        factory: &ParserFactory,
+        cache: &Cache,
+    ) -> Result<Self> {
+        let parser = factory
+            .get_parser(path)
+            .context("No parser available for file")?;
+        let relative = path.strip_prefix(root).with_context(|| {
+            format!("File {} is outside root {}", path.display(), root.display())
+        })?;
+
+        let relative = normalize_separators(&relative.to_string_lossy());
+
+        let source = fs::read_to_string(path).context("Failed to read source file")?;
+        let hash = content_hash(source.as_bytes());
+        let (ast_info, comments) = match cache.entry(&relative, &hash) {
+            Some(entry) => (entry.ast_info.clone(), entry.comments.clone()),
+            None => (
+                parser.extract_ast_info(&source, path)?,
+                parser.extract_toon_comments(&source)?,
+            ),
+        };
+
+        let paired = parser
+            .paired_files(path)
+            .into_iter()
+            .filter(|paired| paired.is_file())
+            .map(|paired| {
+                let relative = paired.strip_prefix(root).unwrap_or(&paired);
+                normalize_separators(&relative.to_string_lossy())
+            })
+            .collect();
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            relative,
+            language: parser.language_name(),
+            ast_info,
+            comments,
+            paired,
+            hash,
+        })
+    }
+
+    /// The @dose purpose, or "<stem> module" when the file has none
+    pub fn purpose(&self) -> String {
+        self.comments
+            .file_block
+            .as_ref()
+            .and_then(|b| b.purpose.clone())
+            .unwrap_or_else(|| {
+                let stem = self.path.file_stem().unwrap_or_default().to_string_lossy();
+                format!("{} module", stem)
+            })
+    }
+
+    /// Resolved graph edges: import targets and (call target, method) pairs
+    pub fn edges(&self, resolver: &ImportResolver) -> Edges {
+        let imports = self
+            .ast_info
+            .imports
+            .iter()
+            .flat_map(|import| resolver.resolve_import(import, &self.path, self.language))
+            .collect();
+        let calls = self
+            .ast_info
+            .calls
+            .iter()
+            .flat_map(|call| {
+                resolver
+                    .resolve(&call.target, &self.path, self.language)
+                    .into_iter()
+                    .map(|target| (target, call.method.clone()))
+            })
+            .collect();
+        (imports, calls)
+    }
+
+    /// Hash of the source text and the luny version, stamped into the TOON so validate can
+    /// tell when it is stale
+    pub fn fingerprint(&self) -> String {
+        content_hash(format!("{}:{}", self.hash, env!("CARGO_PKG_VERSION")).as_bytes())
+    }
+
+    /// Combine AST data, @dose comments and the file's graph entries into TOON data
+    pub fn toon_data(&self, graph: &DependencyGraph) -> ToonData {
+        let ast_info = &self.ast_info;
+        let mut toon_data =
+            ToonData::new(self.purpose(), ast_info.tokens, ast_info.exports.clone());
+        toon_data.fingerprint = Some(self.fingerprint());
+
+        if !ast_info.imports.is_empty() {
+            toon_data.imports = Some(ast_info.imports.clone());
+        }
+        if !ast_info.calls.is_empty() {
+            toon_data.calls = Some(ast_info.calls.clone());
+        }
+        if !ast_info.signatures.is_empty() {
+            toon_data.signatures = Some(ast_info.signatures.clone());
+        }
+
+        let (imported_by, called_by) = graph.get_dependents(&self.relative);
+        if !imported_by.is_empty() {
+            toon_data.imported_by = Some(imported_by);
+        }
+        if !called_by.is_empty() {
+            toon_data.called_by = Some(called_by);
+        }
+
+        if let Some(ref block) = self.comments.file_block {
+            toon_data.when_editing = block.when_editing.clone();
+            toon_data.do_not = block.do_not.clone();
+            toon_data.invariants = block.invariants.clone();
+            toon_data.error_handling = block.error_handling.clone();
+            toon_data.constraints = block.constraints.clone();
+            toon_data.gotchas = block.gotchas.clone();
+            toon_data.flows = block.flows.clone();
+            toon_data.testing = block.testing.clone();
+            toon_data.common_mistakes = block.common_mistakes.clone();
+            toon_data.change_impacts = block.change_impacts.clone();
+            toon_data.related = block.related.clone();
+        }
+
+        // Paired files go after any related entries the author listed
+        for paired in &self.paired {
+            let related = toon_data.related.get_or_insert_with(Vec::new);
+            if !related.contains(paired) {
+                related.push(paired.clone());
+            }
+        }
+
+        if !self.comments.function_annotations.is_empty() {
+            // Sorted by name: the map's order would make output differ from run to run
+            let mut annotations: Vec<_> = self
+                .comments
+                .function_annotations
+                .values()
+                .cloned()
+                .collect();
+            annotations.sort_by(|a, b| a.name.cmp(&b.name));
+            toon_data.function_annotations = Some(annotations);
+        }
+
+        toon_data
+    }
+
+    /// The token threshold error or warning for this file, if it exceeds one
+    pub fn token_warning(&self, thresholds: FileThresholds) -> Option<String> {
+        let tokens = self.ast_info.tokens;
+        if let Some(error_threshold) = thresholds.error {
+            if tokens > error_threshold {
+                return Some(format!(
+                    "ERROR: {} has {} tokens (exceeds error threshold of {})",
+                    self.path.display(),
+                    tokens,
+                    error_threshold
+                ));
+            }
+        }
+        match thresholds.warn {
+            Some(warn_threshold) if tokens > warn_threshold => Some(format!(
+                "WARNING: {} has {} tokens (exceeds warning threshold of {})",
+                self.path.display(),
+                tokens,
+                warn_threshold
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Parse files in parallel, reusing cached parses; results are in the order of paths
+pub fn parse_files(
+    paths: &[PathBuf],
+    root: &Path,
+    factory: &ParserFactory,
+    cache: &Cache,
+) -> Vec<Result<ParsedFile>> {
+    paths
+        .par_iter()
+        .map(|path| ParsedFile::parse_cached(path, root, factory, cache))
+        .collect()
+}
+
+/// Build the dependency graph for a set of parsed files
+pub fn build_graph(files: &[ParsedFile], resolver: &ImportResolver) -> DependencyGraph {
+    graph_from_edges(files, &resolve_edges(files, resolver, &Cache::default()))
+}
+
+/// Each file's edges, in file order: cached when the cache allows it, else resolved in parallel
+pub fn resolve_edges(files: &[ParsedFile], resolver: &ImportResolver, cache: &Cache) -> Vec<Edges> {
+    let reuse = cache.reusable_edges(files);
+    files
+        .par_iter()
+        .map(|file| {
+            let cached = reuse
+                .then(|| cache.entry(&file.relative, &file.hash))
+                .flatten();
+            match cached {
+                Some(entry) => entry.edges.clone(),
+                None => file.edges(resolver),
+            }
+        })
+        .collect()
+}
+
+/// Build the dependency graph from resolve_edges output
+pub fn graph_from_edges(files: &[ParsedFile], edges: &[Edges]) -> DependencyGraph {
+    // Edges are added in file order so the graph is the same whatever the thread count
+    let mut graph = DependencyGraph::new();
+    for (file, (imports, calls)) in files.iter().zip(edges) {
+        graph.add_file(&file.relative, imports.clone(), calls.clone());
+    }
+    graph
+}
+
+/// The .ai/ path of a source file's TOON: .ai/path/to/file.ext.toon
+pub fn toon_path(root: &Path, source_path: &Path) -> PathBuf {
+    let relative = source_path.strip_prefix(root).unwrap_or(source_path);
+    let toon_filename = format!(
+        "{}.toon",
+        relative.file_name().unwrap_or_default().to_string_lossy()
+    );
+    root.join(".ai")
+        .join(relative.with_file_name(toon_filename))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn parse_all(root: &Path, names: &[&str]) -> Vec<ParsedFile> {
+        let paths: Vec<PathBuf> = names.iter().map(|name| root.join(name)).collect();
+        parse_files(&paths, root, &ParserFactory::new(), &Cache::default())
+            .into_iter()
+            .map(Result::unwrap)
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir(root.join("src")).unwrap();
+        fs::write(
+            root.join("src/utils.ts"),
+            "/** @dose\npurpose: String helpers\ngotchas: ASCII only\n*/\nexport function slug() {}\n",
+        )
+        .unwrap();
+
+        let file =
+            ParsedFile::parse(&root.join("src/utils.ts"), root, &ParserFactory::new()).unwrap();
+        assert_eq!(file.relative, "src/utils.ts");
+        assert_eq!(file.language, "typescript");
+        assert_eq!(file.purpose(), "String helpers");
+        assert_eq!(file.ast_info.exports[0].name, "slug");
+
+        let outside = TempDir::new().unwrap();
+        fs::write(outside.path().join("x.ts"), "").unwrap();
+        assert!(
+            ParsedFile::parse(&outside.path().join("x.ts"), root, &ParserFactory::new()).is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_cached_reuses_unchanged_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("a.ts"), "export const a = 1;\n").unwrap();
+        let mut file = parse_all(root, &["a.ts"]).remove(0);
+        file.ast_info.exports[0].name = "from_cache".to_string();
+        let cache = Cache::from_files("cfg", &[file], &[(vec![], vec![])]);
+
+        let factory = ParserFactory::new();
+        let reparsed = ParsedFile::parse_cached(&root.join("a.ts"), root, &factory, &cache);
+        assert_eq!(reparsed.unwrap().ast_info.exports[0].name, "from_cache");
+
+        fs::write(root.join("a.ts"), "export const b = 1;\n").unwrap();
+        let reparsed = ParsedFile::parse_cached(&root.join("a.ts"), root, &factory, &cache);
+        assert_eq!(reparsed.unwrap().ast_info.exports[0].name, "b");
+    }
+
+    #[test]
+    fn test_build_graph_and_toon_data() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(
+            root.join("main.ts"),
+            "import { foo } from './utils';\nfoo();\n",
+        )
+        .unwrap();
+        fs::write(root.join("app.ts"), "import { foo } from './utils';\n").unwrap();
+        fs::write(root.join("utils.ts"), "export function foo() {}\n").unwrap();
+
+        let files = parse_all(root, &["main.ts", "app.ts", "utils.ts"]);
+        let graph = build_graph(&files, &ImportResolver::new(root));
+
+        let utils = files[2].toon_data(&graph);
+        assert_eq!(
+            utils.imported_by,
+            Some(vec!["app.ts".to_string(), "main.ts".to_string()])
+        );
+        assert_eq!(utils.purpose, "utils module");
+        assert!(files[0].toon_data(&graph).imported_by.is_none());
+    }
+
+    #[test]
+    fn test_parse_files_keeps_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let names: Vec<String> = (0..32).map(|i| format!("m{:02}.ts", i)).collect();
+        for name in &names {
+            fs::write(root.join(name), "export const x = 1;\n").unwrap();
+        }
+        let mut paths: Vec<PathBuf> = names.iter().map(|name| root.join(name)).collect();
+        paths.push(root.join("missing.ts"));
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+        let results =
+            pool.install(|| parse_files(&paths, root, &ParserFactory::new(), &Cache::default()));
+
+        let relative: Vec<_> = results[..32]
+            .iter()
+            .map(|r| r.as_ref().unwrap().relative.clone())
+            .collect();
+        assert_eq!(relative, names);
+        assert!(results[32].is_err());
+    }
+
+    #[test]
+    fn test_token_warning() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("a.ts"), "export const x = 1;\n").unwrap();
+        let file = &parse_all(root, &["a.ts"])[0];
+        let thresholds = |warn, error| FileThresholds { warn, error };
+
+        assert_eq!(file.token_warning(thresholds(Some(1000), Some(2000))), None);
+        assert!(file
+            .token_warning(thresholds(Some(0), Some(2000)))
+            .unwrap()
+            .starts_with("WARNING:"));
+        assert!(file
+            .token_warning(thresholds(Some(0), Some(0)))
+            .unwrap()
+            .starts_with("ERROR:"));
+    }
+
+    #[test]
+    fn test_toon_path() {
+        let root = Path::new("/project");
+        assert_eq!(
+            toon_path(root, Path::new("/project/src/main.rs")),
+            PathBuf::from("/project/.ai/src/main.rs.toon")
+        );
+    }
+}
@@ -0,0 +1,257 @@
+//! @dose
+//! purpose: Resolves Go import paths to the files of the in-module package they name, using
+//!     go.mod module paths and go.work workspaces.
+//!
+//! when-editing:
+//!     - !A Go import names a package directory; resolve returns every non-test .go file in
+//!       it, so imported-by fans out to the whole package
+//!     - The longest matching module path wins (nested modules shadow their parent)
+//!
+//! invariants:
+//!     - _test.go files are never import targets: no other package can import them
+//!     - Returned files are sorted so graph order is deterministic
+//!
+//! do-not:
+//!     - Never walk subdirectories of the package directory: each is a separate package
+//!
+//! gotchas:
+//!     - Without go.work, only the module containing the importing file is known, so imports
+//!       of sibling modules (via replace directives) stay unresolved
+//!
+//! flows:
+//!     - Modules: go.work use directives above the file, plus the nearest go.mod, cached
+//!     - Resolve: longest module path prefix -> module dir + remainder -> package's .go files
+
+use crate::dependency::normalize_path;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Resolver for Go import paths
+pub struct GoResolver {
+    root: PathBuf,
+    /// go.mod or go.work file -> (module path, module directory) pairs it declares
+    modules: Mutex<HashMap<PathBuf, Vec<(String, PathBuf)>>>,
+}
+
+impl GoResolver {
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            modules: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Absolute paths of the non-test .go files in the package an import path names
+    pub fn resolve(&self, import_path: &str, from_file: &Path) -> Vec<PathBuf> {
+        let from_file = normalize_path(from_file);
+        let mut modules = Vec::new();
+        for name in ["go.work", "go.mod"] {
+            if let Some(file) = self.nearest(&from_file, name) {
+                modules.extend(self.load(&file));
+            }
+        }
+
+        let best = modules
+            .iter()
+            .filter_map(|(module, dir)| {
+                let rest = import_path.strip_prefix(module.as_str())?;
+                if rest.is_empty() {
+                    Some((module.len(), dir.clone()))
+                } else {
+                    let rest = rest.strip_prefix('/')?;
+                    Some((module.len(), dir.join(rest)))
+                }
+            })
+            .max_by_key(|(len, _)| *len);
+
+        best.map(|(_, dir)| package_files(&dir)).unwrap_or_default()
+    }
+
+    /// The nearest file with this name above from_file, within root
+    fn nearest(&self, from_file: &Path, name: &str) -> Option<PathBuf> {
+        let root = normalize_path(&self.root);
+        from_file
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| dir.starts_with(&root))
+            .map(|dir| dir.join(name))
+            .find(|file| file.is_file())
+    }
+
+    fn load(&self, file: &Path) -> Vec<(String, PathBuf)> {
+        let mut modules = self.modules.lock().unwrap_or_else(|e| e.into_inner());
+        modules
+            .entry(file.to_path_buf())
+            .or_insert_with(|| {
+                let dir = file.parent().unwrap_or(Path::new(""));
+                if file.file_name().is_some_and(|n| n == "go.work") {
+                    work_uses(file)
+                        .into_iter()
+                        .filter_map(|used| {
+                            let used = normalize_path(&dir.join(used));
+                            Some((module_path(&used.join("go.mod"))?, used))
+                        })
+                        .collect()
+                } else {
+                    module_path(file)
+                        .map(|module| vec![(module, dir.to_path_buf())])
+                        .unwrap_or_default()
+                }
+            })
+            .clone()
+    }
+}
+
+/// The module path declared in a go.mod file
+fn module_path(go_mod: &Path) -> Option<String> {
+    let content = fs::read_to_string(go_mod).ok()?;
+    content.lines().find_map(|line| {
+        let module = strip_line_comment(line).trim().strip_prefix("module")?;
+        let module = module.trim().trim_matches('"');
+        (!module.is_empty()).then(|| module.to_string())
+    })
+}
+
+/// Directories listed by `use` directives in a go.work file, single or parenthesized
+fn work_uses(go_work: &Path) -> Vec<String> {
+    let Ok(content) = fs::read_to_string(go_work) else {
+        return Vec::new();
+    };
+    let mut uses = Vec::new();
+    let mut in_block = false;
+    for line in content.lines() {
+        let line = strip_line_comment(line).trim();
+        if in_block {
+            if line == ")" {
+                in_block = false;
+            } else if !line.is_empty() {
+                uses.push(line.trim_matches('"').to_string());
+            }
+        } else if let Some(rest) = line.strip_prefix("use") {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_block = true;
+            } else if !rest.is_empty() {
+                uses.push(rest.trim_matches('"').to_string());
+            }
+        }
+    }
+    uses
+}
+
+fn strip_line_comment(line: &str) -> &str {
+    line.split("//").next().unwrap_or(line)
+}
+
+/// Non-test .go files directly in a package directory, sorted
+fn package_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|path| {
+            path.is_file()
+                && path.extension().is_some_and(|e| e == "go")
+                && !path.to_string_lossy().ends_with("_test.go")
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn resolve(resolver: &GoResolver, import_path: &str, from: &str) -> Vec<String> {
+        let root = resolver.root.clone();
+        resolver
+            .resolve(import_path, &root.join(from))
+            .into_iter()
+            .map(|p| {
+                p.strip_prefix(&root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_module_packages() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write(
+            root,
+            "go.mod",
+            "module github.com/ourorg/svc // main module\n\ngo 1.22\n",
+        );
+        write(root, "cmd/server/main.go", "package main\n");
+        write(root, "internal/auth/token.go", "package auth\n");
+        write(root, "internal/auth/session.go", "package auth\n");
+        write(root, "internal/auth/token_test.go", "package auth\n");
+        write(root, "internal/auth/jwt/jwt.go", "package jwt\n");
+        let resolver = GoResolver::new(root);
+
+        assert_eq!(
+            resolve(
+                &resolver,
+                "github.com/ourorg/svc/internal/auth",
+                "cmd/server/main.go"
+            ),
+            vec!["internal/auth/session.go", "internal/auth/token.go"]
+        );
+        assert_eq!(
+            resolve(
+                &resolver,
+                "github.com/ourorg/svc/internal/auth/jwt",
+                "cmd/server/main.go"
+            ),
+            vec!["internal/auth/jwt/jwt.go"]
+        );
+        assert!(resolve(
+            &resolver,
+            "github.com/ourorg/svcx/auth",
+            "cmd/server/main.go"
+        )
+        .is_empty());
+        assert!(resolve(&resolver, "fmt", "cmd/server/main.go").is_empty());
+    }
+
+    #[test]
+    fn test_workspace_modules() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write(
+            root,
+            "go.work",
+            "go 1.22\n\nuse (\n\t./api\n\t./lib // shared\n)\n",
+        );
+        write(root, "api/go.mod", "module example.com/api\n");
+        write(root, "api/main.go", "package main\n");
+        write(root, "lib/go.mod", "module example.com/lib\n");
+        write(root, "lib/lib.go", "package lib\n");
+        write(root, "lib/text/slug.go", "package text\n");
+        let resolver = GoResolver::new(root);
+
+        assert_eq!(
+            resolve(&resolver, "example.com/lib", "api/main.go"),
+            vec!["lib/lib.go"]
+        );
+        assert_eq!(
+            resolve(&resolver, "example.com/lib/text", "api/main.go"),
+            vec!["lib/text/slug.go"]
+        );
+    }
+}
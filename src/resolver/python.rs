@@ -0,0 +1,349 @@
+//! @dose
+//! purpose: Resolves Python module names (app.services.billing, .models, ..) to project files
+//!     by searching source roots the way sys.path would, with __init__.py as the package file.
+//!
+//! when-editing:
+//!     - !Source roots are searched in order: luny.toml [python] source-roots, roots declared
+//!       by the nearest pyproject.toml/setup.cfg, its src/ directory, the project directory,
+//!       then the importing file's own directory (script-style sibling imports)
+//!     - Relative imports never consult source roots: one dot is the importing file's package
+//!
+//! invariants:
+//!     - A module resolves to name.py before name/__init__.py, as CPython's finder does
+//!     - Source roots declared by a project are relative to that project's directory
+//!
+//! do-not:
+//!     - Never resolve past the top of root for relative imports with too many dots
+//!
+//! gotchas:
+//!     - Namespace packages (directories without __init__.py) have no file, so importing the
+//!       package itself resolves to nothing; their modules still resolve
+//!     - setup.cfg is read with a minimal INI scan, only package_dir and packages.find where
+//!
+//! flows:
+//!     - Roots: nearest pyproject.toml or setup.cfg above the file, cached per project dir
+//!     - Resolve: leading dots -> package dir walk; else first root containing the module
+
+use crate::dependency::normalize_path;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Resolver for Python module names
+pub struct PythonResolver {
+    root: PathBuf,
+    /// Source roots from luny.toml, relative to root
+    configured: Vec<PathBuf>,
+    /// Project directory -> source roots it declares or implies
+    projects: Mutex<HashMap<PathBuf, Vec<PathBuf>>>,
+}
+
+impl PythonResolver {
+    pub fn new(root: &Path, source_roots: &[String]) -> Self {
+        let root = normalize_path(root);
+        Self {
+            configured: source_roots.iter().map(|r| root.join(r)).collect(),
+            root,
+            projects: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Absolute path of the module file a (possibly relative) dotted name refers to
+    pub fn resolve(&self, module: &str, from_file: &Path) -> Option<PathBuf> {
+        let from_file = normalize_path(from_file);
+        let from_dir = from_file.parent()?;
+
+        let dots = module.len() - module.trim_start_matches('.').len();
+        let parts: Vec<&str> = module[dots..]
+            .split('.')
+            .filter(|p| !p.is_empty())
+            .collect();
+
+        if dots > 0 {
+            let mut package = from_dir.to_path_buf();
+            for _ in 1..dots {
+                if package == self.root || !package.pop() {
+                    return None;
+                }
+            }
+            return module_file(&package, &parts);
+        }
+
+        if parts.is_empty() {
+            return None;
+        }
+        self.source_roots(&from_file)
+            .iter()
+            .chain(std::iter::once(&from_dir.to_path_buf()))
+            .find_map(|source_root| module_file(source_root, &parts))
+    }
+
+    /// Roots to search for absolute imports from a file
+    fn source_roots(&self, from_file: &Path) -> Vec<PathBuf> {
+        let project = from_file
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| dir.starts_with(&self.root))
+            .find(|dir| dir.join("pyproject.toml").is_file() || dir.join("setup.cfg").is_file())
+            .unwrap_or(&self.root)
+            .to_path_buf();
+
+        let mut projects = self.projects.lock().unwrap_or_else(|e| e.into_inner());
+        let detected = projects
+            .entry(project.clone())
+            .or_insert_with(|| detect_source_roots(&project));
+
+        let mut roots = self.configured.clone();
+        for root in detected.iter() {
+            if !roots.contains(root) {
+                roots.push(root.clone());
+            }
+        }
+        roots
+    }
+}
+
+/// base/a/b.py, else base/a/b/__init__.py; the package's __init__.py when parts is empty
+fn module_file(base: &Path, parts: &[&str]) -> Option<PathBuf> {
+    let mut path = base.to_path_buf();
+    path.extend(parts);
+    if let Some((last, _)) = parts.split_last() {
+        let file = path.with_file_name(format!("{}.py", last));
+        if file.is_file() {
+            return Some(file);
+        }
+    }
+    Some(path.join("__init__.py")).filter(|init| init.is_file())
+}
+
+/// Source roots a project declares in pyproject.toml or setup.cfg, then src/ and the project
+fn detect_source_roots(project: &Path) -> Vec<PathBuf> {
+    let mut declared = Vec::new();
+    if let Some(pyproject) = fs::read_to_string(project.join("pyproject.toml"))
+        .ok()
+        .and_then(|content| content.parse::<toml::Value>().ok())
+    {
+        declared.extend(pyproject_roots(&pyproject));
+    }
+    if let Ok(setup_cfg) = fs::read_to_string(project.join("setup.cfg")) {
+        declared.extend(setup_cfg_roots(&setup_cfg));
+    }
+
+    let mut roots: Vec<PathBuf> = Vec::new();
+    let implied = [project.join("src"), project.to_path_buf()];
+    let candidates = declared
+        .iter()
+        .map(|dir| normalize_path(&project.join(dir)))
+        .chain(implied);
+    for dir in candidates {
+        if dir.is_dir() && !roots.contains(&dir) {
+            roots.push(dir);
+        }
+    }
+    roots
+}
+
+/// Package directories from setuptools, Poetry and Hatch configuration
+fn pyproject_roots(pyproject: &toml::Value) -> Vec<String> {
+    let mut roots = Vec::new();
+    let Some(tool) = pyproject.get("tool") else {
+        return roots;
+    };
+    let strings = |value: Option<&toml::Value>| -> Vec<String> {
+        match value {
+            Some(toml::Value::String(s)) => vec![s.clone()],
+            Some(toml::Value::Array(items)) => items
+                .iter()
+                .filter_map(|item| item.as_str().map(String::from))
+                .collect(),
+            _ => Vec::new(),
+        }
+    };
+
+    if let Some(setuptools) = tool.get("setuptools") {
+        roots.extend(strings(
+            setuptools
+                .get("packages")
+                .and_then(|p| p.get("find"))
+                .and_then(|f| f.get("where")),
+        ));
+        roots.extend(strings(
+            setuptools.get("package-dir").and_then(|dirs| dirs.get("")),
+        ));
+    }
+    if let Some(packages) = tool
+        .get("poetry")
+        .and_then(|p| p.get("packages"))
+        .and_then(|p| p.as_array())
+    {
+        for package in packages {
+            roots.extend(strings(package.get("from")));
+        }
+    }
+    // Hatch lists package directories; the source root is their parent
+    let hatch = tool
+        .get("hatch")
+        .and_then(|h| h.get("build"))
+        .and_then(|b| b.get("targets"))
+        .and_then(|t| t.get("wheel"))
+        .and_then(|w| w.get("packages"));
+    for package in strings(hatch) {
+        let parent = Path::new(&package).parent().unwrap_or(Path::new(""));
+        roots.push(parent.to_string_lossy().to_string());
+    }
+    roots
+}
+
+/// Package directories from setup.cfg: `package_dir = =src` and `[options.packages.find] where`
+fn setup_cfg_roots(content: &str) -> Vec<String> {
+    let mut roots = Vec::new();
+    let mut section = String::new();
+    let mut key = String::new();
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with(';') {
+            continue;
+        }
+        if let Some(name) = trimmed.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+            section = name.trim().to_string();
+            key.clear();
+            continue;
+        }
+
+        // Indented lines continue the previous key's value
+        let value = if line.starts_with([' ', '\t']) {
+            trimmed
+        } else if let Some((k, v)) = trimmed.split_once('=') {
+            key = k.trim().to_string();
+            v.trim()
+        } else {
+            continue;
+        };
+
+        match (section.as_str(), key.as_str()) {
+            ("options", "package_dir") => {
+                // "=src" maps the root package; "name=dir" maps a single package
+                if let Some(dir) = value.strip_prefix('=') {
+                    roots.push(dir.trim().to_string());
+                }
+            }
+            ("options.packages.find", "where") if !value.is_empty() => {
+                roots.push(value.to_string());
+            }
+            _ => {}
+        }
+    }
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn resolve(resolver: &PythonResolver, module: &str, from: &str) -> Option<String> {
+        let root = resolver.root.clone();
+        resolver.resolve(module, &root.join(from)).map(|p| {
+            p.strip_prefix(&root)
+                .unwrap()
+                .to_string_lossy()
+                .replace('\\', "/")
+        })
+    }
+
+    #[test]
+    fn test_absolute_and_relative_modules() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write(root, "app/__init__.py", "");
+        write(root, "app/models.py", "");
+        write(root, "app/services/__init__.py", "");
+        write(root, "app/services/billing.py", "");
+        write(root, "app/services/payments/__init__.py", "");
+        let resolver = PythonResolver::new(root, &[]);
+        let from = "app/services/billing.py";
+
+        assert_eq!(
+            resolve(&resolver, "app.services.billing", "app/models.py").as_deref(),
+            Some("app/services/billing.py")
+        );
+        assert_eq!(
+            resolve(&resolver, "app.services.payments", from).as_deref(),
+            Some("app/services/payments/__init__.py")
+        );
+        assert_eq!(
+            resolve(&resolver, ".", from).as_deref(),
+            Some("app/services/__init__.py")
+        );
+        assert_eq!(
+            resolve(&resolver, "..models", from).as_deref(),
+            Some("app/models.py")
+        );
+        assert_eq!(resolve(&resolver, "...", from), None);
+        assert_eq!(resolve(&resolver, "....x", from), None);
+        assert_eq!(resolve(&resolver, "requests", from), None);
+    }
+
+    #[test]
+    fn test_detected_source_roots() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write(
+            root,
+            "backend/pyproject.toml",
+            "[tool.setuptools.packages.find]\nwhere = [\"lib\"]\n",
+        );
+        write(root, "backend/lib/shop/cart.py", "");
+        write(root, "backend/tests/test_cart.py", "");
+        write(
+            root,
+            "worker/setup.cfg",
+            "[metadata]\nname = worker\n\n[options]\npackage_dir =\n    =source\n",
+        );
+        write(root, "worker/source/jobs/run.py", "");
+        write(root, "worker/main.py", "");
+        write(root, "web/pyproject.toml", "[project]\nname = \"web\"\n");
+        write(root, "web/src/site/views.py", "");
+        write(root, "web/manage.py", "");
+        write(root, "scripts/helpers.py", "");
+        write(root, "scripts/deploy.py", "");
+        write(root, "vendor/thing.py", "");
+        let resolver = PythonResolver::new(root, &["vendor".to_string()]);
+
+        assert_eq!(
+            resolve(&resolver, "shop.cart", "backend/tests/test_cart.py").as_deref(),
+            Some("backend/lib/shop/cart.py")
+        );
+        assert_eq!(
+            resolve(&resolver, "jobs.run", "worker/main.py").as_deref(),
+            Some("worker/source/jobs/run.py")
+        );
+        assert_eq!(
+            resolve(&resolver, "site.views", "web/manage.py").as_deref(),
+            Some("web/src/site/views.py")
+        );
+        assert_eq!(
+            resolve(&resolver, "helpers", "scripts/deploy.py").as_deref(),
+            Some("scripts/helpers.py")
+        );
+        assert_eq!(
+            resolve(&resolver, "thing", "worker/main.py").as_deref(),
+            Some("vendor/thing.py")
+        );
+    }
+
+    #[test]
+    fn test_setup_cfg_roots() {
+        let roots = setup_cfg_roots(
+            "[options]\npackage_dir = =src\n[options.packages.find]\nwhere = lib\nexclude = tests\n",
+        );
+        assert_eq!(roots, vec!["src", "lib"]);
+    }
+}
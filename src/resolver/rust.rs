@@ -0,0 +1,467 @@
+//! @dose
+//! purpose: Resolves Rust use paths (crate::, self::, super::, workspace crate names) to the
+//!     files defining those modules, by walking each crate's mod declarations from its root
+//!     files the way rustc does.
+//!
+//! when-editing:
+//!     - !Module trees come from mod declarations, never from the directory layout alone,
+//!       so #[path] attributes and non-mod-rs files resolve correctly
+//!     - A path resolves to its longest prefix that is a module: crate::types::ToonData names
+//!       the file defining crate::types
+//!
+//! invariants:
+//!     - Every crate root (lib, main, bin, tests, examples, benches) gets its own tree:
+//!       crate:: in src/main.rs means the binary's tree, not the library's
+//!     - Workspace crates are keyed by their Rust name (hyphens become underscores)
+//!
+//! do-not:
+//!     - Never resolve an extern crate path to a file outside its library's module tree
+//!
+//! gotchas:
+//!     - mod declarations are found by regex, so a `mod x;` inside an inline mod block is
+//!       treated as if declared at file level
+//!     - A bare first segment is tried as a child of the current module before a crate name
+//!       (2018 uniform paths)
+//!
+//! flows:
+//!     - Crate: nearest Cargo.toml above the importing file, loaded and cached on first use
+//!     - Tree: BFS from each root file over mod declarations, mapping module path <-> file
+//!     - Resolve: anchor (crate/self/super/child/extern crate) + longest module prefix
+
+use crate::dependency::normalize_path;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// `#[path = "x.rs"] pub(crate) mod name;` with any attributes in front
+static MOD_DECLARATION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?m)^[ \t]*((?:#\[[^\]]*\][ \t\r\n]*)*)(?:pub(?:\([^)]*\))?[ \t]+)?mod[ \t]+(\w+)[ \t]*;"#)
+        .unwrap()
+});
+
+/// `path = "..."` inside an attribute
+static PATH_ATTRIBUTE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"#\[\s*path\s*=\s*"([^"]+)"\s*\]"#).unwrap());
+
+/// One crate target's modules: module path ("" for the root, "a::b") <-> file
+#[derive(Debug, Default)]
+struct ModuleTree {
+    modules: HashMap<String, PathBuf>,
+    files: HashMap<PathBuf, String>,
+}
+
+/// A Cargo package and the module trees of its targets
+#[derive(Debug, Default)]
+struct CrateInfo {
+    /// Rust name of the library target
+    name: Option<String>,
+    /// Index into trees of the library target
+    lib: Option<usize>,
+    trees: Vec<ModuleTree>,
+}
+
+/// Resolver for Rust use paths
+pub struct RustResolver {
+    root: PathBuf,
+    /// Workspace crate name -> manifest directory
+    workspace: HashMap<String, PathBuf>,
+    /// Manifest directory -> loaded crate
+    crates: Mutex<HashMap<PathBuf, Arc<CrateInfo>>>,
+}
+
+impl RustResolver {
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            workspace: load_workspace(root),
+            crates: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Absolute path of the file defining the module a use path names
+    pub fn resolve(&self, use_path: &str, from_file: &Path) -> Option<PathBuf> {
+        let from_file = normalize_path(from_file);
+        let segments: Vec<&str> = use_path
+            .trim_start_matches("::")
+            .split("::")
+            .take_while(|s| !s.is_empty() && s.chars().all(|c| c.is_alphanumeric() || c == '_'))
+            .collect();
+        let (&first, rest) = segments.split_first()?;
+
+        let local = self.crate_for(&from_file);
+        let local_tree = local.as_ref().and_then(|info| {
+            info.trees
+                .iter()
+                .find_map(|tree| Some((tree, tree.files.get(&from_file)?)))
+        });
+
+        // Anchor: the tree and module path the rest of the segments are relative to
+        let (tree, mut base, rest): (&ModuleTree, Vec<&str>, &[&str]) = match first {
+            "crate" => (local_tree?.0, Vec::new(), rest),
+            "self" | "super" => {
+                let (tree, current) = local_tree?;
+                let mut base = module_segments(current);
+                let mut rest = segments.as_slice();
+                while let Some((&"super", tail)) = rest.split_first() {
+                    base.pop()?;
+                    rest = tail;
+                }
+                if let Some((&"self", tail)) = rest.split_first() {
+                    rest = tail;
+                }
+                (tree, base, rest)
+            }
+            _ => {
+                let child = local_tree.and_then(|(tree, current)| {
+                    let mut base = module_segments(current);
+                    base.push(first);
+                    tree.modules
+                        .contains_key(&base.join("::"))
+                        .then_some((tree, base))
+                });
+                match child {
+                    Some((tree, base)) => (tree, base, rest),
+                    None => {
+                        let info = self.extern_crate(first, local.as_ref())?;
+                        return info
+                            .lib
+                            .and_then(|lib| longest_module(&info.trees[lib], Vec::new(), rest));
+                    }
+                }
+            }
+        };
+
+        base.reserve(rest.len());
+        longest_module(tree, base, rest)
+    }
+
+    /// A crate named in a use path: a workspace member, or the importing package's own library
+    /// (binaries, tests and examples use it by name)
+    fn extern_crate(&self, name: &str, local: Option<&Arc<CrateInfo>>) -> Option<Arc<CrateInfo>> {
+        if let Some(dir) = self.workspace.get(name) {
+            return Some(self.load_crate(dir));
+        }
+        local
+            .filter(|info| info.name.as_deref() == Some(name))
+            .map(Arc::clone)
+    }
+
+    /// The crate whose manifest is nearest above a file, within root
+    fn crate_for(&self, file: &Path) -> Option<Arc<CrateInfo>> {
+        let root = normalize_path(&self.root);
+        let manifest_dir = file
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| dir.starts_with(&root))
+            .find(|dir| dir.join("Cargo.toml").is_file())?;
+        Some(self.load_crate(manifest_dir))
+    }
+
+    fn load_crate(&self, manifest_dir: &Path) -> Arc<CrateInfo> {
+        let mut crates = self.crates.lock().unwrap_or_else(|e| e.into_inner());
+        Arc::clone(
+            crates
+                .entry(manifest_dir.to_path_buf())
+                .or_insert_with(|| Arc::new(load_crate_info(manifest_dir))),
+        )
+    }
+}
+
+/// "a::b" -> ["a", "b"], "" -> []
+fn module_segments(module: &str) -> Vec<&str> {
+    module.split("::").filter(|s| !s.is_empty()).collect()
+}
+
+/// The file of the longest module path base ++ rest[..k]
+fn longest_module(tree: &ModuleTree, base: Vec<&str>, rest: &[&str]) -> Option<PathBuf> {
+    (0..=rest.len()).rev().find_map(|k| {
+        let mut path = base.clone();
+        path.extend_from_slice(&rest[..k]);
+        tree.modules.get(&path.join("::")).cloned()
+    })
+}
+
+/// Workspace member names from the root Cargo.toml, plus the root package itself
+fn load_workspace(root: &Path) -> HashMap<String, PathBuf> {
+    let mut members = HashMap::new();
+    let Some(manifest) = read_manifest(&root.join("Cargo.toml")) else {
+        return members;
+    };
+    let root = normalize_path(root);
+
+    if let Some(name) = lib_name(&manifest) {
+        members.insert(name, root.clone());
+    }
+
+    let patterns = manifest
+        .get("workspace")
+        .and_then(|w| w.get("members"))
+        .and_then(|m| m.as_array())
+        .cloned()
+        .unwrap_or_default();
+    for pattern in patterns.iter().filter_map(|p| p.as_str()) {
+        let Ok(matches) = glob::glob(&root.join(pattern).to_string_lossy()) else {
+            continue;
+        };
+        for dir in matches.filter_map(|m| m.ok()) {
+            if let Some(name) = read_manifest(&dir.join("Cargo.toml")).and_then(|m| lib_name(&m)) {
+                members.insert(name, normalize_path(&dir));
+            }
+        }
+    }
+    members
+}
+
+fn read_manifest(path: &Path) -> Option<toml::Value> {
+    toml::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+/// The name other crates use for a package's library
+fn lib_name(manifest: &toml::Value) -> Option<String> {
+    let name = manifest
+        .get("lib")
+        .and_then(|lib| lib.get("name"))
+        .or_else(|| manifest.get("package")?.get("name"))?
+        .as_str()?;
+    Some(name.replace('-', "_"))
+}
+
+/// Find a package's targets and build a module tree for each
+fn load_crate_info(manifest_dir: &Path) -> CrateInfo {
+    let manifest = read_manifest(&manifest_dir.join("Cargo.toml"));
+    let target_path = |table: Option<&toml::Value>| {
+        table
+            .and_then(|t| t.get("path"))
+            .and_then(|p| p.as_str())
+            .map(|p| manifest_dir.join(p))
+    };
+
+    let mut info = CrateInfo {
+        name: manifest.as_ref().and_then(lib_name),
+        ..CrateInfo::default()
+    };
+    let lib = target_path(manifest.as_ref().and_then(|m| m.get("lib")))
+        .unwrap_or_else(|| manifest_dir.join("src/lib.rs"));
+    if lib.is_file() {
+        info.lib = Some(0);
+        info.trees.push(build_tree(&lib));
+    }
+
+    let mut roots = vec![manifest_dir.join("src/main.rs")];
+    if let Some(bins) = manifest
+        .as_ref()
+        .and_then(|m| m.get("bin"))
+        .and_then(|b| b.as_array())
+    {
+        roots.extend(bins.iter().filter_map(|bin| target_path(Some(bin))));
+    }
+    for dir in ["src/bin", "tests", "examples", "benches"] {
+        let Ok(entries) = fs::read_dir(manifest_dir.join(dir)) else {
+            continue;
+        };
+        let mut found: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter_map(|path| {
+                if path.is_dir() {
+                    Some(path.join("main.rs")).filter(|main| main.is_file())
+                } else {
+                    Some(path).filter(|p| p.extension().is_some_and(|e| e == "rs"))
+                }
+            })
+            .collect();
+        found.sort();
+        roots.extend(found);
+    }
+
+    for root in roots {
+        let root = normalize_path(&root);
+        let known = info.trees.iter().any(|tree| tree.files.contains_key(&root));
+        if root.is_file() && !known {
+            info.trees.push(build_tree(&root));
+        }
+    }
+    info
+}
+
+/// Map every module reachable from a crate root through mod declarations
+fn build_tree(root_file: &Path) -> ModuleTree {
+    let mut tree = ModuleTree::default();
+    let mut queue = VecDeque::from([(normalize_path(root_file), String::new(), true)]);
+
+    while let Some((file, module, is_mod_rs)) = queue.pop_front() {
+        if tree.files.contains_key(&file) {
+            continue;
+        }
+        tree.modules.insert(module.clone(), file.clone());
+        tree.files.insert(file.clone(), module.clone());
+
+        let Ok(source) = fs::read_to_string(&file) else {
+            continue;
+        };
+        let dir = file.parent().unwrap_or(Path::new("")).to_path_buf();
+        // Children of foo.rs live in foo/, children of mod.rs and crate roots next to them
+        let child_dir = if is_mod_rs {
+            dir.clone()
+        } else {
+            dir.join(file.file_stem().unwrap_or_default())
+        };
+
+        for caps in MOD_DECLARATION.captures_iter(&source) {
+            let name = &caps[2];
+            let child_module = if module.is_empty() {
+                name.to_string()
+            } else {
+                format!("{}::{}", module, name)
+            };
+
+            let explicit = PATH_ATTRIBUTE
+                .captures(&caps[1])
+                .map(|attr| normalize_path(&dir.join(&attr[1])));
+            let child = explicit.map(|path| (path, true)).or_else(|| {
+                let flat = child_dir.join(format!("{}.rs", name));
+                let nested = child_dir.join(name).join("mod.rs");
+                if flat.is_file() {
+                    Some((flat, false))
+                } else {
+                    Some((nested, true)).filter(|(p, _)| p.is_file())
+                }
+            });
+            if let Some((child, child_is_mod_rs)) = child {
+                queue.push_back((normalize_path(&child), child_module, child_is_mod_rs));
+            }
+        }
+    }
+    tree
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn resolve(resolver: &RustResolver, use_path: &str, from: &str) -> Option<String> {
+        let root = resolver.root.clone();
+        resolver.resolve(use_path, &root.join(from)).map(|p| {
+            p.strip_prefix(&root)
+                .unwrap()
+                .to_string_lossy()
+                .replace('\\', "/")
+        })
+    }
+
+    fn workspace() -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write(
+            root,
+            "Cargo.toml",
+            "[package]\nname = \"my-app\"\n\n[workspace]\nmembers = [\"crates/*\"]\n",
+        );
+        write(
+            root,
+            "src/lib.rs",
+            "pub mod parser;\nmod types;\n#[cfg(test)]\nmod tests;\n",
+        );
+        write(root, "src/main.rs", "mod cli;\nuse my_app::parser;\n");
+        write(root, "src/cli.rs", "");
+        write(root, "src/types/mod.rs", "pub mod toon;\n");
+        write(root, "src/types/toon.rs", "");
+        write(
+            root,
+            "src/parser/mod.rs",
+            "mod rust;\n#[path = \"gen/tokens_impl.rs\"]\npub(crate) mod tokens;\n",
+        );
+        write(root, "src/parser/rust.rs", "mod visit;\n");
+        write(root, "src/parser/rust/visit.rs", "");
+        write(root, "src/parser/gen/tokens_impl.rs", "");
+        write(
+            root,
+            "crates/util-core/Cargo.toml",
+            "[package]\nname = \"util-core\"\n",
+        );
+        write(root, "crates/util-core/src/lib.rs", "pub mod text;\n");
+        write(root, "crates/util-core/src/text.rs", "");
+        temp_dir
+    }
+
+    #[test]
+    fn test_crate_paths() {
+        let temp_dir = workspace();
+        let resolver = RustResolver::new(temp_dir.path());
+        let from = "src/parser/rust.rs";
+
+        assert_eq!(
+            resolve(&resolver, "crate::types", from).as_deref(),
+            Some("src/types/mod.rs")
+        );
+        assert_eq!(
+            resolve(&resolver, "crate::types::toon::ToonData", from).as_deref(),
+            Some("src/types/toon.rs")
+        );
+        assert_eq!(
+            resolve(&resolver, "crate", from).as_deref(),
+            Some("src/lib.rs")
+        );
+        assert_eq!(
+            resolve(&resolver, "crate::parser::tokens", from).as_deref(),
+            Some("src/parser/gen/tokens_impl.rs")
+        );
+        // Non-mod-rs children live in a directory named after the file
+        assert_eq!(
+            resolve(&resolver, "self::visit", from).as_deref(),
+            Some("src/parser/rust/visit.rs")
+        );
+        assert_eq!(
+            resolve(&resolver, "visit::walk", from).as_deref(),
+            Some("src/parser/rust/visit.rs")
+        );
+        assert_eq!(
+            resolve(&resolver, "super::tokens", from).as_deref(),
+            Some("src/parser/gen/tokens_impl.rs")
+        );
+        assert_eq!(
+            resolve(&resolver, "super::super::types", from).as_deref(),
+            Some("src/types/mod.rs")
+        );
+        assert_eq!(resolve(&resolver, "std::collections", from), None);
+    }
+
+    #[test]
+    fn test_binary_and_workspace_crates() {
+        let temp_dir = workspace();
+        let resolver = RustResolver::new(temp_dir.path());
+
+        // crate:: in main.rs is the binary's tree
+        assert_eq!(
+            resolve(&resolver, "crate::cli", "src/main.rs").as_deref(),
+            Some("src/cli.rs")
+        );
+        assert_eq!(
+            resolve(&resolver, "crate::types", "src/main.rs").as_deref(),
+            Some("src/main.rs")
+        );
+        // The package's own library by name
+        assert_eq!(
+            resolve(&resolver, "my_app::parser", "src/main.rs").as_deref(),
+            Some("src/parser/mod.rs")
+        );
+        assert_eq!(
+            resolve(&resolver, "util_core::text::slug", "src/cli.rs").as_deref(),
+            Some("crates/util-core/src/text.rs")
+        );
+        assert_eq!(
+            resolve(&resolver, "crate::text", "crates/util-core/src/lib.rs").as_deref(),
+            Some("crates/util-core/src/text.rs")
+        );
+    }
+}
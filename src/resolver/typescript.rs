@@ -0,0 +1,604 @@
+//! @dose
+//! purpose: Resolves JavaScript and TypeScript module specifiers to source files: relative
+//!     paths, tsconfig.json/jsconfig.json baseUrl and paths aliases (following extends), and
+//!     package.json workspace packages in monorepos.
+//!
+//! when-editing:
+//!     - !Resolution order mirrors tsc: relative, then paths, then baseUrl, then packages
+//!     - !The nearest tsconfig.json (or jsconfig.json) above the importing file applies,
+//!       so each monorepo package can carry its own aliases
+//!
+//! invariants:
+//!     - paths targets are relative to baseUrl when set, else to the config defining paths
+//!     - A specifier resolves only to a source file (.d.ts and .json never match)
+//!
+//! gotchas:
+//!     - ESM imports name the emitted file (./auth.js) while the source is ./auth.ts
+//!     - Config files are JSONC: comments and trailing commas are stripped before parsing
+//!     - Workspace package entry points prefer source (exports.source, source, src/index)
+//!       over build output (main, module), which may not exist before a build
+//!
+//! flows:
+//!     - Relative: probe from the importing file's directory
+//!     - Alias: nearest config's paths patterns, longest prefix wins, targets probed in order
+//!     - Package: workspace name prefix -> package directory -> exports/entry/subpath
+
+use super::with_appended_extension;
+use crate::dependency::normalize_path;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Extensions tried, in order, for a specifier without one
+const EXTENSIONS: &[&str] = &[
+    "ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs", "vue", "svelte", "astro",
+];
+
+/// ESM specifiers name the emitted JavaScript; these are the sources it is built from
+const ESM_SOURCES: &[(&str, &[&str])] = &[
+    ("js", &["ts", "tsx"]),
+    ("jsx", &["tsx"]),
+    ("mjs", &["mts"]),
+    ("cjs", &["cts"]),
+];
+
+/// Config files checked in each directory, in order
+const CONFIG_NAMES: &[&str] = &["tsconfig.json", "jsconfig.json"];
+
+/// Conditions tried when reading a package.json exports entry
+const EXPORT_CONDITIONS: &[&str] = &["source", "development", "import", "default", "require"];
+
+/// Bound on extends chains, which can be cyclic in broken configs
+const MAX_EXTENDS_DEPTH: usize = 8;
+
+/// The compilerOptions that affect resolution, after following extends
+#[derive(Debug, Default, Clone)]
+struct CompilerPaths {
+    /// Absolute baseUrl
+    base_url: Option<PathBuf>,
+    /// paths patterns and their targets, in declaration order
+    paths: Vec<(String, Vec<String>)>,
+    /// Directory of the config that declared paths
+    paths_dir: PathBuf,
+}
+
+/// Resolver for JavaScript and TypeScript imports
+pub struct TypeScriptResolver {
+    root: PathBuf,
+    /// Workspace package name -> package directory
+    workspaces: HashMap<String, PathBuf>,
+    /// Directory -> nearest config at or above it (None when there is none under root)
+    configs: Mutex<HashMap<PathBuf, Option<Arc<CompilerPaths>>>>,
+}
+
+impl TypeScriptResolver {
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            workspaces: load_workspaces(root),
+            configs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Absolute path of the source file a specifier names, if it is in the project
+    pub fn resolve(&self, specifier: &str, from_file: &Path) -> Option<PathBuf> {
+        let from_dir = from_file.parent()?;
+        if specifier.starts_with('.') {
+            return probe(&from_dir.join(specifier));
+        }
+
+        if let Some(config) = self.config_for(from_dir) {
+            if let Some(found) = resolve_paths(&config, specifier) {
+                return Some(found);
+            }
+            if let Some(found) = config
+                .base_url
+                .as_ref()
+                .and_then(|base| probe(&base.join(specifier)))
+            {
+                return Some(found);
+            }
+        }
+
+        self.resolve_workspace(specifier)
+    }
+
+    /// The nearest config at or above dir, cached for every directory walked through
+    fn config_for(&self, dir: &Path) -> Option<Arc<CompilerPaths>> {
+        let mut cache = self.configs.lock().unwrap_or_else(|e| e.into_inner());
+        let mut visited = Vec::new();
+        let mut current = Some(dir);
+        let mut found = None;
+
+        while let Some(dir) = current {
+            if let Some(cached) = cache.get(dir) {
+                found = cached.clone();
+                break;
+            }
+            visited.push(dir.to_path_buf());
+            let config_file = CONFIG_NAMES
+                .iter()
+                .map(|name| dir.join(name))
+                .find(|path| path.is_file());
+            if let Some(config_file) = config_file {
+                found = Some(Arc::new(load_config(&config_file, 0)));
+                break;
+            }
+            current = dir.parent().filter(|parent| parent.starts_with(&self.root));
+        }
+
+        for dir in visited {
+            cache.insert(dir, found.clone());
+        }
+        found
+    }
+
+    /// `@org/ui` or `@org/ui/button` against the workspace packages
+    fn resolve_workspace(&self, specifier: &str) -> Option<PathBuf> {
+        let (name, dir) = self
+            .workspaces
+            .iter()
+            .filter(|(name, _)| {
+                specifier == name.as_str()
+                    || specifier
+                        .strip_prefix(name.as_str())
+                        .is_some_and(|rest| rest.starts_with('/'))
+            })
+            .max_by_key(|(name, _)| name.len())?;
+        let subpath = specifier[name.len()..].trim_start_matches('/');
+        let manifest = read_jsonc(&dir.join("package.json"));
+        let exports = manifest.as_ref().and_then(|m| m.get("exports"));
+
+        if subpath.is_empty() {
+            let from_exports = exports
+                .and_then(|e| match e {
+                    Value::Object(map) if map.contains_key(".") => map.get("."),
+                    _ => Some(e),
+                })
+                .and_then(export_target)
+                .and_then(|target| probe(&dir.join(target)));
+            let field = |name: &str| {
+                manifest
+                    .as_ref()
+                    .and_then(|m| m.get(name))
+                    .and_then(Value::as_str)
+                    .and_then(|target| probe(&dir.join(target)))
+            };
+            return from_exports
+                .or_else(|| field("source"))
+                .or_else(|| probe(&dir.join("src/index")))
+                .or_else(|| probe(&dir.join("index")))
+                .or_else(|| field("module"))
+                .or_else(|| field("main"));
+        }
+
+        exports
+            .and_then(|e| e.get(format!("./{}", subpath)))
+            .and_then(export_target)
+            .and_then(|target| probe(&dir.join(target)))
+            .or_else(|| probe(&dir.join(subpath)))
+            .or_else(|| probe(&dir.join("src").join(subpath)))
+    }
+}
+
+/// Match a specifier against paths patterns: an exact pattern wins, then the wildcard
+/// pattern with the longest prefix
+fn resolve_paths(config: &CompilerPaths, specifier: &str) -> Option<PathBuf> {
+    let base = config.base_url.as_ref().unwrap_or(&config.paths_dir);
+
+    let mut best: Option<(usize, &[String], &str)> = None;
+    for (pattern, targets) in &config.paths {
+        match pattern.split_once('*') {
+            None if pattern == specifier => {
+                best = Some((usize::MAX, targets, ""));
+                break;
+            }
+            Some((prefix, suffix))
+                if specifier.len() >= prefix.len() + suffix.len()
+                    && specifier.starts_with(prefix)
+                    && specifier.ends_with(suffix)
+                    && best.is_none_or(|(len, _, _)| prefix.len() > len) =>
+            {
+                let captured = &specifier[prefix.len()..specifier.len() - suffix.len()];
+                best = Some((prefix.len(), targets, captured));
+            }
+            _ => {}
+        }
+    }
+
+    let (_, targets, captured) = best?;
+    targets
+        .iter()
+        .find_map(|target| probe(&base.join(target.replacen('*', captured, 1))))
+}
+
+/// Read a tsconfig/jsconfig, applying the configs it extends first
+fn load_config(path: &Path, depth: usize) -> CompilerPaths {
+    let Some(json) = read_jsonc(path) else {
+        return CompilerPaths::default();
+    };
+    let dir = path.parent().unwrap_or(Path::new(""));
+
+    let extends: Vec<&str> = match json.get("extends") {
+        Some(Value::String(parent)) => vec![parent.as_str()],
+        Some(Value::Array(parents)) => parents.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    let mut config = CompilerPaths::default();
+    if depth < MAX_EXTENDS_DEPTH {
+        for parent in extends {
+            if let Some(parent_path) = extends_path(dir, parent) {
+                let parent = load_config(&parent_path, depth + 1);
+                if parent.base_url.is_some() {
+                    config.base_url = parent.base_url;
+                }
+                if !parent.paths.is_empty() {
+                    config.paths = parent.paths;
+                    config.paths_dir = parent.paths_dir;
+                }
+            }
+        }
+    }
+
+    let Some(options) = json.get("compilerOptions") else {
+        return config;
+    };
+    if let Some(base_url) = options.get("baseUrl").and_then(Value::as_str) {
+        config.base_url = Some(normalize_path(&dir.join(base_url)));
+    }
+    if let Some(Value::Object(paths)) = options.get("paths") {
+        config.paths = paths
+            .iter()
+            .map(|(pattern, targets)| {
+                let targets = match targets {
+                    Value::Array(targets) => targets
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .map(str::to_string)
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                (pattern.clone(), targets)
+            })
+            .collect();
+        config.paths_dir = dir.to_path_buf();
+    }
+    config
+}
+
+/// The file an extends entry names: a relative path, or a config inside node_modules
+fn extends_path(dir: &Path, extends: &str) -> Option<PathBuf> {
+    let with_json = |path: PathBuf| {
+        if path.is_file() {
+            Some(path)
+        } else if path.is_dir() {
+            Some(path.join("tsconfig.json")).filter(|p| p.is_file())
+        } else {
+            Some(with_appended_extension(&path, "json")).filter(|p| p.is_file())
+        }
+    };
+
+    if extends.starts_with('.') || Path::new(extends).is_absolute() {
+        return with_json(dir.join(extends));
+    }
+    dir.ancestors()
+        .find_map(|ancestor| with_json(ancestor.join("node_modules").join(extends)))
+}
+
+/// Workspace package names from the root package.json `workspaces` globs
+fn load_workspaces(root: &Path) -> HashMap<String, PathBuf> {
+    let mut packages = HashMap::new();
+    let Some(manifest) = read_jsonc(&root.join("package.json")) else {
+        return packages;
+    };
+    let patterns = match manifest.get("workspaces") {
+        Some(Value::Array(patterns)) => patterns,
+        Some(Value::Object(config)) => match config.get("packages") {
+            Some(Value::Array(patterns)) => patterns,
+            _ => return packages,
+        },
+        _ => return packages,
+    };
+
+    for pattern in patterns.iter().filter_map(Value::as_str) {
+        if pattern.starts_with('!') {
+            continue;
+        }
+        let full_pattern = root.join(pattern).to_string_lossy().to_string();
+        let Ok(matches) = glob::glob(&full_pattern) else {
+            continue;
+        };
+        for dir in matches.filter_map(|m| m.ok()) {
+            if dir.components().any(|c| c.as_os_str() == "node_modules") {
+                continue;
+            }
+            let name = read_jsonc(&dir.join("package.json")).and_then(|package| {
+                package
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+            });
+            if let Some(name) = name {
+                packages.insert(name, dir);
+            }
+        }
+    }
+    packages
+}
+
+/// First path in a package.json exports entry, trying conditions in EXPORT_CONDITIONS order
+fn export_target(entry: &Value) -> Option<&str> {
+    match entry {
+        Value::String(target) => Some(target),
+        Value::Object(conditions) => EXPORT_CONDITIONS
+            .iter()
+            .find_map(|condition| conditions.get(*condition).and_then(export_target)),
+        Value::Array(targets) => targets.iter().find_map(export_target),
+        _ => None,
+    }
+}
+
+/// The source file a path names: as written, its ESM source, with an extension, or as a
+/// directory index
+fn probe(path: &Path) -> Option<PathBuf> {
+    let path = normalize_path(path);
+    if is_source(&path) && path.is_file() {
+        return Some(path);
+    }
+
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        if let Some((_, sources)) = ESM_SOURCES.iter().find(|(js, _)| *js == ext) {
+            if let Some(found) = sources
+                .iter()
+                .map(|source| path.with_extension(source))
+                .find(|candidate| candidate.is_file())
+            {
+                return Some(found);
+            }
+        }
+    }
+
+    EXTENSIONS
+        .iter()
+        .map(|ext| with_appended_extension(&path, ext))
+        .chain(
+            EXTENSIONS
+                .iter()
+                .map(|ext| path.join(format!("index.{}", ext))),
+        )
+        .find(|candidate| candidate.is_file())
+}
+
+/// Whether a file is a JS/TS source (declaration files are not)
+fn is_source(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy())
+        .unwrap_or_default();
+    !name.ends_with(".d.ts")
+        && path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|ext| EXTENSIONS.contains(&ext))
+}
+
+/// Parse a JSON file that may contain comments and trailing commas
+fn read_jsonc(path: &Path) -> Option<Value> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&strip_jsonc(&content)).ok()
+}
+
+/// Remove // and /* */ comments and trailing commas, leaving strings intact
+fn strip_jsonc(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            if c == '\\' {
+                if let Some(escaped) = chars.next() {
+                    out.push(escaped);
+                }
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            (',', _) => {
+                // Drop the comma if only whitespace separates it from a closing bracket
+                let next = chars.clone().find(|c| !c.is_whitespace());
+                if !matches!(next, Some('}') | Some(']')) {
+                    out.push(c);
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn resolve(resolver: &TypeScriptResolver, specifier: &str, from: &str) -> Option<String> {
+        let root = resolver.root.clone();
+        resolver.resolve(specifier, &root.join(from)).map(|p| {
+            p.strip_prefix(&root)
+                .unwrap()
+                .to_string_lossy()
+                .replace('\\', "/")
+        })
+    }
+
+    #[test]
+    fn test_relative_and_esm_imports() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write(root, "src/auth.ts", "");
+        write(root, "src/date.service.ts", "");
+        write(root, "src/ui/index.tsx", "");
+        write(root, "src/types.d.ts", "");
+
+        let resolver = TypeScriptResolver::new(root);
+        let from = "src/main.ts";
+        assert_eq!(
+            resolve(&resolver, "./auth", from).as_deref(),
+            Some("src/auth.ts")
+        );
+        assert_eq!(
+            resolve(&resolver, "./auth.js", from).as_deref(),
+            Some("src/auth.ts")
+        );
+        assert_eq!(
+            resolve(&resolver, "./date.service", from).as_deref(),
+            Some("src/date.service.ts")
+        );
+        assert_eq!(
+            resolve(&resolver, "./ui", from).as_deref(),
+            Some("src/ui/index.tsx")
+        );
+        assert_eq!(resolve(&resolver, "./types", from), None);
+        assert_eq!(resolve(&resolver, "react", from), None);
+    }
+
+    #[test]
+    fn test_tsconfig_paths_and_extends() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write(
+            root,
+            "tsconfig.base.json",
+            r#"{
+                // Shared aliases
+                "compilerOptions": {
+                    "baseUrl": ".",
+                    "paths": {
+                        "@app/*": ["packages/app/src/*"],
+                        "@app/auth": ["packages/auth/src/index.ts"],
+                        "~/*": ["packages/web/src/*", "shared/*"],
+                    },
+                },
+            }"#,
+        );
+        write(
+            root,
+            "packages/web/tsconfig.json",
+            r#"{ "extends": "../../tsconfig.base" }"#,
+        );
+        write(root, "packages/app/src/session.ts", "");
+        write(root, "packages/auth/src/index.ts", "");
+        write(root, "shared/utils.ts", "");
+        write(root, "packages/web/src/lib/fmt.ts", "");
+
+        let resolver = TypeScriptResolver::new(root);
+        let from = "packages/web/src/page.tsx";
+        assert_eq!(
+            resolve(&resolver, "@app/session", from).as_deref(),
+            Some("packages/app/src/session.ts")
+        );
+        assert_eq!(
+            resolve(&resolver, "@app/auth", from).as_deref(),
+            Some("packages/auth/src/index.ts")
+        );
+        // Falls through to the second target
+        assert_eq!(
+            resolve(&resolver, "~/utils", from).as_deref(),
+            Some("shared/utils.ts")
+        );
+        // baseUrl
+        assert_eq!(
+            resolve(&resolver, "packages/web/src/lib/fmt", from).as_deref(),
+            Some("packages/web/src/lib/fmt.ts")
+        );
+        // No config above this file
+        assert_eq!(resolve(&resolver, "@app/session", "other/x.ts"), None);
+    }
+
+    #[test]
+    fn test_workspace_packages() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write(
+            root,
+            "package.json",
+            r#"{ "workspaces": { "packages": ["packages/*"] } }"#,
+        );
+        write(
+            root,
+            "packages/ui/package.json",
+            r#"{ "name": "@org/ui", "main": "dist/index.js", "exports": { "./button": { "import": "./src/button/index.ts" } } }"#,
+        );
+        write(root, "packages/ui/src/index.ts", "");
+        write(root, "packages/ui/src/button/index.ts", "");
+        write(root, "packages/ui/src/theme.ts", "");
+        write(
+            root,
+            "packages/core/package.json",
+            r#"{ "name": "core", "exports": { ".": { "source": "./lib/core.ts", "default": "./dist/core.js" } } }"#,
+        );
+        write(root, "packages/core/lib/core.ts", "");
+
+        let resolver = TypeScriptResolver::new(root);
+        let from = "apps/web/main.ts";
+        assert_eq!(
+            resolve(&resolver, "@org/ui", from).as_deref(),
+            Some("packages/ui/src/index.ts")
+        );
+        assert_eq!(
+            resolve(&resolver, "@org/ui/button", from).as_deref(),
+            Some("packages/ui/src/button/index.ts")
+        );
+        assert_eq!(
+            resolve(&resolver, "@org/ui/theme", from).as_deref(),
+            Some("packages/ui/src/theme.ts")
+        );
+        assert_eq!(
+            resolve(&resolver, "core", from).as_deref(),
+            Some("packages/core/lib/core.ts")
+        );
+        assert_eq!(resolve(&resolver, "@org/uikit", from), None);
+    }
+
+    #[test]
+    fn test_strip_jsonc() {
+        let stripped = strip_jsonc("{\"a\": \"// not a comment\", /* b */ \"c\": [1, 2,],\n}");
+        let value: Value = serde_json::from_str(&stripped).unwrap();
+        assert_eq!(value["a"], "// not a comment");
+        assert_eq!(value["c"], serde_json::json!([1, 2]));
+    }
+}
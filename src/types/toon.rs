@@ -7,11 +7,14 @@
 //!     - !ToonData is the central type - all fields map directly to TOON file format
 //!     - !ExportInfo.kind must match the values used by each language parser
 //!     - All Option fields use skip_serializing_if to avoid empty fields in output
+//!     - ASTInfo and ExtractedComments are serialized into the generation cache (see cache);
+//!       changing their shape only costs a re-parse since the cache is keyed by luny version
 //!
 //! invariants:
 //!     - ToonData.purpose is the only user-required field; tokens/exports are auto-generated
 //!     - All semantic fields (invariants, gotchas, etc.) are optional
 //!     - ValidationResult tracks both errors (fatal) and warnings (non-fatal)
+//!     - ToonData and everything in it is PartialEq so formatter round-trips can be asserted
 //!
 //! do-not:
 //!     - Never change ExportInfo or ImportInfo without updating all parsers
@@ -21,6 +24,8 @@
 //!     - ToonCommentBlock is used for parsing, ToonData is used for generating
 //!     - FunctionAnnotation supports per-function metadata but is rarely populated
 //!     - WhenEditingItem.important=true maps to the ! prefix in TOON format
+//!     - ToonData also carries directory summaries (_dir.toon), which fill files instead of
+//!       signatures and qualify exports with their file name
 //!
 //! flows:
 //!     - Parser extracts ASTInfo from source code
@@ -32,7 +37,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Export information extracted from AST
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ExportInfo {
     /// Export name (e.g., "AuthProvider", "useAuth")
     pub name: String,
@@ -41,7 +46,7 @@ pub struct ExportInfo {
 }
 
 /// Import information extracted from AST
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ImportInfo {
     /// Source module (e.g., "react", "./shared")
     pub from: String,
@@ -50,7 +55,7 @@ pub struct ImportInfo {
 }
 
 /// Call information extracted from AST
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CallInfo {
     /// Target module (e.g., "./api-client", "./storage")
     pub target: String,
@@ -59,7 +64,7 @@ pub struct CallInfo {
 }
 
 /// Reverse call information (what calls this file)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CalledByInfo {
     /// File path that calls this
     pub from: String,
@@ -68,7 +73,7 @@ pub struct CalledByInfo {
 }
 
 /// Full signature information for AI reasoning
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SignatureInfo {
     /// Export name
     pub name: String,
@@ -82,6 +87,15 @@ pub struct SignatureInfo {
     pub end_line: usize,
 }
 
+/// One file's line in a directory summary
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileSummary {
+    /// File name within the directory (e.g., "provider.ts")
+    pub file: String,
+    /// First sentence of the file's purpose
+    pub purpose: String,
+}
+
 /// When-editing item with optional importance flag
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WhenEditingItem {
@@ -92,7 +106,7 @@ pub struct WhenEditingItem {
 }
 
 /// Function-level annotations (subset of fields applicable to functions)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FunctionAnnotation {
     /// Function name
     pub name: String,
@@ -114,7 +128,7 @@ pub struct FunctionAnnotation {
 }
 
 /// Structural information extracted from source AST
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ASTInfo {
     /// Approximate token count (source.length / 4)
     pub tokens: usize,
@@ -129,7 +143,7 @@ pub struct ASTInfo {
 }
 
 /// Result of extracting @dose comments from source
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ExtractedComments {
     /// File-level @dose block content (parsed fields)
     pub file_block: Option<ToonCommentBlock>,
@@ -138,7 +152,7 @@ pub struct ExtractedComments {
 }
 
 /// Parsed content from a @dose block comment
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ToonCommentBlock {
     pub purpose: Option<String>,
     pub when_editing: Option<Vec<WhenEditingItem>>,
@@ -155,7 +169,7 @@ pub struct ToonCommentBlock {
 }
 
 /// Combined data for generating TOON DOSE
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ToonData {
     // Required fields
     pub purpose: String,
@@ -179,6 +193,9 @@ pub struct ToonData {
     pub called_by: Option<Vec<CalledByInfo>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub signatures: Option<Vec<SignatureInfo>>,
+    /// Files in a directory summary (_dir.toon only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub files: Option<Vec<FileSummary>>,
 
     // Semantic (from docs)
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -207,6 +224,9 @@ pub struct ToonData {
     // Validation control
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ignore: Option<Vec<String>>,
+    /// Hash of the source text and luny version the TOON was generated from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
 }
 
 impl ToonData {
@@ -222,6 +242,7 @@ impl ToonData {
             imported_by: None,
             called_by: None,
             signatures: None,
+            files: None,
             invariants: None,
             error_handling: None,
             constraints: None,
@@ -233,6 +254,7 @@ impl ToonData {
             related: None,
             function_annotations: None,
             ignore: None,
+            fingerprint: None,
         }
     }
 }
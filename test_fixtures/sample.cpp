@@ -0,0 +1,45 @@
+/* @dose
+purpose: Sample C++ implementation fixture for testing the luny C/C++ parser.
+*/
+
+#include "sample.hpp"
+#include "util/log.h"
+#include <cmath>
+
+namespace geo {
+
+namespace {
+int counter = 0;
+}
+
+static int scale(int v) {
+    return v * 2;
+}
+
+// @dose invariant: never returns a negative area
+int area(const Size& size) {
+    log_info("area");
+    return scale(size.width) * size.height;
+}
+
+const char* shape_name(Shape shape) {
+    switch (shape) {
+    case Shape::Circle:
+        return "circle";
+    default:
+        return "other";
+    }
+}
+
+template <typename T>
+T Point<T>::length() const {
+    return std::sqrt(x_ * x_);
+}
+
+}  // namespace geo
+
+int sample_count = 0;
+
+void sample_init(void) {
+    sample_count = 0;
+}
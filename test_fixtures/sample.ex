@@ -0,0 +1,48 @@
+defmodule MyApp.Accounts do
+  @moduledoc """
+  @dose
+  purpose: Sample Elixir fixture for testing the luny Elixir parser. Manages user
+      accounts on top of the Repo.
+
+  when-editing:
+      - !Every write goes through a changeset
+
+  invariants:
+      - Emails are stored lowercased
+  """
+
+  alias MyApp.Repo
+  alias MyApp.Accounts.{User, Token}
+  import Ecto.Query, only: [from: 2]
+  use MyApp.Context
+  require Logger
+
+  @doc "Fetch a user by id."
+  def get_user(id), do: Repo.get(User, id)
+
+  # @dose invariant: email is normalized before insert
+  def create_user(attrs \\ %{}) do
+    %User{}
+    |> User.changeset(attrs)
+    |> Repo.insert()
+  end
+
+  def delete_user(%User{} = user) do
+    Logger.info("deleting user")
+    Repo.delete(user)
+  end
+
+  def delete_user(id) when is_binary(id), do: id |> get_user() |> delete_user()
+
+  defp normalize(email), do: String.downcase(email)
+
+  defmacro with_user(id, do: block) do
+    quote do: unquote(block)
+  end
+
+  defmodule Token do
+    defstruct [:value, :user_id]
+
+    def new(user), do: %__MODULE__{value: :crypto.strong_rand_bytes(16), user_id: user.id}
+  end
+end
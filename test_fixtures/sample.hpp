@@ -0,0 +1,72 @@
+/** @dose
+purpose: Sample C++ header fixture for testing the luny C/C++ parser.
+    This file contains classes, structs, enums, templates, and namespaces
+    to verify header extraction works correctly.
+
+when-editing:
+    - !Keep sample.cpp in sync with the declarations here
+
+invariants:
+    - Every non-static declaration in this header is part of the public API
+
+gotchas:
+    - Members of a class are private until the first public: section
+*/
+
+#ifndef LUNY_SAMPLE_HPP
+#define LUNY_SAMPLE_HPP
+
+#include <string>
+#include <vector>
+#include "util/log.h"
+
+#define SAMPLE_VERSION "1.0.0"
+#define SAMPLE_MAX(a, b) ((a) > (b) ? (a) : (b))
+
+namespace geo {
+
+enum class Shape { Circle, Square, Triangle };
+
+struct Size {
+    int width;
+    int height;
+};
+
+template <typename T>
+class Point : public Base<T> {
+    T x_;
+
+public:
+    Point(T x, T y);
+    T length() const;
+    static Point origin();
+
+private:
+    void normalize();
+};
+
+int area(const Size& size);
+const char* shape_name(Shape shape);
+
+template <typename T>
+T max_of(T a, T b) {
+    return a > b ? a : b;
+}
+
+static inline int clamp(int v) { return v < 0 ? 0 : v; }
+
+using Sizes = std::vector<Size>;
+
+}  // namespace geo
+
+typedef struct {
+    double lat;
+    double lng;
+} Coord;
+
+extern "C" {
+void sample_init(void);
+extern int sample_count;
+}
+
+#endif  // LUNY_SAMPLE_HPP
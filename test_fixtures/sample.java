@@ -0,0 +1,116 @@
+/** @dose
+purpose: Sample Java fixture for testing the luny Java parser.
+    This file contains various Java constructs including classes, interfaces,
+    records, enums, and annotations to verify extraction works correctly.
+
+when-editing:
+    - !Keep all visibility modifiers represented for comprehensive testing
+    - Maintain the mix of annotated and plain methods
+
+invariants:
+    - All public items must have clear, testable names
+    - Include examples of each visibility modifier
+
+do-not:
+    - Remove any exports without updating corresponding tests
+
+gotchas:
+    - Java default (package-private) visibility is not public
+    - Interface methods are implicitly public
+*/
+
+package com.luny.fixtures;
+
+import java.io.IOException;
+import java.nio.file.Files;
+import java.nio.file.Path;
+import java.util.HashMap;
+import java.util.List;
+import java.util.Map;
+import java.util.concurrent.*;
+import static java.util.Objects.requireNonNull;
+
+public enum UserStatus {
+    ACTIVE,
+    INACTIVE,
+    PENDING,
+    SUSPENDED
+}
+
+public interface Repository<T> {
+    T findById(String id) throws IOException;
+    boolean save(T entity);
+    List<T> findAll();
+}
+
+public record UserConfig(
+    String id,
+    String name,
+    String email
+) implements Comparable<UserConfig> {
+    public int compareTo(UserConfig other) {
+        return id.compareTo(other.id);
+    }
+}
+
+@Service
+public class UserService<T extends UserConfig> extends BaseService implements Repository<T> {
+    private final Path dataDir;
+    private final Map<String, T> cache;
+
+    public UserService(Path dataDir) {
+        this.dataDir = requireNonNull(dataDir);
+        this.cache = new HashMap<>();
+    }
+
+    @Override
+    public T findById(String id) throws IOException {
+        if (cache.containsKey(id)) {
+            return cache.get(id);
+        }
+        String json = Files.readString(dataDir.resolve(id + ".json"));
+        return parse(json);
+    }
+
+    @Override
+    public boolean save(T user) {
+        validate(user);
+        cache.put(user.id(), user);
+        return true;
+    }
+
+    @Override
+    public List<T> findAll() {
+        return List.copyOf(cache.values());
+    }
+
+    @SafeVarargs
+    public final <R> List<R> mapAll(Function<T, R> mapper, T... users) {
+        return List.of();
+    }
+
+    // Private validation method
+    private void validate(T user) {
+        if (user.id().isEmpty()) {
+            throw new IllegalArgumentException("User ID is required");
+        }
+    }
+
+    // Package-private method
+    T parse(String json) {
+        return null;
+    }
+
+    protected void onSaved(T user) {
+        // Hook for subclasses
+    }
+}
+
+// Package-private class
+class CacheManager {
+    public void clear() {}
+}
+
+public @interface Audited {
+    String value();
+}
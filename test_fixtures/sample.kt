@@ -0,0 +1,112 @@
+/** @dose
+purpose: Sample Kotlin fixture for testing the luny Kotlin parser.
+    This file contains various Kotlin constructs including data and sealed classes,
+    objects, companion objects, and extension functions to verify extraction works correctly.
+
+when-editing:
+    - !Keep all visibility modifiers represented for comprehensive testing
+    - Maintain the mix of top-level and member declarations
+
+invariants:
+    - All public items must have clear, testable names
+    - Include examples of each visibility modifier
+
+do-not:
+    - Remove any exports without updating corresponding tests
+
+gotchas:
+    - Kotlin declarations are public unless marked private, internal, or protected
+    - Companion object members are called through the enclosing class name
+*/
+
+package com.luny.fixtures
+
+import java.io.File
+import kotlinx.coroutines.withContext
+import kotlinx.coroutines.Dispatchers
+import kotlinx.serialization.json.Json as JsonCodec
+import com.luny.model.*
+
+const val VERSION = "1.0.0"
+val DEFAULT_TIMEOUT: Int = 30
+internal val cacheSize = 100
+
+enum class UserStatus {
+    ACTIVE,
+    INACTIVE,
+    PENDING("awaiting review"),
+    SUSPENDED;
+
+    fun isActive(): Boolean = this == ACTIVE
+}
+
+data class UserConfig(
+    val id: String,
+    val name: String,
+    val email: String? = null,
+) {
+    fun displayName(): String = "$name <${email ?: "none"}>"
+}
+
+sealed class Result<out T> {
+    data class Success<T>(val value: T) : Result<T>()
+    data class Failure(val error: Throwable) : Result<Nothing>()
+    object Loading : Result<Nothing>()
+}
+
+interface Repository<T> {
+    suspend fun findById(id: String): T?
+    suspend fun save(entity: T): Boolean
+}
+
+@Singleton
+class UserService(private val dataDir: File) : Repository<UserConfig> {
+    private val cache = mutableMapOf<String, UserConfig>()
+
+    override suspend fun findById(id: String): UserConfig? = withContext(Dispatchers.IO) {
+        cache[id] ?: File(dataDir, "$id.json")
+            .takeIf { it.exists() }
+            ?.let { JsonCodec.decodeFromString<UserConfig>(it.readText()) }
+    }
+
+    override suspend fun save(entity: UserConfig): Boolean {
+        validate(entity)
+        dataDir.mkdirs()
+        cache[entity.id] = entity
+        return true
+    }
+
+    // Private validation method
+    private fun validate(user: UserConfig) {
+        require(user.id.isNotEmpty()) { "User ID is required" }
+    }
+
+    internal fun clearCache() = cache.clear()
+
+    companion object {
+        const val DEFAULT_DIR = "data"
+
+        fun create(dir: String = DEFAULT_DIR): UserService = UserService(File(dir))
+    }
+}
+
+object UserRegistry {
+    private val services = mutableListOf<UserService>()
+
+    fun register(service: UserService) {
+        services.add(service)
+    }
+}
+
+fun <T> List<T>.second(): T = this[1]
+
+fun String.toSlug(separator: Char = '-'): String =
+    lowercase().replace(' ', separator)
+
+suspend fun loadAll(service: UserService, ids: List<String>): List<UserConfig> {
+    return ids.mapNotNull { service.findById(it) }
+}
+
+private fun helper() = Unit
+
+typealias UserMap = Map<String, UserConfig>
@@ -0,0 +1,103 @@
+<?php
+/** @dose
+purpose: Sample PHP fixture for testing the luny PHP parser.
+    This file contains classes, traits, interfaces, enums, and functions
+    to verify extraction works correctly.
+
+when-editing:
+    - !Keep all visibility modifiers represented for comprehensive testing
+    - Maintain the mix of static and instance calls
+
+invariants:
+    - All public items must have clear, testable names
+
+do-not:
+    - Remove any exports without updating corresponding tests
+
+gotchas:
+    - Class members without a visibility modifier are public
+*/
+
+declare(strict_types=1);
+
+namespace App\Billing;
+
+use App\Models\Invoice;
+use App\Models\{Customer, Payment as PaymentModel};
+use Illuminate\Support\Facades\Log;
+use Illuminate\Support\Str;
+use function App\Support\format_money;
+use const App\Support\CURRENCY;
+
+const DEFAULT_TERMS = 30;
+
+enum InvoiceStatus: string
+{
+    case Draft = 'draft';
+    case Sent = 'sent';
+    case Paid = 'paid';
+
+    public function label(): string
+    {
+        return Str::title($this->value);
+    }
+}
+
+interface Billable
+{
+    public function charge(int $amount, ?string $currency = null): bool;
+}
+
+trait LogsActivity
+{
+    protected function logActivity(string $message): void
+    {
+        Log::info($message);
+    }
+}
+
+#[Service]
+final class InvoiceService implements Billable
+{
+    use LogsActivity;
+
+    public const MAX_LINES = 100;
+
+    private array $cache = [];
+
+    public function __construct(private readonly PaymentModel $payments)
+    {
+    }
+
+    public function charge(int $amount, ?string $currency = null): bool
+    {
+        $this->logActivity("charge {$amount}");
+        return $this->payments->capture($amount, $currency ?? CURRENCY);
+    }
+
+    public static function find(string|int $id): ?Invoice
+    {
+        return Invoice::query()->find($id);
+    }
+
+    function total(Invoice $invoice): float
+    {
+        return array_sum($invoice->lines);
+    }
+
+    private function validate(Customer $customer): void
+    {
+        if (!$customer->active) {
+            throw new \InvalidArgumentException('Inactive customer');
+        }
+    }
+
+    protected function onCharged(Invoice $invoice): void
+    {
+    }
+}
+
+function summarize(Invoice ...$invoices): string
+{
+    return format_money(Invoice::sum($invoices)) . PHP_EOL;
+}
@@ -0,0 +1,47 @@
+#!/usr/bin/env bash
+# @dose
+# purpose: Sample shell fixture for testing the luny shell parser. Deploys a
+#     service to the cluster.
+#
+# when-editing:
+#     - !Keep the script idempotent; it is re-run on every failed deploy
+#
+# gotchas:
+#     - Requires kubectl to point at the right context
+
+set -euo pipefail
+
+SCRIPT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)"
+source "$SCRIPT_DIR/lib/common.sh"
+. ./lib/log.sh
+
+readonly NAMESPACE="prod"
+
+# @dose invariant: never deploys an untagged image
+deploy() {
+  local service="$1"
+  local version="$2"
+  log_info "deploying $service:$version"
+  kubectl apply -f "manifests/$service.yaml" --namespace "$NAMESPACE"
+  kubectl rollout status "deployment/$service"
+  _notify "$service"
+}
+
+function rollback {
+  local service=$1
+  sudo -u deploy kubectl rollout undo "deployment/$service"
+}
+
+_notify() {
+  curl -fsS -X POST "$SLACK_URL" -d "{\"text\": \"$*\"}" | jq -r '.ok' >/dev/null
+}
+
+main() {
+  case "${1:-}" in
+    deploy) deploy "$2" "$3" ;;
+    rollback) rollback "$2" ;;
+    *) echo "usage: $0 deploy|rollback <service>" >&2; exit 1 ;;
+  esac
+}
+
+main "$@"
@@ -0,0 +1,104 @@
+/** @dose
+purpose: Sample Swift fixture for testing the luny Swift parser.
+    This file contains structs, classes, enums, protocols, and extensions
+    to verify extraction works correctly.
+
+when-editing:
+    - !Keep all access levels represented for comprehensive testing
+    - Maintain the mix of generic and async declarations
+
+invariants:
+    - All public items must have clear, testable names
+
+do-not:
+    - Remove any exports without updating corresponding tests
+
+gotchas:
+    - Declarations without an access modifier are internal, not public
+*/
+
+import Foundation
+import Combine
+@testable import CoreModels
+import struct SwiftUI.Color
+
+public let defaultTimeout: TimeInterval = 30
+
+public enum UserStatus: String, Codable {
+    case active
+    case inactive
+    case pending(reason: String)
+}
+
+public protocol Repository {
+    associatedtype Entity
+    func find(byId id: String) async throws -> Entity?
+    func save(_ entity: Entity) async throws
+}
+
+public struct UserConfig: Codable, Equatable {
+    public let id: String
+    public var name: String
+    var email: String?
+
+    public var displayName: String {
+        "\(name) <\(email ?? "none")>"
+    }
+
+    public init(id: String, name: String) {
+        self.id = id
+        self.name = name
+    }
+}
+
+open class UserService<Store: Repository>: NSObject where Store.Entity == UserConfig {
+    private let store: Store
+    private var cache: [String: UserConfig] = [:]
+
+    public init(store: Store) {
+        self.store = store
+    }
+
+    public func user(withId id: String) async throws -> UserConfig? {
+        if let cached = cache[id] {
+            return cached
+        }
+        return try await store.find(byId: id)
+    }
+
+    @discardableResult
+    open func save(_ user: UserConfig, overwrite: Bool = false) async throws -> Bool {
+        try validate(user)
+        try await store.save(user)
+        return true
+    }
+
+    // Private validation method
+    private func validate(_ user: UserConfig) throws {
+        guard !user.id.isEmpty else { throw ValidationError.missingId }
+    }
+
+    func clearCache() {
+        cache.removeAll()
+    }
+}
+
+public extension Array where Element == UserConfig {
+    func sortedByName() -> [UserConfig] {
+        sorted { $0.name < $1.name }
+    }
+}
+
+extension String {
+    public func slugified(separator: Character = "-") -> String {
+        lowercased().replacingOccurrences(of: " ", with: String(separator))
+    }
+}
+
+public func loadAll<S: Sequence>(_ ids: S, from service: UserService<InMemoryStore>) async -> [UserConfig] where S.Element == String {
+    []
+}
+
+func helper() {}
+
+public typealias UserMap = [String: UserConfig]
@@ -151,3 +151,118 @@ fn e2e_validate_fix_regenerates_invalid_toon_file() {
         .expect("run validate --strict");
     assert!(strict_status.success());
 }
+
+#[test]
+fn e2e_inline_annotations_for_python() {
+    let temp_dir = TempDir::new().expect("temp dir");
+
+    let source = r#""""@dose
+purpose: Connection pool
+"""
+
+class Pool:
+    # @dose invariant: always returns a live connection
+    def acquire(self):
+        pass
+"#;
+
+    std::fs::write(temp_dir.path().join("pool.py"), source).expect("write");
+
+    let status = bin()
+        .args([
+            "--root",
+            temp_dir.path().to_string_lossy().as_ref(),
+            "generate",
+        ])
+        .status()
+        .expect("run");
+    assert!(status.success());
+
+    let output = std::fs::read_to_string(temp_dir.path().join(".ai/pool.py.toon")).expect("read");
+    assert!(
+        output.contains("fn:Pool.acquire: invariants: always returns a live connection"),
+        "Got:\n{}",
+        output
+    );
+}
+
+#[test]
+fn e2e_cpp_header_lists_implementation_in_related() {
+    let temp_dir = TempDir::new().expect("temp dir");
+    let src = temp_dir.path().join("src");
+    std::fs::create_dir_all(&src).expect("mkdir");
+
+    std::fs::write(
+        src.join("geo.hpp"),
+        "/** @dose\npurpose: Geometry API\nrelated: docs/geo.md\n*/\n\nint area(int w, int h);\n",
+    )
+    .expect("write header");
+    std::fs::write(
+        src.join("geo.cpp"),
+        "#include \"geo.hpp\"\n\nint area(int w, int h) { return w * h; }\n",
+    )
+    .expect("write implementation");
+
+    let status = bin()
+        .args([
+            "--root",
+            temp_dir.path().to_string_lossy().as_ref(),
+            "generate",
+        ])
+        .status()
+        .expect("run");
+    assert!(status.success());
+
+    let header =
+        std::fs::read_to_string(temp_dir.path().join(".ai/src/geo.hpp.toon")).expect("read");
+    assert!(
+        header.contains("related[2]: docs/geo.md,src/geo.cpp"),
+        "Got:\n{}",
+        header
+    );
+    assert!(
+        header.contains("imported-by[1]: src/geo.cpp"),
+        "Got:\n{}",
+        header
+    );
+
+    let implementation =
+        std::fs::read_to_string(temp_dir.path().join(".ai/src/geo.cpp.toon")).expect("read");
+    assert!(
+        !implementation.contains("related"),
+        "Got:\n{}",
+        implementation
+    );
+}
+
+#[test]
+fn e2e_luny_toml_maps_dialect_extensions() {
+    let temp_dir = TempDir::new().expect("temp dir");
+    let src = temp_dir.path().join("src");
+    std::fs::create_dir_all(&src).expect("mkdir");
+
+    std::fs::write(
+        temp_dir.path().join("luny.toml"),
+        "[[language]]\ngrammar = \"javascript\"\nextensions = [\"mjs\"]\n",
+    )
+    .expect("write config");
+    std::fs::write(
+        src.join("util.mjs"),
+        "export function slugify(text) {\n  return text.toLowerCase();\n}\n",
+    )
+    .expect("write source");
+
+    let status = bin()
+        .args([
+            "--root",
+            temp_dir.path().to_string_lossy().as_ref(),
+            "generate",
+        ])
+        .status()
+        .expect("run");
+    assert!(status.success());
+
+    let toon =
+        std::fs::read_to_string(temp_dir.path().join(".ai/src/util.mjs.toon")).expect("read");
+    assert!(toon.contains("exports[1]: slugify(fn)"), "Got:\n{}", toon);
+}